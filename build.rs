@@ -0,0 +1,22 @@
+fn main() {
+    // gRPC服务桩代码只在启用 grpc 特性时生成，不需要protoc（消息类型在 src/grpc.rs 中手写）
+    #[cfg(feature = "grpc")]
+    {
+        let service = tonic_build::manual::Service::builder()
+            .name("Phonemizer")
+            .package("g2p")
+            .method(
+                tonic_build::manual::Method::builder()
+                    .name("phonemize")
+                    .route_name("Phonemize")
+                    .input_type("super::PhonemizeRequest")
+                    .output_type("super::PhonemizeResponse")
+                    .codec_path("tonic::codec::ProstCodec")
+                    .client_streaming()
+                    .server_streaming()
+                    .build(),
+            )
+            .build();
+        tonic_build::manual::Builder::new().compile(&[service]);
+    }
+}
@@ -12,6 +12,11 @@ fn main() -> anyhow::Result<()> {
     println!("Rules: {}", stats.rule_count);
     println!();
     
+    // 显示内存占用估算
+    println!("=== Memory footprint ===");
+    println!("{}", g2p.memory_report());
+    println!();
+    
     // 测试单词转换
     let test_words = vec![
         "hello",
@@ -66,5 +71,13 @@ fn main() -> anyhow::Result<()> {
         println!();
     }
     
+    // 运行时统计
+    let stats = g2p.get_stats();
+    println!("=== Runtime statistics ===");
+    println!("Words converted: {}", stats.words_converted);
+    println!("Dictionary hits: {}", stats.dictionary_hits);
+    println!("OOV rate: {:.1}%", stats.oov_rate() * 100.0);
+    println!("Rule cache hit rate: {:.1}%", stats.rule_cache_hit_rate() * 100.0);
+    
     Ok(())
 }
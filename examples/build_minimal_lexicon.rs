@@ -0,0 +1,23 @@
+use rust_g2p::dict::{self, Dictionary};
+use rust_g2p::RulesEngine;
+
+/// 生成精简词典：
+/// cargo run --example build_minimal_lexicon -- [词频表] [单词数] [输出文件]
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let freq_path = args.get(1).map_or("data/en_common_words.txt", String::as_str);
+    let keep_n: usize = args.get(2).map_or(Ok(10_000), |n| n.parse())?;
+    let output_path = args.get(3).map_or("data/cmudict_min.txt", String::as_str);
+    
+    let mut dict = Dictionary::load_cmu_dict("data/cmudict.txt")?;
+    let ranked_words = dict::parse_frequency_list(&std::fs::read_to_string(freq_path)?);
+    
+    let report = dict.prune_by_frequency(&ranked_words, keep_n);
+    std::fs::write(output_path, dict.to_cmu_string())?;
+    println!("Wrote {} entries to {}", report.kept, output_path);
+    
+    // 被裁掉的单词改由规则引擎处理
+    let rules = RulesEngine::load_english_rules("data/en_rules.txt")?;
+    println!("Rules on removed words: {}", report.accuracy_impact(&rules));
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use rust_g2p::dict::Dictionary;
+use std::time::Instant;
+
+/// 生成二进制词典：
+/// cargo run --release --example compile_dictionary -- [文本词典] [输出文件]
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let input_path = args.get(1).map_or("data/cmudict.txt", String::as_str);
+    let output_path = args.get(2).map_or("data/cmudict.bin", String::as_str);
+
+    Dictionary::compile(input_path, output_path)?;
+    let text_size = std::fs::metadata(input_path)?.len();
+    let compiled_size = std::fs::metadata(output_path)?.len();
+    println!("Wrote {} ({} bytes, text {} bytes)", output_path, compiled_size, text_size);
+
+    // 比较两种格式的加载时间
+    let start = Instant::now();
+    let dict = Dictionary::load_cmu_dict(input_path)?;
+    let text_time = start.elapsed();
+    let start = Instant::now();
+    let compiled = Dictionary::load_compiled(output_path)?;
+    let compiled_time = start.elapsed();
+    assert_eq!(dict.size(), compiled.size());
+    println!("Load time: text {:?}, compiled {:?}", text_time, compiled_time);
+    Ok(())
+}
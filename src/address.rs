@@ -0,0 +1,114 @@
+use crate::numbers;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+
+/// 街道类型缩写
+const STREET_TYPES: [(&str, &str); 15] = [
+    ("st", "street"),
+    ("ave", "avenue"),
+    ("rd", "road"),
+    ("blvd", "boulevard"),
+    ("dr", "drive"),
+    ("ln", "lane"),
+    ("ct", "court"),
+    ("pl", "place"),
+    ("sq", "square"),
+    ("hwy", "highway"),
+    ("pkwy", "parkway"),
+    ("ter", "terrace"),
+    ("cir", "circle"),
+    ("fwy", "freeway"),
+    ("expy", "expressway"),
+];
+
+/// 方位缩写
+const DIRECTIONS: [(&str, &str); 8] = [
+    ("n", "north"),
+    ("s", "south"),
+    ("e", "east"),
+    ("w", "west"),
+    ("ne", "northeast"),
+    ("nw", "northwest"),
+    ("se", "southeast"),
+    ("sw", "southwest"),
+];
+
+/// 门牌内的单元缩写
+const UNITS: [(&str, &str); 6] = [
+    ("apt", "apartment"),
+    ("ste", "suite"),
+    ("bldg", "building"),
+    ("rm", "room"),
+    ("fl", "floor"),
+    ("unit", "unit"),
+];
+
+/// 展开地址中的缩写，需要在转小写之前处理
+///
+/// 只有"门牌号 + 街名 + 街道类型"的组合才按地址处理，因此 `123 Main St.` 读作 street，
+/// 而其他位置的 `St.` 不受影响。门牌号按两位一组读（`123 → one twenty three`），
+/// 紧跟地址的单元号读作数字加字母（`Apt. 4B → apartment four B`）。
+pub fn expand_addresses(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref ADDRESS_RE: Regex = Regex::new(&format!(
+            r"\b(\d{{1,6}})\s+(?:(?i:({}))\.?\s+)?((?:[A-Z0-9][\w'-]*\s+){{1,4}}?)(?i:({}))\b\.?",
+            alternation(DIRECTIONS.iter().map(|(abbrev, _)| abbrev)),
+            alternation(STREET_TYPES.iter().map(|(abbrev, _)| abbrev)),
+        ))
+        .unwrap();
+        static ref UNIT_RE: Regex = Regex::new(&format!(
+            r"(?i)(,?\s*)\b({})\b\.?\s*#?\s*(\d+)([A-Z])?\b",
+            alternation(UNITS.iter().map(|(abbrev, _)| abbrev)),
+        ))
+        .unwrap();
+    }
+
+    if !ADDRESS_RE.is_match(text) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len() + 32);
+    let mut last = 0;
+    for caps in ADDRESS_RE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        out.push_str(&text[last..whole.start()]);
+
+        out.push_str(&numbers::paired(&caps[1]));
+        out.push(' ');
+        if let Some(direction) = caps.get(2) {
+            out.push_str(lookup(&DIRECTIONS, direction.as_str()));
+            out.push(' ');
+        }
+        out.push_str(&caps[3]);
+        out.push_str(lookup(&STREET_TYPES, &caps[4]));
+        last = whole.end();
+
+        // 紧跟在地址后面的单元号
+        if let Some(unit) = UNIT_RE.captures(&text[last..]).filter(|unit| unit.get(0).unwrap().start() == 0) {
+            out.push_str(&expand_unit(&unit));
+            last += unit.get(0).unwrap().end();
+        }
+    }
+    out.push_str(&text[last..]);
+    Cow::Owned(out)
+}
+
+/// 单元号，字母保持大写以便按字母名称读
+fn expand_unit(caps: &Captures) -> String {
+    let mut unit = format!("{}{} {}", &caps[1], lookup(&UNITS, &caps[2]), numbers::paired(&caps[3]));
+    if let Some(letter) = caps.get(4) {
+        unit.push(' ');
+        unit.push_str(&letter.as_str().to_uppercase());
+    }
+    unit
+}
+
+fn lookup(table: &[(&str, &'static str)], abbrev: &str) -> &'static str {
+    let abbrev = abbrev.to_lowercase();
+    table.iter().find(|(key, _)| *key == abbrev).map_or("", |(_, expansion)| expansion)
+}
+
+fn alternation<'a>(items: impl Iterator<Item = &'a &'a str>) -> String {
+    items.copied().collect::<Vec<_>>().join("|")
+}
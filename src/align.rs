@@ -0,0 +1,151 @@
+//! 音素序列的加权编辑距离对齐
+//!
+//! 插入、删除和替换的代价可以配置；替换代价可以按发音特征的差异计算，使 T→D 这类相近的替换
+//! 比 T→IY 代价更低，对齐结果更接近实际的读音错误。音素比较时忽略重音。
+
+use crate::phoneme::{Phoneme, PhonemeType};
+
+/// 对齐的一步，下标分别指向两个序列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignStep {
+    Match(usize, usize),
+    Substitute(usize, usize),
+    Delete(usize), // 仅在第一个序列中出现
+    Insert(usize), // 仅在第二个序列中出现
+}
+
+/// 替换代价的计算方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubstitutionCost {
+    /// 所有替换代价相同
+    Uniform(f32),
+    /// 按 `feature_distance` 缩放，取值在 (0, max] 之间
+    Features { max: f32 },
+}
+
+/// 对齐的代价设置，默认为插入、删除、替换代价均为1的编辑距离
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignCosts {
+    pub insertion: f32,
+    pub deletion: f32,
+    pub substitution: SubstitutionCost,
+}
+
+/// 对齐结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    pub steps: Vec<AlignStep>,
+    pub cost: f32, // 总代价
+}
+
+impl Default for AlignCosts {
+    fn default() -> Self {
+        Self { insertion: 1.0, deletion: 1.0, substitution: SubstitutionCost::Uniform(1.0) }
+    }
+}
+
+impl AlignCosts {
+    /// 替换代价按发音特征差异计算，其余与默认相同
+    pub fn features() -> Self {
+        Self { substitution: SubstitutionCost::Features { max: 1.0 }, ..Self::default() }
+    }
+
+    /// 两个音素的替换代价，符号相同（忽略重音）时为0
+    pub fn substitution_cost(&self, a: &Phoneme, b: &Phoneme) -> f32 {
+        if a.symbol == b.symbol {
+            return 0.0;
+        }
+        match self.substitution {
+            SubstitutionCost::Uniform(cost) => cost,
+            SubstitutionCost::Features { max } => max * feature_distance(a, b),
+        }
+    }
+}
+
+impl Alignment {
+    /// 非 `Match` 的步数
+    pub fn edits(&self) -> usize {
+        self.steps.iter().filter(|step| !matches!(step, AlignStep::Match(..))).count()
+    }
+}
+
+/// 两个音素发音特征的差异，取值在 [0, 1] 之间，符号相同时为0
+///
+/// 元音比较舌位高低和前后，辅音比较发音方法、部位和清浊；元音与辅音之间、以及边界等特殊符号为1。
+/// 特征完全相同的不同音素（如 IH 和 IY）也不为0。
+pub fn feature_distance(a: &Phoneme, b: &Phoneme) -> f32 {
+    if a.symbol == b.symbol {
+        return 0.0;
+    }
+    let (fa, fb) = (&a.features, &b.features);
+    let differing = match (&fa.phoneme_type, &fb.phoneme_type) {
+        (PhonemeType::Vowel, PhonemeType::Vowel) => {
+            [fa.height != fb.height, fa.backness != fb.backness]
+                .iter()
+                .filter(|&&d| d)
+                .count()
+        }
+        (PhonemeType::Consonant, PhonemeType::Consonant) => {
+            [fa.manner != fb.manner, fa.place != fb.place, fa.voicing != fb.voicing]
+                .iter()
+                .filter(|&&d| d)
+                .count()
+        }
+        _ => return 1.0,
+    };
+    let total = if a.is_vowel() { 2 } else { 3 };
+    (differing + 1) as f32 / (total + 1) as f32
+}
+
+/// 按给定代价对齐两个音素序列，代价相同的对齐中优先替换，其次删除
+pub fn align(a: &[Phoneme], b: &[Phoneme], costs: &AlignCosts) -> Alignment {
+    align_by(a, b, costs.insertion, costs.deletion, |x, y| {
+        (x.symbol != y.symbol).then(|| costs.substitution_cost(x, y))
+    })
+}
+
+/// 通用的加权对齐，`substitution` 返回 None 表示两者相同
+pub(crate) fn align_by<T>(
+    a: &[T],
+    b: &[T],
+    insertion: f32,
+    deletion: f32,
+    substitution: impl Fn(&T, &T) -> Option<f32>,
+) -> Alignment {
+    let mut dist = vec![vec![0.0f32; b.len() + 1]; a.len() + 1];
+    for i in 1..=a.len() {
+        dist[i][0] = dist[i - 1][0] + deletion;
+    }
+    for j in 1..=b.len() {
+        dist[0][j] = dist[0][j - 1] + insertion;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let diagonal = dist[i - 1][j - 1] + substitution(&a[i - 1], &b[j - 1]).unwrap_or(0.0);
+            dist[i][j] = diagonal.min(dist[i - 1][j] + deletion).min(dist[i][j - 1] + insertion);
+        }
+    }
+
+    // 回溯
+    let mut steps = Vec::with_capacity(a.len().max(b.len()));
+    let (mut i, mut j) = (a.len(), b.len());
+    while i > 0 || j > 0 {
+        let cost = if i > 0 && j > 0 { Some(substitution(&a[i - 1], &b[j - 1])) } else { None };
+        if let Some(cost) = cost.filter(|cost| dist[i][j] == dist[i - 1][j - 1] + cost.unwrap_or(0.0)) {
+            steps.push(match cost {
+                None => AlignStep::Match(i - 1, j - 1),
+                Some(_) => AlignStep::Substitute(i - 1, j - 1),
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && dist[i][j] == dist[i - 1][j] + deletion {
+            steps.push(AlignStep::Delete(i - 1));
+            i -= 1;
+        } else {
+            steps.push(AlignStep::Insert(j - 1));
+            j -= 1;
+        }
+    }
+    steps.reverse();
+    Alignment { steps, cost: dist[a.len()][b.len()] }
+}
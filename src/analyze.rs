@@ -0,0 +1,175 @@
+//! 文本的音素频率分布
+//!
+//! `phoneme_profile` 统计文本中各音素（不计重音）和单词内相邻音素对的出现次数，
+//! `PhonemeProfile::compare` 与英语的基准分布比较，列出偏离最大的音素和音素对，
+//! 用于文体分析或检查人造语言的音系与英语的差异。基准分布由 `RustG2P::lexicon_profile`
+//! 按转换器的词典统计（每个词条计一次，不按词频加权）。
+
+use crate::dict::Dictionary;
+use crate::phoneme::{Phoneme, PhonemeCode};
+use crate::RustG2P;
+use crate::error::Result;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// 音素和音素对的出现次数
+#[derive(Debug, Clone, Default)]
+pub struct PhonemeProfile {
+    pub phonemes: HashMap<&'static str, u64>,
+    pub biphones: HashMap<(&'static str, &'static str), u64>, // 只统计同一单词内的相邻音素
+    pub total_phonemes: u64,
+    pub total_biphones: u64,
+}
+
+/// 一个音素或音素对与基准的比较
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deviation {
+    pub symbols: String, // 音素，音素对以空格分隔
+    pub observed: f64,   // 在文本中的比例
+    pub expected: f64,   // 在基准中的比例
+}
+
+/// 与基准分布的比较结果
+#[derive(Debug, Clone, Default)]
+pub struct ProfileComparison {
+    pub phonemes: Vec<Deviation>, // 两边出现过的所有音素，按比例之差的绝对值从大到小
+    pub biphones: Vec<Deviation>, // 文本中出现过的音素对，按比例之差的绝对值从大到小
+    pub divergence: f64,          // 音素分布的总变差距离，0 ~ 1，0 表示分布相同
+}
+
+/// 统计文本中的音素频率
+pub fn phoneme_profile(g2p: &RustG2P, text: &str) -> Result<PhonemeProfile> {
+    let mut profile = PhonemeProfile::default();
+    for word in g2p.text_to_words(text)? {
+        profile.add(&word.phonemes);
+    }
+    Ok(profile)
+}
+
+impl PhonemeProfile {
+    /// 按词典统计，每个读音（含变体）计一次
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        let mut profile = Self::default();
+        for codes in dictionary.pronunciation_codes() {
+            profile.add_symbols(codes.iter().map(|code| code.symbol()).collect());
+        }
+        profile
+    }
+
+    /// 加入一个单词的音素
+    pub fn add(&mut self, phonemes: &[Phoneme]) {
+        self.add_symbols(phonemes.iter().filter_map(PhonemeCode::encode).map(PhonemeCode::symbol).collect());
+    }
+
+    fn add_symbols(&mut self, symbols: Vec<&'static str>) {
+        for &symbol in &symbols {
+            *self.phonemes.entry(symbol).or_default() += 1;
+        }
+        for pair in symbols.windows(2) {
+            *self.biphones.entry((pair[0], pair[1])).or_default() += 1;
+        }
+        self.total_phonemes += symbols.len() as u64;
+        self.total_biphones += symbols.len().saturating_sub(1) as u64;
+    }
+
+    /// 音素（ARPAbet符号，不带重音）所占比例
+    pub fn frequency(&self, symbol: &str) -> f64 {
+        ratio(self.phonemes.get(symbol).copied().unwrap_or(0), self.total_phonemes)
+    }
+
+    /// 音素对所占比例
+    pub fn biphone_frequency(&self, first: &str, second: &str) -> f64 {
+        let count = self.biphones.iter().find(|((a, b), _)| *a == first && *b == second).map_or(0, |(_, &n)| n);
+        ratio(count, self.total_biphones)
+    }
+
+    /// 出现最多的 `n` 个音素，次数相同时按符号排列
+    pub fn top_phonemes(&self, n: usize) -> Vec<(&'static str, u64)> {
+        let mut top: Vec<(&'static str, u64)> = self.phonemes.iter().map(|(&symbol, &count)| (symbol, count)).collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        top.truncate(n);
+        top
+    }
+
+    /// 出现最多的 `n` 个音素对
+    pub fn top_biphones(&self, n: usize) -> Vec<((&'static str, &'static str), u64)> {
+        let mut top: Vec<_> = self.biphones.iter().map(|(&pair, &count)| (pair, count)).collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(n);
+        top
+    }
+
+    /// 与基准分布比较
+    pub fn compare(&self, baseline: &PhonemeProfile) -> ProfileComparison {
+        let symbols: HashSet<&'static str> = self.phonemes.keys().chain(baseline.phonemes.keys()).copied().collect();
+        let mut phonemes: Vec<Deviation> = symbols
+            .into_iter()
+            .map(|symbol| Deviation {
+                symbols: symbol.to_string(),
+                observed: self.frequency(symbol),
+                expected: baseline.frequency(symbol),
+            })
+            .collect();
+        let divergence = phonemes.iter().map(|d| d.difference().abs()).sum::<f64>() / 2.0;
+
+        let mut biphones: Vec<Deviation> = self
+            .biphones
+            .iter()
+            .map(|(&(a, b), &count)| Deviation {
+                symbols: format!("{} {}", a, b),
+                observed: ratio(count, self.total_biphones),
+                expected: ratio(baseline.biphones.get(&(a, b)).copied().unwrap_or(0), baseline.total_biphones),
+            })
+            .collect();
+        for deviations in [&mut phonemes, &mut biphones] {
+            deviations.sort_by(|a, b| {
+                b.difference().abs().total_cmp(&a.difference().abs()).then_with(|| a.symbols.cmp(&b.symbols))
+            });
+        }
+        ProfileComparison { phonemes, biphones, divergence }
+    }
+}
+
+impl Deviation {
+    /// 文本比例减去基准比例
+    pub fn difference(&self) -> f64 {
+        self.observed - self.expected
+    }
+
+    /// 文本比例与基准比例之比的自然对数，正数表示比基准多；一侧为0时为正负无穷
+    pub fn log_ratio(&self) -> f64 {
+        (self.observed / self.expected).ln()
+    }
+}
+
+impl fmt::Display for PhonemeProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} phonemes, {} biphones", self.total_phonemes, self.total_biphones)?;
+        for (symbol, count) in self.top_phonemes(10) {
+            write!(f, "\n  {:<3} {:>6} {:>5.1}%", symbol, count, ratio(count, self.total_phonemes) * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Deviation {
+    /// 形如 `SH 4.2% (baseline 1.1%)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:.1}% (baseline {:.1}%)", self.symbols, self.observed * 100.0, self.expected * 100.0)
+    }
+}
+
+impl RustG2P {
+    /// 按本转换器的词典统计的音素频率，作为 `PhonemeProfile::compare` 的英语基准
+    pub fn lexicon_profile(&self) -> PhonemeProfile {
+        PhonemeProfile::from_dictionary(&self.dictionary)
+    }
+}
+
+fn ratio(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
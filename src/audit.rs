@@ -0,0 +1,166 @@
+//! 词典与规则的一致性检查
+//!
+//! 用规则引擎转换词典中的每个单词，与词典读音（取最接近的一个变体）做忽略重音的音素对齐。
+//! 差异很大的词条通常是词典错误（拼写或读音录错、缩写、外来词），按差异从大到小列出供人工检查；
+//! 所有词条中反复出现的替换、插入和删除汇总为差异模式，出现次数多的模式提示规则集缺少的规则：
+//!
+//! ```text
+//! 124515 words audited, 7999 flagged (6.4%)
+//!   [Z→S]    20417
+//!   [AH→AE]  18294
+//! ```
+
+use crate::dict::Dictionary;
+use crate::eval::{self, DiffOp};
+use crate::phoneme::Phoneme;
+use crate::rules::RulesEngine;
+use crate::RustG2P;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+/// 标记词条的阈值，两个条件都满足才标记
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AuditThresholds {
+    pub min_distance: usize, // 音素编辑距离
+    pub min_ratio: f64,      // 编辑距离与较长读音音素数之比，0 ~ 1
+}
+
+/// 一个与规则预测差异很大的词条
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub word: String,
+    pub dictionary: Vec<Phoneme>, // 与规则预测最接近的词典读音
+    pub predicted: Vec<Phoneme>,  // 规则引擎的结果，规则出错时为空
+    pub distance: usize,
+    pub ratio: f64,
+}
+
+/// 一致性检查的结果
+#[derive(Debug, Clone, Default)]
+pub struct AuditReport {
+    pub total: usize,
+    pub flagged: Vec<AuditEntry>,        // 按差异比例、编辑距离从大到小，再按单词排列
+    pub patterns: Vec<(DiffOp, usize)>, // 所有词条中的差异（词典→规则）及出现次数，从多到少
+}
+
+impl Default for AuditThresholds {
+    fn default() -> Self {
+        Self { min_distance: 3, min_ratio: 0.6 }
+    }
+}
+
+/// 检查词典中的所有单词
+pub fn audit_dictionary(dictionary: &Dictionary, rules: &RulesEngine, thresholds: AuditThresholds) -> AuditReport {
+    let mut report = AuditReport::default();
+    let mut patterns: HashMap<DiffOp, usize> = HashMap::new();
+
+    for word in dictionary.words() {
+        report.total += 1;
+        let predicted = rules.apply_rules(&word).unwrap_or_default();
+        let predicted_symbols = symbols(&predicted);
+        let Some((distance, reading)) = dictionary
+            .lookup_all(&word)
+            .into_iter()
+            .map(|reading| (eval::edit_distance(&symbols(&reading), &predicted_symbols), reading))
+            .min_by_key(|(distance, _)| *distance)
+        else {
+            continue;
+        };
+        if distance == 0 {
+            continue;
+        }
+
+        for op in eval::align(&symbols(&reading), &predicted_symbols) {
+            if !matches!(op, DiffOp::Same(_)) {
+                *patterns.entry(op).or_default() += 1;
+            }
+        }
+        let ratio = distance as f64 / reading.len().max(predicted.len()) as f64;
+        if distance >= thresholds.min_distance && ratio >= thresholds.min_ratio {
+            report.flagged.push(AuditEntry {
+                word,
+                dictionary: reading,
+                predicted,
+                distance,
+                ratio,
+            });
+        }
+    }
+
+    report.flagged.sort_by(|a, b| {
+        b.ratio
+            .total_cmp(&a.ratio)
+            .then_with(|| b.distance.cmp(&a.distance))
+            .then_with(|| a.word.cmp(&b.word))
+    });
+    report.patterns = patterns.into_iter().collect();
+    report.patterns.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    report
+}
+
+impl AuditReport {
+    /// 被标记词条所占比例
+    pub fn flagged_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.flagged.len() as f64 / self.total as f64
+        }
+    }
+
+    /// 渲染完整的文本报告：摘要、前 `top_patterns` 个差异模式和所有被标记的词条
+    pub fn render(&self, top_patterns: usize) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", self);
+        for (op, count) in self.patterns.iter().take(top_patterns) {
+            let _ = writeln!(out, "  {:<8} {}", op.to_string(), count);
+        }
+        for entry in &self.flagged {
+            out.push_str(&entry.render());
+        }
+        out
+    }
+}
+
+impl AuditEntry {
+    /// 词典读音到规则预测的对齐差异
+    pub fn diff(&self) -> Vec<DiffOp> {
+        eval::align(&symbols(&self.dictionary), &symbols(&self.predicted))
+    }
+
+    /// 渲染单个词条，如 `colonel  K ER N AH L → K AA L AH N EH L`
+    pub fn render(&self) -> String {
+        let ops: Vec<String> = self.diff().iter().map(DiffOp::to_string).collect();
+        format!(
+            "{}  {} → {}\n  diff: {}\n",
+            self.word,
+            symbols(&self.dictionary).join(" "),
+            symbols(&self.predicted).join(" "),
+            ops.join(" ")
+        )
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} words audited, {} flagged ({:.1}%)",
+            self.total,
+            self.flagged.len(),
+            self.flagged_rate() * 100.0
+        )
+    }
+}
+
+impl RustG2P {
+    /// 用本转换器的规则引擎检查其词典
+    pub fn audit_dictionary(&self, thresholds: AuditThresholds) -> AuditReport {
+        audit_dictionary(&self.dictionary, &self.rules_engine, thresholds)
+    }
+}
+
+/// 不带重音的音素符号
+fn symbols(phonemes: &[Phoneme]) -> Vec<String> {
+    phonemes.iter().map(|p| p.symbol.clone()).collect()
+}
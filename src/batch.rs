@@ -0,0 +1,57 @@
+use crate::phoneme::PhonemeCode;
+use crate::RustG2P;
+use crate::error::Result;
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+
+/// 批量转换会话
+///
+/// 会话持有一个bump内存池，每篇文档的符号切片和音素表之外的符号都从池中分配，
+/// 处理下一篇文档时整体重置，避免处理大型语料时产生大量小块分配。
+pub struct BatchSession<'g> {
+    g2p: &'g RustG2P,
+    arena: Bump,
+}
+
+impl<'g> BatchSession<'g> {
+    pub fn new(g2p: &'g RustG2P) -> Self {
+        Self {
+            g2p,
+            arena: Bump::new(),
+        }
+    }
+    
+    /// 转换一篇文档，返回音素符号
+    ///
+    /// 转换过程与 `text_to_phonemes` 相同（标准化、音素输入、连读、节奏规则、音素改写等都按转换器的配置），
+    /// 音素符号与 `text_to_phone_string` 一致，重音按 `with_phone_stress` 处理；
+    /// 边界标记按 `with_boundary_symbols` 输出，写法为空字符串的标记省略；事件音素和引号标记原样输出。
+    /// 返回的切片借用会话的内存池，在下一次调用时失效。
+    pub fn convert_document(&mut self, text: &str) -> Result<&[&str]> {
+        self.arena.reset();
+        let arena = &self.arena;
+        let g2p = self.g2p;
+        
+        let phonemes = g2p.text_to_phonemes(text)?;
+        let mut symbols = BumpVec::with_capacity_in(phonemes.len(), arena);
+        for phoneme in &phonemes {
+            // 音素表中的符号和边界写法无需分配
+            if let Some(symbol) = g2p.boundary_symbols.of(phoneme) {
+                if !symbol.is_empty() {
+                    symbols.push(symbol);
+                }
+            } else if let Some(code) = PhonemeCode::encode(phoneme) {
+                symbols.push(code.phone_symbol(g2p.phone_stress));
+            } else {
+                symbols.push(arena.alloc_str(&phoneme.symbol));
+            }
+        }
+        
+        Ok(symbols.into_bump_slice())
+    }
+    
+    /// 当前内存池已分配的字节数
+    pub fn allocated_bytes(&self) -> usize {
+        self.arena.allocated_bytes()
+    }
+}
@@ -0,0 +1,164 @@
+//! 吞吐量基准测试
+//!
+//! 在词典单词为主和未登录词为主的两类文本上测量每秒单词数和音素数，同一配置可以用于不同的转换器
+//! （词典、规则、缓存设置不同），比较结果。
+
+use crate::RustG2P;
+use crate::error::Result;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// 词典单词为主的文本
+const DICTIONARY_TEXT: &str = "The quick brown fox jumps over the lazy dog. \
+    She said that the people in the house would come back after dinner, \
+    and we thought about what to do with the time that was left before the train.";
+
+/// 未登录词为主的文本，单词都不在CMU词典中
+const OOV_TEXT: &str = "The blorfing snazzletrix grumphed past the quibbery flandows, \
+    zorbifying every plinkster and wobblecrank it glimped along the drevish snorkways.";
+
+/// 一段测试文本
+#[derive(Debug, Clone)]
+pub struct Workload {
+    pub name: String,
+    pub text: String,
+}
+
+/// 基准测试设置
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    pub workloads: Vec<Workload>,
+    pub iterations: u32,
+    pub warmup: u32,
+    pub min_words_per_sec: Option<f64>, // 吞吐量目标，低于目标的文本在报告中标出
+}
+
+/// 一段文本的测量结果
+#[derive(Debug, Clone)]
+pub struct WorkloadResult {
+    pub name: String,
+    pub words: usize,     // 每次转换的单词数
+    pub oov_words: usize, // 其中词典未收录的单词数
+    pub phonemes: usize,  // 每次转换的音素数，不含词边界
+    pub elapsed: Duration,
+    pub iterations: u32,
+    pub target: Option<f64>,
+}
+
+/// 基准测试报告
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub results: Vec<WorkloadResult>,
+}
+
+impl Workload {
+    pub fn new(name: &str, text: &str) -> Self {
+        Self { name: name.to_string(), text: text.to_string() }
+    }
+
+    /// 词典单词为主的内置文本
+    pub fn dictionary_heavy() -> Self {
+        Self::new("dictionary", DICTIONARY_TEXT)
+    }
+
+    /// 未登录词为主的内置文本，主要测量规则引擎
+    pub fn oov_heavy() -> Self {
+        Self::new("oov", OOV_TEXT)
+    }
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            workloads: vec![Workload::dictionary_heavy(), Workload::oov_heavy()],
+            iterations: 200,
+            warmup: 10,
+            min_words_per_sec: None,
+        }
+    }
+}
+
+/// 在每段文本上预热后重复转换，测量吞吐量
+pub fn run(g2p: &RustG2P, config: &BenchConfig) -> Result<BenchReport> {
+    let mut results = Vec::with_capacity(config.workloads.len());
+    for workload in &config.workloads {
+        let words = g2p.text_to_words(&workload.text)?;
+        for _ in 0..config.warmup {
+            g2p.text_to_words(&workload.text)?;
+        }
+
+        let start = Instant::now();
+        for _ in 0..config.iterations {
+            g2p.text_to_words(&workload.text)?;
+        }
+        results.push(WorkloadResult {
+            name: workload.name.clone(),
+            words: words.len(),
+            oov_words: words.iter().filter(|word| word.oov).count(),
+            phonemes: words.iter().map(|word| word.phonemes.len()).sum(),
+            elapsed: start.elapsed(),
+            iterations: config.iterations,
+            target: config.min_words_per_sec,
+        });
+    }
+    Ok(BenchReport { results })
+}
+
+impl WorkloadResult {
+    /// 每秒转换的单词数
+    pub fn words_per_sec(&self) -> f64 {
+        self.rate(self.words)
+    }
+
+    /// 每秒输出的音素数
+    pub fn phonemes_per_sec(&self) -> f64 {
+        self.rate(self.phonemes)
+    }
+
+    /// 是否达到吞吐量目标，没有目标时为true
+    pub fn meets_target(&self) -> bool {
+        self.target.is_none_or(|target| self.words_per_sec() >= target)
+    }
+
+    fn rate(&self, count: usize) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        (count as f64 * self.iterations as f64) / seconds
+    }
+}
+
+impl BenchReport {
+    /// 按名称查找测量结果
+    pub fn get(&self, name: &str) -> Option<&WorkloadResult> {
+        self.results.iter().find(|result| result.name == name)
+    }
+
+    /// 所有文本是否都达到吞吐量目标
+    pub fn meets_targets(&self) -> bool {
+        self.results.iter().all(WorkloadResult::meets_target)
+    }
+
+    /// 渲染文本报告，每段文本一行
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for result in &self.results {
+            let _ = write!(
+                out,
+                "{:<12} {:>4} words ({} oov) {:>12.0} words/s {:>12.0} phonemes/s",
+                result.name,
+                result.words,
+                result.oov_words,
+                result.words_per_sec(),
+                result.phonemes_per_sec()
+            );
+            if let Some(target) = result.target {
+                let verdict = if result.meets_target() { "ok" } else { "below target" };
+                let _ = write!(out, "  [{} {:.0}]", verdict, target);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
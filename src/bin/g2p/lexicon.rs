@@ -0,0 +1,108 @@
+use anyhow::{anyhow, Context, Result};
+use rust_g2p::dict::parse_frequency_list;
+use rust_g2p::phoneme::{self, Notation, Phoneme};
+use rust_g2p::RustG2P;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+
+/// 词典格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// CMU词典：`WORD  W ER1 D`
+    Cmu,
+    /// Montreal Forced Aligner：`word\tW ER1 D`
+    Mfa,
+    /// Kaldi lexicon.txt：`word W ER1 D`
+    Kaldi,
+    /// `word\tIPA`，同 `Dictionary::export_ipa`
+    Ipa,
+}
+
+/// `lexicon` 的参数
+#[derive(Debug, Clone)]
+pub struct Args {
+    pub input: Option<String>,  // 省略或为 `-` 时读stdin
+    pub output: Option<String>, // 省略时写stdout
+    pub format: Format,
+}
+
+/// 逐个转换单词表中的单词（词典和未登录词策略），按指定格式输出词典
+pub fn run(g2p: RustG2P, args: Args) -> Result<()> {
+    let content = match args.input.as_deref() {
+        None | Some("-") => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            content
+        }
+        Some(path) => fs::read_to_string(path).with_context(|| format!("Failed to read word list '{}'", path))?,
+    };
+
+    let mut out: BufWriter<Box<dyn Write>> = BufWriter::new(match args.output.as_deref() {
+        Some(path) => Box::new(fs::File::create(path).with_context(|| format!("Failed to create lexicon '{}'", path))?),
+        None => Box::new(io::stdout().lock()),
+    });
+
+    // 单词表每行第一个字段为单词，重复的单词只输出一次
+    let mut seen = HashSet::new();
+    let mut written = 0;
+    let mut failed = 0;
+    for word in parse_frequency_list(&content) {
+        if !seen.insert(word.clone()) {
+            continue;
+        }
+        match g2p.word_to_phonemes(&word) {
+            Ok(phonemes) if !phonemes.is_empty() => {
+                writeln!(out, "{}", entry(&word, &phonemes, args.format))?;
+                written += 1;
+            }
+            Ok(_) => {
+                eprintln!("Warning: No phonemes for '{}'", word);
+                failed += 1;
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to convert '{}': {:#}", word, e);
+                failed += 1;
+            }
+        }
+    }
+    out.flush()?;
+
+    eprintln!("Wrote {} entries ({} words failed)", written, failed);
+    Ok(())
+}
+
+/// 一行词条
+fn entry(word: &str, phonemes: &[Phoneme], format: Format) -> String {
+    let arpabet = || phoneme::render(phonemes, Notation::Arpabet);
+    match format {
+        Format::Cmu => format!("{}  {}", word.to_uppercase(), arpabet()),
+        Format::Mfa => format!("{}\t{}", word, arpabet()),
+        Format::Kaldi => format!("{} {}", word, arpabet()),
+        Format::Ipa => format!("{}\t{}", word, phoneme::ipa_transcription(phonemes)),
+    }
+}
+
+/// 解析 `lexicon` 的参数
+pub fn parse_args(args: &[String]) -> Result<Args> {
+    let mut parsed = Args { input: None, output: None, format: Format::Cmu };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| anyhow!("{} requires a value", arg));
+        match arg.as_str() {
+            "--input" => parsed.input = Some(value()?),
+            "--output" => parsed.output = Some(value()?),
+            "--format" => {
+                parsed.format = match value()?.as_str() {
+                    "cmu" => Format::Cmu,
+                    "mfa" => Format::Mfa,
+                    "kaldi" => Format::Kaldi,
+                    "ipa" => Format::Ipa,
+                    other => return Err(anyhow!("Unknown format '{}' (expected cmu, mfa, kaldi or ipa)", other)),
+                };
+            }
+            _ => return Err(anyhow!("Unexpected argument '{}' for 'lexicon'", arg)),
+        }
+    }
+    Ok(parsed)
+}
@@ -0,0 +1,130 @@
+//! g2p 命令行工具
+
+mod lexicon;
+mod pipe;
+mod repl;
+mod subtitles;
+#[cfg(feature = "grpc")]
+mod serve;
+
+use anyhow::{anyhow, Result};
+use rust_g2p::RustG2P;
+
+const USAGE: &str = "\
+Usage: g2p [--config FILE] <command>
+
+Options:
+  --config FILE   Load settings from a TOML config file
+  -h, --help      Show this help
+
+Commands:
+  repl            Convert text interactively with line editing
+  pipe            Convert each stdin line to stdout
+                    --format jsonl   One JSON object per line (default)
+                    --format text    Phoneme string per line, words separated by |
+  lexicon         Convert a word list (one word per line) to a pronunciation lexicon
+                    --input FILE     Word list (default stdin)
+                    --output FILE    Lexicon file (default stdout)
+                    --format FORMAT  cmu (default), mfa, kaldi or ipa
+  subtitles       Convert each cue of an SRT or WebVTT file, keeping its timestamps
+                    --input FILE     Subtitle file (default stdin)
+                    --output FILE    Output file (default stdout)
+                    --format FORMAT  subtitle (same format as input, default) or jsonl
+  serve           Start the gRPC Phonemizer server (requires the grpc feature)
+                    --addr ADDR      Listen address (default 127.0.0.1:50051)
+";
+
+/// 把库的日志（加载摘要和数据文件中的问题）输出到stderr，不影响stdout上的转换结果
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            log::Level::Error => eprintln!("Error: {}", record.args()),
+            log::Level::Warn => eprintln!("Warning: {}", record.args()),
+            _ => eprintln!("{}", record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn main() {
+    if log::set_logger(&StderrLogger).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+    if let Err(e) = run() {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut config = None;
+    
+    // 全局选项在子命令之前，子命令之后的参数交给子命令处理
+    let command = loop {
+        match args.next() {
+            Some(arg) if arg == "--config" => {
+                config = Some(args.next().ok_or_else(|| anyhow!("--config requires a file path"))?);
+            }
+            Some(arg) if arg == "-h" || arg == "--help" => {
+                print!("{}", USAGE);
+                return Ok(());
+            }
+            Some(arg) => break arg,
+            None => return Err(anyhow!("No command given\n\n{}", USAGE)),
+        }
+    };
+    let rest: Vec<String> = args.collect();
+    
+    match command.as_str() {
+        "repl" => {
+            no_extra_args(&command, &rest)?;
+            repl::run(load(config.as_deref())?)
+        }
+        "pipe" => {
+            let format = pipe::parse_args(&rest)?;
+            pipe::run(load(config.as_deref())?, format)
+        }
+        "lexicon" => {
+            let args = lexicon::parse_args(&rest)?;
+            lexicon::run(load(config.as_deref())?, args)
+        }
+        "subtitles" => {
+            let args = subtitles::parse_args(&rest)?;
+            subtitles::run(load(config.as_deref())?, args)
+        }
+        #[cfg(feature = "grpc")]
+        "serve" => {
+            let addr = serve::parse_args(&rest)?;
+            serve::run(load(config.as_deref())?, addr)
+        }
+        #[cfg(not(feature = "grpc"))]
+        "serve" => Err(anyhow!("g2p was built without the 'grpc' feature")),
+        _ => Err(anyhow!("Unknown command '{}'\n\n{}", command, USAGE)),
+    }
+}
+
+/// 按配置文件（如果有）创建转换器
+fn load(config: Option<&str>) -> Result<RustG2P> {
+    Ok(match config {
+        Some(path) => RustG2P::from_config(path)?,
+        None => RustG2P::new()?,
+    })
+}
+
+fn no_extra_args(command: &str, args: &[String]) -> Result<()> {
+    match args.first() {
+        Some(arg) => Err(anyhow!("Unexpected argument '{}' for '{}'", arg, command)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Result};
+use rust_g2p::RustG2P;
+use serde_json::json;
+use std::io::{self, BufRead, BufWriter, Write};
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// 每行一个JSON对象
+    Jsonl,
+    /// 每行一个音素串，词间以 `|` 分隔
+    Text,
+}
+
+/// 从stdin逐行读取文本，向stdout逐行输出转换结果
+pub fn run(g2p: RustG2P, format: Format) -> Result<()> {
+    let stdin = io::stdin();
+    let mut out = BufWriter::new(io::stdout().lock());
+    for line in stdin.lock().lines() {
+        let line = line?;
+        match format {
+            Format::Jsonl => writeln!(out, "{}", jsonl_record(&g2p, &line))?,
+            Format::Text => match g2p.text_to_phonemes(&line) {
+                Ok(mut phonemes) => {
+                    if phonemes.last().is_some_and(|p| p.symbol == " ") {
+                        phonemes.pop();
+                    }
+                    writeln!(out, "{}", g2p.render(&phonemes))?;
+                }
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    writeln!(out)?;
+                }
+            },
+        }
+        // 逐行刷新，便于作为管道中的协同进程使用
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// 解析 `pipe` 的参数
+pub fn parse_args(args: &[String]) -> Result<Format> {
+    let mut format = Format::Jsonl;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().map(String::as_str) {
+                    Some("jsonl") => Format::Jsonl,
+                    Some("text") => Format::Text,
+                    Some(other) => return Err(anyhow!("Unknown format '{}' (expected jsonl or text)", other)),
+                    None => return Err(anyhow!("--format requires a value")),
+                };
+            }
+            _ => return Err(anyhow!("Unexpected argument '{}' for 'pipe'", arg)),
+        }
+    }
+    Ok(format)
+}
+
+/// 一行文本的JSON记录；转换失败时记录错误信息而不中断整个管道
+fn jsonl_record(g2p: &RustG2P, text: &str) -> serde_json::Value {
+    match g2p.text_to_words(text) {
+        Ok(words) => json!({
+            "text": text,
+            "words": words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>(),
+            "surfaces": words.iter().map(|w| w.surface.as_str()).collect::<Vec<_>>(),
+            "phonemes": words
+                .iter()
+                .map(|w| w.phonemes.iter().map(|p| p.to_notation(g2p.notation())).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            "oov": words.iter().map(|w| w.oov).collect::<Vec<_>>(),
+            "kinds": words.iter().map(|w| w.kind).collect::<Vec<_>>(),
+        }),
+        Err(e) => json!({
+            "text": text,
+            "error": format!("{:#}", e),
+        }),
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::{anyhow, Result};
+use rust_g2p::RustG2P;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+const HELP: &str = "\
+Enter a word or sentence to convert it.
+  :trace WORD...         Show how each word was converted
+  :silent WORD...        Show silent letters and the rules that silence them
+  :add WORD PH ON EMS    Add a user lexicon entry (ARPAbet, e.g. :add gif JH IH1 F)
+  :help                  Show this help
+  :quit                  Exit (or Ctrl-D)
+";
+
+/// 交互式转换
+pub fn run(mut g2p: RustG2P) -> Result<()> {
+    let mut editor = DefaultEditor::new()?;
+    println!("Type text to convert, :help for commands.");
+    
+    loop {
+        match editor.readline("g2p> ") {
+            Ok(line) => {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let _ = editor.add_history_entry(line);
+                
+                match handle_line(&mut g2p, line) {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(e) => eprintln!("Error: {:#}", e),
+                }
+            }
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    
+    Ok(())
+}
+
+/// 处理一行输入，返回false表示退出
+fn handle_line(g2p: &mut RustG2P, line: &str) -> Result<bool> {
+    let Some(command) = line.strip_prefix(':') else {
+        let mut phonemes = g2p.text_to_phonemes(line)?;
+        if phonemes.last().is_some_and(|p| p.symbol == " ") {
+            phonemes.pop();
+        }
+        println!("{}", g2p.render(&phonemes));
+        return Ok(true);
+    };
+    
+    let (name, args) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+    let args = args.trim();
+    match name {
+        "q" | "quit" | "exit" => return Ok(false),
+        "h" | "help" => print!("{}", HELP),
+        "t" | "trace" => {
+            if args.is_empty() {
+                return Err(anyhow!("Usage: :trace WORD..."));
+            }
+            for word in args.split_whitespace() {
+                println!("{}", g2p.trace_word(word)?);
+            }
+        }
+        "s" | "silent" => {
+            if args.is_empty() {
+                return Err(anyhow!("Usage: :silent WORD..."));
+            }
+            for word in args.split_whitespace() {
+                let silent = g2p.silent_letters(word)?;
+                if silent.is_empty() {
+                    println!("{}: no silent letters", word);
+                }
+                for letter in silent {
+                    println!("{}: {}", word, letter);
+                }
+            }
+        }
+        "add" => {
+            let (word, pronunciation) = args
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| anyhow!("Usage: :add WORD PH ON EMS"))?;
+            g2p.add_word(word, pronunciation)?;
+            println!("Added '{}'", word.to_lowercase());
+        }
+        _ => return Err(anyhow!("Unknown command ':{}' (try :help)", name)),
+    }
+    Ok(true)
+}
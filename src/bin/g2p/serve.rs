@@ -0,0 +1,25 @@
+use anyhow::{anyhow, Context, Result};
+use rust_g2p::RustG2P;
+use std::net::SocketAddr;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:50051";
+
+/// 启动gRPC服务器，直到进程被终止
+pub fn run(g2p: RustG2P, addr: SocketAddr) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    eprintln!("Listening on {}", addr);
+    Ok(runtime.block_on(rust_g2p::grpc::serve(g2p, addr))?)
+}
+
+/// 解析 `serve` 的参数
+pub fn parse_args(args: &[String]) -> Result<SocketAddr> {
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => addr = args.next().ok_or_else(|| anyhow!("--addr requires a value"))?.clone(),
+            _ => return Err(anyhow!("Unexpected argument '{}' for 'serve'", arg)),
+        }
+    }
+    addr.parse().with_context(|| format!("Invalid listen address '{}'", addr))
+}
@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Context, Result};
+use rust_g2p::RustG2P;
+use serde_json::json;
+use std::fs;
+use std::io::{self, Read, Write};
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// 与输入相同的字幕格式，文本换成音素
+    Subtitle,
+    /// 每条字幕一个JSON对象
+    Jsonl,
+}
+
+/// `subtitles` 的参数
+#[derive(Debug, Clone)]
+pub struct Args {
+    pub input: Option<String>,  // 省略或为 `-` 时读stdin
+    pub output: Option<String>, // 省略时写stdout
+    pub format: Format,
+}
+
+/// 转换SRT或WebVTT文件中的每条字幕，保留时间戳
+pub fn run(g2p: RustG2P, args: Args) -> Result<()> {
+    let content = match args.input.as_deref() {
+        None | Some("-") => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            content
+        }
+        Some(path) => fs::read_to_string(path).with_context(|| format!("Failed to read subtitles '{}'", path))?,
+    };
+
+    let output = match args.format {
+        Format::Subtitle => g2p.phonemize_subtitle_file(&content)?,
+        Format::Jsonl => {
+            let mut out = String::new();
+            for result in g2p.phonemize_subtitles(&content)? {
+                let record = json!({
+                    "id": result.cue.id,
+                    "start_ms": result.cue.start.as_millis() as u64,
+                    "end_ms": result.cue.end.as_millis() as u64,
+                    "text": result.cue.text,
+                    "phonemes": result.phonemes.iter().map(|p| p.to_notation(g2p.notation())).collect::<Vec<_>>(),
+                });
+                out.push_str(&format!("{}\n", record));
+            }
+            out
+        }
+    };
+
+    match args.output.as_deref() {
+        Some(path) => fs::write(path, output).with_context(|| format!("Failed to write '{}'", path))?,
+        None => io::stdout().lock().write_all(output.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// 解析 `subtitles` 的参数
+pub fn parse_args(args: &[String]) -> Result<Args> {
+    let mut parsed = Args { input: None, output: None, format: Format::Subtitle };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| anyhow!("{} requires a value", arg));
+        match arg.as_str() {
+            "--input" => parsed.input = Some(value()?),
+            "--output" => parsed.output = Some(value()?),
+            "--format" => {
+                parsed.format = match value()?.as_str() {
+                    "subtitle" => Format::Subtitle,
+                    "jsonl" => Format::Jsonl,
+                    other => return Err(anyhow!("Unknown format '{}' (expected subtitle or jsonl)", other)),
+                };
+            }
+            _ => return Err(anyhow!("Unexpected argument '{}' for 'subtitles'", arg)),
+        }
+    }
+    Ok(parsed)
+}
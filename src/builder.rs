@@ -0,0 +1,266 @@
+//! 逐项设置的转换器构建器
+//!
+//! `RustG2P::new()` 固定使用 `data/` 下的数据文件。构建器可以改用应用自己的数据文件并调整处理流程，
+//! 未设置的项与 `new()` 相同：
+//!
+//! ```text
+//! RustG2P::builder()
+//!     .dict_path("assets/lexicon.txt")
+//!     .rules_path("assets/rules.txt")
+//!     .word_boundaries(false)
+//!     .build()?
+//! ```
+//!
+//! 数据文件和配置文件能表示的选项记在 `G2PConfig` 中，由 `build` 统一加载，
+//! 因此也可以先读取配置文件（`from_config`），再覆盖其中的个别项。
+
+use crate::config::G2PConfig;
+use crate::dict::DuplicatePolicy;
+use crate::phoneme::{BoundarySymbols, Notation, StressMarks};
+use crate::numbers::NumberWords;
+use crate::report::LoadProgress;
+use crate::rules::RuleFormat;
+use crate::text::NormalizeOptions;
+use crate::{LongWordPolicy, Observer, RoutingConfig, RustG2P};
+use crate::error::Result;
+use std::sync::Arc;
+
+/// 加载进度回调
+type ProgressCallback = Arc<dyn Fn(&LoadProgress) + Send + Sync>;
+
+/// `RustG2P` 的构建器
+#[derive(Clone)]
+pub struct G2PBuilder {
+    config: G2PConfig,
+    word_boundaries: bool,
+    phoneme_input: bool,
+    quote_markers: bool,
+    observers: Vec<Arc<dyn Observer>>,
+    progress: Option<ProgressCallback>,
+    number_words: Option<Arc<dyn NumberWords>>,
+}
+
+impl Default for G2PBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl G2PBuilder {
+    /// 默认设置的构建器，同 `RustG2P::builder()`
+    pub fn new() -> Self {
+        Self::from_config(G2PConfig::default())
+    }
+
+    /// 以已解析的配置为起点
+    pub fn from_config(config: G2PConfig) -> Self {
+        Self {
+            config,
+            word_boundaries: true,
+            phoneme_input: false,
+            quote_markers: false,
+            observers: Vec::new(),
+            number_words: None,
+            progress: None,
+        }
+    }
+
+    /// CMU格式的词典文件
+    pub fn dict_path(mut self, path: impl Into<String>) -> Self {
+        self.config.data.dictionary = path.into();
+        self
+    }
+
+    /// 规则文件
+    pub fn rules_path(mut self, path: impl Into<String>) -> Self {
+        self.config.data.rules = path.into();
+        self
+    }
+
+    /// 规则文件的格式，默认为竖线分隔格式
+    pub fn rules_format(mut self, format: RuleFormat) -> Self {
+        self.config.data.rules_format = format;
+        self
+    }
+
+    /// 不规则词汇文件，空字符串表示不使用
+    pub fn irregular_path(mut self, path: impl Into<String>) -> Self {
+        self.config.data.irregular = path.into();
+        self
+    }
+
+    /// Hunspell 词缀词典（`base.aff` 和 `base.dic`），用于词干还原
+    pub fn hunspell(mut self, base: impl Into<String>) -> Self {
+        self.config.data.hunspell = Some(base.into());
+        self
+    }
+
+    /// 添加一个音素改写规则文件，按添加顺序应用
+    pub fn rewrite_path(mut self, path: impl Into<String>) -> Self {
+        self.config.data.rewrite.push(path.into());
+        self
+    }
+
+    /// 词典中重复单词的处理方式
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.config.data.duplicates = policy;
+        self
+    }
+
+    /// 词典使用压缩存储，见 `Dictionary::into_compact`
+    pub fn compact_dictionary(mut self, enabled: bool) -> Self {
+        self.config.data.compact_dictionary = enabled;
+        self
+    }
+
+    /// 未登录词的磁盘缓存文件
+    pub fn cache_path(mut self, path: impl Into<String>) -> Self {
+        self.config.cache.path = Some(path.into());
+        self
+    }
+
+    /// 规则结果缓存的容量，见 `RustG2P::with_rule_cache`
+    pub fn rule_cache_size(mut self, capacity: usize) -> Self {
+        self.config.cache.rule_cache_size = capacity;
+        self
+    }
+
+    /// 文本标准化步骤开关
+    pub fn normalization(mut self, options: NormalizeOptions) -> Self {
+        self.config.normalization = options.into();
+        self
+    }
+
+    /// `render` 使用的音素记法
+    pub fn notation(mut self, notation: Notation) -> Self {
+        self.config.output.notation = notation;
+        self
+    }
+
+    /// 音素串中重音的处理方式，见 `RustG2P::with_phone_stress`
+    pub fn phone_stress(mut self, stress: StressMarks) -> Self {
+        self.config.output.phone_stress = stress;
+        self
+    }
+
+    /// 整段输出中是否在单词之间插入词边界，见 `RustG2P::with_word_boundaries`
+    pub fn word_boundaries(mut self, enabled: bool) -> Self {
+        self.word_boundaries = enabled;
+        self
+    }
+
+    /// 停顿和句子边界标记，见 `RustG2P::with_pause_markers`
+    pub fn pause_markers(mut self, enabled: bool) -> Self {
+        self.config.output.pause_markers = enabled;
+        self
+    }
+
+    /// 边界标记的输出写法，见 `RustG2P::with_boundary_symbols`
+    pub fn boundary_symbols(mut self, symbols: BoundarySymbols) -> Self {
+        self.config.output.boundaries = symbols;
+        self
+    }
+
+    /// 连接R和插入R，见 `RustG2P::with_linking_r`
+    pub fn linking_r(mut self, enabled: bool) -> Self {
+        self.config.output.linking_r = enabled;
+        self
+    }
+
+    /// 节奏规则，见 `RustG2P::with_rhythm_rule`
+    pub fn rhythm_rule(mut self, enabled: bool) -> Self {
+        self.config.output.rhythm_rule = enabled;
+        self
+    }
+
+    /// 严格模式，见 `RustG2P::with_strict`
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.config.oov.strict = strict;
+        self
+    }
+
+    /// 规则输出的置信度路由
+    pub fn routing(mut self, routing: RoutingConfig) -> Self {
+        self.config.oov.min_confidence = routing.min_confidence;
+        self.config.oov.require_valid_phonotactics = routing.require_valid_phonotactics;
+        self.config.oov.fallback = routing.fallback;
+        self
+    }
+
+    /// 未登录词的最大长度，见 `RustG2P::with_max_word_length`
+    pub fn max_word_length(mut self, limit: usize, policy: LongWordPolicy) -> Self {
+        self.config.oov.max_word_length = Some(limit);
+        self.config.oov.long_words = policy;
+        self
+    }
+
+    /// 未登录的派生词是否先还原词干
+    pub fn stem_recovery(mut self, enabled: bool) -> Self {
+        self.config.oov.stem_recovery = enabled;
+        self
+    }
+
+    /// 希腊字母、西里尔字母的单词是否转写后按英语读
+    pub fn transliteration(mut self, enabled: bool) -> Self {
+        self.config.oov.transliterate = enabled;
+        self
+    }
+
+    /// 规则读音是否指派主重音
+    pub fn stress_assignment(mut self, enabled: bool) -> Self {
+        self.config.oov.assign_stress = enabled;
+        self
+    }
+
+    /// 音素输入模式，见 `RustG2P::with_phoneme_input`
+    pub fn phoneme_input(mut self, enabled: bool) -> Self {
+        self.phoneme_input = enabled;
+        self
+    }
+
+    /// 引号边界标记，见 `RustG2P::with_quote_markers`
+    pub fn quote_markers(mut self, enabled: bool) -> Self {
+        self.quote_markers = enabled;
+        self
+    }
+
+    /// 数字的读法，见 `NumberWords`；默认为英语
+    pub fn number_words(mut self, number_words: Arc<dyn NumberWords>) -> Self {
+        self.number_words = Some(number_words);
+        self
+    }
+
+    /// 注册转换事件的观察者，可注册多个
+    pub fn observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// `build` 加载词典和规则文件时的进度回调，见 `LoadProgress`
+    pub fn on_progress(mut self, progress: impl Fn(&LoadProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// 当前的配置，其中的路径和选项由 `build` 加载
+    pub fn config(&self) -> &G2PConfig {
+        &self.config
+    }
+
+    /// 加载数据文件并创建转换器
+    pub fn build(self) -> Result<RustG2P> {
+        let g2p = match &self.progress {
+            Some(progress) => self.config.build_with_progress(progress.as_ref())?,
+            None => self.config.build()?,
+        };
+        let g2p = g2p
+            .with_word_boundaries(self.word_boundaries)
+            .with_phoneme_input(self.phoneme_input)
+            .with_quote_markers(self.quote_markers);
+        let g2p = match self.number_words {
+            Some(number_words) => g2p.with_number_words(number_words),
+            None => g2p,
+        };
+        Ok(self.observers.into_iter().fold(g2p, RustG2P::with_observer))
+    }
+}
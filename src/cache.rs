@@ -0,0 +1,180 @@
+use crate::memory;
+use crate::phoneme::{Phoneme, PhonemeSeq};
+use crate::error::{Context, Result};
+use lru::LruCache;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::mem::size_of;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+/// 规则结果缓存的默认容量（单词数）
+pub const DEFAULT_RULE_CACHE_SIZE: usize = 1024;
+
+/// 持久化的单词转换缓存
+///
+/// 以追加方式写入文本文件，每行为 `单词<TAB>音素 音素 ...`。打开已有文件时载入其中的
+/// 全部结果，因此可以用上一次运行的结果预热。缓存内容与生成它的配置相关，
+/// 更换规则或路由配置后应使用新的缓存文件。
+pub struct DiskCache {
+    path: String,
+    state: Mutex<CacheState>,
+}
+
+struct CacheState {
+    entries: HashMap<String, Vec<Phoneme>>,
+    writer: BufWriter<File>,
+}
+
+impl DiskCache {
+    /// 打开（或创建）缓存文件并载入已有结果
+    pub fn open(path: &str) -> Result<Self> {
+        let mut entries = HashMap::new();
+        if std::path::Path::new(path).exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read cache file: {}", path))?;
+            // 格式不完整的行（如中断时写了一半的最后一行）直接忽略
+            for line in content.lines() {
+                if let Some((word, phonemes)) = line.split_once('\t') {
+                    if !word.is_empty() && !phonemes.is_empty() {
+                        let phonemes = phonemes.split(' ').map(Phoneme::from_arpabet).collect();
+                        entries.insert(word.to_string(), phonemes);
+                    }
+                }
+            }
+        }
+        
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open cache file: {}", path))?;
+        
+        Ok(Self {
+            path: path.to_string(),
+            state: Mutex::new(CacheState {
+                entries,
+                writer: BufWriter::new(file),
+            }),
+        })
+    }
+    
+    /// 查询缓存的转换结果
+    pub fn get(&self, word: &str) -> Option<Vec<Phoneme>> {
+        self.lock().entries.get(word).cloned()
+    }
+    
+    /// 同 `get`，结果放在栈上，供转换过程使用
+    pub(crate) fn get_seq(&self, word: &str) -> Option<PhonemeSeq> {
+        self.lock().entries.get(word).map(|phonemes| phonemes.iter().cloned().collect())
+    }
+    
+    /// 记录一个转换结果并追加到文件
+    pub fn insert(&self, word: &str, phonemes: &[Phoneme]) -> Result<()> {
+        // 含分隔符的单词或空结果无法按行格式保存
+        if word.is_empty() || word.contains(['\t', '\n']) || phonemes.is_empty() {
+            return Ok(());
+        }
+        
+        let mut state = self.lock();
+        if state.entries.contains_key(word) {
+            return Ok(());
+        }
+        
+        let symbols: Vec<String> = phonemes.iter().map(|p| p.to_string()).collect();
+        writeln!(state.writer, "{}\t{}", word, symbols.join(" "))
+            .with_context(|| format!("Failed to write cache file: {}", self.path))?;
+        state.entries.insert(word.to_string(), phonemes.to_vec());
+        Ok(())
+    }
+    
+    /// 将缓冲的结果写入磁盘
+    pub fn flush(&self) -> Result<()> {
+        self.lock()
+            .writer
+            .flush()
+            .with_context(|| format!("Failed to write cache file: {}", self.path))
+    }
+    
+    /// 缓存的单词数量
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// 估算缓存的堆内存占用（字节）
+    pub fn heap_size(&self) -> usize {
+        let state = self.lock();
+        memory::map_heap(&state.entries)
+            + state.writer.capacity()
+            + state.entries
+                .iter()
+                .map(|(word, phonemes)| word.capacity() + memory::phonemes_heap(phonemes))
+                .sum::<usize>()
+    }
+    
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // 缓存只是加速手段，锁中毒时继续使用已有数据
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for DiskCache {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// 规则转换结果的内存缓存
+///
+/// 真实语料中的人名、拼写错误等未登录词会反复出现。缓存容量固定，满了以后淘汰最久未使用的单词，
+/// 命中时不再经过规则引擎和后备策略。
+pub(crate) struct RuleCache {
+    entries: Mutex<LruCache<String, PhonemeSeq>>,
+}
+
+impl RuleCache {
+    /// 最多缓存 `capacity` 个单词，为0时返回None
+    pub(crate) fn new(capacity: usize) -> Option<Self> {
+        let capacity = NonZeroUsize::new(capacity)?;
+        Some(Self { entries: Mutex::new(LruCache::new(capacity)) })
+    }
+
+    /// 查询并标记为最近使用
+    pub(crate) fn get(&self, word: &str) -> Option<PhonemeSeq> {
+        self.lock().get(word).cloned()
+    }
+
+    pub(crate) fn insert(&self, word: &str, phonemes: &PhonemeSeq) {
+        self.lock().put(word.to_string(), phonemes.clone());
+    }
+
+    /// 清空缓存，影响规则输出的设置改变时调用
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// 估算堆内存占用（字节），每个单词另有链表节点和哈希表槽位
+    pub(crate) fn heap_size(&self) -> usize {
+        let entries = self.lock();
+        let node = size_of::<String>() + size_of::<PhonemeSeq>() + 2 * size_of::<usize>();
+        entries.cap().get() * (size_of::<usize>() + 1)
+            + entries
+                .iter()
+                .map(|(word, phonemes)| {
+                    // 超过内联容量的序列另有堆上的缓冲区
+                    let spilled = if phonemes.spilled() { size_of::<Phoneme>() * phonemes.capacity() } else { 0 };
+                    node + word.capacity() + spilled + phonemes.iter().map(|p| p.symbol.capacity()).sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<String, PhonemeSeq>> {
+        // 同 `DiskCache`，锁中毒时继续使用已有数据
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,93 @@
+//! 发音比对：把学习者的发音与标准发音逐音素对齐，给出增删替换和重音错误，供发音训练反馈
+
+use crate::align::{self, AlignCosts, AlignStep};
+use crate::phoneme::{Notation, Phoneme};
+use std::fmt;
+
+/// 对齐后一个位置的差异类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    Match,      // 音素相同（重音可能不同）
+    Substitute, // 读成了别的音素
+    Delete,     // 漏读
+    Insert,     // 多读
+}
+
+/// 对齐后的一个位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhonemeEdit {
+    pub kind: EditKind,
+    pub expected: Option<Phoneme>, // 多读时为 None
+    pub observed: Option<Phoneme>, // 漏读时为 None
+    pub stress_mismatch: bool,     // 两边都是元音且重音不同
+}
+
+/// 发音比对报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffReport {
+    pub edits: Vec<PhonemeEdit>,
+}
+
+/// 比对标准发音和实际发音
+///
+/// 按音素对齐（忽略重音，替换代价按发音特征差异计算），再在对齐的元音上检查重音是否一致。
+pub fn compare_pronunciations(expected: &[Phoneme], observed: &[Phoneme]) -> DiffReport {
+    compare_pronunciations_with(expected, observed, &AlignCosts::features())
+}
+
+/// 按给定的对齐代价比对发音
+pub fn compare_pronunciations_with(expected: &[Phoneme], observed: &[Phoneme], costs: &AlignCosts) -> DiffReport {
+    let mut edits = Vec::with_capacity(expected.len().max(observed.len()));
+    for step in align::align(expected, observed, costs).steps {
+        let (kind, a, b) = match step {
+            AlignStep::Match(i, j) => (EditKind::Match, Some(&expected[i]), Some(&observed[j])),
+            AlignStep::Substitute(i, j) => (EditKind::Substitute, Some(&expected[i]), Some(&observed[j])),
+            AlignStep::Delete(i) => (EditKind::Delete, Some(&expected[i]), None),
+            AlignStep::Insert(j) => (EditKind::Insert, None, Some(&observed[j])),
+        };
+        let stress_mismatch = match (a, b) {
+            (Some(a), Some(b)) => a.is_vowel() && b.is_vowel() && a.stress != b.stress,
+            _ => false,
+        };
+        edits.push(PhonemeEdit { kind, expected: a.cloned(), observed: b.cloned(), stress_mismatch });
+    }
+    DiffReport { edits }
+}
+
+impl DiffReport {
+    /// 发音完全正确（包括重音）
+    pub fn is_correct(&self) -> bool {
+        self.edits.iter().all(|edit| edit.kind == EditKind::Match && !edit.stress_mismatch)
+    }
+
+    /// 增删替换的次数，不计重音
+    pub fn phoneme_errors(&self) -> usize {
+        self.edits.iter().filter(|edit| edit.kind != EditKind::Match).count()
+    }
+
+    /// 重音不一致的元音数
+    pub fn stress_errors(&self) -> usize {
+        self.edits.iter().filter(|edit| edit.stress_mismatch).count()
+    }
+}
+
+impl fmt::Display for DiffReport {
+    /// 形如 `HH [AH0→EH1] L [OW0≠OW1] [-T] [+S]`，方括号中为错误
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arpabet = |p: &Option<Phoneme>| p.as_ref().map_or(String::new(), |p| p.to_notation(Notation::Arpabet));
+        for (i, edit) in self.edits.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            let (a, b) = (arpabet(&edit.expected), arpabet(&edit.observed));
+            match edit.kind {
+                EditKind::Match if edit.stress_mismatch => write!(f, "[{}≠{}]", a, b)?,
+                EditKind::Match => f.write_str(&a)?,
+                EditKind::Substitute => write!(f, "[{}→{}]", a, b)?,
+                EditKind::Delete => write!(f, "[-{}]", a)?,
+                EditKind::Insert => write!(f, "[+{}]", b)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use crate::cache::DEFAULT_RULE_CACHE_SIZE;
+use crate::dict::DuplicatePolicy;
+use crate::lang::CaseRules;
+use crate::numbers::{self, NumberLocale};
+use crate::phoneme::{BoundarySymbols, Notation, StressMarks};
+use crate::report::LoadProgress;
+use crate::rules::{RuleFormat, RulesEngine};
+use crate::text::{DisfluencyStyle, LatinStyle, ListStyle, NonVerbalStyle, NormalizeOptions};
+use crate::{AffixDictionary, Dictionary, DiskCache, Fallback, LexiconLayer, LongWordPolicy, RewriteEngine, RoutingConfig, RustG2P, SchwaInsertion, LANGUAGE};
+use crate::error::{Context, G2PError, Result};
+use serde::Deserialize;
+
+/// TOML配置文件，所有字段均可省略
+///
+/// ```toml
+/// language = "en"              # 语言代码，如 en-GB、es；不是英语时不使用英语专用的处理，见 `RustG2P::with_language`
+///
+/// [data]
+/// dictionary = "data/cmudict.txt"
+/// rules = "data/en_rules.txt"
+/// rules_format = "pipe"        # pipe | nrl | festival
+/// irregular = "data/en_irregular.txt"  # 不规则词汇层，空字符串表示不使用
+/// hunspell = "dicts/en_US"     # Hunspell 词缀词典（en_US.aff 和 en_US.dic），用于词干还原，省略则不使用
+/// rewrite = []                 # 音素改写规则文件，按顺序应用，如 ["data/flapping.rewrite"]
+/// duplicates = "keep_last"     # keep_last | keep_first | keep_variants | overwrite | error（词典中重复的单词）
+/// compact_dictionary = false   # 词典使用压缩存储，内存占用约为五分之一，查找稍慢
+///
+/// [normalization]
+/// expand_abbreviations = true
+/// expand_numbers = true
+/// strip_punctuation = true
+/// strip_markup = false         # 去掉Markdown和HTML标记
+/// latin_style = "expand"       # expand | letters（e.g. 读作 for example 或 E G）
+/// number_locale = "us"         # us (1,000.5) | eu (1.000,5)
+/// list_style = "cardinal"      # cardinal | ordinal（行首的 1. 读作 one 或 first）
+/// non_verbal = "event"         # event | tagged | literal（[laughter] 输出事件音素、不发音或按文字读）
+/// disfluency = "keep"          # keep | normalize | drop（um、uh 等填充词保留、换成规范写法或去掉）
+/// case_rules = "unicode"       # unicode | turkic | german（转小写的规则，turkic 中 I 的小写为 ı；省略时按 language 选择）
+/// number_words = "es"          # 数字的读法，`numbers::register_number_words` 注册的名称；省略时按 language 选择
+///
+/// [output]
+/// notation = "arpabet"         # arpabet | arpabet_no_stress | ipa
+/// phone_stress = "strip"       # strip | keep | primary_only（word_to_phone_string 等音素串中的重音数字）
+/// schwa_clusters = []          # 插入央元音的辅音丛，如 ["# S [consonant]"]，写法见 `epenthesis` 模块
+/// linking_r = false            # 连接R和插入R（law and order），用于非卷舌口音
+/// rhythm_rule = false          # 相邻单词重音冲突时前移主重音（thirteen men → THIRteen MEN）
+/// pause_markers = false        # 输出句中停顿和句子边界标记
+///
+/// [output.boundaries]          # 边界标记的输出写法，空字符串表示不输出
+/// word = "|"
+/// pause = ","
+/// sentence = "."
+///
+/// [cache]
+/// path = "g2p-cache.tsv"       # 未登录词的磁盘缓存，省略则不使用
+/// rule_cache_size = 1024       # 规则转换结果的内存缓存容量（单词数），0 表示不使用
+///
+/// [oov]
+/// strict = false
+/// min_confidence = 0.0
+/// require_valid_phonotactics = false
+/// fallback = "keep"            # keep | spell_out | error
+/// stem_recovery = true         # 派生词先还原词干（running → run）再查词典
+/// transliterate = true         # 希腊字母、西里尔字母转写为拉丁字母后按英语读
+/// assign_stress = true         # 规则读音按后缀和音节数指派唯一的主重音
+/// max_word_length = 64         # 未登录词的最大长度（按字符计），省略则不限制
+/// long_words = "spell_out"     # skip | spell_out | error（超长时不发音、拼读前 max_word_length 个字符或返回错误）
+/// ```
+///
+/// 相对路径相对于当前工作目录解析。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct G2PConfig {
+    pub language: String,
+    pub data: DataConfig,
+    pub normalization: NormalizationConfig,
+    pub output: OutputConfig,
+    pub cache: CacheConfig,
+    pub oov: OovConfig,
+}
+
+/// 数据文件路径
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataConfig {
+    pub dictionary: String,
+    pub rules: String,
+    pub rules_format: RuleFormat,
+    pub irregular: String,
+    pub hunspell: Option<String>,
+    pub rewrite: Vec<String>,
+    pub duplicates: DuplicatePolicy,
+    pub compact_dictionary: bool,
+}
+
+/// 文本标准化开关
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NormalizationConfig {
+    pub expand_abbreviations: bool,
+    pub expand_numbers: bool,
+    pub strip_punctuation: bool,
+    pub strip_markup: bool,
+    pub latin_style: LatinStyle,
+    pub number_locale: NumberLocale,
+    pub list_style: ListStyle,
+    pub non_verbal: NonVerbalStyle,
+    pub disfluency: DisfluencyStyle,
+    pub case_rules: Option<CaseRules>, // 省略时按 `language` 选择
+    pub number_words: Option<String>, // 省略时按 `language` 选择
+}
+
+/// 输出设置
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub notation: Notation,
+    pub phone_stress: StressMarks,
+    pub schwa_clusters: Vec<String>,
+    pub linking_r: bool,
+    pub rhythm_rule: bool,
+    pub pause_markers: bool,
+    pub boundaries: BoundarySymbols,
+}
+
+/// 缓存设置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    pub path: Option<String>,
+    pub rule_cache_size: usize,
+}
+
+/// 未登录词处理策略
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OovConfig {
+    pub strict: bool,
+    pub min_confidence: f32,
+    pub require_valid_phonotactics: bool,
+    pub fallback: Fallback,
+    pub stem_recovery: bool,
+    pub transliterate: bool,
+    pub assign_stress: bool,
+    pub max_word_length: Option<usize>,
+    pub long_words: LongWordPolicy,
+}
+
+impl Default for G2PConfig {
+    fn default() -> Self {
+        Self {
+            language: LANGUAGE.to_string(),
+            data: DataConfig::default(),
+            normalization: NormalizationConfig::default(),
+            output: OutputConfig::default(),
+            cache: CacheConfig::default(),
+            oov: OovConfig::default(),
+        }
+    }
+}
+
+impl Default for DataConfig {
+    fn default() -> Self {
+        Self {
+            dictionary: "data/cmudict.txt".to_string(),
+            rules: "data/en_rules.txt".to_string(),
+            rules_format: RuleFormat::Pipe,
+            irregular: "data/en_irregular.txt".to_string(),
+            hunspell: None,
+            rewrite: Vec::new(),
+            duplicates: DuplicatePolicy::default(),
+            compact_dictionary: false,
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            rule_cache_size: DEFAULT_RULE_CACHE_SIZE,
+        }
+    }
+}
+
+impl Default for NormalizationConfig {
+    fn default() -> Self {
+        let mut config: Self = NormalizeOptions::default().into();
+        config.case_rules = None;
+        config
+    }
+}
+
+impl From<NormalizeOptions> for NormalizationConfig {
+    fn from(options: NormalizeOptions) -> Self {
+        Self {
+            expand_abbreviations: options.expand_abbreviations,
+            expand_numbers: options.expand_numbers,
+            strip_punctuation: options.strip_punctuation,
+            strip_markup: options.strip_markup,
+            latin_style: options.latin_style,
+            number_locale: options.number_locale,
+            list_style: options.list_style,
+            non_verbal: options.non_verbal,
+            disfluency: options.disfluency,
+            case_rules: Some(options.case_rules),
+            number_words: None,
+        }
+    }
+}
+
+impl NormalizationConfig {
+    /// 对应的文本标准化选项，未设置大小写规则时使用Unicode的通用规则
+    pub fn options(&self) -> NormalizeOptions {
+        NormalizeOptions {
+            expand_abbreviations: self.expand_abbreviations,
+            expand_numbers: self.expand_numbers,
+            strip_punctuation: self.strip_punctuation,
+            strip_markup: self.strip_markup,
+            latin_style: self.latin_style,
+            number_locale: self.number_locale,
+            list_style: self.list_style,
+            non_verbal: self.non_verbal,
+            disfluency: self.disfluency,
+            case_rules: self.case_rules.unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for OovConfig {
+    fn default() -> Self {
+        let routing = RoutingConfig::default();
+        Self {
+            strict: false,
+            min_confidence: routing.min_confidence,
+            require_valid_phonotactics: routing.require_valid_phonotactics,
+            fallback: routing.fallback,
+            stem_recovery: true,
+            transliterate: true,
+            assign_stress: true,
+            max_word_length: None,
+            long_words: LongWordPolicy::default(),
+        }
+    }
+}
+
+impl G2PConfig {
+    /// 读取TOML配置文件
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path))?;
+        Self::parse(&content).with_context(|| format!("Invalid config file: {}", path))
+    }
+    
+    /// 解析TOML配置文本
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+    
+    /// 转小写的规则：`[normalization]` 下的 `case_rules`，省略时按 `language` 选择（见 `CaseRules::for_language`）
+    pub fn case_rules(&self) -> CaseRules {
+        self.normalization.case_rules.unwrap_or_else(|| CaseRules::for_language(&self.language))
+    }
+    
+    /// 按配置加载数据并创建转换器
+    pub(crate) fn build(&self) -> Result<RustG2P> {
+        self.build_with_progress(&|_| {})
+    }
+    
+    /// 按配置加载数据并创建转换器，词典和规则文件的加载进度报告给 `progress`
+    pub(crate) fn build_with_progress(&self, progress: &dyn Fn(&LoadProgress)) -> Result<RustG2P> {
+        let mut dictionary = Dictionary::load_cmu_dict_with_progress(&self.data.dictionary, self.data.duplicates, progress)?;
+        if self.data.compact_dictionary {
+            dictionary = dictionary.into_compact()?;
+        }
+        progress(&LoadProgress::Started { path: self.data.rules.clone() });
+        let rules_engine = match self.data.rules_format {
+            RuleFormat::Pipe => RulesEngine::load_english_rules(&self.data.rules)?,
+            RuleFormat::Nrl => RulesEngine::load_nrl_rules(&self.data.rules)?,
+            RuleFormat::Festival => RulesEngine::load_festival_rules(&self.data.rules)?,
+        };
+        progress(&LoadProgress::Finished { path: self.data.rules.clone(), entries: rules_engine.rule_count() });
+        
+        let mut g2p = RustG2P::from_parts(dictionary, rules_engine)
+            .with_normalization(self.normalization.options())
+            .with_notation(self.output.notation)
+            .with_phone_stress(self.output.phone_stress)
+            .with_pause_markers(self.output.pause_markers)
+            .with_boundary_symbols(self.output.boundaries.clone())
+            .with_strict(self.oov.strict)
+            .with_stem_recovery(self.oov.stem_recovery)
+            .with_transliteration(self.oov.transliterate)
+            .with_stress_assignment(self.oov.assign_stress)
+            .with_routing(RoutingConfig {
+                min_confidence: self.oov.min_confidence,
+                require_valid_phonotactics: self.oov.require_valid_phonotactics,
+                fallback: self.oov.fallback,
+            });
+        if let Some(limit) = self.oov.max_word_length {
+            g2p = g2p.with_max_word_length(limit, self.oov.long_words);
+        }
+        if !self.data.irregular.is_empty() {
+            g2p = g2p.with_irregular(LexiconLayer::load(&self.data.irregular)?);
+        }
+        if let Some(base) = &self.data.hunspell {
+            g2p = g2p.with_affix_dictionary(AffixDictionary::load(&format!("{}.aff", base), &format!("{}.dic", base))?);
+        }
+        for path in &self.data.rewrite {
+            g2p = g2p.with_rewrite(RewriteEngine::load(path)?);
+        }
+        let mut schwa = SchwaInsertion::new();
+        for cluster in &self.output.schwa_clusters {
+            schwa = schwa.with_cluster(cluster)?;
+        }
+        if self.output.linking_r {
+            g2p = g2p.with_linking_r();
+        }
+        g2p = g2p.with_schwa_insertion(schwa).with_rhythm_rule(self.output.rhythm_rule);
+        g2p = g2p.with_rule_cache(self.cache.rule_cache_size);
+        // 语言放在各开关之后设置，英语以外的语言关闭英语专用的处理；配置的大小写规则优先
+        g2p = g2p.with_language(&self.language).with_case_rules(self.case_rules());
+        if let Some(name) = &self.normalization.number_words {
+            let number_words = numbers::number_words_for(name)
+                .ok_or_else(|| G2PError::Config(format!("Unknown number words '{}'", name)))?;
+            g2p = g2p.with_number_words(number_words);
+        }
+        if let Some(path) = &self.cache.path {
+            g2p = g2p.with_disk_cache(DiskCache::open(path)?);
+        }
+        
+        Ok(g2p)
+    }
+}
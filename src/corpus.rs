@@ -0,0 +1,96 @@
+use crate::phoneme::{self, Notation, Phoneme};
+use crate::RustG2P;
+use crate::error::Result;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+/// 语料中的未登录词统计
+#[derive(Debug, Clone, Default)]
+pub struct OovReport {
+    pub total_words: usize,    // 语料中的单词总数（按出现次数计）
+    pub entries: Vec<OovEntry>, // 每个不同的未登录词一项，默认按频率从高到低
+}
+
+/// 单个未登录词
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OovEntry {
+    pub word: String,
+    pub count: usize,
+    pub phonemes: Vec<Phoneme>, // 转换器当前给出的读音（规则或后备策略）
+}
+
+/// 转换语料中的所有文本，统计词典未收录的单词及其出现次数和读音
+///
+/// 与 `text_to_words` 使用相同的标准化和分词，每个不同的未登录词只保留第一次出现时的读音。
+pub fn oov_report<S: AsRef<str>>(g2p: &RustG2P, texts: &[S]) -> Result<OovReport> {
+    let mut total_words = 0;
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut entries: Vec<OovEntry> = Vec::new();
+
+    for text in texts {
+        for word in g2p.text_to_words(text.as_ref())? {
+            total_words += 1;
+            if !word.oov {
+                continue;
+            }
+            match index.get(&word.word) {
+                Some(&i) => entries[i].count += 1,
+                None => {
+                    index.insert(word.word.clone(), entries.len());
+                    entries.push(OovEntry { word: word.word, count: 1, phonemes: word.phonemes });
+                }
+            }
+        }
+    }
+
+    let mut report = OovReport { total_words, entries };
+    report.sort_by_frequency();
+    Ok(report)
+}
+
+impl OovReport {
+    /// 按出现次数从高到低排序，次数相同时按字母顺序
+    pub fn sort_by_frequency(&mut self) {
+        self.entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    }
+
+    /// 按字母顺序排序
+    pub fn sort_alphabetically(&mut self) {
+        self.entries.sort_by(|a, b| a.word.cmp(&b.word));
+    }
+
+    /// 未登录词的出现次数之和
+    pub fn oov_words(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    /// 按出现次数计的未登录率
+    pub fn oov_rate(&self) -> f64 {
+        if self.total_words == 0 {
+            0.0
+        } else {
+            self.oov_words() as f64 / self.total_words as f64
+        }
+    }
+
+    /// 按当前顺序输出 `单词\t次数\t读音` 的TSV，可直接修改后作为用户词典的初稿
+    pub fn render(&self, notation: Notation) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            let _ = writeln!(out, "{}\t{}\t{}", entry.word, entry.count, phoneme::render(&entry.phonemes, notation));
+        }
+        out
+    }
+}
+
+impl fmt::Display for OovReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} words, {} unique OOV words, {:.1}% OOV rate",
+            self.total_words,
+            self.entries.len(),
+            self.oov_rate() * 100.0
+        )
+    }
+}
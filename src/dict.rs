@@ -1,21 +1,125 @@
-use crate::phoneme::Phoneme;
-use anyhow::{Result, Context};
-use std::collections::HashMap;
+use crate::eval::{self, AccuracyReport};
+use crate::letters;
+use crate::memory;
+use crate::phoneme::{self, Phoneme, PhonemeCode};
+use crate::report::{IssueKind, LoadIssue, LoadProgress, LoadReport};
+use crate::rules::RulesEngine;
+use crate::schema::{self, Capability, DataSchema};
+use crate::error::{invalid_data, Context, G2PError, Result};
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use smallvec::SmallVec;
+use std::io::{BufWriter, Write};
 
-/// CMU发音词典
+mod arena;
+mod compact;
+mod compiled;
+
+use arena::{Arena, Interner, Span};
+pub use compiled::is_compiled;
+
+/// CMU发音词典，发音以紧凑编码存储，查询时解码
+///
+/// 默认用哈希表存储，相同的读音只存一份（见 `arena` 模块）；`into_compact` 转换为基于有限状态转换器的压缩存储，
+/// 内存占用小得多，修改词条（`add_entry`、`prune_by_frequency`）时自动转换回哈希表。
 pub struct Dictionary {
-    entries: HashMap<String, Vec<Phoneme>>,
+    entries: HashMap<String, Span>,
+    variants: HashMap<String, Vec<Span>>,     // 同一单词的其他读音，按在文件中的顺序，不含主读音
+    arena: Arena,                             // entries 和 variants 指向的读音
+    compact: Option<compact::CompactStorage>, // 压缩存储，使用时 entries、variants 和 arena 为空
+    schema: DataSchema,                       // 词典文件的版本和用到的特性
+    duplicates: DuplicatePolicy,              // 加载时的重复处理方式，决定输出时读音的顺序
+}
+
+/// 加载时同一单词（忽略 `WORD(2)` 这样的变体标记）出现多次的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// 带编号的变体（`WORD(2)`）和去掉数字、标点后才相同的写法（`A42128`、`CAT-1`）排在主读音之后
+    /// （见 `Dictionary::lookup_all`）；写法相同的单词重复出现时，后出现的读音成为主读音，先出现的读音作为变体保留
+    #[default]
+    KeepLast,
+    /// 保留第一次出现的读音，丢弃之后的读音
+    KeepFirst,
+    /// 第一次出现的读音为主读音，其余读音作为变体保留
+    KeepVariants,
+    /// 后出现的读音覆盖先出现的，不保留变体，占用内存最少
+    Overwrite,
+    /// 返回错误
+    Error,
+}
+
+/// 词频裁剪的结果
+#[derive(Debug, Clone)]
+pub struct PruneReport {
+    pub kept: usize,
+    pub removed: Vec<(String, Box<[PhonemeCode]>)>, // 按单词排序
+}
+
+/// 单个线程的解析结果
+struct ChunkResult {
+    entries: Vec<(usize, String, Box<[PhonemeCode]>, bool)>, // (行号, 单词, 读音, 是否为带编号或需要去掉数字、标点的写法)
+    issues: Vec<LoadIssue>,
+    variants: bool, // 含有 `WORD(2)` 形式的多读音
 }
 
 impl Dictionary {
     /// 加载CMU词典 - 正确处理编码问题
+    ///
+    /// 加载摘要和前10个跳过的行记入日志（`log` crate），不直接输出。
+    /// `compile` 生成的二进制词典按魔数识别，直接读取，使用编译时的重复词条处理方式。
     pub fn load_cmu_dict(path: &str) -> Result<Self> {
-        println!("Loading CMU dictionary from: {}", path);
-        
+        Self::load_any(path, None, &|_| {})
+    }
+    
+    /// 按指定的重复词条处理方式加载CMU词典（文本或二进制），解析进度报告给 `progress`
+    ///
+    /// 与 `load_cmu_dict` 一样，加载摘要和前10个跳过的行记入日志。二进制词典编译时的处理方式与 `duplicates`
+    /// 不同时返回 `G2PError::Config`，须重新编译或改用相同的处理方式。
+    pub fn load_cmu_dict_with_progress(path: &str, duplicates: DuplicatePolicy, progress: &dyn Fn(&LoadProgress)) -> Result<Self> {
+        Self::load_any(path, Some(duplicates), progress)
+    }
+    
+    /// 加载文本或二进制词典；`duplicates` 为None时文本词典使用默认的处理方式，二进制词典不做检查
+    fn load_any(path: &str, duplicates: Option<DuplicatePolicy>, progress: &dyn Fn(&LoadProgress)) -> Result<Self> {
+        progress(&LoadProgress::Started { path: path.to_string() });
+        let dictionary = if is_compiled(path) {
+            log::info!("Loading compiled dictionary from: {}", path);
+            let dictionary = Self::load_compiled(path)?;
+            if let Some(requested) = duplicates.filter(|&requested| requested != dictionary.duplicates) {
+                return Err(G2PError::Config(format!(
+                    "Compiled dictionary '{}' was built with duplicate policy {:?}, but {:?} was requested",
+                    path, dictionary.duplicates, requested
+                )));
+            }
+            dictionary
+        } else {
+            let duplicates = duplicates.unwrap_or_default();
+            log::info!("Loading CMU dictionary from: {}", path);
+            let (dictionary, report) = Self::read_cmu_dict(path, duplicates, progress)?;
+            Self::log_report(&report);
+            dictionary
+        };
+        progress(&LoadProgress::Finished { path: path.to_string(), entries: dictionary.size() });
+        Ok(dictionary)
+    }
+    
+    /// 加载CMU词典，不输出警告，跳过的行和修复的音素在报告中返回
+    pub fn load_cmu_dict_with_report(path: &str) -> Result<(Self, LoadReport)> {
+        Self::load_cmu_dict_with_policy(path, DuplicatePolicy::default())
+    }
+    
+    /// 加载CMU词典，重复的单词按 `duplicates` 处理，不输出警告
+    pub fn load_cmu_dict_with_policy(path: &str, duplicates: DuplicatePolicy) -> Result<(Self, LoadReport)> {
+        Self::read_cmu_dict(path, duplicates, &|_| {})
+    }
+    
+    fn read_cmu_dict(path: &str, duplicates: DuplicatePolicy, progress: &dyn Fn(&LoadProgress)) -> Result<(Self, LoadReport)> {
         // 确保文件存在
         if !std::path::Path::new(path).exists() {
-            return Err(anyhow::anyhow!("CMU dictionary file not found: {}", path));
+            return Err(G2PError::DictNotFound { path: path.to_string() });
         }
         
         // 读取原始字节并处理编码问题
@@ -25,13 +129,151 @@ impl Dictionary {
         // 将字节转换为字符串，替换无效的UTF-8字符
         let content = String::from_utf8_lossy(&bytes);
         
-        let mut entries = HashMap::new();
-        let mut line_count = 0;
-        let mut valid_entries = 0;
-        let mut skipped_lines = 0;
+        Self::parse_cmu(&content, duplicates, progress)
+    }
+    
+    /// 从CMU词典格式的文本构建词典
+    ///
+    /// 以 `;;;!g2p-lexicon 1` 开头的文件按严格模式解析，任何无法解析的行都会报错；
+    /// 没有版本头的文件跳过这些行并输出警告。
+    pub fn from_cmu_str(content: &str) -> Result<Self> {
+        let (dictionary, report) = Self::from_cmu_str_with_report(content)?;
+        Self::log_report(&report);
+        Ok(dictionary)
+    }
+    
+    /// 从CMU词典格式的文本构建词典，不输出警告，跳过的行和修复的音素在报告中返回
+    pub fn from_cmu_str_with_report(content: &str) -> Result<(Self, LoadReport)> {
+        Self::from_cmu_str_with_policy(content, DuplicatePolicy::default())
+    }
+    
+    /// 从CMU词典格式的文本构建词典，重复的单词按 `duplicates` 处理，不输出警告
+    ///
+    /// 重复的次数记在报告的 `duplicates` 中，`DuplicatePolicy::Error` 时第一个重复的单词报错。
+    pub fn from_cmu_str_with_policy(content: &str, duplicates: DuplicatePolicy) -> Result<(Self, LoadReport)> {
+        Self::parse_cmu(content, duplicates, &|_| {})
+    }
+    
+    fn parse_cmu(content: &str, duplicates: DuplicatePolicy, progress: &dyn Fn(&LoadProgress)) -> Result<(Self, LoadReport)> {
+        let mut schema = schema::LEXICON_FORMAT.read_header(content)?;
+        let strict = schema.is_versioned();
+        let lines: Vec<&str> = content.lines().collect();
+        let line_count = lines.len();
+        
+        // 按线程数切分行，各线程独立解析后按原顺序合并（重复的单词按 `duplicates` 处理）
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = line_count.div_ceil(threads).max(1);
+        let chunks: Vec<ChunkResult> = std::thread::scope(|scope| {
+            let handles: Vec<_> = lines
+                .chunks(chunk_size)
+                .enumerate()
+                .map(|(i, chunk)| scope.spawn(move || Self::parse_chunk(chunk, i * chunk_size, strict)))
+                .collect();
+            handles
+                .into_iter()
+                .enumerate()
+                .map(|(i, handle)| {
+                    let chunk = handle.join().expect("dictionary parser thread panicked");
+                    progress(&LoadProgress::Lines { done: ((i + 1) * chunk_size).min(line_count), total: line_count });
+                    chunk
+                })
+                .collect()
+        });
+        
+        let mut entries: HashMap<String, Span> = HashMap::with_capacity(line_count);
+        let mut variants: HashMap<String, Vec<Span>> = HashMap::new();
+        let mut interner = Interner::default();
+        let mut report = LoadReport { lines: line_count, ..LoadReport::default() };
+        
+        for chunk in chunks {
+            if let (true, Some(issue)) = (strict, chunk.issues.first()) {
+                return Err(G2PError::MalformedEntry {
+                    line: issue.line,
+                    message: format!("{}: '{}'", issue.message, issue.text),
+                });
+            }
+            if chunk.variants {
+                schema.record(Capability::Variants);
+            }
+            report.loaded += chunk.entries.len();
+            report.issues.extend(chunk.issues);
+            for (line_no, word, codes, secondary) in chunk.entries {
+                // 读音已去重，位置相同即读音相同
+                let codes = interner.intern(&codes);
+                let mut existing = match entries.entry(word) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(codes);
+                        continue;
+                    }
+                    Entry::Occupied(entry) => entry,
+                };
+                report.duplicates += 1;
+                // 带编号的变体和去掉数字、标点后才相同的写法总是排在主读音之后
+                let policy = match duplicates {
+                    DuplicatePolicy::KeepLast if secondary => DuplicatePolicy::KeepVariants,
+                    policy => policy,
+                };
+                match policy {
+                    DuplicatePolicy::KeepLast => {
+                        let others = variants.entry(existing.key().clone()).or_default();
+                        others.retain(|other| *other != codes);
+                        let previous = existing.insert(codes);
+                        if *existing.get() != previous && !others.contains(&previous) {
+                            others.push(previous);
+                        }
+                    }
+                    DuplicatePolicy::Overwrite => {
+                        existing.insert(codes);
+                    }
+                    DuplicatePolicy::KeepFirst => {}
+                    DuplicatePolicy::KeepVariants => {
+                        let others = variants.entry(existing.key().clone()).or_default();
+                        if *existing.get() != codes && !others.contains(&codes) {
+                            others.push(codes);
+                        }
+                    }
+                    DuplicatePolicy::Error => {
+                        return Err(G2PError::MalformedEntry {
+                            line: line_no,
+                            message: format!("duplicate entry for '{}'", existing.key()),
+                        });
+                    }
+                }
+            }
+        }
+        
+        if report.loaded == 0 {
+            return Err(invalid_data!("No valid entries found in CMU dictionary"));
+        }
         
-        for line in content.lines() {
-            line_count += 1;
+        variants.retain(|_, others| !others.is_empty());
+        let arena = interner.finish();
+        Ok((Self { entries, variants, arena, compact: None, schema, duplicates }, report))
+    }
+    
+    /// 记录加载摘要和前10个问题
+    fn log_report(report: &LoadReport) {
+        report.log_issues(10);
+        log::info!(
+            "Loaded CMU dictionary: {} lines, {} entries, {} skipped lines, {} duplicates",
+            report.lines,
+            report.loaded,
+            report.skipped_lines(),
+            report.duplicates
+        );
+    }
+    
+    /// 解析一段连续的词典行，`first_line` 为该段之前的行数；`strict` 时不修复错误的音素
+    fn parse_chunk(lines: &[&str], first_line: usize, strict: bool) -> ChunkResult {
+        let mut result = ChunkResult {
+            entries: Vec::with_capacity(lines.len()),
+            issues: Vec::new(),
+            variants: false,
+        };
+        let mut notes = Vec::new();
+        
+        for (offset, line) in lines.iter().enumerate() {
+            let line_no = first_line + offset + 1;
             
             // 跳过注释行和空行
             if line.starts_with(";;;") || line.trim().is_empty() {
@@ -40,52 +282,35 @@ impl Dictionary {
             
             // 检查行是否包含有效字符
             if !Self::is_valid_line(line) {
-                skipped_lines += 1;
-                if skipped_lines <= 10 {  // 只显示前10个跳过的行
-                    eprintln!("Warning: Skipping invalid line {}: '{}'", line_count, 
-                             Self::truncate_string(line, 50));
-                }
+                result.issues.push(LoadIssue::new(line_no, IssueKind::InvalidLine, "invalid lexicon entry", line));
                 continue;
             }
             
             // 解析词典条目
             if let Some((word, phonemes_str)) = Self::parse_cmu_line(line) {
-                match Self::parse_phonemes(&phonemes_str) {
-                    Ok(phonemes) => {
-                        let clean_word = Self::clean_word(&word);
-                        entries.insert(clean_word, phonemes);
-                        valid_entries += 1;
+                let parsed = Self::parse_phonemes(&phonemes_str, strict, &mut notes);
+                result.issues.extend(notes.drain(..).map(|(kind, message)| LoadIssue::new(line_no, kind, message, line)));
+                match parsed {
+                    Ok(codes) => {
+                        let numbered = word.ends_with(')');
+                        result.variants |= numbered;
+                        // 去掉数字和标点后才与别的单词相同的写法（A42128、CAT-1）与带编号的变体一样排在主读音之后
+                        let cleaned = Self::clean_word(&word);
+                        let spelled = word.split('(').next().unwrap_or_default().to_lowercase();
+                        let secondary = numbered || cleaned != spelled;
+                        result.entries.push((line_no, cleaned, codes, secondary));
                     }
                     Err(e) => {
-                        eprintln!("Warning: Failed to parse phonemes for '{}' on line {}: {}", 
-                                word, line_count, e);
+                        let message = format!("failed to parse phonemes for '{}': {}", word, e);
+                        result.issues.push(LoadIssue::new(line_no, IssueKind::InvalidEntry, message, line));
                     }
                 }
             } else {
-                skipped_lines += 1;
-                if skipped_lines <= 10 {
-                    eprintln!("Warning: Skipping malformed line {}: '{}'", line_count, 
-                             Self::truncate_string(line, 50));
-                }
+                result.issues.push(LoadIssue::new(line_no, IssueKind::MalformedLine, "malformed lexicon entry", line));
             }
-            
-            // 每10000行显示一次进度
-            if line_count % 10000 == 0 {
-                println!("Processed {} lines, {} valid entries, {} skipped", 
-                        line_count, valid_entries, skipped_lines);
-            }
-        }
-        
-        println!("Successfully loaded CMU dictionary:");
-        println!("  Total lines processed: {}", line_count);
-        println!("  Valid entries: {}", valid_entries);
-        println!("  Skipped lines: {}", skipped_lines);
-        
-        if valid_entries == 0 {
-            return Err(anyhow::anyhow!("No valid entries found in CMU dictionary"));
         }
         
-        Ok(Self { entries })
+        result
     }
     
     /// 检查行是否包含有效字符
@@ -111,15 +336,6 @@ impl Dictionary {
         true
     }
     
-    /// 截断字符串用于显示
-    fn truncate_string(s: &str, max_len: usize) -> String {
-        if s.len() <= max_len {
-            s.to_string()
-        } else {
-            format!("{}...", &s[..max_len])
-        }
-    }
-    
     /// 解析CMU词典行格式 - 更健壮的版本
     fn parse_cmu_line(line: &str) -> Option<(String, String)> {
         // 清理行内容
@@ -195,42 +411,58 @@ impl Dictionary {
     }
     
     /// 解析音素字符串 - 更健壮的版本
-    fn parse_phonemes(phonemes_str: &str) -> Result<Vec<Phoneme>> {
+    ///
+    /// 宽松模式下修复或丢弃的音素记入 `notes`。
+    fn parse_phonemes(phonemes_str: &str, strict: bool, notes: &mut Vec<(IssueKind, String)>) -> Result<Box<[PhonemeCode]>> {
         let phoneme_tokens: Vec<&str> = phonemes_str
             .split_whitespace()
             .filter(|p| !p.is_empty())
             .collect();
         
         if phoneme_tokens.is_empty() {
-            return Err(anyhow::anyhow!("No phonemes found"));
+            return Err(invalid_data!("No phonemes found"));
         }
         
         let mut phonemes = Vec::new();
         
         for token in phoneme_tokens {
+            if strict {
+                let code = Some(token)
+                    .filter(|token| Self::is_valid_arpabet(token))
+                    .and_then(|token| PhonemeCode::encode(&Phoneme::from_arpabet(token)))
+                    .ok_or_else(|| G2PError::InvalidPhoneme(token.to_string()))?;
+                phonemes.push(code);
+                continue;
+            }
+            
             // 跳过明显错误的token
             if token.len() > 4 || token.is_empty() {
+                notes.push((IssueKind::SkippedPhoneme, format!("skipped invalid phoneme '{}'", token)));
                 continue;
             }
             
-            // 验证并创建音素
-            if Self::is_valid_arpabet(token) {
-                phonemes.push(Phoneme::from_arpabet(token));
+            // 验证、修复常见错误并编码
+            let code = if Self::is_valid_arpabet(token) {
+                PhonemeCode::encode(&Phoneme::from_arpabet(token))
             } else {
-                // 尝试修复常见错误
-                if let Some(fixed) = Self::try_fix_phoneme(token) {
-                    phonemes.push(Phoneme::from_arpabet(&fixed));
-                } else {
-                    eprintln!("Warning: Skipping invalid phoneme: '{}'", token);
-                }
+                Self::try_fix_phoneme(token).and_then(|fixed| {
+                    let code = PhonemeCode::encode(&Phoneme::from_arpabet(&fixed))?;
+                    notes.push((IssueKind::FixedPhoneme, format!("fixed phoneme '{}' to '{}'", token, fixed)));
+                    Some(code)
+                })
+            };
+            
+            match code {
+                Some(code) => phonemes.push(code),
+                None => notes.push((IssueKind::SkippedPhoneme, format!("skipped invalid phoneme '{}'", token))),
             }
         }
         
         if phonemes.is_empty() {
-            return Err(anyhow::anyhow!("No valid phonemes after parsing"));
+            return Err(invalid_data!("No valid phonemes after parsing"));
         }
         
-        Ok(phonemes)
+        Ok(phonemes.into_boxed_slice())
     }
     
     /// 尝试修复常见的音素错误
@@ -289,28 +521,344 @@ impl Dictionary {
     
     /// 查找单词的发音
     pub fn lookup(&self, word: &str) -> Option<Vec<Phoneme>> {
-        self.entries.get(&word.to_lowercase()).cloned()
+        self.lookup_ref(word).map(|codes| codes.iter().map(|code| code.decode()).collect())
+    }
+    
+    /// 查找单词的发音编码，借用词典中的数据而不解码
+    pub fn lookup_ref(&self, word: &str) -> Option<&[PhonemeCode]> {
+        if word.chars().any(char::is_uppercase) {
+            self.primary(&word.to_lowercase())
+        } else {
+            self.primary(word)
+        }
+    }
+    
+    /// 小写单词的主读音
+    fn primary(&self, word: &str) -> Option<&[PhonemeCode]> {
+        match &self.compact {
+            Some(compact) => compact.primary(word),
+            None => self.entries.get(word).map(|&span| self.arena.get(span)),
+        }
+    }
+    
+    /// 单词的所有读音，主读音（即 `lookup` 的结果）在前，其余按在词典文件中的顺序；
+    /// 按 `DuplicatePolicy::KeepFirst` 或 `Overwrite` 加载时只有一个读音
+    pub fn lookup_all(&self, word: &str) -> Vec<Vec<Phoneme>> {
+        self.readings(&word.to_lowercase())
+            .into_iter()
+            .map(|codes| codes.iter().map(|code| code.decode()).collect())
+            .collect()
+    }
+    
+    /// 小写单词的所有读音，主读音在前
+    fn readings(&self, word: &str) -> SmallVec<[&[PhonemeCode]; 4]> {
+        if let Some(compact) = &self.compact {
+            return compact.all(word);
+        }
+        let Some(primary) = self.entries.get(word) else {
+            return SmallVec::new();
+        };
+        let others = self.variants.get(word).into_iter().flatten();
+        std::iter::once(primary).chain(others).map(|&span| self.arena.get(span)).collect()
+    }
+    
+    /// 依次访问每个单词和它的读音（主读音在前）；`sorted` 为true时按单词排序，压缩存储总是有序
+    pub(crate) fn for_each_entry<'a>(&'a self, sorted: bool, mut visit: impl FnMut(&str, &[&'a [PhonemeCode]])) {
+        if let Some(compact) = &self.compact {
+            return compact.for_each(visit);
+        }
+        let mut words: Vec<(&String, &Span)> = self.entries.iter().collect();
+        if sorted {
+            words.sort_unstable_by_key(|&(word, _)| word);
+        }
+        for (word, primary) in words {
+            let others = self.variants.get(word).into_iter().flatten();
+            let readings: SmallVec<[&[PhonemeCode]; 4]> = std::iter::once(primary).chain(others).map(|&span| self.arena.get(span)).collect();
+            visit(word, &readings);
+        }
+    }
+
+    /// 词典中的所有单词（小写），按字母顺序
+    pub(crate) fn words(&self) -> Vec<String> {
+        let mut words = Vec::with_capacity(self.size());
+        self.for_each_entry(true, |word, _| words.push(word.to_string()));
+        words
+    }
+
+    /// 所有读音（含变体）的编码形式
+    pub(crate) fn pronunciation_codes(&self) -> Vec<&[PhonemeCode]> {
+        let mut codes = Vec::with_capacity(self.size());
+        self.for_each_entry(false, |_, readings| codes.extend_from_slice(readings));
+        codes
+    }
+    
+    /// 转换为压缩存储，见 `compact` 模块；已经是压缩存储时不变
+    pub fn into_compact(mut self) -> Result<Self> {
+        if self.compact.is_none() {
+            self.compact = Some(compact::CompactStorage::build(&self.entries, &self.variants, &self.arena)?);
+            self.entries = HashMap::new();
+            self.variants = HashMap::new();
+            self.arena = Arena::default();
+        }
+        Ok(self)
+    }
+    
+    /// 是否为压缩存储
+    pub fn is_compact(&self) -> bool {
+        self.compact.is_some()
+    }
+    
+    /// 修改词条前把压缩存储转换回哈希表
+    fn thaw(&mut self) {
+        let Some(compact) = self.compact.take() else {
+            return;
+        };
+        let mut interner = Interner::default();
+        self.entries.reserve(compact.len());
+        compact.for_each(|word, readings| {
+            self.entries.insert(word.to_string(), interner.intern(readings[0]));
+            if readings.len() > 1 {
+                self.variants.insert(word.to_string(), readings[1..].iter().map(|codes| interner.intern(codes)).collect());
+            }
+        });
+        self.arena = interner.finish();
+    }
+
+    /// 估算词典的堆内存占用（字节）
+    pub fn heap_size(&self) -> usize {
+        if let Some(compact) = &self.compact {
+            return compact.heap_size();
+        }
+        memory::map_heap(&self.entries)
+            + self.entries.keys().map(String::capacity).sum::<usize>()
+            + memory::map_heap(&self.variants)
+            + self.variants
+                .iter()
+                .map(|(word, others)| word.capacity() + std::mem::size_of_val(others.as_slice()))
+                .sum::<usize>()
+            + self.arena.heap_size()
     }
     
     /// 获取词典大小
     pub fn size(&self) -> usize {
-        self.entries.len()
+        self.compact.as_ref().map_or(self.entries.len(), |compact| compact.len())
     }
     
     /// 添加自定义词条
+    ///
+    /// 不属于ARPAbet音素表的音素无法编码，会被跳过。新读音替换该单词原有的全部读音，
+    /// 追加到读音数组末尾，不与已有的读音共享。压缩存储的词典先转换回哈希表。
     pub fn add_entry(&mut self, word: String, phonemes: Vec<Phoneme>) {
-        self.entries.insert(word.to_lowercase(), phonemes);
+        self.thaw();
+        let codes: Vec<PhonemeCode> = phonemes
+            .iter()
+            .filter_map(|phoneme| {
+                let code = PhonemeCode::encode(phoneme);
+                if code.is_none() {
+                    log::warn!("Skipping phoneme '{}' in entry '{}': no compact code", phoneme.symbol, word);
+                }
+                code
+            })
+            .collect();
+        let word = word.to_lowercase();
+        self.variants.remove(&word);
+        let span = self.arena.push(&codes);
+        self.entries.insert(word, span);
     }
     
     /// 检查词典是否为空
     pub fn is_empty(&self) -> bool {
-        self.entries.is_empty()
+        self.size() == 0
+    }
+    
+    /// 按词频排名挑选最常用的 `keep_n` 个单词，构建精简词典
+    ///
+    /// `ranked_words` 按词频从高到低排列，不在词典中的单词被跳过。
+    pub fn minimal_lexicon<S: AsRef<str>>(&self, ranked_words: &[S], keep_n: usize) -> Dictionary {
+        let mut entries: HashMap<String, Span> = HashMap::with_capacity(keep_n);
+        let mut variants: HashMap<String, Vec<Span>> = HashMap::new();
+        let mut interner = Interner::default();
+        for word in self.top_words(ranked_words, keep_n) {
+            let readings = self.readings(&word);
+            if readings.len() > 1 {
+                variants.insert(word.clone(), readings[1..].iter().map(|codes| interner.intern(codes)).collect());
+            }
+            entries.insert(word, interner.intern(readings[0]));
+        }
+        Dictionary {
+            entries,
+            variants,
+            arena: interner.finish(),
+            compact: None,
+            schema: DataSchema::default(),
+            duplicates: self.duplicates,
+        }
+    }
+    
+    /// 只保留词频表中最常用的 `keep_n` 个单词，其余单词交给规则引擎处理
+    ///
+    /// 返回被移除的词条，可用 `PruneReport::accuracy_impact` 评估对准确率的影响。
+    pub fn prune_by_frequency<S: AsRef<str>>(&mut self, freq_list: &[S], keep_n: usize) -> PruneReport {
+        self.thaw();
+        let keep = self.top_words(freq_list, keep_n);
+        
+        // 保留的读音重新存放，释放被移除的读音
+        let mut removed = Vec::new();
+        let mut interner = Interner::default();
+        for (word, span) in std::mem::take(&mut self.entries) {
+            let codes = self.arena.get(span);
+            if keep.contains(&word) {
+                self.entries.insert(word, interner.intern(codes));
+            } else {
+                removed.push((word, codes.into()));
+            }
+        }
+        self.entries.shrink_to_fit();
+        self.variants.retain(|word, _| keep.contains(word));
+        for span in self.variants.values_mut().flatten() {
+            *span = interner.intern(self.arena.get(*span));
+        }
+        self.arena = interner.finish();
+        removed.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        PruneReport {
+            kept: self.entries.len(),
+            removed,
+        }
+    }
+    
+    /// 词频表中排名最靠前、且在词典中的 `keep_n` 个单词
+    fn top_words<S: AsRef<str>>(&self, ranked_words: &[S], keep_n: usize) -> HashSet<String> {
+        let mut words = HashSet::with_capacity(keep_n);
+        for word in ranked_words {
+            if words.len() >= keep_n {
+                break;
+            }
+            let word = word.as_ref().to_lowercase();
+            if self.primary(&word).is_some() {
+                words.insert(word);
+            }
+        }
+        words
+    }
+    
+    /// 以CMU词典格式输出全部词条（按单词排序），变体读音写作 `WORD(2)`
+    ///
+    /// 主读音在前，不带编号；用同样的方式重新加载得到相同的词典。
+    pub fn to_cmu_string(&self) -> String {
+        let mut out = String::new();
+        self.for_each_entry(true, |word, readings| {
+            for (i, codes) in readings.iter().enumerate() {
+                let symbols: Vec<&str> = codes.iter().map(|code| code.arpabet()).collect();
+                out.push_str(&word.to_uppercase());
+                if i > 0 {
+                    out.push_str(&format!("({})", i + 1));
+                }
+                out.push_str("  ");
+                out.push_str(&symbols.join(" "));
+                out.push('\n');
+            }
+        });
+        out
+    }
+    
+    /// 导出为 `单词\tIPA` 格式的TSV文件，按单词排序
+    pub fn export_ipa(&self, path: &str) -> Result<()> {
+        let file = fs::File::create(path).with_context(|| format!("Failed to create IPA lexicon: {}", path))?;
+        let mut out = BufWriter::new(file);
+        let mut result = Ok(());
+        self.for_each_entry(true, |word, readings| {
+            if result.is_ok() {
+                let phonemes: Vec<Phoneme> = readings[0].iter().map(|code| code.decode()).collect();
+                result = writeln!(out, "{}\t{}", word, phoneme::ipa_transcription(&phonemes));
+            }
+        });
+        result?;
+        out.flush()?;
+        Ok(())
+    }
+    
+    /// 拼写最接近的单词，按编辑距离排序
+    ///
+    /// 比较前去掉变音符号，`naïve` 最接近 `naive`。只返回距离不超过单词长度三分之一（至少为1）的单词，
+    /// 距离相同时按字母顺序。
+    pub fn nearest_spellings(&self, word: &str, limit: usize) -> Vec<String> {
+        let target: Vec<char> = word.to_lowercase().chars().map(letters::base_letter).collect();
+        let max = (target.len() / 3).max(1);
+        self.nearest(limit, |entry, _| {
+            if entry.chars().count().abs_diff(target.len()) > max {
+                return None;
+            }
+            let chars: Vec<char> = entry.chars().collect();
+            Some(eval::edit_distance(&target, &chars)).filter(|distance| *distance <= max)
+        })
+    }
+    
+    /// 发音最接近的单词，按忽略重音的音素编辑距离排序，阈值同 `nearest_spellings`
+    pub fn nearest_pronunciations(&self, phonemes: &[Phoneme], limit: usize) -> Vec<String> {
+        let target: Vec<&str> = phonemes.iter().filter_map(PhonemeCode::encode).map(PhonemeCode::symbol).collect();
+        if target.is_empty() {
+            return Vec::new();
+        }
+        let max = (target.len() / 3).max(1);
+        self.nearest(limit, |_, codes| {
+            if codes.len().abs_diff(target.len()) > max {
+                return None;
+            }
+            let symbols: Vec<&str> = codes.iter().map(|code| code.symbol()).collect();
+            Some(eval::edit_distance(&target, &symbols)).filter(|distance| *distance <= max)
+        })
+    }
+    
+    /// 按距离取最近的 `limit` 个单词，`distance` 返回 `None` 表示不是候选
+    fn nearest(&self, limit: usize, distance: impl Fn(&str, &[PhonemeCode]) -> Option<usize>) -> Vec<String> {
+        let mut candidates: Vec<(usize, String)> = Vec::new();
+        self.for_each_entry(false, |word, readings| {
+            if let Some(d) = distance(word, readings[0]) {
+                candidates.push((d, word.to_string()));
+            }
+        });
+        candidates.sort_unstable();
+        candidates.into_iter().take(limit).map(|(_, word)| word).collect()
+    }
+    
+    /// 词典文件的版本和用到的特性
+    pub fn schema(&self) -> &DataSchema {
+        &self.schema
+    }
+    
+    pub(crate) fn into_entries(mut self) -> HashMap<String, Box<[PhonemeCode]>> {
+        self.thaw();
+        let arena = self.arena;
+        self.entries.into_iter().map(|(word, span)| (word, arena.get(span).into())).collect()
     }
     
     /// 获取词典中的所有单词（排序后的前N个）
     pub fn get_sample_words(&self, count: usize) -> Vec<String> {
-        let mut words: Vec<String> = self.entries.keys().cloned().collect();
-        words.sort();
-        words.into_iter().take(count).collect()
+        let mut words = self.words();
+        words.truncate(count);
+        words
     }
-}
\ No newline at end of file
+}
+
+/// 解析词频表：每行第一个字段为单词，按出现顺序视为排名，`#` 开头的行为注释
+pub fn parse_frequency_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl PruneReport {
+    /// 用规则引擎转换被移除的单词，与原词典发音比较，估计裁剪对准确率的影响
+    pub fn accuracy_impact(&self, rules: &RulesEngine) -> AccuracyReport {
+        let references: Vec<(&str, Vec<Phoneme>)> = self.removed
+            .iter()
+            .map(|(word, codes)| (word.as_str(), codes.iter().map(|code| code.decode()).collect()))
+            .collect();
+        eval::rule_accuracy(rules, &references)
+    }
+}
@@ -0,0 +1,68 @@
+//! 读音的去重存储（hash-consing）
+//!
+//! CMU词典中约14%的读音与其他词条完全相同（同音词、去掉变体标记后相同的读音）。哈希表存储的词典把所有不同的读音
+//! 连续存放在一个数组里，相同的读音只存一份，词条只记录读音在数组中的位置（8字节），不再为每个读音单独分配内存。
+//! 去重表只在构建词典时存在；运行时 `add_entry` 添加的读音直接追加到数组末尾。
+
+use crate::phoneme::PhonemeCode;
+use std::collections::HashMap;
+
+/// 读音在数组中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Span {
+    start: u32,
+    len: u32,
+}
+
+/// 连续存放的读音
+#[derive(Debug, Default)]
+pub(super) struct Arena {
+    codes: Vec<PhonemeCode>,
+}
+
+impl Arena {
+    pub(super) fn get(&self, span: Span) -> &[PhonemeCode] {
+        &self.codes[span.start as usize..(span.start + span.len) as usize]
+    }
+
+    /// 不去重，直接追加一个读音
+    pub(super) fn push(&mut self, codes: &[PhonemeCode]) -> Span {
+        let span = Span {
+            start: u32::try_from(self.codes.len()).expect("pronunciation arena exceeds 4G phonemes"),
+            len: codes.len() as u32,
+        };
+        self.codes.extend_from_slice(codes);
+        span
+    }
+
+    /// 堆内存占用（字节）
+    pub(super) fn heap_size(&self) -> usize {
+        self.codes.capacity() * std::mem::size_of::<PhonemeCode>()
+    }
+}
+
+/// 构建词典时的去重表
+#[derive(Default)]
+pub(super) struct Interner {
+    arena: Arena,
+    index: HashMap<Box<[PhonemeCode]>, Span>,
+}
+
+impl Interner {
+    /// 相同的读音已经存放过时返回已有的位置
+    pub(super) fn intern(&mut self, codes: &[PhonemeCode]) -> Span {
+        if let Some(&span) = self.index.get(codes) {
+            return span;
+        }
+        let span = self.arena.push(codes);
+        self.index.insert(codes.into(), span);
+        span
+    }
+
+    /// 丢弃去重表，返回紧凑的数组
+    pub(super) fn finish(self) -> Arena {
+        let mut arena = self.arena;
+        arena.codes.shrink_to_fit();
+        arena
+    }
+}
@@ -0,0 +1,130 @@
+//! 压缩的词典存储
+//!
+//! 单词存放在有限状态转换器（`fst` crate）中，共享前缀和后缀；读音去重后连续存放在一个数组里，
+//! 转换器的值指向读音。完整CMU词典的堆内存约为哈希表存储的五分之一（约2MB），查找稍慢，且不能修改。
+//! 转换器的值（64位）：
+//!
+//! ```text
+//! 最高位为0：读音在 codes 中的起点 << 8 | 音素数
+//! 最高位为1：多读音表 readings 中的起点 << 8 | 读音数，表中每项的编码同上，主读音在前
+//! ```
+
+use super::arena::{Arena, Span};
+use crate::phoneme::PhonemeCode;
+use crate::error::{invalid_data, Result};
+use fst::{Map, MapBuilder, Streamer};
+use smallvec::SmallVec;
+use std::collections::HashMap;
+
+/// 转换器的值指向多读音表
+const MULTIPLE: u64 = 1 << 63;
+
+/// 不可修改的压缩词典存储
+pub(super) struct CompactStorage {
+    words: Map<Vec<u8>>,
+    codes: Vec<PhonemeCode>, // 去重后的读音
+    readings: Vec<u64>,      // 多读音单词的各个读音
+}
+
+impl CompactStorage {
+    /// 由哈希表存储构建
+    pub(super) fn build(
+        entries: &HashMap<String, Span>,
+        variants: &HashMap<String, Vec<Span>>,
+        arena: &Arena,
+    ) -> Result<Self> {
+        let mut words: Vec<&String> = entries.keys().collect();
+        words.sort_unstable();
+
+        let mut codes = Vec::new();
+        let mut readings = Vec::new();
+        let mut interned: HashMap<&[PhonemeCode], u64> = HashMap::new();
+        let mut builder = MapBuilder::memory();
+        for word in words {
+            let primary = intern(arena.get(entries[word]), word, &mut codes, &mut interned)?;
+            let value = match variants.get(word) {
+                Some(others) => {
+                    let count = u8::try_from(others.len() + 1).map_err(|_| invalid_data!("Too many pronunciations for '{}'", word))?;
+                    let start = readings.len() as u64;
+                    readings.push(primary);
+                    for other in others {
+                        readings.push(intern(arena.get(*other), word, &mut codes, &mut interned)?);
+                    }
+                    MULTIPLE | start << 8 | u64::from(count)
+                }
+                None => primary,
+            };
+            builder.insert(word, value).map_err(|e| invalid_data!("Failed to build compact dictionary: {}", e))?;
+        }
+
+        let mut storage = Self { words: builder.into_map(), codes, readings };
+        storage.codes.shrink_to_fit();
+        storage.readings.shrink_to_fit();
+        Ok(storage)
+    }
+
+    /// 单词的主读音，`word` 为小写
+    pub(super) fn primary(&self, word: &str) -> Option<&[PhonemeCode]> {
+        let value = self.words.get(word)?;
+        Some(self.reading(if value & MULTIPLE == 0 { value } else { self.readings[Self::range(value).start] }))
+    }
+
+    /// 单词的所有读音，主读音在前
+    pub(super) fn all(&self, word: &str) -> SmallVec<[&[PhonemeCode]; 4]> {
+        self.words.get(word).map(|value| self.readings_of(value)).unwrap_or_default()
+    }
+
+    /// 按单词顺序访问每个单词和它的读音
+    pub(super) fn for_each<'a>(&'a self, mut visit: impl FnMut(&str, &[&'a [PhonemeCode]])) {
+        let mut stream = self.words.stream();
+        while let Some((word, value)) = stream.next() {
+            visit(&String::from_utf8_lossy(word), &self.readings_of(value));
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// 堆内存占用（字节）
+    pub(super) fn heap_size(&self) -> usize {
+        self.words.as_fst().as_bytes().len()
+            + self.codes.capacity() * std::mem::size_of::<PhonemeCode>()
+            + self.readings.capacity() * std::mem::size_of::<u64>()
+    }
+
+    fn readings_of(&self, value: u64) -> SmallVec<[&[PhonemeCode]; 4]> {
+        if value & MULTIPLE == 0 {
+            smallvec::smallvec![self.reading(value)]
+        } else {
+            self.readings[Self::range(value)].iter().map(|&reading| self.reading(reading)).collect()
+        }
+    }
+
+    fn reading(&self, value: u64) -> &[PhonemeCode] {
+        &self.codes[Self::range(value)]
+    }
+
+    /// 值中的起点和长度
+    fn range(value: u64) -> std::ops::Range<usize> {
+        let start = ((value & !MULTIPLE) >> 8) as usize;
+        start..start + (value & 0xff) as usize
+    }
+}
+
+/// 去重存放一个读音，返回转换器的值
+fn intern<'a>(
+    reading: &'a [PhonemeCode],
+    word: &str,
+    codes: &mut Vec<PhonemeCode>,
+    interned: &mut HashMap<&'a [PhonemeCode], u64>,
+) -> Result<u64> {
+    if let Some(&value) = interned.get(reading) {
+        return Ok(value);
+    }
+    let len = u8::try_from(reading.len()).map_err(|_| invalid_data!("Pronunciation of '{}' is too long", word))?;
+    let value = (codes.len() as u64) << 8 | u64::from(len);
+    codes.extend_from_slice(reading);
+    interned.insert(reading, value);
+    Ok(value)
+}
@@ -0,0 +1,206 @@
+//! 预编译的二进制词典
+//!
+//! 每次启动解析13万行的CMU文本词典需要明显的时间。`Dictionary::compile` 把文本词典转换为二进制文件，
+//! `Dictionary::load_compiled` 直接读取编码后的读音，不再逐行解析和校验音素。文件布局（整数均为小端序）：
+//!
+//! ```text
+//! 0   8  魔数 "G2PDICT\0"
+//! 8   4  格式版本，当前为 2
+//! 12  1  加载文本词典时的 DuplicatePolicy，加载时与要求的处理方式比较
+//! 13  4  文本词典的版本头（`DataSchema::version`），没有版本头时为 0
+//! 17  2  声明的特性（`DataSchema::declared`），按 `Capability` 的顺序编码为位掩码
+//! 19  2  用到的特性（`DataSchema::used`），编码同上
+//! 21  4  单词数
+//! 25  8  其后全部内容的校验和（FNV-1a）
+//! 33  …  每个单词：单词长度（1字节）、单词、读音数（1字节），
+//!        每个读音：音素数（1字节）、各音素的 PhonemeCode；主读音在前
+//! ```
+
+use super::{Dictionary, DuplicatePolicy, Interner, Span};
+use crate::golden::StableHasher;
+use crate::phoneme::PhonemeCode;
+use crate::schema::{Capability, DataSchema};
+use crate::error::{invalid_data, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+
+/// 二进制词典的魔数
+const MAGIC: &[u8; 8] = b"G2PDICT\0";
+
+/// 当前的二进制格式版本
+const FORMAT_VERSION: u32 = 2;
+
+const HEADER_LEN: usize = 33;
+
+/// 文件是否以二进制词典的魔数开头
+pub fn is_compiled(path: &str) -> bool {
+    let mut magic = [0; MAGIC.len()];
+    fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic))
+        .is_ok_and(|_| &magic == MAGIC)
+}
+
+impl Dictionary {
+    /// 加载文本词典并写出二进制词典，跳过的行等问题与 `load_cmu_dict` 一样记入日志
+    pub fn compile(path: &str, out: &str) -> Result<()> {
+        Self::load_cmu_dict(path)?.write_compiled(out)
+    }
+
+    /// 把词典写为二进制文件
+    pub fn write_compiled(&self, out: &str) -> Result<()> {
+        fs::write(out, self.to_compiled_bytes()?).with_context(|| format!("Failed to write compiled dictionary '{}'", out))
+    }
+
+    /// 读取 `compile` 生成的二进制词典，校验版本和校验和
+    pub fn load_compiled(path: &str) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read compiled dictionary '{}'", path))?;
+        Self::from_compiled_bytes(&bytes).with_context(|| format!("Failed to load compiled dictionary '{}'", path))
+    }
+
+    /// 编码为二进制词典，单词按字母顺序排列，输出稳定
+    pub fn to_compiled_bytes(&self) -> Result<Vec<u8>> {
+        let mut payload = Vec::new();
+        let mut result = Ok(());
+        self.for_each_entry(true, |word, readings| {
+            if result.is_ok() {
+                result = push_entry(&mut payload, word, readings);
+            }
+        });
+        result?;
+
+        let mut hasher = StableHasher::new();
+        hasher.write(&payload);
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.push(policy_byte(self.duplicates));
+        bytes.extend_from_slice(&self.schema.version.unwrap_or(0).to_le_bytes());
+        bytes.extend_from_slice(&Capability::to_bits(&self.schema.declared).to_le_bytes());
+        bytes.extend_from_slice(&Capability::to_bits(&self.schema.used).to_le_bytes());
+        bytes.extend_from_slice(&(self.size() as u32).to_le_bytes());
+        bytes.extend_from_slice(&hasher.finish().to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    /// 解码二进制词典
+    pub fn from_compiled_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data!("Not a compiled dictionary"));
+        }
+        let version = u32::from_le_bytes(bytes[8..12].try_into().map_err(|_| invalid_data!("Truncated header"))?);
+        if version != FORMAT_VERSION {
+            return Err(invalid_data!("Unsupported compiled dictionary version {} (expected {})", version, FORMAT_VERSION));
+        }
+        let duplicates = policy_from_byte(bytes[12])?;
+        let truncated = |_| invalid_data!("Truncated header");
+        let schema_version = u32::from_le_bytes(bytes[13..17].try_into().map_err(truncated)?);
+        let schema = DataSchema {
+            version: (schema_version != 0).then_some(schema_version),
+            declared: Capability::from_bits(u16::from_le_bytes(bytes[17..19].try_into().map_err(truncated)?))?,
+            used: Capability::from_bits(u16::from_le_bytes(bytes[19..21].try_into().map_err(truncated)?))?,
+        };
+        let count = u32::from_le_bytes(bytes[21..25].try_into().map_err(truncated)?) as usize;
+        let checksum = u64::from_le_bytes(bytes[25..33].try_into().map_err(truncated)?);
+
+        let payload = &bytes[HEADER_LEN..];
+        let mut hasher = StableHasher::new();
+        hasher.write(payload);
+        if hasher.finish() != checksum {
+            return Err(invalid_data!("Checksum mismatch, the file is corrupt or truncated"));
+        }
+
+        let mut reader = Reader { bytes: payload, pos: 0 };
+        // 每个单词至少占3字节，单词数不可信时也不会预留过多内存
+        let mut entries = HashMap::with_capacity(count.min(payload.len() / 3));
+        let mut variants: HashMap<String, Vec<Span>> = HashMap::new();
+        let mut interner = Interner::default();
+        for _ in 0..count {
+            let len = reader.byte()?;
+            let word = std::str::from_utf8(reader.take(len.into())?).map_err(|_| invalid_data!("Invalid UTF-8 in word"))?.to_string();
+            let readings = reader.byte()?;
+            if readings == 0 {
+                return Err(invalid_data!("No pronunciations for '{}'", word));
+            }
+            let mut codes = Vec::with_capacity(readings.into());
+            for _ in 0..readings {
+                let len = reader.byte()?;
+                let reading = reader
+                    .take(len.into())?
+                    .iter()
+                    .map(|&byte| PhonemeCode::from_u8(byte).ok_or_else(|| invalid_data!("Invalid phoneme code {} in '{}'", byte, word)))
+                    .collect::<Result<Vec<PhonemeCode>>>()?;
+                codes.push(interner.intern(&reading));
+            }
+            let primary = codes.remove(0);
+            if !codes.is_empty() {
+                variants.insert(word.clone(), codes);
+            }
+            entries.insert(word, primary);
+        }
+        if reader.pos != payload.len() {
+            return Err(invalid_data!("{} trailing bytes after {} words", payload.len() - reader.pos, count));
+        }
+
+        Ok(Self { entries, variants, arena: interner.finish(), compact: None, schema, duplicates })
+    }
+}
+
+/// 写入一个单词和它的读音
+fn push_entry(payload: &mut Vec<u8>, word: &str, readings: &[&[PhonemeCode]]) -> Result<()> {
+    push_len(payload, word.len(), word)?;
+    payload.extend_from_slice(word.as_bytes());
+    push_len(payload, readings.len(), word)?;
+    for codes in readings {
+        push_len(payload, codes.len(), word)?;
+        payload.extend(codes.iter().map(|code| code.to_u8()));
+    }
+    Ok(())
+}
+
+/// 写入一个单字节长度，超过255时报错
+fn push_len(out: &mut Vec<u8>, len: usize, word: &str) -> Result<()> {
+    let len = u8::try_from(len).map_err(|_| invalid_data!("Entry '{}' is too long for the compiled format", word))?;
+    out.push(len);
+    Ok(())
+}
+
+fn policy_byte(policy: DuplicatePolicy) -> u8 {
+    match policy {
+        DuplicatePolicy::KeepLast => 0,
+        DuplicatePolicy::KeepFirst => 1,
+        DuplicatePolicy::KeepVariants => 2,
+        DuplicatePolicy::Overwrite => 3,
+        DuplicatePolicy::Error => 4,
+    }
+}
+
+fn policy_from_byte(byte: u8) -> Result<DuplicatePolicy> {
+    Ok(match byte {
+        0 => DuplicatePolicy::KeepLast,
+        1 => DuplicatePolicy::KeepFirst,
+        2 => DuplicatePolicy::KeepVariants,
+        3 => DuplicatePolicy::Overwrite,
+        4 => DuplicatePolicy::Error,
+        _ => return Err(invalid_data!("Unknown duplicate policy {}", byte)),
+    })
+}
+
+/// 按顺序读取载荷，越界时报错
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos + len;
+        let slice = self.bytes.get(self.pos..end).ok_or_else(|| invalid_data!("Unexpected end of data"))?;
+        self.pos = end;
+        Ok(slice)
+    }
+}
@@ -0,0 +1,167 @@
+//! 按音系特征评估文本的朗读难度
+//!
+//! 每个单词的难度由三部分相加：
+//!
+//! - 音节数：第一个音节之后每个音节加 1
+//! - 辅音丛：每个音节的首音或尾音中，辅音丛从第二个辅音起每个加 1
+//! - 少见的音素组合：词典中出现频率低于万分之一的相邻音素对，每处加 2（不计重音）
+//!
+//! 文本的难度为各单词难度的平均值，0 表示全是单音节、没有辅音丛的常见单词。
+//! 音素组合的频率从转换器的词典统计，`RustG2P::difficulty_scorer` 统计一次后可以反复使用。
+
+use crate::dict::Dictionary;
+use crate::phoneme::{Phoneme, PhonemeCode};
+use crate::syllable;
+use crate::RustG2P;
+use crate::error::Result;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+/// 低于这个比例的音素对算作少见
+const RARE_BIGRAM_RATIO: f64 = 1e-4;
+
+/// 每处少见音素对的分数
+const RARE_BIGRAM_WEIGHT: f64 = 2.0;
+
+/// 按词典中的音素对频率评分
+#[derive(Debug, Clone, Default)]
+pub struct DifficultyScorer {
+    bigrams: HashMap<(&'static str, &'static str), u32>,
+    total: u64,
+}
+
+/// 单个单词的难度
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordDifficulty {
+    pub word: String,
+    pub syllables: usize,
+    pub max_cluster: usize,    // 最长的首音或尾音辅音丛
+    pub cluster_cost: usize,   // 辅音丛的分数
+    pub rare_bigrams: usize,   // 少见音素对的个数
+    pub score: f64,
+}
+
+/// 一段文本的难度
+#[derive(Debug, Clone, Default)]
+pub struct DifficultyReport {
+    pub words: Vec<WordDifficulty>, // 按文本顺序，不含不发音的词元
+}
+
+impl DifficultyScorer {
+    /// 统计词典中相邻音素对的出现次数
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        let mut scorer = Self::default();
+        for codes in dictionary.pronunciation_codes() {
+            for pair in codes.windows(2) {
+                *scorer.bigrams.entry((pair[0].symbol(), pair[1].symbol())).or_default() += 1;
+                scorer.total += 1;
+            }
+        }
+        scorer
+    }
+
+    /// 音素对是否少见；词典为空时都不算少见
+    fn is_rare(&self, a: &'static str, b: &'static str) -> bool {
+        let count = self.bigrams.get(&(a, b)).copied().unwrap_or(0);
+        self.total > 0 && (count as f64) < self.total as f64 * RARE_BIGRAM_RATIO
+    }
+
+    /// 单词的难度
+    pub fn word(&self, word: &str, phonemes: &[Phoneme]) -> WordDifficulty {
+        let syllables = syllable::syllabify(phonemes);
+        let mut max_cluster = 0;
+        let mut cluster_cost = 0;
+        for syllable in &syllables {
+            let (onset, coda) = match syllable.nucleus {
+                Some(nucleus) => (nucleus - syllable.start, syllable.end - nucleus - 1),
+                None => (syllable.len(), 0),
+            };
+            for cluster in [onset, coda] {
+                max_cluster = max_cluster.max(cluster);
+                cluster_cost += cluster.saturating_sub(1);
+            }
+        }
+
+        let codes: Vec<&'static str> = phonemes.iter().filter_map(PhonemeCode::encode).map(PhonemeCode::symbol).collect();
+        let rare_bigrams = codes.windows(2).filter(|pair| self.is_rare(pair[0], pair[1])).count();
+
+        let score = syllables.len().saturating_sub(1) as f64 + cluster_cost as f64 + rare_bigrams as f64 * RARE_BIGRAM_WEIGHT;
+        WordDifficulty {
+            word: word.to_string(),
+            syllables: syllables.len(),
+            max_cluster,
+            cluster_cost,
+            rare_bigrams,
+            score,
+        }
+    }
+
+    /// 转换文本并评估每个单词
+    pub fn text(&self, g2p: &RustG2P, text: &str) -> Result<DifficultyReport> {
+        let words = g2p
+            .text_to_words(text)?
+            .into_iter()
+            .filter(|word| !word.phonemes.is_empty())
+            .map(|word| self.word(&word.word, &word.phonemes))
+            .collect();
+        Ok(DifficultyReport { words })
+    }
+}
+
+impl DifficultyReport {
+    /// 平均每词的难度，没有单词时为 0
+    pub fn score(&self) -> f64 {
+        self.mean(|word| word.score)
+    }
+
+    /// 平均每词的音节数
+    pub fn syllables_per_word(&self) -> f64 {
+        self.mean(|word| word.syllables as f64)
+    }
+
+    /// 三个及以上音节的单词所占比例
+    pub fn polysyllabic_ratio(&self) -> f64 {
+        self.mean(|word| if word.syllables >= 3 { 1.0 } else { 0.0 })
+    }
+
+    /// 最难的 `n` 个单词，同分时按文本顺序
+    pub fn hardest(&self, n: usize) -> Vec<&WordDifficulty> {
+        let mut words: Vec<&WordDifficulty> = self.words.iter().collect();
+        words.sort_by(|a, b| b.score.total_cmp(&a.score));
+        words.truncate(n);
+        words
+    }
+
+    fn mean(&self, value: impl Fn(&WordDifficulty) -> f64) -> f64 {
+        if self.words.is_empty() {
+            return 0.0;
+        }
+        self.words.iter().map(value).sum::<f64>() / self.words.len() as f64
+    }
+}
+
+impl fmt::Display for DifficultyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!(
+            "score {:.2}, {:.2} syllables/word, {:.0}% polysyllabic\n",
+            self.score(),
+            self.syllables_per_word(),
+            self.polysyllabic_ratio() * 100.0
+        );
+        for word in self.hardest(5).into_iter().filter(|word| word.score > 0.0) {
+            let _ = writeln!(
+                out,
+                "  {:<16} {:>5.1}  ({} syllables, cluster {}, {} rare)",
+                word.word, word.score, word.syllables, word.max_cluster, word.rare_bigrams
+            );
+        }
+        f.write_str(&out)
+    }
+}
+
+impl RustG2P {
+    /// 用本转换器的词典统计音素对频率，创建难度评分器
+    pub fn difficulty_scorer(&self) -> DifficultyScorer {
+        DifficultyScorer::from_dictionary(&self.dictionary)
+    }
+}
@@ -0,0 +1,194 @@
+//! 发音对比练习（绕口令）生成
+//!
+//! 言语治疗常用一对容易混淆的音素（如 /s/ 和 /ʃ/）做对比练习。`DrillGenerator` 为一组词汇建立
+//! 音素到单词的反向索引，按两个音素在单词中出现的密度挑选单词，交替排成练习序列；
+//! 读音只差这一对音素的单词（最小对立体，如 sip / ship）成对排在最前面：
+//!
+//! ```text
+//! sue shoe sell shell sip ship miss fish
+//! ```
+//!
+//! `contrast_distance` 按发音特征给出两个音素的差异，`nearest_contrasts` 列出与某个音素最接近、
+//! 也就最容易混淆的音素，供挑选练习的对比。
+
+use crate::align;
+use crate::phoneme::{Phoneme, PhonemeCode};
+use crate::RustG2P;
+use crate::error::{invalid_data, G2PError, Result};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// 为一组词汇生成对比练习
+#[derive(Debug, Clone, Default)]
+pub struct DrillGenerator {
+    words: Vec<(String, Vec<&'static str>)>,  // 单词和不带重音的音素符号
+    index: HashMap<&'static str, Vec<usize>>, // 音素 → 含有该音素的单词编号
+}
+
+/// 一组对比练习
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drill {
+    pub contrast: (&'static str, &'static str),
+    pub distance: f32,                        // 两个音素的发音特征差异，越小越难区分
+    pub minimal_pairs: Vec<(String, String)>, // 按 (含前一个音素的单词, 含后一个音素的单词) 排列
+    pub words: Vec<String>,                   // 练习序列
+}
+
+impl DrillGenerator {
+    /// 转换词汇表中的单词并建立反向索引，重复的单词只保留一个
+    pub fn build<S: AsRef<str>>(g2p: &RustG2P, words: &[S]) -> Result<Self> {
+        let mut generator = Self::default();
+        let mut seen = HashSet::new();
+        for word in words {
+            let word = word.as_ref().to_lowercase();
+            if !seen.insert(word.clone()) {
+                continue;
+            }
+            let symbols: Vec<&'static str> = g2p
+                .word_to_phonemes(&word)?
+                .iter()
+                .filter_map(PhonemeCode::encode)
+                .map(PhonemeCode::symbol)
+                .collect();
+            let id = generator.words.len();
+            for &symbol in &symbols {
+                let postings = generator.index.entry(symbol).or_default();
+                if postings.last() != Some(&id) {
+                    postings.push(id);
+                }
+            }
+            generator.words.push((word, symbols));
+        }
+        Ok(generator)
+    }
+
+    /// 生成集中练习 `a` 和 `b` 两个音素（ARPAbet符号，忽略重音）的序列，至多 `length` 个单词
+    ///
+    /// 先按长度从短到长排列最小对立体，再交替取含 `a` 和含 `b` 的单词，按音素密度从高到低。
+    pub fn generate(&self, a: &str, b: &str, length: usize) -> Result<Drill> {
+        let (a, b) = (parse_symbol(a)?, parse_symbol(b)?);
+        if a == b {
+            return Err(invalid_data!("A contrast needs two different phonemes, got '{}' twice", a));
+        }
+
+        let minimal_pairs = self.minimal_pairs(a, b);
+        let mut used: HashSet<usize> = HashSet::new();
+        let mut words = Vec::new();
+        for &(x, y) in &minimal_pairs {
+            if words.len() + 2 > length {
+                break;
+            }
+            used.extend([x, y]);
+            words.extend([x, y]);
+        }
+
+        let (mut with_a, mut with_b) = (self.ranked(a, b, &used).into_iter(), self.ranked(b, a, &used).into_iter());
+        while words.len() < length {
+            let next = if words.len() % 2 == 0 {
+                with_a.next().or_else(|| with_b.next())
+            } else {
+                with_b.next().or_else(|| with_a.next())
+            };
+            match next {
+                Some(id) if used.insert(id) => words.push(id),
+                Some(_) => {}
+                None => break,
+            }
+        }
+
+        let word = |id: usize| self.words[id].0.clone();
+        Ok(Drill {
+            contrast: (a, b),
+            distance: contrast_distance(a, b),
+            minimal_pairs: minimal_pairs.into_iter().map(|(x, y)| (word(x), word(y))).collect(),
+            words: words.into_iter().map(word).collect(),
+        })
+    }
+
+    /// 把一个 `a` 换成 `b` 后与另一个单词读音相同的单词对，按长度和拼写排列，每个单词只出现一次
+    fn minimal_pairs(&self, a: &'static str, b: &'static str) -> Vec<(usize, usize)> {
+        let by_sound: HashMap<&[&'static str], usize> = self
+            .postings(b)
+            .iter()
+            .map(|&id| (self.words[id].1.as_slice(), id))
+            .collect();
+
+        let mut pairs = Vec::new();
+        let mut used = HashSet::new();
+        for &id in self.postings(a) {
+            let symbols = &self.words[id].1;
+            for (i, _) in symbols.iter().enumerate().filter(|(_, &symbol)| symbol == a) {
+                let mut swapped = symbols.clone();
+                swapped[i] = b;
+                if let Some(&other) = by_sound.get(swapped.as_slice()) {
+                    pairs.push((id, other));
+                }
+            }
+        }
+        pairs.sort_by_key(|&(x, y)| (self.words[x].1.len() + self.words[y].1.len(), self.words[x].0.as_str()));
+        pairs.retain(|&(x, y)| {
+            let fresh = !used.contains(&x) && !used.contains(&y);
+            if fresh {
+                used.extend([x, y]);
+            }
+            fresh
+        });
+        pairs
+    }
+
+    /// 含 `target` 的单词，按两个音素所占比例从高到低、再按拼写排列
+    fn ranked(&self, target: &str, other: &str, used: &HashSet<usize>) -> Vec<usize> {
+        let density = |id: usize| {
+            let symbols = &self.words[id].1;
+            symbols.iter().filter(|&&s| s == target || s == other).count() as f32 / symbols.len() as f32
+        };
+        let mut ids: Vec<usize> = self.postings(target).iter().copied().filter(|id| !used.contains(id)).collect();
+        ids.sort_by(|&x, &y| density(y).total_cmp(&density(x)).then_with(|| self.words[x].0.cmp(&self.words[y].0)));
+        ids
+    }
+
+    fn postings(&self, symbol: &str) -> &[usize] {
+        self.index.get(symbol).map_or(&[], Vec::as_slice)
+    }
+
+    /// 词汇表中的单词数
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl fmt::Display for Drill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.words.join(" "))
+    }
+}
+
+/// 两个音素（ARPAbet符号）的发音特征差异，见 `align::feature_distance`
+pub fn contrast_distance(a: &str, b: &str) -> f32 {
+    align::feature_distance(&Phoneme::from_arpabet(a), &Phoneme::from_arpabet(b))
+}
+
+/// 与 `symbol` 发音特征最接近的 `n` 个同类音素（元音或辅音），按差异从小到大排列
+pub fn nearest_contrasts(symbol: &str, n: usize) -> Result<Vec<(&'static str, f32)>> {
+    let symbol = parse_symbol(symbol)?;
+    let mut nearest: Vec<(&'static str, f32)> = PhonemeCode::all()
+        .map(PhonemeCode::symbol)
+        .filter(|&other| other != symbol)
+        .map(|other| (other, contrast_distance(symbol, other)))
+        .filter(|&(_, distance)| distance < 1.0)
+        .collect();
+    nearest.sort_by(|x, y| x.1.total_cmp(&y.1));
+    nearest.truncate(n);
+    Ok(nearest)
+}
+
+/// 解析ARPAbet符号，去掉重音数字
+fn parse_symbol(symbol: &str) -> Result<&'static str> {
+    PhonemeCode::encode(&Phoneme::from_arpabet(&symbol.to_uppercase()))
+        .map(PhonemeCode::symbol)
+        .ok_or_else(|| G2PError::InvalidPhoneme(symbol.to_string()))
+}
@@ -0,0 +1,27 @@
+//! 编译时嵌入的默认数据文件（`embedded-data` 特性）
+//!
+//! 启用后 `RustG2P::new()` 不再读取 `data/` 目录，二进制文件可以单独发布。嵌入的是完整的CMU词典、
+//! 英语规则和不规则词汇，与 `data/` 中的文件相同；按路径加载的接口（配置文件、构建器、语言包）不受影响。
+
+use crate::{Dictionary, LexiconLayer, RulesEngine, RustG2P};
+use crate::error::Result;
+
+/// CMU词典，文件中有个别非UTF-8字节，按字节嵌入
+pub const DICTIONARY: &[u8] = include_bytes!("../data/cmudict.txt");
+
+/// 英语规则
+pub const RULES: &str = include_str!("../data/en_rules.txt");
+
+/// 不规则词汇
+pub const IRREGULAR: &str = include_str!("../data/en_irregular.txt");
+
+impl RustG2P {
+    /// 使用嵌入的默认数据创建转换器，不读取任何文件
+    pub fn embedded() -> Result<Self> {
+        let dictionary = Dictionary::from_cmu_str(&String::from_utf8_lossy(DICTIONARY))?;
+        let rules_engine = RulesEngine::parse_english_rules(RULES)?;
+        let irregular = LexiconLayer::from_cmu_str(IRREGULAR)?;
+
+        Ok(Self::from_parts(dictionary, rules_engine).with_irregular(irregular))
+    }
+}
@@ -0,0 +1,129 @@
+//! 辅音丛中插入央元音（增音）
+//!
+//! 部分合成目标（外来词、面向二语学习者的慢速语音）需要把难发的辅音丛拆开，如 "street" 读作
+//! `S AH0 T R IY1 T`。每条规则写作两个音素，可加词首或词尾标记：
+//!
+//! - `S T`：任意位置的 S T 之间插入
+//! - `# S [consonant]`：只在词首
+//! - `[consonant] L #`：只在词尾
+//!
+//! 音素写法与音素改写规则相同（`[vowel]`、`[consonant]`、不带重音数字时忽略重音），两个音素都须是辅音。
+
+use crate::phoneme::Phoneme;
+use crate::rewrite;
+use crate::error::{invalid_data, Result};
+use std::fmt;
+
+/// 辅音丛在单词中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterPosition {
+    Anywhere,
+    WordInitial,
+    WordFinal,
+}
+
+/// 需要插入央元音的辅音丛
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterContext {
+    pub first: String,
+    pub second: String,
+    pub position: ClusterPosition,
+}
+
+/// 央元音插入设置，默认不插入
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchwaInsertion {
+    contexts: Vec<ClusterContext>,
+}
+
+impl ClusterContext {
+    /// 解析 `# S [consonant]` 形式的规则
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut items: Vec<&str> = spec.split_whitespace().collect();
+        let initial = items.first() == Some(&"#");
+        let last = items.len().saturating_sub(1);
+        let is_final = items.len() > 1 && items[last] == "#";
+        if is_final {
+            items.pop();
+        }
+        if initial {
+            items.remove(0);
+        }
+        let position = match (initial, is_final) {
+            (false, false) => ClusterPosition::Anywhere,
+            (true, false) => ClusterPosition::WordInitial,
+            (false, true) => ClusterPosition::WordFinal,
+            (true, true) => return Err(invalid_data!("Cluster '{}' cannot be both word-initial and word-final", spec)),
+        };
+        let [first, second] = items[..] else {
+            return Err(invalid_data!("Expected two phonemes in cluster '{}'", spec));
+        };
+        for item in [first, second] {
+            if matches!(item, "_" | "[vowel]") {
+                return Err(invalid_data!("Cluster '{}' must consist of consonants", spec));
+            }
+            rewrite::validate_item(item)?;
+        }
+        Ok(Self { first: first.to_string(), second: second.to_string(), position })
+    }
+
+    /// 从 `index` 开始的两个音素是否匹配
+    fn matches(&self, phonemes: &[Phoneme], index: usize) -> bool {
+        let (a, b) = (&phonemes[index], &phonemes[index + 1]);
+        let boundary = |i: Option<usize>| i.and_then(|i| phonemes.get(i)).is_none_or(|p| p.symbol == " ");
+        a.is_consonant()
+            && b.is_consonant()
+            && rewrite::item_matches(&self.first, a)
+            && rewrite::item_matches(&self.second, b)
+            && match self.position {
+                ClusterPosition::Anywhere => true,
+                ClusterPosition::WordInitial => boundary(index.checked_sub(1)),
+                ClusterPosition::WordFinal => boundary(Some(index + 2)),
+            }
+    }
+}
+
+impl fmt::Display for ClusterContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            ClusterPosition::Anywhere => write!(f, "{} {}", self.first, self.second),
+            ClusterPosition::WordInitial => write!(f, "# {} {}", self.first, self.second),
+            ClusterPosition::WordFinal => write!(f, "{} {} #", self.first, self.second),
+        }
+    }
+}
+
+impl SchwaInsertion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一条 `# S [consonant]` 形式的规则
+    pub fn with_cluster(mut self, spec: &str) -> Result<Self> {
+        self.contexts.push(ClusterContext::parse(spec)?);
+        Ok(self)
+    }
+
+    pub fn contexts(&self) -> &[ClusterContext] {
+        &self.contexts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    /// 在匹配的辅音之间插入 `AH0`，匹配按插入前的序列判断
+    pub fn apply(&self, phonemes: Vec<Phoneme>) -> Vec<Phoneme> {
+        if self.contexts.is_empty() || phonemes.len() < 2 {
+            return phonemes;
+        }
+        let mut out = Vec::with_capacity(phonemes.len() + 2);
+        for i in 0..phonemes.len() {
+            out.push(phonemes[i].clone());
+            if i + 1 < phonemes.len() && self.contexts.iter().any(|context| context.matches(&phonemes, i)) {
+                out.push(Phoneme::from_arpabet("AH0"));
+            }
+        }
+        out
+    }
+}
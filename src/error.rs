@@ -0,0 +1,135 @@
+//! 库的错误类型
+//!
+//! 所有公开函数都返回 `G2PError`，调用方可以按变体区分错误（词典文件不存在、规则文件格式错误、
+//! 未登录词无法转换等），不必匹配错误信息。加载过程的上下文（如正在读取的文件）包装为 `Context` 变体，
+//! `G2PError::root` 取得最内层的错误。
+
+use crate::UnknownCharacterError;
+use std::fmt;
+
+/// 库的错误类型
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum G2PError {
+    /// 词典文件不存在
+    #[error("CMU dictionary file not found: {path}")]
+    DictNotFound { path: String },
+
+    /// 规则文件中无法解析的行，`line` 从1开始
+    #[error("Line {line}: {message}")]
+    MalformedRule { line: usize, message: String },
+
+    /// 词典文件中无法解析的行，`line` 从1开始
+    #[error("Line {line}: {message}")]
+    MalformedEntry { line: usize, message: String },
+
+    /// 不在音素表中的音素
+    #[error("Unknown phoneme '{0}'")]
+    InvalidPhoneme(String),
+
+    /// 未登录词的规则读音不可靠，且后备策略为 `Fallback::Error`
+    #[error("Unreliable rule pronunciation for '{word}' (confidence {confidence:.2}, phonotactically valid: {valid})")]
+    OovWord { word: String, confidence: f32, valid: bool },
+
+    /// 未登录词超过最大长度，且处理方式为 `LongWordPolicy::Error`；信息中不含单词本身，以免输出过长
+    #[error("Word of {length} characters exceeds the maximum length {limit}")]
+    WordTooLong { word: String, length: usize, limit: usize },
+
+    /// 规则引擎没有适用于某个字符的规则，`position` 按字符计
+    #[error("No rule found for character '{character}' at position {position}")]
+    NoRule { character: char, position: usize },
+
+    /// 严格模式下遇到无法处理的字符
+    #[error(transparent)]
+    UnknownCharacter(#[from] UnknownCharacterError),
+
+    /// 配置无效
+    #[error("{0}")]
+    Config(String),
+
+    /// 数据格式错误（快照、二进制词典、字幕等）
+    #[error("{0}")]
+    InvalidData(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// gRPC服务器的传输层错误
+    #[cfg(feature = "grpc")]
+    #[error(transparent)]
+    Transport(#[from] tonic::transport::Error),
+
+    /// 带上下文说明的错误
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<G2PError>,
+    },
+}
+
+/// 库函数的返回类型
+pub type Result<T, E = G2PError> = std::result::Result<T, E>;
+
+impl G2PError {
+    /// 去掉所有 `Context` 包装后的错误
+    pub fn root(&self) -> &G2PError {
+        match self {
+            G2PError::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// 上下文和各层原因，以 ": " 连接
+    pub fn chain(&self) -> Chain<'_> {
+        Chain(self)
+    }
+}
+
+/// `G2PError::chain` 的显示格式
+pub struct Chain<'a>(&'a G2PError);
+
+impl fmt::Display for Chain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        let mut source = std::error::Error::source(self.0);
+        while let Some(error) = source {
+            write!(f, ": {}", error)?;
+            source = error.source();
+        }
+        Ok(())
+    }
+}
+
+/// 为错误加上上下文说明
+pub(crate) trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T>;
+    fn with_context<S: Into<String>>(self, context: impl FnOnce() -> S) -> Result<T>;
+}
+
+impl<T, E: Into<G2PError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.with_context(|| context)
+    }
+
+    fn with_context<S: Into<String>>(self, context: impl FnOnce() -> S) -> Result<T> {
+        self.map_err(|error| G2PError::Context {
+            context: context().into(),
+            source: Box::new(error.into()),
+        })
+    }
+}
+
+/// 以格式化字符串构造 `G2PError::InvalidData`
+macro_rules! invalid_data {
+    ($($arg:tt)*) => {
+        $crate::error::G2PError::InvalidData(format!($($arg)*))
+    };
+}
+pub(crate) use invalid_data;
@@ -0,0 +1,218 @@
+use crate::align::{self, AlignStep};
+use crate::phoneme::Phoneme;
+use crate::rules::RulesEngine;
+use crate::RustG2P;
+use std::fmt::{self, Write};
+
+/// 两种配置在同一词表上的对比报告
+#[derive(Debug, Clone)]
+pub struct StrategyComparison {
+    pub total: usize,
+    pub disagreements: Vec<Disagreement>,
+}
+
+/// 两种配置结果不一致的单词
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+    pub word: String,
+    pub a: Result<Vec<Phoneme>, String>,
+    pub b: Result<Vec<Phoneme>, String>,
+}
+
+/// 对齐后的一个差异位置
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DiffOp {
+    Same(String),
+    Substitute(String, String),
+    Delete(String), // 仅在A中出现
+    Insert(String), // 仅在B中出现
+}
+
+/// 规则引擎在参考发音上的准确率（比较时忽略重音）
+#[derive(Debug, Clone, Default)]
+pub struct AccuracyReport {
+    pub total: usize,
+    pub words_correct: usize,
+    pub phoneme_errors: usize, // 对齐后的编辑距离之和
+    pub reference_phonemes: usize,
+}
+
+/// 用两种配置转换同一词表，收集结果不一致的单词
+pub fn compare_strategies<S: AsRef<str>>(g2p_a: &RustG2P, g2p_b: &RustG2P, word_list: &[S]) -> StrategyComparison {
+    let mut disagreements = Vec::new();
+
+    for word in word_list {
+        let word = word.as_ref();
+        let a = g2p_a.word_to_phonemes(word).map_err(|e| e.to_string());
+        let b = g2p_b.word_to_phonemes(word).map_err(|e| e.to_string());
+        if a != b {
+            disagreements.push(Disagreement { word: word.to_string(), a, b });
+        }
+    }
+
+    StrategyComparison {
+        total: word_list.len(),
+        disagreements,
+    }
+}
+
+/// 用规则引擎转换每个参考单词，统计单词准确率和音素错误率
+pub fn rule_accuracy<S: AsRef<str>>(rules: &RulesEngine, references: &[(S, Vec<Phoneme>)]) -> AccuracyReport {
+    let mut report = AccuracyReport {
+        total: references.len(),
+        ..Default::default()
+    };
+    
+    for (word, expected) in references {
+        let expected: Vec<String> = expected.iter().map(|p| p.symbol.clone()).collect();
+        let actual: Vec<String> = match rules.apply_rules(word.as_ref()) {
+            Ok(phonemes) => phonemes.into_iter().map(|p| p.symbol).collect(),
+            Err(_) => Vec::new(),
+        };
+        
+        let errors = align(&expected, &actual)
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Same(_)))
+            .count();
+        if errors == 0 {
+            report.words_correct += 1;
+        }
+        report.phoneme_errors += errors;
+        report.reference_phonemes += expected.len();
+    }
+    
+    report
+}
+
+impl AccuracyReport {
+    pub fn word_accuracy(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.words_correct as f64 / self.total as f64
+        }
+    }
+    
+    pub fn phoneme_error_rate(&self) -> f64 {
+        if self.reference_phonemes == 0 {
+            0.0
+        } else {
+            self.phoneme_errors as f64 / self.reference_phonemes as f64
+        }
+    }
+}
+
+impl fmt::Display for AccuracyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} words, {:.1}% word accuracy, {:.1}% phoneme error rate",
+            self.total,
+            self.word_accuracy() * 100.0,
+            self.phoneme_error_rate() * 100.0
+        )
+    }
+}
+
+impl StrategyComparison {
+    /// 两种配置结果一致的比例
+    pub fn agreement_rate(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.total - self.disagreements.len()) as f64 / self.total as f64
+        }
+    }
+
+    /// 渲染完整的文本报告
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{} words compared, {} disagreements ({:.1}% agreement)",
+            self.total,
+            self.disagreements.len(),
+            self.agreement_rate() * 100.0
+        );
+        for disagreement in &self.disagreements {
+            out.push_str(&disagreement.render());
+        }
+        out
+    }
+}
+
+impl Disagreement {
+    /// 音素级对齐差异，任一方出错时为空
+    pub fn diff(&self) -> Vec<DiffOp> {
+        match (&self.a, &self.b) {
+            (Ok(a), Ok(b)) => align(&symbols(a), &symbols(b)),
+            _ => Vec::new(),
+        }
+    }
+
+    /// 渲染单个差异，如 `HH [AH0→EH1] L OW1`
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", self.word);
+        let _ = writeln!(out, "  A: {}", render_outcome(&self.a));
+        let _ = writeln!(out, "  B: {}", render_outcome(&self.b));
+
+        let diff = self.diff();
+        if !diff.is_empty() {
+            let ops: Vec<String> = diff.iter().map(DiffOp::to_string).collect();
+            let _ = writeln!(out, "  diff: {}", ops.join(" "));
+        }
+        out
+    }
+}
+
+impl fmt::Display for DiffOp {
+    /// 相同的音素原样输出，差异输出为 `[AH0→EH1]`、`[-T]`、`[+T]`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffOp::Same(p) => f.write_str(p),
+            DiffOp::Substitute(a, b) => write!(f, "[{}→{}]", a, b),
+            DiffOp::Delete(a) => write!(f, "[-{}]", a),
+            DiffOp::Insert(b) => write!(f, "[+{}]", b),
+        }
+    }
+}
+
+fn symbols(phonemes: &[Phoneme]) -> Vec<String> {
+    phonemes.iter().map(|p| p.to_string()).collect()
+}
+
+fn render_outcome(outcome: &Result<Vec<Phoneme>, String>) -> String {
+    match outcome {
+        Ok(phonemes) => symbols(phonemes).join(" "),
+        Err(e) => format!("ERROR: {}", e),
+    }
+}
+
+/// 编辑距离（插入、删除、替换代价均为1）
+pub(crate) fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = (previous[j] + usize::from(x != y)).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// 最小编辑距离对齐
+pub(crate) fn align(a: &[String], b: &[String]) -> Vec<DiffOp> {
+    align::align_by(a, b, 1.0, 1.0, |x, y| (x != y).then_some(1.0))
+        .steps
+        .into_iter()
+        .map(|step| match step {
+            AlignStep::Match(i, _) => DiffOp::Same(a[i].clone()),
+            AlignStep::Substitute(i, j) => DiffOp::Substitute(a[i].clone(), b[j].clone()),
+            AlignStep::Delete(i) => DiffOp::Delete(a[i].clone()),
+            AlignStep::Insert(j) => DiffOp::Insert(b[j].clone()),
+        })
+        .collect()
+}
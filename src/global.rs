@@ -0,0 +1,60 @@
+//! 进程内共享的转换器
+//!
+//! `global()` 在第一次调用时创建转换器，之后返回同一个实例，`phonemize` 这样的工具函数不必在每个调用点
+//! 传递转换器。配置按以下顺序查找：
+//!
+//! 1. 环境变量 `G2P_CONFIG` 指定的配置文件
+//! 2. 当前目录下的 `g2p.toml`
+//! 3. 都没有时使用默认的数据文件（同 `RustG2P::new`）
+//!
+//! 创建失败时返回错误，下一次调用重新尝试。
+
+use crate::phoneme::Phoneme;
+use crate::RustG2P;
+use crate::error::{Context, Result};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// 指定共享转换器配置文件的环境变量
+pub const CONFIG_ENV: &str = "G2P_CONFIG";
+
+/// 未设置 `G2P_CONFIG` 时在当前目录查找的配置文件
+pub const DEFAULT_CONFIG: &str = "g2p.toml";
+
+static GLOBAL: OnceLock<RustG2P> = OnceLock::new();
+static INIT: Mutex<()> = Mutex::new(());
+
+/// 进程内共享的转换器，第一次调用时按配置创建
+pub fn global() -> Result<&'static RustG2P> {
+    if let Some(g2p) = GLOBAL.get() {
+        return Ok(g2p);
+    }
+    // 同时只有一个线程创建，失败时不缓存错误
+    let _guard = INIT.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(g2p) = GLOBAL.get() {
+        return Ok(g2p);
+    }
+    let g2p = load().context("Failed to initialize the global converter")?;
+    Ok(GLOBAL.get_or_init(|| g2p))
+}
+
+/// 用共享转换器转换单词
+pub fn phonemize(word: &str) -> Result<Vec<Phoneme>> {
+    global()?.word_to_phonemes(word)
+}
+
+/// 用共享转换器转换文本，按配置的记法输出
+pub fn phonemize_text(text: &str) -> Result<String> {
+    let g2p = global()?;
+    Ok(g2p.render(&g2p.text_to_phonemes(text)?))
+}
+
+fn load() -> Result<RustG2P> {
+    if let Some(path) = std::env::var_os(CONFIG_ENV) {
+        return RustG2P::from_config(&path.to_string_lossy());
+    }
+    if Path::new(DEFAULT_CONFIG).is_file() {
+        return RustG2P::from_config(DEFAULT_CONFIG);
+    }
+    RustG2P::new()
+}
@@ -0,0 +1,212 @@
+//! 黄金语料快照：记录一组固定输入的转换结果，升级前后比对以锁定行为
+//!
+//! 转换结果是确定的：同样的输入和同样的数据文件（词典、规则、用户词典层）在任何平台、
+//! 任何一次运行中都给出逐字节相同的音素输出。规则按优先级稳定排序，优先级相同时取文件中
+//! 先出现的规则；候选列表都按（距离，单词）排序，不依赖哈希表的遍历顺序。
+//! 例外是 `text_to_phonemes_budgeted`（结果取决于耗时）和磁盘缓存（结果取决于生成缓存时的配置）。
+
+use crate::phoneme::{self, Notation};
+use crate::RustG2P;
+use crate::error::{invalid_data, Context, Result};
+use std::fmt;
+use std::fs;
+
+const HEADER: &str = "# rust-g2p golden snapshot";
+
+/// 与平台和Rust版本无关的64位FNV-1a哈希，用于数据指纹
+pub(crate) struct StableHasher(u64);
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// 写入一个字段，字段之间加分隔符，避免 `ab|c` 与 `a|bc` 相同
+    pub fn write_field(&mut self, field: &str) {
+        self.write(field.as_bytes());
+        self.write(&[0]);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// 一组输入及其转换结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenSnapshot {
+    pub notation: Notation,
+    pub fingerprint: u64,           // 记录时的 `data_fingerprint`
+    pub entries: Vec<GoldenEntry>,
+}
+
+/// 单条输入及其音素输出
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenEntry {
+    pub input: String,
+    pub output: String,
+}
+
+/// 与快照不一致的一条输入
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenMismatch {
+    pub input: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// 用当前转换器重新转换快照输入的结果
+#[derive(Debug, Clone, Default)]
+pub struct GoldenReport {
+    pub checked: usize,
+    pub fingerprint_changed: bool, // 数据文件与记录时不同，输出变化可能是数据更新所致
+    pub mismatches: Vec<GoldenMismatch>,
+}
+
+impl GoldenSnapshot {
+    /// 按转换器当前的记法转换每条输入并记录结果
+    pub fn record<S: AsRef<str>>(g2p: &RustG2P, inputs: &[S]) -> Result<Self> {
+        let notation = g2p.notation();
+        let entries = inputs
+            .iter()
+            .map(|input| {
+                let input = input.as_ref();
+                Ok(GoldenEntry { input: input.to_string(), output: convert(g2p, input, notation)? })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { notation, fingerprint: g2p.data_fingerprint(), entries })
+    }
+
+    /// 重新转换快照中的输入，与记录的输出逐条比较；使用快照的记法，与转换器的设置无关
+    pub fn check(&self, g2p: &RustG2P) -> Result<GoldenReport> {
+        let mut report = GoldenReport {
+            checked: self.entries.len(),
+            fingerprint_changed: g2p.data_fingerprint() != self.fingerprint,
+            mismatches: Vec::new(),
+        };
+        for entry in &self.entries {
+            let actual = convert(g2p, &entry.input, self.notation)?;
+            if actual != entry.output {
+                report.mismatches.push(GoldenMismatch {
+                    input: entry.input.clone(),
+                    expected: entry.output.clone(),
+                    actual,
+                });
+            }
+        }
+        Ok(report)
+    }
+
+    /// 输出快照文件：两行头部之后每行一条 `输入\t输出`，输入中的制表符、换行和反斜杠转义
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{}\n# notation: {}\n# fingerprint: {:016x}\n",
+            HEADER,
+            self.notation.as_str(),
+            self.fingerprint
+        );
+        for entry in &self.entries {
+            out.push_str(&escape(&entry.input));
+            out.push('\t');
+            out.push_str(&entry.output);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// 解析 `render` 输出的快照文件
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut lines = content.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(invalid_data!("Not a golden snapshot: missing '{}' header", HEADER));
+        }
+        let notation = header_field(lines.next(), "notation")?;
+        let notation = Notation::from_name(notation)
+            .ok_or_else(|| invalid_data!("Unknown notation in golden snapshot: {}", notation))?;
+        let fingerprint = header_field(lines.next(), "fingerprint")?;
+        let fingerprint = u64::from_str_radix(fingerprint, 16)
+            .map_err(|_| invalid_data!("Invalid fingerprint in golden snapshot: {}", fingerprint))?;
+
+        let mut entries = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let (input, output) = line
+                .split_once('\t')
+                .ok_or_else(|| invalid_data!("Invalid golden snapshot line {}: missing tab", i + 4))?;
+            entries.push(GoldenEntry { input: unescape(input), output: output.to_string() });
+        }
+        Ok(Self { notation, fingerprint, entries })
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read golden snapshot: {}", path))?;
+        Self::parse(&content).with_context(|| format!("Failed to parse golden snapshot: {}", path))
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, self.render()).with_context(|| format!("Failed to write golden snapshot: {}", path))
+    }
+}
+
+impl GoldenReport {
+    /// 所有输入的输出都与快照一致
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for GoldenReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} inputs changed", self.mismatches.len(), self.checked)?;
+        if self.fingerprint_changed {
+            write!(f, " (data files changed since the snapshot was recorded)")?;
+        }
+        for mismatch in &self.mismatches {
+            write!(f, "\n  {}\n    - {}\n    + {}", mismatch.input, mismatch.expected, mismatch.actual)?;
+        }
+        Ok(())
+    }
+}
+
+fn convert(g2p: &RustG2P, input: &str, notation: Notation) -> Result<String> {
+    let phonemes = g2p
+        .text_to_phonemes(input)
+        .with_context(|| format!("Failed to convert golden input: {}", input))?;
+    Ok(phoneme::render(&phonemes, notation))
+}
+
+fn header_field<'a>(line: Option<&'a str>, name: &str) -> Result<&'a str> {
+    line.and_then(|line| line.strip_prefix("# "))
+        .and_then(|line| line.strip_prefix(name))
+        .and_then(|line| line.strip_prefix(": "))
+        .ok_or_else(|| invalid_data!("Invalid golden snapshot: missing '{}' header", name))
+}
+
+fn escape(input: &str) -> String {
+    input.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
@@ -0,0 +1,121 @@
+//! gRPC服务（`grpc` 特性）
+//!
+//! `Phonemize` 是双向流式RPC：客户端在同一个流上连续发送文本，服务端按顺序逐条返回结果，
+//! 省去每次请求的连接和HTTP开销。线格式见 `proto/g2p.proto`。
+
+use crate::RustG2P;
+use crate::error::Result;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+
+/// 消息类型和生成的服务桩代码
+pub mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PhonemizeRequest {
+        #[prost(string, tag = "1")]
+        pub text: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Word {
+        #[prost(string, tag = "1")]
+        pub word: String,
+        #[prost(string, repeated, tag = "2")]
+        pub phonemes: Vec<String>,
+        #[prost(bool, tag = "3")]
+        pub oov: bool,
+        #[prost(string, tag = "4")]
+        pub kind: String,
+        #[prost(string, tag = "5")]
+        pub surface: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PhonemizeResponse {
+        #[prost(string, tag = "1")]
+        pub text: String,
+        #[prost(message, repeated, tag = "2")]
+        pub words: Vec<Word>,
+        #[prost(string, tag = "3")]
+        pub error: String, // 非空时转换失败
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/g2p.Phonemizer.rs"));
+}
+
+pub use proto::phonemizer_client::PhonemizerClient;
+pub use proto::phonemizer_server::PhonemizerServer;
+use proto::{PhonemizeRequest, PhonemizeResponse};
+
+/// Phonemizer服务实现，可以和其他服务一起挂到已有的tonic服务器上
+#[derive(Clone)]
+pub struct PhonemizerService {
+    g2p: Arc<RustG2P>,
+}
+
+impl PhonemizerService {
+    pub fn new(g2p: Arc<RustG2P>) -> Self {
+        Self { g2p }
+    }
+
+    /// 包装成可以传给 `Server::add_service` 的服务
+    pub fn into_server(self) -> PhonemizerServer<Self> {
+        PhonemizerServer::new(self)
+    }
+
+    fn phonemize_one(&self, text: String) -> PhonemizeResponse {
+        // 单条文本出错时在响应里报告，不中断整个流
+        match self.g2p.text_to_words(&text) {
+            Ok(words) => PhonemizeResponse {
+                words: words
+                    .into_iter()
+                    .map(|w| proto::Word {
+                        phonemes: w.phonemes.iter().map(|p| p.to_notation(self.g2p.notation())).collect(),
+                        word: w.word,
+                        surface: w.surface,
+                        oov: w.oov,
+                        kind: w.kind.as_str().to_string(),
+                    })
+                    .collect(),
+                text,
+                error: String::new(),
+            },
+            Err(e) => PhonemizeResponse {
+                text,
+                words: Vec::new(),
+                error: format!("{:#}", e),
+            },
+        }
+    }
+}
+
+type ResponseStream = Pin<Box<dyn Stream<Item = Result<PhonemizeResponse, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl proto::phonemizer_server::Phonemizer for PhonemizerService {
+    type PhonemizeStream = ResponseStream;
+
+    #[allow(clippy::result_large_err)] // Status的大小由tonic决定
+    async fn phonemize(
+        &self,
+        request: Request<Streaming<PhonemizeRequest>>,
+    ) -> Result<Response<Self::PhonemizeStream>, Status> {
+        let service = self.clone();
+        let output = request
+            .into_inner()
+            .map(move |request| request.map(|request| service.phonemize_one(request.text)));
+        Ok(Response::new(Box::pin(output)))
+    }
+}
+
+/// 在指定地址上启动只包含Phonemizer服务的gRPC服务器
+pub async fn serve(g2p: RustG2P, addr: SocketAddr) -> Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(PhonemizerService::new(Arc::new(g2p)).into_server())
+        .serve(addr)
+        .await?;
+    Ok(())
+}
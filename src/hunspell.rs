@@ -0,0 +1,257 @@
+//! Hunspell 词缀词典（`.aff` / `.dic`）
+//!
+//! 用 Hunspell 的词缀规则分析派生词，找出词干和前后缀，供词干还原使用，其他语言只需提供对应的词缀词典：
+//!
+//! ```text
+//! # en.aff
+//! SFX D Y 2
+//! SFX D   0  d   e
+//! SFX D   y  ied [^aeiou]y
+//! # en.dic
+//! 2
+//! hope/D
+//! try/D
+//! ```
+//!
+//! 支持 `FLAG`（默认单字符、`long`、`num`）、`PFX`、`SFX` 和条件中的 `.`、`[abc]`、`[^abc]`，
+//! 其他指令忽略。文件须为UTF-8编码。
+
+use crate::error::{invalid_data, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+
+/// 词缀规则的条件，按字符匹配
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConditionItem {
+    Any,
+    Char(char),
+    Set(Vec<char>, bool), // 字符集合，第二项为true时取反
+}
+
+/// 一条前缀或后缀规则
+#[derive(Debug, Clone)]
+struct AffixRule {
+    flag: String,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Vec<ConditionItem>,
+}
+
+/// 一种分析结果：词干和去掉的前后缀（按派生词中的写法）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffixAnalysis {
+    pub stem: String,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+}
+
+/// 标志的写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagType {
+    Char,
+    Long,
+    Num,
+}
+
+/// Hunspell 词缀词典
+#[derive(Debug, Clone, Default)]
+pub struct AffixDictionary {
+    prefixes: Vec<AffixRule>,
+    suffixes: Vec<AffixRule>,
+    words: HashMap<String, Vec<String>>, // 小写的词干 → 标志
+}
+
+impl AffixDictionary {
+    /// 加载 `.aff` 和 `.dic` 文件
+    pub fn load(aff_path: &str, dic_path: &str) -> Result<Self> {
+        let aff = fs::read_to_string(aff_path).with_context(|| format!("Failed to read affix file '{}'", aff_path))?;
+        let dic = fs::read_to_string(dic_path).with_context(|| format!("Failed to read dictionary file '{}'", dic_path))?;
+        Self::parse(&aff, &dic).with_context(|| format!("Failed to parse Hunspell dictionary '{}'", dic_path))
+    }
+
+    /// 解析 `.aff` 和 `.dic` 文本
+    pub fn parse(aff: &str, dic: &str) -> Result<Self> {
+        let mut dictionary = Self::default();
+        let mut flag_type = FlagType::Char;
+        let mut cross_products: HashMap<(String, String), bool> = HashMap::new();
+
+        for (i, line) in aff.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => flag_type = FlagType::Long,
+                ["FLAG", "num", ..] => flag_type = FlagType::Num,
+                ["FLAG", "UTF-8", ..] => flag_type = FlagType::Char,
+                [kind @ ("PFX" | "SFX"), flag, cross, count] if count.parse::<usize>().is_ok() => {
+                    cross_products.insert((kind.to_string(), flag.to_string()), *cross == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let cross_product = *cross_products
+                        .get(&(kind.to_string(), flag.to_string()))
+                        .ok_or_else(|| invalid_data!("Line {}: affix rule for '{}' before its header", i + 1, flag))?;
+                    let condition = parse_condition(rest.first().copied().unwrap_or("."))
+                        .map_err(|e| invalid_data!("Line {}: {}", i + 1, e))?;
+                    // 附加部分可以带 `/标志`，这里不处理二次词缀
+                    let add = add.split('/').next().unwrap_or_default();
+                    let rule = AffixRule {
+                        flag: flag.to_string(),
+                        cross_product,
+                        strip: if *strip == "0" { String::new() } else { strip.to_lowercase() },
+                        add: if add == "0" { String::new() } else { add.to_lowercase() },
+                        condition,
+                    };
+                    if *kind == "PFX" {
+                        dictionary.prefixes.push(rule);
+                    } else {
+                        dictionary.suffixes.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // 第一行是词数，其余每行 `word/FLAGS`，之后可能有以空白分隔的形态字段
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            if word.is_empty() {
+                continue;
+            }
+            let flags = split_flags(flags, flag_type);
+            dictionary.words.entry(word.to_lowercase()).or_default().extend(flags);
+        }
+        Ok(dictionary)
+    }
+
+    /// 词干数
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// 是否为词典中的词干
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(&word.to_lowercase())
+    }
+
+    /// 按词缀规则分析单词，列出所有可能的词干；单词本身不作为结果
+    pub fn analyze(&self, word: &str) -> Vec<AffixAnalysis> {
+        let word = word.to_lowercase();
+        let mut analyses = Vec::new();
+        for (stem, rule) in self.strip_suffixes(&word) {
+            if self.has_flag(&stem, &rule.flag) {
+                analyses.push(AffixAnalysis { stem, prefix: None, suffix: Some(rule.add.clone()) });
+            }
+        }
+        for (rest, prefix) in self.strip_prefixes(&word) {
+            if self.has_flag(&rest, &prefix.flag) {
+                analyses.push(AffixAnalysis { stem: rest.clone(), prefix: Some(prefix.add.clone()), suffix: None });
+            }
+            if !prefix.cross_product {
+                continue;
+            }
+            for (stem, suffix) in self.strip_suffixes(&rest) {
+                if suffix.cross_product && self.has_flag(&stem, &prefix.flag) && self.has_flag(&stem, &suffix.flag) {
+                    analyses.push(AffixAnalysis {
+                        stem,
+                        prefix: Some(prefix.add.clone()),
+                        suffix: Some(suffix.add.clone()),
+                    });
+                }
+            }
+        }
+        analyses
+    }
+
+    /// 去掉后缀、补回被删去的字母，检查条件
+    fn strip_suffixes<'a>(&'a self, word: &str) -> impl Iterator<Item = (String, &'a AffixRule)> + 'a {
+        let word = word.to_string();
+        self.suffixes.iter().filter_map(move |rule| {
+            let base = word.strip_suffix(rule.add.as_str())?;
+            if base.is_empty() {
+                return None;
+            }
+            let stem = format!("{}{}", base, rule.strip);
+            let chars: Vec<char> = stem.chars().collect();
+            let tail = chars.len().checked_sub(rule.condition.len())?;
+            matches_condition(&rule.condition, &chars[tail..]).then_some((stem, rule))
+        })
+    }
+
+    fn strip_prefixes<'a>(&'a self, word: &str) -> impl Iterator<Item = (String, &'a AffixRule)> + 'a {
+        let word = word.to_string();
+        self.prefixes.iter().filter_map(move |rule| {
+            let base = word.strip_prefix(rule.add.as_str())?;
+            if base.is_empty() {
+                return None;
+            }
+            let stem = format!("{}{}", rule.strip, base);
+            let chars: Vec<char> = stem.chars().collect();
+            let head = chars.get(..rule.condition.len())?;
+            matches_condition(&rule.condition, head).then_some((stem, rule))
+        })
+    }
+
+    fn has_flag(&self, word: &str, flag: &str) -> bool {
+        self.words.get(word).is_some_and(|flags| flags.iter().any(|f| f == flag))
+    }
+}
+
+/// 解析条件，`.` 表示没有条件
+fn parse_condition(condition: &str) -> Result<Vec<ConditionItem>> {
+    if condition == "." {
+        return Ok(Vec::new());
+    }
+    let mut items = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => items.push(ConditionItem::Any),
+            '[' => {
+                let mut set: Vec<char> = Vec::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    set.extend(c.to_lowercase());
+                }
+                if !closed {
+                    return Err(invalid_data!("unclosed '[' in condition '{}'", condition));
+                }
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                items.push(ConditionItem::Set(set, negated));
+            }
+            c => items.extend(c.to_lowercase().map(ConditionItem::Char)),
+        }
+    }
+    Ok(items)
+}
+
+fn matches_condition(condition: &[ConditionItem], chars: &[char]) -> bool {
+    condition.iter().zip(chars).all(|(item, &c)| match item {
+        ConditionItem::Any => true,
+        ConditionItem::Char(expected) => c == *expected,
+        ConditionItem::Set(set, negated) => set.contains(&c) != *negated,
+    })
+}
+
+fn split_flags(flags: &str, flag_type: FlagType) -> Vec<String> {
+    match flag_type {
+        FlagType::Char => flags.chars().map(String::from).collect(),
+        FlagType::Long => {
+            let chars: Vec<char> = flags.chars().collect();
+            chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+        }
+        FlagType::Num => flags.split(',').map(|flag| flag.trim().to_string()).filter(|flag| !flag.is_empty()).collect(),
+    }
+}
@@ -1,27 +1,134 @@
+use serde::Deserialize;
+use std::borrow::Cow;
+
 /// 语言特定处理的trait
 pub trait Language {
-    fn normalize_text(&self, text: &str) -> anyhow::Result<String>;
-    fn tokenize(&self, text: &str) -> anyhow::Result<Vec<String>>;
-    fn get_stress_pattern(&self, word: &str) -> anyhow::Result<Vec<usize>>;
+    fn normalize_text(&self, text: &str) -> crate::error::Result<String>;
+    fn tokenize(&self, text: &str) -> crate::error::Result<Vec<String>>;
+    fn get_stress_pattern(&self, word: &str) -> crate::error::Result<Vec<usize>>;
+    
+    /// 该语言的大小写转换规则，默认为Unicode的通用规则
+    fn case_rules(&self) -> CaseRules {
+        CaseRules::Unicode
+    }
+    
+    /// 按该语言的规则转小写
+    fn lowercase<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        self.case_rules().lowercase(text)
+    }
+}
+
+/// 大小写转换规则
+///
+/// Unicode的通用规则不考虑语言：土耳其语、阿塞拜疆语中 `I` 的小写是无点的 `ı`，`İ` 的小写是 `i`，
+/// 通用规则却分别得到 `i` 和 `i̇`（带组合附加点）；德语的 `ß` 转大写时通用规则得到两个字母 `SS`，无法再转回。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseRules {
+    /// Unicode的通用规则，同 `str::to_lowercase`
+    #[default]
+    Unicode,
+    /// 土耳其语和阿塞拜疆语：`I ↔ ı`，`İ ↔ i`
+    Turkic,
+    /// 德语：`ß` 的大写为 `ẞ`，转换前后字母数不变
+    German,
+}
+
+impl CaseRules {
+    /// 语言代码（如 `tr`、`de-AT`）对应的规则
+    pub fn for_language(code: &str) -> Self {
+        match code.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase().as_str() {
+            "tr" | "az" => CaseRules::Turkic,
+            "de" => CaseRules::German,
+            _ => CaseRules::Unicode,
+        }
+    }
+    
+    /// ASCII字母转小写的结果是否与 `to_ascii_lowercase` 相同，土耳其语的 `I` 转为 `ı`，不同
+    pub fn is_ascii_compatible(self) -> bool {
+        self != CaseRules::Turkic
+    }
+    
+    /// 转小写，已经是小写时不复制
+    pub fn lowercase<'t>(self, text: &'t str) -> Cow<'t, str> {
+        if text.chars().all(|c| self.lower_char(c).eq([c])) {
+            return Cow::Borrowed(text);
+        }
+        // 通用规则需要上下文（词尾的 Σ 转为 ς），特殊字母替换后再整体转换
+        Cow::Owned(match self {
+            CaseRules::Turkic => text.replace('İ', "i").replace('I', "ı").to_lowercase(),
+            CaseRules::Unicode | CaseRules::German => text.to_lowercase(),
+        })
+    }
+    
+    /// 转大写
+    pub fn uppercase(self, text: &str) -> String {
+        match self {
+            CaseRules::Unicode => text.to_uppercase(),
+            CaseRules::Turkic => text
+                .chars()
+                .flat_map(|c| match c {
+                    'i' => CaseIter::One(Some('İ')),
+                    'ı' => CaseIter::One(Some('I')),
+                    c => CaseIter::Upper(c.to_uppercase()),
+                })
+                .collect(),
+            CaseRules::German => text
+                .chars()
+                .flat_map(|c| match c {
+                    'ß' => CaseIter::One(Some('ẞ')),
+                    c => CaseIter::Upper(c.to_uppercase()),
+                })
+                .collect(),
+        }
+    }
+    
+    fn lower_char(self, c: char) -> CaseIter {
+        match (self, c) {
+            (CaseRules::Turkic, 'I') => CaseIter::One(Some('ı')),
+            (CaseRules::Turkic, 'İ') => CaseIter::One(Some('i')),
+            _ => CaseIter::Lower(c.to_lowercase()),
+        }
+    }
+}
+
+/// 单个字符转换大小写的结果
+enum CaseIter {
+    One(Option<char>),
+    Lower(std::char::ToLowercase),
+    Upper(std::char::ToUppercase),
+}
+
+impl Iterator for CaseIter {
+    type Item = char;
+    
+    fn next(&mut self) -> Option<char> {
+        match self {
+            CaseIter::One(c) => c.take(),
+            CaseIter::Lower(chars) => chars.next(),
+            CaseIter::Upper(chars) => chars.next(),
+        }
+    }
 }
 
 /// 英语语言处理
 pub struct English;
 
 impl Language for English {
-    fn normalize_text(&self, text: &str) -> anyhow::Result<String> {
-        // 使用TextProcessor进行标准化
-        let processor = crate::text::TextProcessor::new();
-        processor.normalize(text)
+    fn normalize_text(&self, text: &str) -> crate::error::Result<String> {
+        // 使用TextProcessor进行标准化，大小写按本语言的规则转换
+        let options = crate::text::NormalizeOptions { case_rules: self.case_rules(), ..Default::default() };
+        let processor = crate::text::TextProcessor::new().with_options(options);
+        processor.normalize(text).map(|text| text.into_owned())
     }
     
-    fn tokenize(&self, text: &str) -> anyhow::Result<Vec<String>> {
+    fn tokenize(&self, text: &str) -> crate::error::Result<Vec<String>> {
         let processor = crate::text::TextProcessor::new();
         processor.tokenize(text)
     }
     
-    fn get_stress_pattern(&self, _word: &str) -> anyhow::Result<Vec<usize>> {
+    fn get_stress_pattern(&self, _word: &str) -> crate::error::Result<Vec<usize>> {
         // 简化的重音规则：单音节词重音在第一个音节
         Ok(vec![0])
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,108 @@
+//! 从人工纠正中归纳候选规则
+
+use crate::eval::{self, DiffOp};
+use crate::phoneme::{Notation, Phoneme};
+use crate::rules::{Rule, RuleStep, RulesEngine};
+use crate::error::Result;
+
+/// 由纠正归纳出的候选规则
+#[derive(Debug, Clone)]
+pub struct RuleSuggestion {
+    pub rule: Rule,
+    pub words: Vec<String>, // 支持这条规则的纠正
+}
+
+impl RuleSuggestion {
+    /// 竖线格式的规则行，审核后可直接加入规则文件
+    pub fn line(&self) -> String {
+        self.rule.to_pipe_line()
+    }
+}
+
+/// 把每条纠正与规则引擎的推导过程对齐，为读错的字母组合生成带左右一个字母上下文的规则
+///
+/// 相同的规则合并，按支持的单词数从多到少排列。不规则词汇表中的单词没有推导过程，不产生规则。
+pub(crate) fn suggest_rules(rules: &RulesEngine, corrections: &[(String, Vec<Phoneme>)]) -> Result<Vec<RuleSuggestion>> {
+    let mut suggestions: Vec<RuleSuggestion> = Vec::new();
+    for (word, expected) in corrections {
+        for rule in word_rules(rules, word, expected)? {
+            let line = rule.to_pipe_line();
+            match suggestions.iter_mut().find(|suggestion| suggestion.line() == line) {
+                Some(suggestion) => suggestion.words.push(word.clone()),
+                None => suggestions.push(RuleSuggestion { rule, words: vec![word.clone()] }),
+            }
+        }
+    }
+    suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.words.len()));
+    Ok(suggestions)
+}
+
+/// 单个纠正的候选规则
+fn word_rules(rules: &RulesEngine, word: &str, expected: &[Phoneme]) -> Result<Vec<Rule>> {
+    let (output, steps) = rules.trace_rules(word)?;
+    if output.irregular || steps.iter().all(|step| step.phonemes.is_empty()) {
+        return Ok(Vec::new());
+    }
+    let assigned = assign_phonemes(&steps, expected);
+
+    let chars: Vec<char> = word.chars().collect();
+    let mut suggested = Vec::new();
+    for (step, correct) in steps.iter().zip(assigned) {
+        let actual = step.phonemes.iter().map(|p| strip_stress(p));
+        if actual.eq(correct.iter().map(|p| p.symbol.as_str())) {
+            continue;
+        }
+        let start = step.position;
+        let end = start + step.letters.chars().count();
+        let context = |index: Option<usize>| index.and_then(|index| chars.get(index)).map_or("_".to_string(), char::to_string);
+        suggested.push(Rule {
+            pattern: step.letters.clone(),
+            left_context: Some(context(start.checked_sub(1))),
+            right_context: Some(context(Some(end))),
+            phonemes: correct.iter().map(|p| p.to_notation(Notation::Arpabet)).collect(),
+            priority: step.rule.as_ref().map_or(step.letters.len(), |rule| rule.priority) + 1,
+            conditions: Vec::new(),
+            stress: None,
+        });
+    }
+    Ok(suggested)
+}
+
+/// 把正确的发音按对齐结果分给推导的各步，比较时忽略重音；多出的音素归前一步
+pub(crate) fn assign_phonemes<'a>(steps: &[RuleStep], expected: &'a [Phoneme]) -> Vec<Vec<&'a Phoneme>> {
+    // 规则输出的每个音素属于哪一步
+    let mut predicted = Vec::new();
+    let mut owners = Vec::new();
+    for (index, step) in steps.iter().enumerate() {
+        for phoneme in &step.phonemes {
+            predicted.push(strip_stress(phoneme).to_string());
+            owners.push(index);
+        }
+    }
+    let mut assigned: Vec<Vec<&Phoneme>> = vec![Vec::new(); steps.len()];
+    if owners.is_empty() {
+        return assigned;
+    }
+    let target: Vec<String> = expected.iter().map(|p| p.symbol.clone()).collect();
+
+    let (mut i, mut j) = (0, 0);
+    for op in eval::align(&predicted, &target) {
+        match op {
+            DiffOp::Same(_) | DiffOp::Substitute(..) => {
+                assigned[owners[i]].push(&expected[j]);
+                i += 1;
+                j += 1;
+            }
+            DiffOp::Delete(_) => i += 1,
+            DiffOp::Insert(_) => {
+                assigned[owners[i.saturating_sub(1)]].push(&expected[j]);
+                j += 1;
+            }
+        }
+    }
+    assigned
+}
+
+pub(crate) fn strip_stress(phoneme: &str) -> &str {
+    phoneme.trim_end_matches(|c: char| c.is_ascii_digit())
+}
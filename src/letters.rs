@@ -0,0 +1,71 @@
+use crate::phoneme::Phoneme;
+
+/// 获取字母或数字名称的ARPAbet读音
+pub fn letter_name(ch: char) -> Option<&'static [&'static str]> {
+    let phonemes: &'static [&'static str] = match ch.to_ascii_lowercase() {
+        'a' => &["EY1"],
+        'b' => &["B", "IY1"],
+        'c' => &["S", "IY1"],
+        'd' => &["D", "IY1"],
+        'e' => &["IY1"],
+        'f' => &["EH1", "F"],
+        'g' => &["JH", "IY1"],
+        'h' => &["EY1", "CH"],
+        'i' => &["AY1"],
+        'j' => &["JH", "EY1"],
+        'k' => &["K", "EY1"],
+        'l' => &["EH1", "L"],
+        'm' => &["EH1", "M"],
+        'n' => &["EH1", "N"],
+        'o' => &["OW1"],
+        'p' => &["P", "IY1"],
+        'q' => &["K", "Y", "UW1"],
+        'r' => &["AA1", "R"],
+        's' => &["EH1", "S"],
+        't' => &["T", "IY1"],
+        'u' => &["Y", "UW1"],
+        'v' => &["V", "IY1"],
+        'w' => &["D", "AH1", "B", "AH0", "L", "Y", "UW0"],
+        'x' => &["EH1", "K", "S"],
+        'y' => &["W", "AY1"],
+        'z' => &["Z", "IY1"],
+        '0' => &["Z", "IH1", "R", "OW0"],
+        '1' => &["W", "AH1", "N"],
+        '2' => &["T", "UW1"],
+        '3' => &["TH", "R", "IY1"],
+        '4' => &["F", "AO1", "R"],
+        '5' => &["F", "AY1", "V"],
+        '6' => &["S", "IH1", "K", "S"],
+        '7' => &["S", "EH1", "V", "AH0", "N"],
+        '8' => &["EY1", "T"],
+        '9' => &["N", "AY1", "N"],
+        _ => return None,
+    };
+    Some(phonemes)
+}
+
+/// 逐个字母拼读单词，跳过没有名称的字符
+pub fn spell_out(word: &str) -> Vec<Phoneme> {
+    word.chars()
+        .filter_map(letter_name)
+        .flatten()
+        .map(|p| Phoneme::from_arpabet(p))
+        .collect()
+}
+
+/// 去掉常见拉丁字母的变音符号（`ï → i`、`ç → c`），其他字符原样返回
+pub fn base_letter(ch: char) -> char {
+    match ch {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' | 'č' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'š' => 's',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ž' => 'z',
+        _ => ch,
+    }
+}
@@ -0,0 +1,112 @@
+use crate::golden::StableHasher;
+use crate::phoneme::{self, Phoneme, PhonemeCode};
+use crate::Dictionary;
+use crate::error::{Context, Result};
+use crate::lang::CaseRules;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// 可在运行时修改的词典层，用于用户词典和不规则词汇
+///
+/// 读写都只需要共享引用，内部用读写锁保护，可以在多个线程共享的转换器上直接修改。
+/// 单词不区分大小写，按 `case_rules` 转小写。
+#[derive(Debug, Default)]
+pub struct LexiconLayer {
+    entries: RwLock<HashMap<String, Box<[PhonemeCode]>>>,
+    case_rules: CaseRules,
+}
+
+impl LexiconLayer {
+    /// 创建空的词典层
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从CMU词典格式的文件加载
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lexicon file: {}", path))?;
+        Self::from_cmu_str(&content).with_context(|| format!("Failed to parse lexicon file: {}", path))
+    }
+
+    /// 从CMU词典格式的文本构建，格式与 `Dictionary::from_cmu_str` 相同
+    pub fn from_cmu_str(content: &str) -> Result<Self> {
+        let entries = Dictionary::from_cmu_str(content)?.into_entries();
+        Ok(Self { entries: RwLock::new(entries), case_rules: CaseRules::default() })
+    }
+
+    /// 设置转小写的规则，默认为Unicode的通用规则；已有的词条不受影响
+    pub fn with_case_rules(mut self, case_rules: CaseRules) -> Self {
+        self.case_rules = case_rules;
+        self
+    }
+
+    /// 加入或替换词条
+    pub fn insert(&self, word: &str, pronunciation: &str) -> Result<()> {
+        let phonemes = phoneme::parse_arpabet(pronunciation)
+            .with_context(|| format!("Invalid pronunciation for '{}'", word))?;
+        self.insert_phonemes(word, &phonemes);
+        Ok(())
+    }
+
+    /// 发音须已通过 `parse_arpabet` 校验，因此都能编码
+    pub(crate) fn insert_phonemes(&self, word: &str, phonemes: &[Phoneme]) {
+        let codes = phonemes.iter().filter_map(PhonemeCode::encode).collect();
+        self.write().insert(self.case_rules.lowercase(word).into_owned(), codes);
+    }
+
+    /// 删除词条，返回词条是否存在
+    pub fn remove(&self, word: &str) -> bool {
+        self.write().remove(self.case_rules.lowercase(word).as_ref()).is_some()
+    }
+
+    pub fn lookup(&self, word: &str) -> Option<Vec<Phoneme>> {
+        self.lookup_codes(word).map(|codes| codes.iter().map(|code| code.decode()).collect())
+    }
+
+    /// 空的词典层不做小写转换
+    pub(crate) fn lookup_codes(&self, word: &str) -> Option<Vec<PhonemeCode>> {
+        let entries = self.read();
+        if entries.is_empty() {
+            return None;
+        }
+        entries.get(self.case_rules.lowercase(word).as_ref()).map(|codes| codes.to_vec())
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// 依次访问每个词条
+    pub(crate) fn for_each(&self, mut visit: impl FnMut(&str, &[PhonemeCode])) {
+        for (word, codes) in self.read().iter() {
+            visit(word, codes);
+        }
+    }
+
+    /// 按单词排序写入全部词条
+    pub(crate) fn fingerprint(&self, hasher: &mut StableHasher) {
+        let entries = self.read();
+        let mut words: Vec<&String> = entries.keys().collect();
+        words.sort();
+        for word in words {
+            hasher.write_field(word);
+            for code in entries[word].iter() {
+                hasher.write_field(code.arpabet());
+            }
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Box<[PhonemeCode]>>> {
+        // 写入只替换单个词条，锁中毒时数据仍然一致
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Box<[PhonemeCode]>>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -3,79 +3,1202 @@ pub mod rules;
 pub mod dict;
 pub mod text;
 pub mod lang;
+pub mod error;
+pub mod letters;
+pub mod numbers;
+pub mod address;
+pub mod markup;
+pub mod align;
+pub mod eval;
+pub mod compare;
+pub mod search;
+pub mod drill;
+pub mod syllable;
+pub mod difficulty;
+pub mod analyze;
+pub mod audit;
+pub mod lyrics;
+pub mod subtitle;
+pub mod epenthesis;
+pub mod rhythm;
+pub mod corpus;
+pub mod learn;
+pub mod golden;
+pub mod schema;
+pub mod report;
+pub mod rewrite;
+pub mod lexicon;
+pub mod frequent;
+pub mod stem;
+pub mod hunspell;
+pub mod translit;
+pub mod memory;
+pub mod batch;
+pub mod bench;
+pub mod cache;
+pub mod stats;
+pub mod observer;
+pub mod config;
+pub mod builder;
+pub mod pack;
+pub mod global;
+#[cfg(feature = "embedded-data")]
+pub mod embedded;
+pub mod trace;
+pub mod nbest;
+pub mod stream;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 
+pub use error::G2PError;
 pub use phoneme::Phoneme;
 pub use rules::RulesEngine;
-pub use dict::Dictionary;
+pub use dict::{Dictionary, DuplicatePolicy};
+pub use memory::MemoryReport;
+pub use batch::BatchSession;
+pub use cache::DiskCache;
+pub use stats::G2PStats;
+pub use observer::Observer;
+pub use config::G2PConfig;
+pub use builder::G2PBuilder;
+pub use pack::LanguagePack;
+pub use global::{global, phonemize, phonemize_text};
+pub use trace::{LetterCategory, LetterHighlight, SilentLetter, WordSource, WordTrace};
+pub use nbest::Candidate;
+pub use stream::PhonemeStream;
+pub use golden::GoldenSnapshot;
+pub use rewrite::RewriteEngine;
+pub use lexicon::LexiconLayer;
+pub use compare::{compare_pronunciations, compare_pronunciations_with, DiffReport};
+pub use search::PhoneticIndex;
+pub use lyrics::LyricSyllable;
+pub use epenthesis::SchwaInsertion;
+pub use hunspell::AffixDictionary;
 
-use anyhow::Result;
+use crate::error::{Context, Result};
+use lang::CaseRules;
+use phoneme::{PhonemeCode, PhonemeSeq};
+use stats::Counters;
+use text::TokenKind;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// 默认的语言代码，英语专用的处理只用于这种语言及其地区变体
+const LANGUAGE: &str = "en";
+
+/// 错误中每种建议最多列出的单词数
+const SUGGESTION_LIMIT: usize = 3;
 
 /// 主要的G2P转换器
 pub struct RustG2P {
     dictionary: Dictionary,
+    frequent: frequent::FrequentWords, // 高频单词的发音，查找时不计算哈希
     rules_engine: RulesEngine,
     text_processor: text::TextProcessor,
+    routing: RoutingConfig,
+    cache: Option<DiskCache>,
+    rule_cache: Option<cache::RuleCache>, // 规则转换结果的内存缓存
+    counters: Counters,
+    observers: Vec<Arc<dyn Observer>>,
+    strict: bool,
+    notation: phoneme::Notation,
+    phoneme_input: bool, // 斜杠片段按音素解析
+    quote_markers: bool, // 输出中标出引语的起止
+    pause_markers: bool, // 输出中标出句中停顿和句子边界
+    boundary_symbols: phoneme::BoundarySymbols,
+    phone_stress: phoneme::StressMarks, // phone string 中重音的处理方式
+    word_boundaries: bool, // 整段输出中保留词边界
+    user: LexiconLayer,                              // 运行时加入的词条，优先于主词典
+    corrections: RwLock<Vec<(String, Vec<Phoneme>)>>, // 通过 record_correction 记录的纠正
+    irregular: LexiconLayer,                         // 不规则词汇，主词典未收录时优先于规则
+    rewrites: Vec<RewriteEngine>, // 词汇后的音素改写，按顺序应用
+    schwa: SchwaInsertion,        // 改写之后在辅音丛中插入央元音
+    rhythm_rule: bool,            // 跨词连读之后消除相邻单词的重音冲突
+    stem_recovery: bool,          // 未登录词先还原词干再查词典
+    transliteration: bool,        // 希腊字母、西里尔字母转写后按英语读
+    affixes: Option<AffixDictionary>, // 词干还原使用的 Hunspell 词缀词典
+    long_words: Option<(usize, LongWordPolicy)>, // 未登录词的最大长度（按字符计）和超长时的处理方式
+    phoneme_set: phoneme::PhonemeSet, // 输出的词元标注的音素集
+    language: String,                 // 语言代码，见 `with_language`
+}
+
+/// 规则输出不可靠时的后备策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fallback {
+    /// 仍然使用规则输出
+    Keep,
+    /// 逐个字母拼读
+    SpellOut,
+    /// 返回错误
+    Error,
+}
+
+/// 未登录词超过最大长度时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LongWordPolicy {
+    /// 不发音，输出空的音素序列
+    Skip,
+    /// 逐个拼读前面最大长度以内的字符
+    #[default]
+    SpellOut,
+    /// 返回 `G2PError::WordTooLong`
+    Error,
+}
+
+/// 规则输出的置信度路由配置
+#[derive(Debug, Clone)]
+pub struct RoutingConfig {
+    pub min_confidence: f32,               // 低于该置信度时使用后备策略
+    pub require_valid_phonotactics: bool,  // 不符合音位配列时使用后备策略
+    pub fallback: Fallback,
+}
+
+/// 严格模式下遇到无法处理的字符时返回的错误，包装在 `G2PError::UnknownCharacter` 中
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCharacterError {
+    pub word: String,
+    pub offset: usize, // 字符在单词中的位置（按字符计）
+    pub character: char,
+    pub suggestions: Suggestions,
+}
+
+/// 与未登录词相近的词典单词
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Suggestions {
+    pub spelling: Vec<String>, // 拼写相近
+    pub sound: Vec<String>,    // 规则读音与词典读音相近
+}
+
+impl Suggestions {
+    /// 去重后的全部建议，拼写相近的在前
+    pub fn all(&self) -> Vec<&str> {
+        let mut words: Vec<&str> = Vec::new();
+        for word in self.spelling.iter().chain(&self.sound) {
+            if !words.contains(&word.as_str()) {
+                words.push(word);
+            }
+        }
+        words
+    }
+}
+
+impl fmt::Display for UnknownCharacterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown character '{}' at offset {} in '{}'", self.character, self.offset, self.word)?;
+        let suggestions = self.suggestions.all();
+        if !suggestions.is_empty() {
+            write!(f, " (did you mean: {}?)", suggestions.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownCharacterError {}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: 0.0,
+            require_valid_phonotactics: false,
+            fallback: Fallback::Keep,
+        }
+    }
 }
 
 impl RustG2P {
-    /// 创建新的G2P转换器
+    /// 创建新的G2P转换器，使用 `data/` 下的默认数据文件；启用 `embedded-data` 特性时使用嵌入的同一组数据
     pub fn new() -> Result<Self> {
-        let dictionary = Dictionary::load_cmu_dict("data/cmudict.txt")?;
-        let rules_engine = RulesEngine::load_english_rules("data/en_rules.txt")?;
-        let text_processor = text::TextProcessor::new();
-        
-        Ok(Self {
+        #[cfg(feature = "embedded-data")]
+        return Self::embedded();
+        #[cfg(not(feature = "embedded-data"))]
+        Self::builder().build()
+    }
+    
+    /// 逐项设置数据文件和处理选项的构建器，未设置的项与 `new` 相同
+    pub fn builder() -> G2PBuilder {
+        G2PBuilder::new()
+    }
+    
+    /// 用已加载的词典和规则引擎组装转换器，其余选项取默认值
+    pub(crate) fn from_parts(dictionary: Dictionary, rules_engine: RulesEngine) -> Self {
+        Self {
+            frequent: frequent::FrequentWords::from_dictionary(&dictionary),
             dictionary,
             rules_engine,
-            text_processor,
-        })
+            text_processor: text::TextProcessor::new(),
+            routing: RoutingConfig::default(),
+            cache: None,
+            rule_cache: cache::RuleCache::new(cache::DEFAULT_RULE_CACHE_SIZE),
+            counters: Counters::default(),
+            observers: Vec::new(),
+            strict: false,
+            notation: phoneme::Notation::default(),
+            phoneme_input: false,
+            quote_markers: false,
+            pause_markers: false,
+            boundary_symbols: phoneme::BoundarySymbols::default(),
+            phone_stress: phoneme::StressMarks::default(),
+            word_boundaries: true,
+            user: LexiconLayer::new(),
+            corrections: RwLock::default(),
+            irregular: LexiconLayer::new(),
+            rewrites: Vec::new(),
+            schwa: SchwaInsertion::new(),
+            rhythm_rule: false,
+            stem_recovery: true,
+            transliteration: true,
+            affixes: None,
+            long_words: None,
+            phoneme_set: phoneme::PhonemeSet::Arpabet(LANGUAGE.into()),
+            language: LANGUAGE.to_string(),
+        }
+    }
+    
+    /// 使用编译时嵌入的精简词典和规则集创建转换器，无需任何数据文件
+    ///
+    /// 自带的精简词典只含 `data/en_common_words.txt` 中约1000个最常用的单词，
+    /// 其余单词由规则引擎处理。可用 `examples/build_minimal_lexicon.rs` 重新生成 `data/cmudict_min.txt`。
+    #[cfg(feature = "minimal-lexicon")]
+    pub fn new_minimal() -> Result<Self> {
+        let dictionary = Dictionary::from_cmu_str(include_str!("../data/cmudict_min.txt"))?;
+        let rules_engine = RulesEngine::parse_english_rules(include_str!("../data/en_rules.txt"))?;
+        let irregular = LexiconLayer::from_cmu_str(include_str!("../data/en_irregular.txt"))?;
+        
+        Ok(Self::from_parts(dictionary, rules_engine).with_irregular(irregular))
+    }
+    
+    /// 设置不规则词汇层：主词典未收录的单词先查这一层，再交给规则引擎
+    ///
+    /// 不规则词汇单独维护，可以在多个语言变体之间共享；规则文件中的 `IRREGULAR` 行仍然有效。
+    pub fn with_irregular(mut self, irregular: LexiconLayer) -> Self {
+        self.irregular = irregular.with_case_rules(self.case_rules());
+        self
+    }
+    
+    /// 设置规则输出的置信度路由
+    pub fn with_routing(mut self, routing: RoutingConfig) -> Self {
+        self.routing = routing;
+        self.clear_rule_cache();
+        self
+    }
+    
+    /// 使用磁盘缓存保存未登录词的转换结果
+    pub fn with_disk_cache(mut self, cache: DiskCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+    
+    /// 规则转换结果的内存缓存最多保存 `capacity` 个单词（默认1024），为0时不使用
+    ///
+    /// 缓存规则引擎和后备策略的输出，`with_routing` 和 `with_strict` 会清空缓存。
+    pub fn with_rule_cache(mut self, capacity: usize) -> Self {
+        self.rule_cache = cache::RuleCache::new(capacity);
+        self
+    }
+    
+    /// 设置文本标准化步骤开关
+    pub fn with_normalization(mut self, options: text::NormalizeOptions) -> Self {
+        self.text_processor = self.text_processor.with_options(options);
+        self.sync_case_rules();
+        self
+    }
+    
+    /// 设置转小写的规则，如 `CaseRules::for_language("tr")` 或 `Language::case_rules` 给出的规则；
+    /// 标准化、查词典、用户词典层和不规则词汇层都按此规则转小写
+    pub fn with_case_rules(self, case_rules: CaseRules) -> Self {
+        let options = text::NormalizeOptions { case_rules, ..*self.text_processor.options() };
+        self.with_normalization(options)
+    }
+    
+    /// 转小写的规则
+    pub fn case_rules(&self) -> CaseRules {
+        self.text_processor.options().case_rules
+    }
+    
+    /// 词典层使用与文本处理相同的大小写规则
+    fn sync_case_rules(&mut self) {
+        let case_rules = self.case_rules();
+        self.user = std::mem::take(&mut self.user).with_case_rules(case_rules);
+        self.irregular = std::mem::take(&mut self.irregular).with_case_rules(case_rules);
+    }
+    
+    /// 设置数字的读法，见 `numbers::NumberWords`
+    pub fn with_number_words(mut self, number_words: Arc<dyn numbers::NumberWords>) -> Self {
+        self.text_processor = self.text_processor.with_number_words(number_words);
+        self
+    }
+    
+    /// 设置语言代码（如 `en-GB`、`es`），默认为 `en`；配置文件和语言包按其中的 `language` 设置
+    ///
+    /// 同时按语言设置词元标注的音素集（`PhonemeSet::Arpabet`）和大小写规则（`CaseRules::for_language`）。
+    /// 数字的读法取自 `numbers::register_number_words` 为该语言注册的读法，没有注册时英语以外的语言不展开数字
+    /// （之后也可用 `with_number_words` 设置）。不是英语时也不使用英语缩写表、高频单词快速路径、派生词词干还原、
+    /// 希腊字母和西里尔字母的转写以及按英语后缀指派重音。
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = language.to_string();
+        self.phoneme_set = phoneme::PhonemeSet::Arpabet(language.into());
+        let mut options = text::NormalizeOptions { case_rules: CaseRules::for_language(language), ..*self.text_processor.options() };
+        self.text_processor = match numbers::number_words_for(language) {
+            Some(number_words) => self.text_processor.with_number_words(number_words),
+            None if self.is_english() => self.text_processor,
+            None => self.text_processor.without_number_words(),
+        };
+        if !self.is_english() {
+            options.expand_abbreviations = false;
+            self.frequent = frequent::FrequentWords::default();
+            self.stem_recovery = false;
+            self.transliteration = false;
+            self.rules_engine.set_stress_assignment(false);
+            self.clear_rule_cache();
+        }
+        self.with_normalization(options)
+    }
+    
+    /// 语言代码
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+    
+    /// 是否为英语或其地区变体
+    fn is_english(&self) -> bool {
+        self.language.split(['-', '_']).next().is_some_and(|code| code.eq_ignore_ascii_case(LANGUAGE))
+    }
+    
+    /// 设置 `text_to_words` 等输出的词元标注的音素集，默认为 `PhonemeSet::Arpabet("en")`，`with_language` 按语言设置
+    pub fn with_phoneme_set(mut self, phoneme_set: phoneme::PhonemeSet) -> Self {
+        self.phoneme_set = phoneme_set;
+        self
+    }
+    
+    /// 输出的词元标注的音素集
+    pub fn phoneme_set(&self) -> &phoneme::PhonemeSet {
+        &self.phoneme_set
+    }
+    
+    /// 设置 `render` 使用的音素记法
+    pub fn with_notation(mut self, notation: phoneme::Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+    
+    /// 音素输入模式：文本中以斜杠包围的片段（如 `/T EH1 S T/`）直接按ARPAbet音素解析并原样输出
+    pub fn with_phoneme_input(mut self, enabled: bool) -> Self {
+        self.phoneme_input = enabled;
+        self
+    }
+    
+    /// 引号边界标记：`text_to_phonemes` 在每段引语前后输出 `Phoneme::quote_boundary()`
+    pub fn with_quote_markers(mut self, enabled: bool) -> Self {
+        self.quote_markers = enabled;
+        self
+    }
+    
+    /// 停顿标记：`text_to_phonemes` 在逗号、分号、冒号、破折号和括号处输出 `Phoneme::pause()`，
+    /// 在每个句子之后输出 `Phoneme::sentence_boundary()`
+    ///
+    /// 停顿和句子边界也会阻断跨词连读和节奏规则。
+    pub fn with_pause_markers(mut self, enabled: bool) -> Self {
+        self.pause_markers = enabled;
+        self
+    }
+    
+    /// `render` 输出词边界、停顿和句子边界时使用的符号
+    pub fn with_boundary_symbols(mut self, symbols: phoneme::BoundarySymbols) -> Self {
+        self.boundary_symbols = symbols;
+        self
+    }
+    
+    /// `word_to_phone_string` 和 `text_to_phone_string` 中重音的处理方式，默认不带重音数字
+    pub fn with_phone_stress(mut self, stress: phoneme::StressMarks) -> Self {
+        self.phone_stress = stress;
+        self
+    }
+    
+    /// `text_to_phonemes` 等整段输出中是否在单词之间插入 `Phoneme::word_boundary()`，默认插入
+    ///
+    /// 跨词连读、节奏规则和音素改写仍按词边界进行，关闭时在这些步骤之后去掉边界。
+    pub fn with_word_boundaries(mut self, enabled: bool) -> Self {
+        self.word_boundaries = enabled;
+        self
+    }
+    
+    /// 添加一组音素改写规则，作用于跨词连读之后的输出；可多次调用，按添加顺序依次改写
+    ///
+    /// `text_to_phonemes` 改写整段音素序列，`text_to_words` 等逐词接口分别改写每个单词，
+    /// `word_to_phonemes` 给出改写前的词汇读音。
+    pub fn with_rewrite(mut self, engine: RewriteEngine) -> Self {
+        self.rewrites.push(engine);
+        self
+    }
+    
+    /// 在指定的辅音丛中插入央元音，在音素改写之后进行，作用范围与 `with_rewrite` 相同
+    pub fn with_schwa_insertion(mut self, schwa: SchwaInsertion) -> Self {
+        self.schwa = schwa;
+        self
+    }
+    
+    /// 未登录的派生词是否先还原词干（running → run）再查词典，默认启用
+    pub fn with_stem_recovery(mut self, enabled: bool) -> Self {
+        self.stem_recovery = enabled;
+        self
+    }
+    
+    /// 希腊字母、西里尔字母的单词是否转写为拉丁字母后按英语读（见 `translit`），默认启用；
+    /// 关闭后这些字母没有对应的规则，读作空音素
+    pub fn with_transliteration(mut self, enabled: bool) -> Self {
+        self.transliteration = enabled;
+        self
+    }
+    
+    /// 规则读音是否按后缀和音节数指派唯一的主重音（见 `RulesEngine::set_stress_assignment`），默认启用
+    pub fn with_stress_assignment(mut self, enabled: bool) -> Self {
+        self.rules_engine.set_stress_assignment(enabled);
+        self.clear_rule_cache();
+        self
+    }
+    
+    /// 使用 Hunspell 词缀词典分析派生词，在内置的英语拼写规则之前尝试；须启用词干还原
+    pub fn with_affix_dictionary(mut self, affixes: AffixDictionary) -> Self {
+        self.affixes = Some(affixes);
+        self
+    }
+    
+    /// 在跨词连读阶段加入连接R和插入R（law and order → L AO1 | R AH0 N D | ...）
+    ///
+    /// 用于非卷舌口音的输出，通常与删除词尾R的改写规则（`R||_|SILENT|`）一起使用：
+    /// 连接R移到后一个词的开头，不会被改写删除。
+    pub fn with_linking_r(mut self) -> Self {
+        self.rules_engine.add_sandhi_rules(rules::SandhiRule::linking_r());
+        self
+    }
+    
+    /// 启用节奏规则：相邻单词的重读音节冲突时，前一个词的主重音前移（thirteen men → THIRteen MEN）
+    ///
+    /// 只作用于 `text_to_phonemes` 的整段输出，逐词接口看不到相邻的单词。
+    pub fn with_rhythm_rule(mut self, enabled: bool) -> Self {
+        self.rhythm_rule = enabled;
+        self
+    }
+    
+    /// 从TOML配置文件创建转换器
+    pub fn from_config(path: &str) -> Result<Self> {
+        Self::from_parsed_config(&G2PConfig::load(path)?)
+    }
+    
+    /// 按已解析的配置创建转换器
+    pub fn from_parsed_config(config: &G2PConfig) -> Result<Self> {
+        config.build()
+    }
+    
+    /// 严格模式：词典未收录的单词中有规则无法处理的字符时返回错误，而不是跳过该字符
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self.clear_rule_cache();
+        self
+    }
+    
+    /// 清空规则结果缓存，其中的结果按之前的路由和严格模式得出
+    fn clear_rule_cache(&self) {
+        if let Some(rule_cache) = &self.rule_cache {
+            rule_cache.clear();
+        }
+    }
+    
+    /// 限制未登录词的长度：超过 `limit` 个字符的单词不经过规则引擎，按 `policy` 处理
+    ///
+    /// base64 数据、长串乱码等输入会让规则引擎逐个字母匹配，对延迟敏感的服务可以设置上限。词典收录的单词不受限制。
+    pub fn with_max_word_length(mut self, limit: usize, policy: LongWordPolicy) -> Self {
+        self.long_words = Some((limit, policy));
+        self
+    }
+    
+    /// 注册转换事件的观察者，可注册多个
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observers.push(observer);
+        self
     }
     
     /// 将文本转换为音素
     pub fn text_to_phonemes(&self, text: &str) -> Result<Vec<Phoneme>> {
-        // 1. 文本预处理
-        let normalized = self.text_processor.normalize(text)?;
-        
-        // 2. 分词
-        let words = self.text_processor.tokenize(&normalized)?;
-        
-        // 3. 逐词转换
+        // 1. 文本预处理和分词
+        self.phonemes(self.input_tokens(text)?)
+    }
+    
+    /// 逐句惰性转换文本，适合整本书这样的长文本：内存占用不随文本长度增长，前面的句子可以先送去合成
+    ///
+    /// 跨词连读、节奏规则和音素改写在句子内部应用，不跨越句子；除此之外输出与 `text_to_phonemes` 相同。
+    /// 遇到错误时产生该错误后结束。
+    pub fn phonemize_iter<'a>(&'a self, text: &'a str) -> PhonemeStream<'a> {
+        PhonemeStream::new(self, text)
+    }
+    
+    /// 输入单元逐词转换后做词汇后处理
+    fn phonemes(&self, tokens: Vec<InputToken>) -> Result<Vec<Phoneme>> {
+        // 2. 逐词转换
         let mut phonemes = Vec::new();
-        for word in words {
-            let word_phonemes = self.word_to_phonemes(&word)?;
-            phonemes.extend(word_phonemes);
+        for token in tokens {
+            match token {
+                InputToken::Word { word, .. } => phonemes.extend(self.word_phonemes(&word)?),
+                InputToken::Phonemes { phonemes: given, .. } => phonemes.extend(given),
+                InputToken::QuoteMark => phonemes.push(Phoneme::quote_boundary()),
+                InputToken::Pause => phonemes.push(Phoneme::pause()),
+                InputToken::SentenceEnd => phonemes.push(Phoneme::sentence_boundary()),
+                InputToken::Silent { .. } => continue,
+            }
             
-            // 添加词间停顿（可选）
+            // 添加词间停顿，关闭词边界时在词汇后处理之后去掉
             phonemes.push(Phoneme::word_boundary());
         }
         
-        Ok(phonemes)
+        // 3. 跨词连读和音素改写
+        Ok(self.post_lexical(phonemes))
+    }
+    
+    /// 将文本逐词转换为音素，并标记词典未收录的单词
+    pub fn text_to_words(&self, text: &str) -> Result<Vec<WordPhonemes>> {
+        self.words(self.input_tokens(text)?, false)
+    }
+    
+    /// 与 `text_to_words` 相同，但保留不发音的标点和表情，音素为空；可按 `kind` 区分各类词元
+    pub fn text_to_tokens(&self, text: &str) -> Result<Vec<WordPhonemes>> {
+        self.words(self.input_tokens(text)?, true)
+    }
+    
+    /// 按句子切分后逐词转换，每个句子中引号内外的片段分开返回，说话人标记不会和引语混在一起
+    ///
+    /// 调用方可以据此在句子之间和引语前后插入停顿。
+    pub fn text_to_sentences(&self, text: &str) -> Result<Vec<Vec<SpanWords>>> {
+        self.text_processor
+            .sentences(text)
+            .into_iter()
+            .map(|spans| {
+                spans
+                    .into_iter()
+                    .map(|span| {
+                        let (text, quoted) = match span {
+                            text::Span::Narration(text) => (text, false),
+                            text::Span::Quote(text) => (text, true),
+                        };
+                        let mut tokens = Vec::new();
+                        self.push_tokens(text, &mut tokens)?;
+                        Ok(SpanWords { quoted, words: self.words(tokens, false)? })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+    
+    /// 逐词转换，引号边界标记不产生单词；`silent` 为true时保留标点和表情
+    fn words(&self, tokens: Vec<InputToken>, silent: bool) -> Result<Vec<WordPhonemes>> {
+        tokens
+            .into_iter()
+            .filter_map(|token| match token {
+                InputToken::Word { word, surface, kind } => {
+                    let oov = !self.in_lexicon(&word);
+                    Some(self.word_to_phonemes(&word).map(|phonemes| WordPhonemes {
+                        word,
+                        surface,
+                        phonemes: self.rewrite(phonemes),
+                        oov,
+                        kind,
+                        phoneme_set: self.phoneme_set.clone(),
+                    }))
+                }
+                InputToken::Phonemes { text, surface, phonemes, kind } => Some(Ok(WordPhonemes {
+                    word: text,
+                    surface,
+                    phonemes: self.rewrite(phonemes),
+                    oov: false,
+                    kind,
+                    phoneme_set: self.phoneme_set.clone(),
+                })),
+                InputToken::Silent { text, surface, kind } => silent.then(|| {
+                    Ok(WordPhonemes {
+                        word: text,
+                        surface,
+                        phonemes: Vec::new(),
+                        oov: false,
+                        kind,
+                        phoneme_set: self.phoneme_set.clone(),
+                    })
+                }),
+                InputToken::QuoteMark | InputToken::Pause | InputToken::SentenceEnd => None,
+            })
+            .collect()
+    }
+    
+    /// 整段音素序列的词汇后处理：跨词连读和节奏规则，然后依次应用音素改写
+    fn post_lexical(&self, phonemes: Vec<Phoneme>) -> Vec<Phoneme> {
+        let phonemes = self.rules_engine.apply_sandhi(phonemes);
+        let phonemes = if self.rhythm_rule { rhythm::apply_rhythm_rule(phonemes) } else { phonemes };
+        let mut phonemes = self.rewrite(phonemes);
+        if !self.word_boundaries {
+            phonemes.retain(|phoneme| phoneme.symbol != " ");
+        }
+        phonemes
+    }
+    
+    fn rewrite(&self, phonemes: Vec<Phoneme>) -> Vec<Phoneme> {
+        let phonemes = self.rewrites.iter().fold(phonemes, |phonemes, engine| engine.apply(&phonemes));
+        self.schwa.apply(phonemes)
+    }
+    
+    /// 切分为输入单元；启用引号边界标记时在每段引语前后插入标记，启用停顿标记时在每个句子之后插入句子边界
+    fn input_tokens(&self, text: &str) -> Result<Vec<InputToken>> {
+        let mut tokens = Vec::new();
+        if !self.quote_markers && !self.pause_markers {
+            self.push_tokens(text, &mut tokens)?;
+            return Ok(tokens);
+        }
+        
+        for sentence in self.text_processor.sentences(text) {
+            self.push_sentence(sentence, &mut tokens)?;
+        }
+        Ok(tokens)
+    }
+    
+    /// 一个句子的输入单元，按配置插入引号边界标记和句子边界
+    fn push_sentence(&self, sentence: Vec<text::Span>, tokens: &mut Vec<InputToken>) -> Result<()> {
+        for span in sentence {
+            match span {
+                text::Span::Quote(text) if self.quote_markers => {
+                    tokens.push(InputToken::QuoteMark);
+                    self.push_tokens(text, tokens)?;
+                    tokens.push(InputToken::QuoteMark);
+                }
+                text::Span::Narration(text) | text::Span::Quote(text) => self.push_tokens(text, tokens)?,
+            }
+        }
+        if self.pause_markers {
+            // 句末的停顿并入句子边界，空句子不加边界
+            if matches!(tokens.last(), Some(InputToken::Pause)) {
+                tokens.pop();
+            }
+            if !matches!(tokens.last(), None | Some(InputToken::SentenceEnd)) {
+                tokens.push(InputToken::SentenceEnd);
+            }
+        }
+        Ok(())
+    }
+    
+    /// 标准化并分词；拉丁缩写和字母序列按配置的读法处理，启用音素输入时斜杠片段不经标准化直接解析为音素
+    fn push_tokens(&self, text: &str, tokens: &mut Vec<InputToken>) -> Result<()> {
+        let text = self.text_processor.preprocess(text);
+        let phonemic = if self.phoneme_input {
+            text::split_phonemic(&text)
+        } else {
+            vec![text::Segment::Text(&text)]
+        };
+        let segments = phonemic.into_iter().flat_map(|segment| match segment {
+            text::Segment::Text(text) => self.text_processor.segments(text),
+            other => vec![other],
+        });
+        
+        for segment in segments {
+            match segment {
+                text::Segment::Text(text) => {
+                    // 逐块标准化，块内的单词都属于去掉标点后的那个词元；前后各两块传给标准化，数字的读法与整段标准化相同
+                    let chunks: Vec<&str> = text.split_whitespace().collect();
+                    let fast_path = self.case_rules().is_ascii_compatible() && self.is_english();
+                    for (i, &chunk) in chunks.iter().enumerate() {
+                        // 高频单词不需要标准化；快速路径按ASCII规则转小写，土耳其语规则和英语以外的语言不使用
+                        let index = if fast_path { frequent::index_of_token(chunk) } else { None };
+                        if let Some(index) = index {
+                            let word = frequent::FREQUENT_WORDS[index].to_string();
+                            tokens.push(InputToken::Word { word, surface: chunk.to_string(), kind: TokenKind::Word });
+                            continue;
+                        }
+                        let previous: Vec<&str> = chunks[..i].iter().rev().take(2).copied().collect();
+                        let following = &chunks[i + 1..chunks.len().min(i + 3)];
+                        let normalized = self.text_processor.normalize_in_context(chunk, &previous, following)?;
+                        let mut words = self.text_processor.tokens(&normalized);
+                        for (part, kind) in text::classify_tokens(chunk) {
+                            if kind.is_spoken() {
+                                tokens.extend(words.by_ref().map(|word| InputToken::Word {
+                                    word: word.to_string(),
+                                    surface: part.to_string(),
+                                    kind,
+                                }));
+                            } else {
+                                tokens.push(InputToken::Silent { text: part.to_string(), surface: part.to_string(), kind });
+                                if self.pause_markers && kind == TokenKind::Punctuation && part.contains(text::is_clause_break) {
+                                    tokens.push(InputToken::Pause);
+                                }
+                            }
+                        }
+                    }
+                }
+                text::Segment::Phonemic(symbols) => {
+                    let phonemes = phoneme::parse_arpabet(symbols)
+                        .with_context(|| format!("Invalid phoneme input '/{}/'", symbols))?;
+                    let text = format!("/{}/", symbols);
+                    tokens.push(InputToken::Phonemes { surface: text.clone(), text, phonemes, kind: TokenKind::Word });
+                }
+                text::Segment::Letters(letters) => {
+                    let phonemes = letters::spell_out(letters);
+                    let text = letters.to_lowercase();
+                    let surface = letters.to_string();
+                    tokens.push(InputToken::Phonemes { text, surface, phonemes, kind: TokenKind::Acronym });
+                }
+                text::Segment::Filler(word) => {
+                    let style = self.text_processor.options().disfluency;
+                    if style == text::DisfluencyStyle::Drop {
+                        continue;
+                    }
+                    if let Some((text, pronunciation)) = text::filler(word, style) {
+                        let phonemes = phoneme::parse_arpabet(pronunciation)?;
+                        let surface = word.to_string();
+                        tokens.push(InputToken::Phonemes { text, surface, phonemes, kind: TokenKind::Filler });
+                    }
+                }
+                text::Segment::Event(inner) => {
+                    let name = text::non_verbal_name(inner, true).unwrap_or_default();
+                    let text = format!("[{}]", name);
+                    let surface = inner.to_string();
+                    if self.text_processor.options().non_verbal == text::NonVerbalStyle::Event {
+                        let phonemes = vec![Phoneme::event(&name)];
+                        tokens.push(InputToken::Phonemes { text, surface, phonemes, kind: TokenKind::NonVerbal });
+                    } else {
+                        tokens.push(InputToken::Silent { text, surface, kind: TokenKind::NonVerbal });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// 在时间预算内将文本转换为音素
+    ///
+    /// 预计下一个单词会超出预算时（按已转换单词的平均耗时估计），后续的未登录词不再还原词干、不再进入后备策略，
+    /// 直接使用规则输出并记录在 `degraded_words` 中；转写、不规则词汇层和缓存只是查表，仍然照常使用。
+    pub fn text_to_phonemes_budgeted(&self, text: &str, budget: Duration) -> Result<BudgetedOutput> {
+        let start = Instant::now();
+        let tokens = self.input_tokens(text)?;
+        
+        let mut output = BudgetedOutput {
+            phonemes: Vec::new(),
+            degraded_words: Vec::new(),
+            deadline_exceeded: false,
+        };
+        
+        let mut converted = 0;
+        for token in tokens {
+            match token {
+                InputToken::Word { word, .. } => {
+                    // 用已转换单词的平均耗时估计下一个单词的耗时
+                    let elapsed = start.elapsed();
+                    let per_word = if converted == 0 { Duration::ZERO } else { elapsed / converted };
+                    let allow_fallback = elapsed + per_word <= budget;
+                    converted += 1;
+                    
+                    let (word_phonemes, degraded) = self.convert_word(&word, allow_fallback)?;
+                    if degraded {
+                        output.degraded_words.push(word);
+                    }
+                    output.phonemes.extend(word_phonemes);
+                }
+                InputToken::Phonemes { phonemes, .. } => output.phonemes.extend(phonemes),
+                InputToken::QuoteMark => output.phonemes.push(Phoneme::quote_boundary()),
+                InputToken::Pause => output.phonemes.push(Phoneme::pause()),
+                InputToken::SentenceEnd => output.phonemes.push(Phoneme::sentence_boundary()),
+                InputToken::Silent { .. } => continue,
+            }
+            output.phonemes.push(Phoneme::word_boundary());
+        }
+        
+        output.phonemes = self.post_lexical(output.phonemes);
+        output.deadline_exceeded = start.elapsed() > budget;
+        Ok(output)
+    }
+    
+    /// 当前配置的音素记法
+    pub fn notation(&self) -> phoneme::Notation {
+        self.notation
+    }
+    
+    /// 按配置的记法把音素序列输出为字符串
+    pub fn render(&self, phonemes: &[Phoneme]) -> String {
+        phoneme::render_with(phonemes, self.notation, &self.boundary_symbols)
+    }
+    
+    /// 单词的音素串，如 `HH AH L OW`，见 `phoneme::phone_string`
+    pub fn word_to_phone_string(&self, word: &str) -> Result<String> {
+        Ok(phoneme::phone_string(&self.word_to_phonemes(word)?, self.phone_stress))
+    }
+    
+    /// 文本的音素串，省略词边界等标记，所有音素以空格连接
+    pub fn text_to_phone_string(&self, text: &str) -> Result<String> {
+        Ok(phoneme::phone_string(&self.text_to_phonemes(text)?, self.phone_stress))
     }
     
     /// 单词转音素（核心功能）
     pub fn word_to_phonemes(&self, word: &str) -> Result<Vec<Phoneme>> {
-        let word = word.to_lowercase();
-        
+        Ok(self.word_phonemes(word)?.into_vec())
+    }
+    
+    /// 同 `word_to_phonemes`，结果留在栈上，供逐词拼接整段音素的调用方使用
+    fn word_phonemes(&self, word: &str) -> Result<PhonemeSeq> {
+        // 弯撇号按直撇号查词典，与文本中的单词一致：don’t → don't
+        const APOSTROPHES: [char; 3] = ['’', 'ʼ', '‘'];
+        if word.contains(APOSTROPHES) {
+            return Ok(self.convert_word(&word.replace(APOSTROPHES, "'"), true)?.0);
+        }
+        Ok(self.convert_word(word, true)?.0)
+    }
+    
+    /// 转换单词；`allow_fallback` 为false时跳过后备策略，返回值的第二项表示是否因此降级
+    pub(crate) fn convert_word(&self, word: &str, allow_fallback: bool) -> Result<(PhonemeSeq, bool)> {
         // 1. 先查词典
-        if let Some(phonemes) = self.dictionary.lookup(&word) {
-            return Ok(phonemes);
+        let result = match self.lookup_dictionary(word) {
+            Some(codes) => Ok((codes.iter().map(|code| code.decode()).collect(), false)),
+            None => self.convert_oov(word, allow_fallback),
+        };
+        self.notify_outcome(word, &result);
+        result
+    }
+    
+    /// 通知所有观察者
+    fn notify(&self, event: impl Fn(&dyn Observer)) {
+        for observer in &self.observers {
+            event(observer.as_ref());
+        }
+    }
+    
+    /// 将单词的转换结果通知观察者
+    pub(crate) fn notify_outcome(&self, word: &str, result: &Result<(PhonemeSeq, bool)>) {
+        match result {
+            Ok((phonemes, _)) => self.notify(|o| o.on_word_converted(word, phonemes)),
+            Err(e) => self.notify(|o| o.on_error(word, e)),
+        }
+    }
+    
+    /// 查词典并计数，用户词典层优先；每个待转换的单词都应先经过这里
+    pub(crate) fn lookup_dictionary(&self, word: &str) -> Option<Cow<'_, [PhonemeCode]>> {
+        Counters::add(&self.counters.words, 1);
+        // 按配置的规则转小写，词典内部不再转换
+        let lowercase = self.case_rules().lowercase(word);
+        let codes = match self.lookup_user(&lowercase) {
+            Some(codes) => Some(Cow::Owned(codes)),
+            None => self.frequent.lookup(&lowercase).or_else(|| self.dictionary.lookup_ref(&lowercase)).map(Cow::Borrowed),
+        };
+        if codes.is_some() {
+            Counters::add(&self.counters.dictionary_hits, 1);
+        } else {
+            self.notify(|o| o.on_oov(word));
+        }
+        codes
+    }
+    
+    /// 查用户词典层，不计数
+    pub(crate) fn lookup_user(&self, word: &str) -> Option<Vec<PhonemeCode>> {
+        self.user.lookup_codes(word)
+    }
+    
+    /// 单词是否在用户词典层或主词典中，不计数
+    fn in_lexicon(&self, word: &str) -> bool {
+        self.dictionary.lookup_ref(word).is_some() || self.lookup_user(word).is_some()
+    }
+    
+    /// 转换词典未收录的单词
+    pub(crate) fn convert_oov(&self, word: &str, allow_fallback: bool) -> Result<(PhonemeSeq, bool)> {
+        // 超长的单词不进入后续步骤
+        if let Some((limit, policy)) = self.long_words {
+            if let Some(result) = self.convert_long_word(word, limit, policy) {
+                return result;
+            }
+        }
+        
+        let word = self.case_rules().lowercase(word).into_owned();
+        
+        // 没有对应语言包的文字转写为拉丁字母，再按英语单词转换
+        if self.transliteration {
+            if let Some(latin) = translit::transliterate(&word) {
+                if let Some(codes) = self.lookup_user(&latin).or_else(|| self.dictionary.lookup_ref(&latin).map(<[_]>::to_vec)) {
+                    Counters::add(&self.counters.dictionary_hits, 1);
+                    return Ok((codes.iter().map(|code| code.decode()).collect(), false));
+                }
+                return self.convert_oov(&latin, allow_fallback);
+            }
         }
         
-        // 2. 使用规则引擎
-        self.rules_engine.apply_rules(&word)
+        // 2. 查不规则词汇层
+        if let Some(codes) = self.irregular.lookup_codes(&word) {
+            Counters::add(&self.counters.irregular_hits, 1);
+            return Ok((codes.iter().map(|code| code.decode()).collect(), false));
+        }
+        
+        // 3. 还原派生词的词干；超出时间预算时跳过，结果记为降级
+        let skipped_stem = self.stem_recovery && !allow_fallback;
+        if self.stem_recovery && allow_fallback {
+            if let Some((_, phonemes)) = self.recover_stem(&word) {
+                Counters::add(&self.counters.stem_hits, 1);
+                return Ok((PhonemeSeq::from_vec(phonemes), false));
+            }
+        }
+        
+        // 4. 依次查内存缓存和磁盘缓存，未命中时转换后写入缓存（降级结果不写入）
+        if let Some(rule_cache) = &self.rule_cache {
+            if let Some(phonemes) = rule_cache.get(&word) {
+                Counters::add(&self.counters.rule_cache_hits, 1);
+                return Ok((phonemes, false));
+            }
+            Counters::add(&self.counters.rule_cache_misses, 1);
+        }
+        if let Some(cache) = &self.cache {
+            if let Some(phonemes) = cache.get_seq(&word) {
+                Counters::add(&self.counters.cache_hits, 1);
+                if let Some(rule_cache) = &self.rule_cache {
+                    rule_cache.insert(&word, &phonemes);
+                }
+                return Ok((phonemes, false));
+            }
+            Counters::add(&self.counters.cache_misses, 1);
+        }
+        let (phonemes, degraded) = self.convert_with_rules(&word, allow_fallback)?;
+        let degraded = degraded || skipped_stem;
+        if !degraded {
+            if let Some(rule_cache) = &self.rule_cache {
+                rule_cache.insert(&word, &phonemes);
+            }
+            if let Some(cache) = &self.cache {
+                cache.insert(&word, &phonemes)?;
+            }
+        }
+        Ok((phonemes, degraded))
+    }
+    
+    /// 按 `policy` 处理超过 `limit` 个字符的单词，未超过时返回None
+    fn convert_long_word(&self, word: &str, limit: usize, policy: LongWordPolicy) -> Option<Result<(PhonemeSeq, bool)>> {
+        // 只数到 limit + 1 个字符，不遍历整个单词
+        let (end, _) = word.char_indices().nth(limit)?;
+        Some(match policy {
+            LongWordPolicy::Skip => Ok((PhonemeSeq::new(), false)),
+            LongWordPolicy::SpellOut => Ok((PhonemeSeq::from_vec(letters::spell_out(&word[..end])), false)),
+            LongWordPolicy::Error => Err(G2PError::WordTooLong { word: word.to_string(), length: word.chars().count(), limit }),
+        })
+    }
+    
+    /// 使用规则引擎转换未登录词，必要时转入后备策略
+    fn convert_with_rules(&self, word: &str, allow_fallback: bool) -> Result<(PhonemeSeq, bool)> {
+        // 使用规则引擎
+        let output = self.rules_engine.apply_rules_scored(word)?;
+        if let (true, Some(&offset)) = (self.strict, output.uncovered.first()) {
+            return Err(UnknownCharacterError {
+                word: word.to_string(),
+                offset,
+                character: word.chars().nth(offset).unwrap_or_default(),
+                suggestions: Suggestions {
+                    spelling: self.dictionary.nearest_spellings(word, SUGGESTION_LIMIT),
+                    sound: self.dictionary.nearest_pronunciations(&output.phonemes, SUGGESTION_LIMIT),
+                },
+            }
+            .into());
+        }
+        if output.irregular {
+            Counters::add(&self.counters.irregular_hits, 1);
+        } else {
+            Counters::add(&self.counters.rule_words, 1);
+        }
+        Counters::add(&self.counters.default_letters, output.default_letters as u64);
+        
+        // 低置信度或不符合音位配列时转入后备策略
+        if !self.needs_fallback(&output) {
+            return Ok((PhonemeSeq::from_vec(output.phonemes), false));
+        }
+        if !allow_fallback {
+            return Ok((PhonemeSeq::from_vec(output.phonemes), true));
+        }
+        
+        self.notify(|o| o.on_fallback(word, self.routing.fallback));
+        match self.routing.fallback {
+            Fallback::Keep => Ok((PhonemeSeq::from_vec(output.phonemes), false)),
+            Fallback::SpellOut => Ok((PhonemeSeq::from_vec(letters::spell_out(word)), false)),
+            Fallback::Error => Err(G2PError::OovWord {
+                word: word.to_string(),
+                confidence: output.confidence,
+                valid: phoneme::is_phonotactically_valid(&output.phonemes),
+            }),
+        }
+    }
+    
+    /// 规则输出是否需要转入后备策略
+    fn needs_fallback(&self, output: &rules::RuleOutput) -> bool {
+        let valid = phoneme::is_phonotactically_valid(&output.phonemes);
+        let low_confidence = output.confidence < self.routing.min_confidence;
+        self.routing.fallback != Fallback::Keep
+            && (low_confidence || (!valid && self.routing.require_valid_phonotactics))
+    }
+    
+    /// 创建批量转换会话，适合逐篇处理大量文档
+    pub fn batch_session(&self) -> BatchSession<'_> {
+        BatchSession::new(self)
+    }
+    
+    /// 添加用户词条，发音为空格分隔的ARPAbet音素（如 `F UW1`）
+    pub fn add_word(&mut self, word: &str, pronunciation: &str) -> Result<()> {
+        let phonemes = phoneme::parse_arpabet(pronunciation)
+            .with_context(|| format!("Invalid pronunciation for '{}'", word))?;
+        
+        let word = self.case_rules().lowercase(word).into_owned();
+        self.dictionary.add_entry(word.clone(), phonemes);
+        self.frequent.refresh(&self.dictionary, &word);
+        Ok(())
     }
     
-    /// 获取统计信息
+    /// 在用户词典层中加入或替换词条，查询时优先于主词典
+    ///
+    /// 只需要共享引用，服务端可以在 `Arc<RustG2P>` 上运行时修正发音，不必重建转换器。
+    pub fn insert_word(&self, word: &str, pronunciation: &str) -> Result<()> {
+        self.user.insert(word, pronunciation)
+    }
+    
+    /// 删除用户词典层中的词条，返回词条是否存在；主词典中的发音随之恢复
+    pub fn remove_word(&self, word: &str) -> bool {
+        let word = self.case_rules().lowercase(word).into_owned();
+        self.write_corrections().retain(|(corrected, _)| *corrected != word);
+        self.user.remove(&word)
+    }
+    
+    /// 用户词典层的词条数
+    pub fn user_word_count(&self) -> usize {
+        self.user.len()
+    }
+    
+    /// 用户词典层
+    pub fn user_lexicon(&self) -> &LexiconLayer {
+        &self.user
+    }
+    
+    /// 不规则词汇层，可以在运行时增删词条
+    pub fn irregular_lexicon(&self) -> &LexiconLayer {
+        &self.irregular
+    }
+    
+    /// 记录一条人工纠正：与 `insert_word` 一样写入用户词典层，并保留下来供 `suggest_rules` 归纳规则
+    pub fn record_correction(&self, word: &str, pronunciation: &str) -> Result<()> {
+        let phonemes = phoneme::parse_arpabet(pronunciation)
+            .with_context(|| format!("Invalid pronunciation for '{}'", word))?;
+        
+        let mut corrections = self.write_corrections();
+        self.user.insert_phonemes(word, &phonemes);
+        let word = self.case_rules().lowercase(word).into_owned();
+        corrections.retain(|(corrected, _)| *corrected != word);
+        corrections.push((word, phonemes));
+        Ok(())
+    }
+    
+    /// 从已记录的纠正中归纳候选规则，只给出建议，不修改规则引擎
+    ///
+    /// 不规则词汇层中的单词是已知的例外，不参与归纳。
+    pub fn suggest_rules(&self) -> Result<Vec<learn::RuleSuggestion>> {
+        let corrections: Vec<_> = self
+            .corrections
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(word, _)| self.irregular.lookup_codes(word).is_none())
+            .cloned()
+            .collect();
+        learn::suggest_rules(&self.rules_engine, &corrections)
+    }
+    
+    fn write_corrections(&self) -> std::sync::RwLockWriteGuard<'_, Vec<(String, Vec<Phoneme>)>> {
+        // 纠正只整条追加或删除，锁中毒时数据仍然一致
+        self.corrections.write().unwrap_or_else(|e| e.into_inner())
+    }
+    
+    /// 估算各组件的堆内存占用
+    pub fn memory_report(&self) -> MemoryReport {
+        MemoryReport {
+            dictionary_bytes: self.dictionary.heap_size(),
+            rules_bytes: self.rules_engine.heap_size(),
+            cache_bytes: self.cache.as_ref().map_or(0, DiskCache::heap_size),
+            rule_cache_bytes: self.rule_cache.as_ref().map_or(0, cache::RuleCache::heap_size),
+        }
+    }
+    
+    /// 获取统计信息（词典和规则规模以及运行时计数）
     pub fn get_stats(&self) -> G2PStats {
-        G2PStats {
-            dict_entries: self.dictionary.size(),
-            rule_count: self.rules_engine.rule_count(),
+        self.counters.snapshot(&self.language, self.dictionary.size(), self.rules_engine.rule_count())
+    }
+    
+    /// 清零运行时计数
+    pub fn reset_stats(&self) {
+        self.counters.reset();
+    }
+    
+    /// 词典、规则、不规则词汇层和用户词典层内容的指纹，只由数据决定，与平台和运行次数无关
+    pub fn data_fingerprint(&self) -> u64 {
+        let mut hasher = golden::StableHasher::new();
+        hasher.write_field(&self.dictionary.to_cmu_string());
+        self.rules_engine.fingerprint(&mut hasher);
+        self.irregular.fingerprint(&mut hasher);
+        self.user.fingerprint(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// 预处理后的输入单元
+///
+/// `surface` 为该词元在原文中的写法。
+enum InputToken {
+    Word { word: String, surface: String, kind: TokenKind },
+    Phonemes { text: String, surface: String, phonemes: Vec<Phoneme>, kind: TokenKind }, // 现成的音素：斜杠音素输入、字母拼读、填充词或事件音素
+    Silent { text: String, surface: String, kind: TokenKind }, // 不发音的标点、表情和非语言声音
+    QuoteMark, // 引语的起止
+    Pause,       // 句中停顿
+    SentenceEnd, // 句子结束
+}
+
+/// 单个单词的转换结果
+#[derive(Debug, Clone)]
+pub struct WordPhonemes {
+    pub word: String,
+    pub surface: String, // 原文中的写法，保留大小写；数字、缩写展开出的各个单词共用原文中的同一个写法，非语言声音不含括号
+    pub phonemes: Vec<Phoneme>,
+    pub oov: bool, // 词典未收录
+    pub kind: TokenKind,
+    pub phoneme_set: phoneme::PhonemeSet, // 音素所属的音素集，见 `RustG2P::with_phoneme_set`
+}
+
+impl WordPhonemes {
+    /// 原文的大小写形式
+    pub fn casing(&self) -> text::Casing {
+        text::Casing::of(&self.surface)
+    }
+    
+    /// 投影到IPA（见 `phoneme::project_ipa`），已是IPA时不变；几种语言的词元混在一起时逐个投影，得到同一套符号
+    pub fn to_ipa(&self) -> WordPhonemes {
+        let phonemes = match self.phoneme_set {
+            phoneme::PhonemeSet::Ipa => self.phonemes.clone(),
+            phoneme::PhonemeSet::Arpabet(_) => phoneme::project_ipa(&self.phonemes),
+        };
+        WordPhonemes {
+            word: self.word.clone(),
+            surface: self.surface.clone(),
+            phonemes,
+            oov: self.oov,
+            kind: self.kind,
+            phoneme_set: phoneme::PhonemeSet::Ipa,
         }
     }
 }
 
-#[derive(Debug)]
-pub struct G2PStats {
-    pub dict_entries: usize,
-    pub rule_count: usize,
-}
\ No newline at end of file
+/// 句子中引号内或引号外的一段
+#[derive(Debug, Clone)]
+pub struct SpanWords {
+    pub quoted: bool,
+    pub words: Vec<WordPhonemes>,
+}
+
+/// 限时转换的结果
+#[derive(Debug, Clone)]
+pub struct BudgetedOutput {
+    pub phonemes: Vec<Phoneme>,
+    pub degraded_words: Vec<String>, // 因时间预算跳过后备策略的单词
+    pub deadline_exceeded: bool,
+}
@@ -0,0 +1,118 @@
+//! 歌词模式：把歌词转换为音节单元，供歌声合成使用
+//!
+//! 歌词文件的常见写法：
+//!
+//! - `a-ma-zing` 或 `a -- ma -- zing`：连字符划分音节，每段对应一个音符
+//! - `_`（单独一个或接在音节后，如 `love_`）：延长线，前一个音节再占一个音符（花腔）
+//!
+//! 整个单词按一个词转换，保证读音正确，再切分为音节；连字符划分的段数与音节数一致时，
+//! 每段文字对应一个音节，否则各段分别转换，每段作为一个单元。
+
+use crate::phoneme::Phoneme;
+use crate::syllable;
+use crate::RustG2P;
+use crate::error::Result;
+
+/// 一个歌词音节
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LyricSyllable {
+    pub word: String,           // 所在单词，去掉了连字符
+    pub text: Option<String>,   // 连字符划分出的文字，单词没有连字符时为 None
+    pub phonemes: Vec<Phoneme>,
+    pub nucleus: Option<usize>, // 唱长音时延长的元音在 `phonemes` 中的位置
+    pub notes: usize,           // 占的音符数，至少为1
+    pub word_start: bool,       // 是否为单词的第一个音节
+}
+
+impl LyricSyllable {
+    /// 唱长音时延长的元音
+    pub fn sustained(&self) -> Option<&Phoneme> {
+        self.nucleus.map(|i| &self.phonemes[i])
+    }
+
+    /// 是否跨越多个音符（花腔）
+    pub fn is_melisma(&self) -> bool {
+        self.notes > 1
+    }
+}
+
+impl RustG2P {
+    /// 把一段歌词转换为音节单元
+    pub fn lyrics_to_syllables(&self, lyrics: &str) -> Result<Vec<LyricSyllable>> {
+        let mut syllables: Vec<LyricSyllable> = Vec::new();
+        for (word, extensions) in lyric_words(lyrics) {
+            if !word.is_empty() {
+                self.push_word(&word, &mut syllables)?;
+            }
+            if let Some(last) = syllables.last_mut() {
+                last.notes += extensions;
+            }
+        }
+        Ok(syllables)
+    }
+
+    fn push_word(&self, word: &str, out: &mut Vec<LyricSyllable>) -> Result<()> {
+        let parts: Vec<&str> = word.split('-').filter(|part| !part.is_empty()).collect();
+        let joined = parts.concat();
+        let phonemes = self.word_to_phonemes(&joined)?;
+        let units = syllable::syllabify(&phonemes);
+        let hyphenated = parts.len() > 1;
+
+        let start = out.len();
+        if !hyphenated || units.len() == parts.len() {
+            for (i, unit) in units.iter().enumerate() {
+                out.push(LyricSyllable {
+                    word: joined.clone(),
+                    text: hyphenated.then(|| parts[i].to_string()),
+                    phonemes: phonemes[unit.start..unit.end].to_vec(),
+                    nucleus: unit.nucleus.map(|nucleus| nucleus - unit.start),
+                    notes: 1,
+                    word_start: false,
+                });
+            }
+        } else {
+            // 连字符与切分的音节数不同时，以连字符为准
+            for part in &parts {
+                let phonemes = self.word_to_phonemes(part)?;
+                out.push(LyricSyllable {
+                    word: joined.clone(),
+                    text: Some(part.to_string()),
+                    nucleus: phonemes.iter().position(Phoneme::is_vowel),
+                    phonemes,
+                    notes: 1,
+                    word_start: false,
+                });
+            }
+        }
+        if let Some(first) = out.get_mut(start) {
+            first.word_start = true;
+        }
+        Ok(())
+    }
+}
+
+/// 把歌词拆成单词和每个单词后面的延长线数；`--` 连接前后两段，开头的延长线数记在空单词上
+fn lyric_words(lyrics: &str) -> Vec<(String, usize)> {
+    let mut words: Vec<(String, usize)> = Vec::new();
+    let mut join_next = false;
+    for token in lyrics.split_whitespace() {
+        if token == "--" {
+            join_next = true;
+            continue;
+        }
+        let token = token.trim_matches(|c: char| c.is_ascii_punctuation() && !matches!(c, '-' | '_' | '\''));
+        let word = token.trim_end_matches('_').trim_matches('-');
+        let extensions = token.len() - token.trim_end_matches('_').len();
+        match words.last_mut() {
+            Some(last) if word.is_empty() => last.1 += extensions,
+            Some(last) if join_next && last.1 == 0 => {
+                last.0.push('-');
+                last.0.push_str(word);
+                last.1 = extensions;
+            }
+            _ => words.push((word.to_string(), extensions)),
+        }
+        join_next = false;
+    }
+    words
+}
@@ -0,0 +1,148 @@
+use crate::numbers;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+
+/// 代码中读出名称的符号，其余符号（括号、引号等）不读
+const SYMBOL_NAMES: [(char, &str); 16] = [
+    ('.', "dot"),
+    ('_', "underscore"),
+    ('-', "dash"),
+    ('/', "slash"),
+    ('\\', "backslash"),
+    ('=', "equals"),
+    ('+', "plus"),
+    ('*', "star"),
+    ('#', "hash"),
+    ('@', "at"),
+    ('$', "dollar"),
+    ('%', "percent"),
+    ('&', "and"),
+    ('~', "tilde"),
+    ('|', "pipe"),
+    (':', "colon"),
+];
+
+/// HTML命名实体
+const ENTITIES: [(&str, &str); 8] = [
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", " "),
+    ("mdash", "—"),
+    ("ndash", "–"),
+];
+
+/// 去掉Markdown和HTML标记，保留要读出的文字
+///
+/// - 链接和图片读锚文本或替代文本：`[docs](https://…) → docs`
+/// - 强调（`**bold**`、`_em_`、`~~del~~`）只保留内容，标题和引用块去掉标记
+/// - 行内代码逐字符拼读：`` `ls -a` `` → `L.S. dash A.`，代码块整段去掉
+/// - HTML标签去掉，段落和换行标签换成换行，`<li>` 换成列表项，实体解码
+///
+/// 需要在其他预处理之前进行。
+pub fn strip_markup(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref FENCE_RE: Regex = Regex::new(r"(?ms)^[ \t]*```.*?^[ \t]*```[^\n]*$\n?").unwrap();
+        static ref HIDDEN_RE: Regex = Regex::new(r"(?is)<script\b.*?</script>|<style\b.*?</style>|<!--.*?-->").unwrap();
+        static ref CODE_RE: Regex = Regex::new(r"(?is)`([^`\n]+)`|<code>(.*?)</code>").unwrap();
+        static ref BREAK_RE: Regex = Regex::new(r"(?i)<br\s*/?>|</(?:p|div|li|h[1-6]|tr|blockquote)>").unwrap();
+        static ref LIST_ITEM_RE: Regex = Regex::new(r"(?i)<li\b[^>]*>").unwrap();
+        static ref AUTOLINK_RE: Regex = Regex::new(r"<(https?://[^>\s]+)>").unwrap();
+        static ref TAG_RE: Regex = Regex::new(r"</?[a-zA-Z][^>]*>").unwrap();
+        static ref IMAGE_RE: Regex = Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap();
+        static ref LINK_RE: Regex = Regex::new(r"\[([^\]]+)\](?:\([^)]*\)|\[[^\]]*\])").unwrap();
+        static ref LINK_DEFINITION_RE: Regex = Regex::new(r"(?m)^[ \t]*\[[^\]]+\]:[ \t]*\S+[^\n]*$").unwrap();
+        static ref RULE_RE: Regex = Regex::new(r"(?m)^[ \t]*(?:(?:-[ \t]*){3,}|(?:\*[ \t]*){3,}|(?:_[ \t]*){3,})$").unwrap();
+        static ref HEADING_RE: Regex = Regex::new(r"(?m)^[ \t]*#{1,6}[ \t]+([^\r\n]*?)[ \t#]*$").unwrap();
+        static ref BLOCKQUOTE_RE: Regex = Regex::new(r"(?m)^[ \t]*>[ \t]?").unwrap();
+        static ref EMPHASIS_RE: Regex = Regex::new(
+            r"\*\*([^*\n]+)\*\*|__([^_\n]+)__|~~([^~\n]+)~~|\*([^*\s][^*\n]*?)\*|\b_([^_\n]+?)_\b"
+        )
+        .unwrap();
+        static ref ENTITY_RE: Regex = Regex::new(r"&(#[xX][0-9a-fA-F]+|#\d+|[a-z]+);").unwrap();
+    }
+
+    if !text.contains(['<', '>', '*', '_', '`', '[', '#', '~', '&']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = FENCE_RE.replace_all(text, "").into_owned();
+    out = HIDDEN_RE.replace_all(&out, "").into_owned();
+    out = CODE_RE
+        .replace_all(&out, |caps: &Captures| spell_code(caps.get(1).or_else(|| caps.get(2)).unwrap().as_str()))
+        .into_owned();
+    out = BREAK_RE.replace_all(&out, "\n").into_owned();
+    out = LIST_ITEM_RE.replace_all(&out, "\n- ").into_owned();
+    out = AUTOLINK_RE.replace_all(&out, "$1").into_owned();
+    out = TAG_RE.replace_all(&out, "").into_owned();
+    out = IMAGE_RE.replace_all(&out, "$1").into_owned();
+    out = LINK_RE.replace_all(&out, "$1").into_owned();
+    out = LINK_DEFINITION_RE.replace_all(&out, "").into_owned();
+    out = RULE_RE.replace_all(&out, "").into_owned();
+    out = HEADING_RE
+        .replace_all(&out, |caps: &Captures| {
+            // 标题自成一句
+            let heading = &caps[1];
+            if heading.is_empty() || heading.ends_with(['.', '!', '?', ':']) {
+                heading.to_string()
+            } else {
+                format!("{}.", heading)
+            }
+        })
+        .into_owned();
+    out = BLOCKQUOTE_RE.replace_all(&out, "").into_owned();
+    out = EMPHASIS_RE
+        .replace_all(&out, |caps: &Captures| {
+            (1..=5).find_map(|group| caps.get(group)).unwrap().as_str().to_string()
+        })
+        .into_owned();
+    // 实体最后解码，解码出的 `<`、`*` 不再当作标记
+    out = ENTITY_RE
+        .replace_all(&out, |caps: &Captures| decode_entity(&caps[1]).unwrap_or_else(|| caps[0].to_string()))
+        .into_owned();
+
+    if out == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(out)
+    }
+}
+
+/// 逐字符拼读代码：字母写成带点的大写字母序列，之后按字母名称读；数字逐位读，常见符号读名称
+fn spell_code(code: &str) -> String {
+    let mut words = Vec::new();
+    let mut letters = String::new();
+    for c in code.chars() {
+        if c.is_ascii_alphabetic() {
+            letters.push(c.to_ascii_uppercase());
+            letters.push('.');
+            continue;
+        }
+        if !letters.is_empty() {
+            words.push(std::mem::take(&mut letters));
+        }
+        if let Some(digit) = c.to_digit(10) {
+            words.push(numbers::cardinal(digit as u64));
+        } else if let Some((_, name)) = SYMBOL_NAMES.iter().find(|(symbol, _)| *symbol == c) {
+            words.push(name.to_string());
+        }
+    }
+    if !letters.is_empty() {
+        words.push(letters);
+    }
+    words.join(" ")
+}
+
+fn decode_entity(entity: &str) -> Option<String> {
+    let code = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(decimal) = entity.strip_prefix('#') {
+        decimal.parse().ok()
+    } else {
+        return ENTITIES.iter().find(|(name, _)| *name == entity).map(|(_, text)| text.to_string());
+    };
+    code.and_then(char::from_u32).map(String::from)
+}
@@ -0,0 +1,45 @@
+use crate::phoneme::Phoneme;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::size_of;
+
+/// 堆内存占用估算（字节）
+#[derive(Debug, Clone, Default)]
+pub struct MemoryReport {
+    pub dictionary_bytes: usize,
+    pub rules_bytes: usize,
+    pub cache_bytes: usize,
+    pub rule_cache_bytes: usize, // 规则结果的内存缓存
+}
+
+impl MemoryReport {
+    pub fn total(&self) -> usize {
+        self.dictionary_bytes + self.rules_bytes + self.cache_bytes + self.rule_cache_bytes
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mb = |bytes: usize| bytes as f64 / 1024.0 / 1024.0;
+        writeln!(f, "Dictionary: {:.2} MB", mb(self.dictionary_bytes))?;
+        writeln!(f, "Rules:      {:.2} MB", mb(self.rules_bytes))?;
+        writeln!(f, "Cache:      {:.2} MB", mb(self.cache_bytes))?;
+        writeln!(f, "Rule cache: {:.2} MB", mb(self.rule_cache_bytes))?;
+        write!(f, "Total:      {:.2} MB", mb(self.total()))
+    }
+}
+
+/// 字符串列表的堆占用
+pub(crate) fn strings_heap(strings: &[String]) -> usize {
+    std::mem::size_of_val(strings) + strings.iter().map(String::capacity).sum::<usize>()
+}
+
+/// 音素列表的堆占用
+pub(crate) fn phonemes_heap(phonemes: &[Phoneme]) -> usize {
+    std::mem::size_of_val(phonemes) + phonemes.iter().map(|p| p.symbol.capacity()).sum::<usize>()
+}
+
+/// 哈希表桶数组的堆占用（不含键值自身的堆数据），每个桶另有1字节控制位
+pub(crate) fn map_heap<K, V>(map: &HashMap<K, V>) -> usize {
+    map.capacity() * (size_of::<K>() + size_of::<V>() + 1)
+}
@@ -0,0 +1,77 @@
+//! 单词的多个候选读音
+//!
+//! 首个候选总是与 `word_to_phonemes` 的结果相同，其余候选按分数从高到低排列：
+//!
+//! - 词典读音：`Dictionary::lookup_all` 返回的读音（含 `READ(1)` 这样的变体），
+//!   主读音分数为 1，第 k 个变体为 1/(k+1)
+//! - 规则读音：首选读音的分数为规则置信度，优先级相同的规则同时匹配时给出的备选读音
+//!   （见 `RulesEngine::apply_rules_nbest`）分数为首选分数与备选置信度之积的一半
+//!
+//! 用户词典、不规则词汇、词干还原、缓存和后备策略的结果只有一个读音，分数为 1（后备策略为规则置信度）。
+
+use crate::phoneme::{self, Phoneme};
+use crate::trace::WordSource;
+use crate::{translit, Fallback, RustG2P};
+use crate::error::{G2PError, Result};
+
+/// 一个候选读音
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub phonemes: Vec<Phoneme>,
+    pub source: WordSource,
+    pub score: f32, // 0.0 ~ 1.0
+}
+
+impl RustG2P {
+    /// 单词的至多 `n` 个候选读音，按分数从高到低排列；不计入统计也不通知观察者
+    ///
+    /// 后备策略为 `Fallback::Error` 且规则输出不可靠时，与 `word_to_phonemes` 一样返回错误。
+    pub fn word_to_phonemes_nbest(&self, word: &str, n: usize) -> Result<Vec<Candidate>> {
+        let trace = self.trace_word(word)?;
+        // 希腊字母、西里尔字母的单词按转写结果查词典和应用规则
+        let lookup = match self.transliteration.then(|| translit::transliterate(&trace.word)).flatten() {
+            Some(latin) => latin,
+            None => trace.word.clone(),
+        };
+        let mut candidates = match trace.source {
+            WordSource::Dictionary => self
+                .dictionary
+                .lookup_all(&lookup)
+                .into_iter()
+                .enumerate()
+                .map(|(rank, phonemes)| Candidate {
+                    phonemes,
+                    source: WordSource::Dictionary,
+                    score: 1.0 / (rank + 1) as f32,
+                })
+                .collect(),
+            WordSource::Rules => {
+                let outputs = self.rules_engine.apply_rules_nbest(&lookup, n)?;
+                let best = outputs.first().map_or(0.0, |output| output.confidence);
+                outputs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(rank, output)| Candidate {
+                        score: if rank == 0 { best } else { best * output.confidence / 2.0 },
+                        phonemes: output.phonemes,
+                        source: WordSource::Rules,
+                    })
+                    .collect()
+            }
+            WordSource::Fallback(Fallback::Error) => {
+                return Err(G2PError::OovWord {
+                    valid: phoneme::is_phonotactically_valid(&trace.phonemes),
+                    word: trace.word,
+                    confidence: trace.confidence.unwrap_or_default(),
+                });
+            }
+            source => vec![Candidate {
+                phonemes: trace.phonemes,
+                source,
+                score: trace.confidence.unwrap_or(1.0),
+            }],
+        };
+        candidates.truncate(n);
+        Ok(candidates)
+    }
+}
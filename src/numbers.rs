@@ -0,0 +1,734 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen",
+    "seventeen", "eighteen", "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: [&str; 7] = [
+    "", "thousand", "million", "billion", "trillion", "quadrillion", "quintillion",
+];
+
+/// 基数词，如 `123 → one hundred twenty three`
+pub fn cardinal(n: u64) -> String {
+    if n == 0 {
+        return ONES[0].to_string();
+    }
+
+    // 按千位分组，从高位到低位输出
+    let mut groups = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        groups.push((rest % 1000) as usize);
+        rest /= 1000;
+    }
+
+    let mut words = Vec::new();
+    for (scale, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        words.push(below_thousand(group));
+        if scale > 0 {
+            words.push(SCALES[scale].to_string());
+        }
+    }
+    words.join(" ")
+}
+
+/// 序数词，如 `23 → twenty third`
+pub fn ordinal(n: u64) -> String {
+    let words = cardinal(n);
+    let (head, last) = match words.rsplit_once(' ') {
+        Some((head, last)) => (format!("{} ", head), last),
+        None => (String::new(), words.as_str()),
+    };
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        word if word.ends_with('y') => format!("{}ieth", &word[..word.len() - 1]),
+        word => format!("{}th", word),
+    };
+    head + &last
+}
+
+/// 数词的复数，用于年代和数字的复数，如 `nineteen eighty → nineteen eighties`、`six → sixes`
+pub fn plural(words: &str) -> String {
+    let (head, last) = match words.rsplit_once(' ') {
+        Some((head, last)) => (format!("{} ", head), last),
+        None => (String::new(), words),
+    };
+    let last = match last {
+        word if word.ends_with('y') => format!("{}ies", &word[..word.len() - 1]),
+        word if word.ends_with(['s', 'x']) => format!("{}es", word),
+        word => format!("{}s", word),
+    };
+    head + &last
+}
+
+/// 年代（`1980s`、`80s`）和数字复数（`7s`）的读法：四位的年份按年份读，其余按基数词读，最后一个单词变为复数
+pub fn decade(text: &str) -> String {
+    let Ok(n) = text.parse::<u64>() else {
+        return digits(text);
+    };
+    if is_year(text) {
+        plural(&paired(text))
+    } else {
+        plural(&cardinal(n))
+    }
+}
+
+/// 数词的语法性别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gender {
+    #[default]
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+/// 数词的语法格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrammaticalCase {
+    #[default]
+    Nominative,
+    Genitive,
+    Dative,
+    Accusative,
+    Instrumental,
+    Prepositional,
+}
+
+/// 数词须与之一致的性和格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Agreement {
+    pub gender: Gender,
+    pub case: GrammaticalCase,
+}
+
+/// 数词的读法，每种语言一个实现
+///
+/// 英语数词没有性和格的变化；西班牙语（`una casa`）、德语（`einem Haus`）、俄语（`двух книг`）等语言
+/// 实现这个trait，由 `agreement` 根据数字后面的单词决定性和格，`cardinal` 和 `ordinal` 输出相应的屈折形式。
+/// 通过 `TextProcessor::with_number_words` 或 `RustG2P::with_number_words` 使用。
+pub trait NumberWords: Send + Sync {
+    /// 基数词
+    fn cardinal(&self, n: u64, agreement: Agreement) -> String;
+    
+    /// 序数词
+    fn ordinal(&self, n: u64, agreement: Agreement) -> String;
+    
+    /// 超出u64范围的数字串的读法
+    fn digits(&self, text: &str) -> String;
+    
+    /// 年份，默认读作基数词
+    fn year(&self, n: u64) -> String {
+        self.cardinal(n, Agreement::default())
+    }
+    
+    /// 数词须与之一致的性和格；`following` 为数字后面的单词（已转小写、去掉标点），数字在末尾时为None
+    fn agreement(&self, _following: Option<&str>) -> Agreement {
+        Agreement::default()
+    }
+}
+
+/// 英语数词，不区分性和格
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnglishNumbers;
+
+impl NumberWords for EnglishNumbers {
+    fn cardinal(&self, n: u64, _agreement: Agreement) -> String {
+        cardinal(n)
+    }
+    
+    fn ordinal(&self, n: u64, _agreement: Agreement) -> String {
+        ordinal(n)
+    }
+    
+    fn digits(&self, text: &str) -> String {
+        digits(text)
+    }
+    
+    fn year(&self, n: u64) -> String {
+        paired(&n.to_string())
+    }
+}
+
+lazy_static! {
+    /// 按名称（通常是语言代码）注册的数词读法，预先注册了英语
+    static ref NUMBER_WORDS: RwLock<HashMap<String, Arc<dyn NumberWords>>> = {
+        let mut registry: HashMap<String, Arc<dyn NumberWords>> = HashMap::new();
+        registry.insert("en".to_string(), Arc::new(EnglishNumbers));
+        RwLock::new(registry)
+    };
+}
+
+/// 注册一种数词读法，同名的注册被替换
+///
+/// 名称为语言代码（如 `es`）时，`RustG2P::with_language` 以及该语言的配置文件和语言包自动使用它；
+/// 配置文件也可以在 `[normalization]` 下用 `number_words` 按名称选择。
+pub fn register_number_words(name: &str, number_words: Arc<dyn NumberWords>) {
+    NUMBER_WORDS.write().unwrap_or_else(|e| e.into_inner()).insert(name.to_ascii_lowercase(), number_words);
+}
+
+/// 按名称查找注册的数词读法，不区分大小写；`es-MX` 这样的地区变体没有单独注册时使用主语言（`es`）的
+pub fn number_words_for(name: &str) -> Option<Arc<dyn NumberWords>> {
+    let registry = NUMBER_WORDS.read().unwrap_or_else(|e| e.into_inner());
+    let name = name.to_ascii_lowercase();
+    let primary = name.split(['-', '_']).next().unwrap_or_default();
+    registry.get(&name).or_else(|| registry.get(primary)).cloned()
+}
+
+/// 数字在上下文中的读法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberReading {
+    Cardinal,
+    Ordinal,
+    Year,
+}
+
+/// 月份及其缩写
+const MONTHS: [&str; 24] = [
+    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december",
+    "jan", "feb", "mar", "apr", "jun", "jul", "aug", "sep", "sept", "oct", "nov", "dec",
+];
+
+/// 后面的数字是编号，读作基数词
+const COUNTING_NOUNS: [&str; 24] = [
+    "chapter", "page", "pages", "p", "pp", "room", "number", "no", "figure", "fig", "table", "section", "line",
+    "step", "level", "version", "volume", "vol", "part", "act", "scene", "episode", "season", "platform",
+];
+
+/// 后面的四位数是年份
+const YEAR_CUES: [&str; 11] = ["in", "since", "until", "till", "by", "circa", "from", "during", "year", "before", "after"];
+
+/// 后面是纪年标记的四位数是年份
+const ERAS: [&str; 4] = ["ad", "bc", "bce", "ce"];
+
+/// 按前后的单词选择不带序数词后缀的数字的读法，单词为小写、去掉了标点
+///
+/// `previous` 为前面的单词（由近及远），`following` 为后面的单词（由近及远），各取两个即可：
+///
+/// - 编号名词（`chapter`、`page`、`room` 等）后读作基数词：`chapter 3`、`room 1205`
+/// - 月份前后的1～31读作序数词：`may 3 → may third`，`3 march → third march`，`3 of march → third of march`
+/// - 月份、日期或 `in`、`since` 等介词后的四位数（1100～2099）以及后跟 `bc`、`ad` 的四位数读作年份：
+///   `may 3, 2024`、`in 1984 → in nineteen eighty four`
+/// - 其余读作基数词：`3 apples`
+pub fn reading_in_context(previous: &[&str], number: &str, following: &[&str]) -> NumberReading {
+    let is_month = |word: Option<&&str>| word.is_some_and(|word| MONTHS.contains(word));
+    let is_day = |text: &str| text.len() <= 2 && text.parse::<u32>().is_ok_and(|day| (1..=31).contains(&day));
+    
+    if previous.first().is_some_and(|word| COUNTING_NOUNS.contains(word)) {
+        return NumberReading::Cardinal;
+    }
+    if is_day(number)
+        && (is_month(previous.first())
+            || is_month(following.first())
+            || (following.first() == Some(&"of") && is_month(following.get(1))))
+    {
+        return NumberReading::Ordinal;
+    }
+    if is_year(number)
+        && (is_month(previous.first())
+            || (previous.first().is_some_and(|word| is_day(word)) && is_month(previous.get(1)))
+            || previous.first().is_some_and(|word| YEAR_CUES.contains(word))
+            || following.first().is_some_and(|word| ERAS.contains(word)))
+    {
+        return NumberReading::Year;
+    }
+    NumberReading::Cardinal
+}
+
+/// 逐位读数字，`0` 读作 `oh`，如 `105 → one oh five`
+pub fn digits(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| if d == 0 { "oh" } else { ONES[d as usize] })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 按两位一组读数字，用于门牌号和年份，如 `1234 → twelve thirty four`
+///
+/// 三位数读作 `one twenty three`，整百读作 `twelve hundred`，五位以上逐位读。
+pub fn paired(text: &str) -> String {
+    let Ok(n) = text.parse::<u64>() else {
+        return digits(text);
+    };
+    if text.len() > 1 && text.starts_with('0') {
+        return digits(text);
+    }
+    match text.len() {
+        1 | 2 => cardinal(n),
+        3 | 4 if n % 100 == 0 && n % 1000 != 0 => format!("{} hundred", cardinal(n / 100)),
+        3 | 4 if n % 1000 == 0 => cardinal(n),
+        3 | 4 => format!("{} {}", cardinal(n / 100), pair(n % 100)),
+        _ => digits(text),
+    }
+}
+
+/// 两位一组中的后一组，`05 → oh five`
+fn pair(n: u64) -> String {
+    if n < 10 {
+        format!("oh {}", ONES[n as usize])
+    } else {
+        cardinal(n)
+    }
+}
+
+fn below_thousand(n: usize) -> String {
+    let mut words = Vec::new();
+    if n >= 100 {
+        words.push(ONES[n / 100].to_string());
+        words.push("hundred".to_string());
+    }
+    let rest = n % 100;
+    if rest >= 20 {
+        words.push(TENS[rest / 10].to_string());
+        if !rest.is_multiple_of(10) {
+            words.push(ONES[rest % 10].to_string());
+        }
+    } else if rest > 0 {
+        words.push(ONES[rest].to_string());
+    }
+    words.join(" ")
+}
+
+/// 星期缩写
+const WEEKDAYS: [(&str, &str); 7] = [
+    ("mon", "monday"),
+    ("tue", "tuesday"),
+    ("wed", "wednesday"),
+    ("thu", "thursday"),
+    ("fri", "friday"),
+    ("sat", "saturday"),
+    ("sun", "sunday"),
+];
+
+/// 读出数字范围、比分和减法中的连字符
+///
+/// - 两侧都有空格的 `-` 或数学减号 `−` 读作 minus：`5 - 3 → five minus three`
+/// - 其余的 `-`、`–`、`—` 读作 to：`3-2 → three to two`，`pages 10–15 → ten to fifteen`
+/// - 年份范围按年份读：`2019–2021 → twenty nineteen to twenty twenty one`
+/// - 星期范围读作 through：`Mon–Fri → monday through friday`
+///
+/// 电话号码（`555-1234`）和多段连写（`2020-01-05`）不是范围，保持不变。
+pub fn expand_ranges(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref RANGE_RE: Regex = Regex::new(r"(\d+(?:\.\d+)?)(\s*)([-–—−])(\s*)(\d+(?:\.\d+)?)").unwrap();
+        static ref WEEKDAY_RANGE_RE: Regex = Regex::new(
+            r"(?i)\b(mon|tue|wed|thu|fri|sat|sun)[a-z]*\.?\s*[-–—]\s*(mon|tue|wed|thu|fri|sat|sun)[a-z]*\b\.?"
+        )
+        .unwrap();
+    }
+
+    let text = WEEKDAY_RANGE_RE.replace_all(text, |caps: &Captures| {
+        format!("{} through {}", weekday(&caps[1]), weekday(&caps[2]))
+    });
+
+    if !RANGE_RE.is_match(&text) {
+        return text;
+    }
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut last = 0;
+    for caps in RANGE_RE.captures_iter(&text) {
+        let whole = caps.get(0).unwrap();
+        let before = text[..whole.start()].chars().next_back();
+        let after = text[whole.end()..].chars().next();
+        if before.is_some_and(|c| c.is_alphanumeric() || matches!(c, '.' | ',' | ':' | '/' | '-' | '–' | '+'))
+            || after.is_some_and(|c| c.is_alphanumeric() || matches!(c, ':' | '/' | '-' | '–'))
+            || after == Some('.') && text[whole.end() + 1..].starts_with(|c: char| c.is_ascii_digit())
+        {
+            continue;
+        }
+
+        let (from, to) = (&caps[1], &caps[5]);
+        let spaced = !caps[2].is_empty() && !caps[4].is_empty();
+        let reading = match &caps[3] {
+            "−" => format!("{} minus {}", decimal(from), decimal(to)),
+            "-" if spaced => format!("{} minus {}", decimal(from), decimal(to)),
+            // 电话号码
+            "-" if from.len() == 3 && to.len() == 4 => continue,
+            _ if is_year(from) && (is_year(to) || to.len() == 2) => format!("{} to {}", paired(from), paired(to)),
+            _ => format!("{} to {}", decimal(from), decimal(to)),
+        };
+        out.push_str(&text[last..whole.start()]);
+        out.push_str(&reading);
+        last = whole.end();
+    }
+    out.push_str(&text[last..]);
+    Cow::Owned(out)
+}
+
+/// 时区缩写，只在时间后面展开
+const TIME_ZONES: [(&str, &str); 22] = [
+    ("EST", "eastern standard time"),
+    ("EDT", "eastern daylight time"),
+    ("ET", "eastern time"),
+    ("CST", "central standard time"),
+    ("CDT", "central daylight time"),
+    ("CT", "central time"),
+    ("MST", "mountain standard time"),
+    ("MDT", "mountain daylight time"),
+    ("MT", "mountain time"),
+    ("PST", "pacific standard time"),
+    ("PDT", "pacific daylight time"),
+    ("PT", "pacific time"),
+    ("AKST", "alaska standard time"),
+    ("HST", "hawaii standard time"),
+    ("GMT", "greenwich mean time"),
+    ("UTC", "coordinated universal time"),
+    ("BST", "british summer time"),
+    ("CET", "central european time"),
+    ("CEST", "central european summer time"),
+    ("IST", "india standard time"),
+    ("JST", "japan standard time"),
+    ("AEST", "australian eastern standard time"),
+];
+
+/// 时刻、上下午和时区
+///
+/// - `10:30 → ten thirty`，`10:05 → ten oh five`；整点读作 o'clock，24小时制读作 hundred（`14:00 → fourteen hundred`）
+/// - `AM`、`p.m.` 等统一写成 `A.M.`、`P.M.`，之后按字母读：`10 AM → ten A.M.`
+/// - 时间后面的时区缩写读作全称：`10 AM EST → ten A.M. eastern standard time`
+/// - 时间范围：`9–11 AM → nine to eleven A.M.`
+///
+/// 既没有分钟也没有上下午的数字不是时间，保持不变；带秒的 `10:30:15` 也不处理。
+pub fn expand_times(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref TIME_RE: Regex = Regex::new(&format!(
+            r"(?:\b(\d{{1,2}})(?::(\d{{2}}))?\s*[-–—]\s*)?\b(\d{{1,2}})(?::(\d{{2}}))?(?:\s?([AaPp])\.?\s?[Mm]\b\.?)?(?:\s+({})\b)?",
+            TIME_ZONES.iter().map(|(abbrev, _)| *abbrev).collect::<Vec<_>>().join("|")
+        ))
+        .unwrap();
+    }
+
+    if !TIME_RE.is_match(text) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut last = 0;
+    for caps in TIME_RE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let before = text[..whole.start()].chars().next_back();
+        let after = text[whole.end()..].chars().next();
+        let meridiem = caps.get(5).map(|letter| letter.as_str().to_uppercase());
+        if caps.get(4).is_none() && meridiem.is_none()
+            || before.is_some_and(|c| c.is_alphanumeric() || matches!(c, ':' | '.' | ',' | '-' | '/'))
+            || after.is_some_and(|c| c.is_ascii_digit() || c == ':')
+        {
+            continue;
+        }
+        let limit = if meridiem.is_some() { 12 } else { 24 };
+        let Some(time) = clock(&caps[3], caps.get(4).map(|m| m.as_str()), limit) else {
+            continue;
+        };
+        let from = match caps.get(1) {
+            Some(hour) => match clock(hour.as_str(), caps.get(2).map(|m| m.as_str()), limit) {
+                Some(from) => Some(from),
+                None => continue,
+            },
+            None => None,
+        };
+
+        out.push_str(&text[last..whole.start()]);
+        if let Some(from) = from {
+            out.push_str(&from);
+            out.push_str(" to ");
+        }
+        out.push_str(&time);
+        if let Some(meridiem) = meridiem {
+            out.push_str(&format!(" {}.M.", meridiem));
+        }
+        if let Some(zone) = caps.get(6) {
+            let name = TIME_ZONES.iter().find(|(abbrev, _)| *abbrev == zone.as_str()).map_or("", |(_, name)| name);
+            out.push(' ');
+            out.push_str(name);
+        }
+        last = whole.end();
+    }
+    out.push_str(&text[last..]);
+    Cow::Owned(out)
+}
+
+/// 读出钟点，超出范围时返回 `None`；上下午制的整点不加 o'clock
+fn clock(hour: &str, minute: Option<&str>, limit: u64) -> Option<String> {
+    let hour: u64 = hour.parse().ok()?;
+    let minute_value: u64 = minute.map_or(Ok(0), str::parse).ok()?;
+    if hour > limit || minute_value >= 60 || limit == 12 && hour == 0 {
+        return None;
+    }
+
+    let hour_words = cardinal(hour);
+    Some(match minute {
+        None => hour_words,
+        Some("00") if limit == 12 => hour_words,
+        Some("00") if (1..=12).contains(&hour) => format!("{} o'clock", hour_words),
+        Some("00") => format!("{} hundred", hour_words),
+        Some(_) if minute_value < 10 => format!("{} oh {}", hour_words, cardinal(minute_value)),
+        Some(_) => format!("{} {}", hour_words, cardinal(minute_value)),
+    })
+}
+
+/// 数字的千位分隔符和小数点写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberLocale {
+    /// `1,000.5`
+    #[default]
+    Us,
+    /// `1.000,5`
+    Eu,
+}
+
+/// 按区域设置去掉千位分隔符，并把小数点统一为 `.`，后续步骤只需处理 `1000.5` 这种写法
+///
+/// 分组必须是三位一组，`3.11.4` 这样的版本号和 `1,2,3` 这样的列表不受影响。
+pub fn normalize_separators(text: &str, locale: NumberLocale) -> Cow<'_, str> {
+    lazy_static! {
+        static ref US_RE: Regex = Regex::new(r"\b\d{1,3}(?:,\d{3})+(?:\.\d+)?").unwrap();
+        static ref EU_RE: Regex = Regex::new(r"\b\d{1,3}(?:\.\d{3})+(?:,\d+)?|\b\d+,\d+").unwrap();
+    }
+    
+    let (re, group, point) = match locale {
+        NumberLocale::Us => (&*US_RE, ',', '.'),
+        NumberLocale::Eu => (&*EU_RE, '.', ','),
+    };
+    re.replace_all(text, |caps: &Captures| {
+        let number = caps.get(0).unwrap();
+        // 后面紧跟分隔符和数字时是更长的序列（版本号、IP、列表），保持不变
+        let mut rest = text[number.end()..].chars();
+        if matches!(rest.next(), Some('.' | ',')) && rest.next().is_some_and(|c| c.is_ascii_digit())
+        {
+            return number.as_str().to_string();
+        }
+        number
+            .as_str()
+            .chars()
+            .filter(|&c| c != group)
+            .map(|c| if c == point { '.' } else { c })
+            .collect()
+    })
+}
+
+/// 科学计数法：`6.02e23 → six point oh two times ten to the twenty third`，
+/// `3×10^8 → three times ten to the eighth`，`10^-9 → ten to the minus ninth`
+pub fn expand_scientific(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref SCIENTIFIC_RE: Regex = Regex::new(
+            r"(?:\b(\d+(?:\.\d+)?)(?:[eE]|\s*[×xX*·]\s*10\^)|\b10\^)([+\-−]?)(\d+)\b"
+        )
+        .unwrap();
+    }
+    
+    SCIENTIFIC_RE.replace_all(text, |caps: &Captures| {
+        let sign = if matches!(&caps[2], "-" | "−") { "minus " } else { "" };
+        let power = format!("ten to the {}{}", sign, caps[3].parse().map_or_else(|_| digits(&caps[3]), ordinal));
+        match caps.get(1) {
+            Some(mantissa) => format!("{} times {}", decimal(mantissa.as_str()), power),
+            None => power,
+        }
+    })
+}
+
+/// 数字后的单位
+/// 数字后的单位：(符号, 单数读法, 复数读法)，较长的符号在前
+const UNITS: [(&str, &str, &str); 8] = [
+    ("°C", "degree celsius", "degrees celsius"),
+    ("°F", "degree fahrenheit", "degrees fahrenheit"),
+    ("°K", "kelvin", "kelvins"),
+    ("℃", "degree celsius", "degrees celsius"),
+    ("℉", "degree fahrenheit", "degrees fahrenheit"),
+    ("°", "degree", "degrees"),
+    ("%", "percent", "percent"),
+    ("‰", "per mille", "per mille"),
+];
+
+/// 带符号的数字和带单位的量：`-5°C → minus five degrees celsius`，`−3.2% → minus three point two percent`
+///
+/// 符号须紧贴数字且前面不是数字（`5-3` 由范围处理），`+` 读作 plus。
+/// 数值恰好为1时单位用单数（`1° → one degree`）。开尔文只识别与数字以空格隔开的大写 `K`
+/// （`300 K`），`5K` 这样的写法通常表示千或公里，不按温度读。
+pub fn expand_quantities(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref QUANTITY_RE: Regex = Regex::new(&format!(
+            r"(^|[\s(\[=:,;/])([-−+]?)(\d+(?:\.\d+)?)(?:\s?({})|\s(K)\b|\b)",
+            UNITS.iter().map(|(unit, _, _)| regex::escape(unit)).collect::<Vec<_>>().join("|")
+        ))
+        .unwrap();
+    }
+    
+    QUANTITY_RE.replace_all(text, |caps: &Captures| {
+        let unit = caps.get(4).or_else(|| caps.get(5)).map(|unit| match unit.as_str() {
+            "K" => ("kelvin", "kelvins"),
+            symbol => UNITS
+                .iter()
+                .find(|(unit, _, _)| *unit == symbol)
+                .map_or(("", ""), |(_, singular, plural)| (*singular, *plural)),
+        });
+        if caps[2].is_empty() && unit.is_none() {
+            return caps[0].to_string();
+        }
+        
+        let mut out = caps[1].to_string();
+        match &caps[2] {
+            "-" | "−" => out.push_str("minus "),
+            "+" => out.push_str("plus "),
+            _ => {}
+        }
+        out.push_str(&decimal(&caps[3]));
+        if let Some((singular, plural)) = unit {
+            out.push(' ');
+            out.push_str(if &caps[3] == "1" { singular } else { plural });
+        }
+        out
+    })
+}
+
+/// 货币符号：(符号, 单数, 复数, 辅币单数, 辅币复数)，日元没有辅币
+const CURRENCIES: [(char, &str, &str, &str, &str); 4] = [
+    ('$', "dollar", "dollars", "cent", "cents"),
+    ('£', "pound", "pounds", "penny", "pence"),
+    ('€', "euro", "euros", "cent", "cents"),
+    ('¥', "yen", "yen", "", ""),
+];
+
+/// 金额后的数量级：(写法, 读法)
+const MAGNITUDES: [(&str, &str); 10] = [
+    ("k", "thousand"),
+    ("K", "thousand"),
+    ("m", "million"),
+    ("M", "million"),
+    ("mn", "million"),
+    ("b", "billion"),
+    ("B", "billion"),
+    ("bn", "billion"),
+    ("T", "trillion"),
+    ("tn", "trillion"),
+];
+
+/// 带货币符号的金额：`$5 → five dollars`，`£1.50 → one pound and fifty pence`，`$0.99 → ninety nine cents`
+///
+/// 金额后的数量级（`k`、`M`、`mn`、`bn`、`T` 等紧贴数字的缩写，或以空格隔开的 `thousand`、`million`、`billion`、
+/// `trillion`）读在货币单位之前：`$3.5M → three point five million dollars`，`£2bn → two billion pounds`。
+/// 没有数量级时，两位小数读作辅币，其他小数逐位读（`$3.5 → three point five dollars`）；`-` 读作 minus。
+pub fn expand_currency(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref CURRENCY_RE: Regex = Regex::new(
+            r"(^|[\s(\[=:,;/])([-−]?)([$£€¥])\s?(\d+(?:\.\d+)?)(?:(mn|bn|tn|[kKmMbBT])\b|\s(?i:(thousand|million|billion|trillion))\b)?"
+        )
+        .unwrap();
+    }
+    
+    CURRENCY_RE.replace_all(text, |caps: &Captures| {
+        let symbol = caps[3].chars().next().unwrap_or_default();
+        let Some(&(_, singular, plural, minor, minors)) = CURRENCIES.iter().find(|currency| currency.0 == symbol) else {
+            return caps[0].to_string();
+        };
+        let magnitude = match (caps.get(5), caps.get(6)) {
+            (Some(short), _) => MAGNITUDES.iter().find(|(written, _)| *written == short.as_str()).map(|(_, spoken)| spoken.to_string()),
+            (None, Some(word)) => Some(word.as_str().to_lowercase()),
+            (None, None) => None,
+        };
+        
+        let mut out = caps[1].to_string();
+        if matches!(&caps[2], "-" | "−") {
+            out.push_str("minus ");
+        }
+        let amount = &caps[4];
+        let unit = |n: &str| if n == "1" { singular } else { plural };
+        match (magnitude, amount.split_once('.')) {
+            (Some(magnitude), _) => out.push_str(&format!("{} {} {}", decimal(amount), magnitude, plural)),
+            (None, Some((major, cents))) if cents.len() == 2 && !minor.is_empty() => {
+                let major = match major.trim_start_matches('0') {
+                    "" => "0",
+                    major => major,
+                };
+                let cents = cents.trim_start_matches('0');
+                let cents_words = format!("{} {}", cardinal_str(cents), if cents == "1" { minor } else { minors });
+                match (major, cents) {
+                    (_, "") => out.push_str(&format!("{} {}", cardinal_str(major), unit(major))),
+                    ("0", _) => out.push_str(&cents_words),
+                    _ => out.push_str(&format!("{} {} and {}", cardinal_str(major), unit(major), cents_words)),
+                }
+            }
+            (None, Some(_)) => out.push_str(&format!("{} {}", decimal(amount), plural)),
+            (None, None) => out.push_str(&format!("{} {}", cardinal_str(amount), unit(amount))),
+        }
+        out
+    })
+}
+
+/// 带点的数字：版本号、IP地址和小数
+///
+/// - 带 `v` 前缀、前面是 version 或有三段以上时按版本号读，`0` 读作 oh：
+///   `v2.0 → version two point oh`，`3.11.4 → three point eleven point four`
+/// - 四段且每段不超过255时按IP地址读：`192.168.0.1 → one hundred ninety two dot ...`
+/// - 两段时按小数读，小数部分逐位读：`3.14 → three point one four`
+pub fn expand_dotted(text: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref DOTTED_RE: Regex = Regex::new(r"\b([vV])?(\d+(?:\.\d+)+)").unwrap();
+        static ref VERSION_WORD_RE: Regex = Regex::new(r"(?i)\bversion\s*$").unwrap();
+    }
+    
+    DOTTED_RE.replace_all(text, |caps: &Captures| {
+        let whole = caps.get(0).unwrap();
+        let number = &caps[2];
+        let groups: Vec<&str> = number.split('.').collect();
+        
+        let prefixed = caps.get(1).is_some();
+        let is_ip = groups.len() == 4 && groups.iter().all(|group| group.len() <= 3 && group.parse::<u8>().is_ok());
+        if !prefixed && !VERSION_WORD_RE.is_match(&text[..whole.start()]) {
+            if is_ip {
+                return groups.iter().map(|group| cardinal_str(group)).collect::<Vec<_>>().join(" dot ");
+            }
+            if groups.len() == 2 {
+                return decimal(number);
+            }
+        }
+        
+        let components: Vec<String> = groups
+            .iter()
+            .map(|group| if *group == "0" { "oh".to_string() } else { cardinal_str(group) })
+            .collect();
+        let prefix = if prefixed { "version " } else { "" };
+        format!("{}{}", prefix, components.join(" point "))
+    })
+}
+
+/// 整数或小数，小数部分逐位读
+pub fn decimal(text: &str) -> String {
+    match text.split_once('.') {
+        Some((integer, fraction)) => format!("{} point {}", cardinal_str(integer), digits(fraction)),
+        None => cardinal_str(text),
+    }
+}
+
+/// 数字串的基数词，超出u64范围时逐位读
+pub fn cardinal_str(text: &str) -> String {
+    text.parse().map_or_else(|_| digits(text), cardinal)
+}
+
+fn is_year(text: &str) -> bool {
+    text.len() == 4 && text.parse::<u32>().is_ok_and(|year| (1100..2100).contains(&year))
+}
+
+fn weekday(abbrev: &str) -> &'static str {
+    let abbrev = abbrev.to_lowercase();
+    WEEKDAYS.iter().find(|(key, _)| *key == abbrev).map_or("", |(_, name)| name)
+}
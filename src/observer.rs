@@ -0,0 +1,20 @@
+use crate::error::G2PError;
+use crate::phoneme::Phoneme;
+use crate::Fallback;
+
+/// 转换过程的观察者，用于把内部事件接入监控或日志系统
+///
+/// 所有回调都有空的默认实现，只需实现关心的事件。回调在转换线程中同步执行，应尽量轻量。
+pub trait Observer: Send + Sync {
+    /// 单词转换完成
+    fn on_word_converted(&self, _word: &str, _phonemes: &[Phoneme]) {}
+    
+    /// 遇到词典未收录的单词
+    fn on_oov(&self, _word: &str) {}
+    
+    /// 规则输出不可靠，启用了后备策略
+    fn on_fallback(&self, _word: &str, _fallback: Fallback) {}
+    
+    /// 单词转换失败
+    fn on_error(&self, _word: &str, _error: &G2PError) {}
+}
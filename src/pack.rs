@@ -0,0 +1,208 @@
+//! 语言包：可以单独发布的一组数据文件
+//!
+//! 语言包是一个目录，其中的 `pack.toml` 由 `[pack]` 段和普通配置文件（见 `G2PConfig`）组成，
+//! 数据文件的相对路径相对于语言包目录解析：
+//!
+//! ```text
+//! packs/en-gb/
+//!   pack.toml        # 清单和配置
+//!   dict.txt         # CMU格式词典
+//!   rules.txt        # 规则文件
+//!   irregular.txt    # 不规则词汇（可选）
+//!   phonemes.txt     # 音素集，每行一个ARPAbet符号（可选，给出时加载后检查发音只用到其中的音素）
+//! ```
+//!
+//! ```toml
+//! language = "en-GB"
+//!
+//! [pack]
+//! name = "en-gb"
+//! version = "1.0.0"
+//! description = "British English"
+//! phonemes = "phonemes.txt"
+//!
+//! [data]
+//! dictionary = "dict.txt"
+//! rules = "rules.txt"
+//! irregular = ""
+//!
+//! [normalization]
+//! number_locale = "eu"
+//! ```
+//!
+//! 省略的路径使用配置文件的默认值，同样相对于语言包目录解析，因此没有不规则词汇时应写 `irregular = ""`。
+//! 磁盘缓存的路径仍相对于当前工作目录，避免写入语言包目录。
+
+use crate::config::G2PConfig;
+use crate::phoneme;
+use crate::RustG2P;
+use crate::error::{invalid_data, Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 语言包清单的文件名
+pub const MANIFEST: &str = "pack.toml";
+
+/// 列出已安装语言包时搜索的目录，多个目录按平台的路径分隔符分隔；未设置时为 `packs`
+pub const PACK_PATH_ENV: &str = "G2P_PACK_PATH";
+
+/// 清单中的 `[pack]` 段
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackInfo {
+    name: String,
+    version: Option<String>,
+    description: Option<String>,
+    phonemes: Option<String>,
+}
+
+/// 一个语言包
+#[derive(Debug, Clone)]
+pub struct LanguagePack {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub path: PathBuf,                 // 语言包目录
+    pub phonemes: Option<Vec<String>>, // 音素集，不含重音数字
+    pub config: G2PConfig,             // 路径已按语言包目录解析
+}
+
+impl LanguagePack {
+    /// 读取语言包目录中的清单，不加载数据文件
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let manifest = path.join(MANIFEST);
+        let content = fs::read_to_string(&manifest)
+            .with_context(|| format!("Failed to read language pack manifest '{}'", manifest.display()))?;
+        Self::parse(&content, path).with_context(|| format!("Invalid language pack manifest '{}'", manifest.display()))
+    }
+
+    /// 解析清单文本，`path` 为语言包目录
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        let mut table: toml::Table = toml::from_str(content)?;
+        let info: PackInfo = table
+            .remove("pack")
+            .ok_or_else(|| invalid_data!("missing [pack] section"))?
+            .try_into()?;
+        let mut config: G2PConfig = toml::Value::Table(table).try_into()?;
+        resolve_paths(&mut config, path);
+
+        let phonemes = match &info.phonemes {
+            Some(file) => Some(read_phoneme_set(&path.join(file))?),
+            None => None,
+        };
+        Ok(Self {
+            name: info.name,
+            version: info.version,
+            description: info.description,
+            path: path.to_path_buf(),
+            phonemes,
+            config,
+        })
+    }
+
+    /// 语言代码
+    pub fn language(&self) -> &str {
+        &self.config.language
+    }
+
+    /// 加载语言包的数据并创建转换器；清单给出音素集时，词典、规则和不规则词汇层的发音只能使用其中的音素
+    pub fn load(&self) -> Result<RustG2P> {
+        let g2p = RustG2P::from_parsed_config(&self.config)
+            .and_then(|g2p| match &self.phonemes {
+                Some(phonemes) => check_phoneme_set(&g2p, phonemes).map(|_| g2p),
+                None => Ok(g2p),
+            })
+            .with_context(|| format!("Failed to load language pack '{}'", self.name))?;
+        Ok(g2p)
+    }
+}
+
+/// 列出目录下的语言包（含 `pack.toml` 的子目录），按名称排列；清单无效的语言包报错
+pub fn discover(root: impl AsRef<Path>) -> Result<Vec<LanguagePack>> {
+    let root = root.as_ref();
+    let mut packs = Vec::new();
+    let entries = fs::read_dir(root).with_context(|| format!("Failed to read pack directory '{}'", root.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.join(MANIFEST).is_file() {
+            packs.push(LanguagePack::open(&path)?);
+        }
+    }
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packs)
+}
+
+/// 列出 `G2P_PACK_PATH` 中各目录（未设置时为 `packs`）下已安装的语言包，不存在的目录跳过
+pub fn installed() -> Result<Vec<LanguagePack>> {
+    let search = std::env::var_os(PACK_PATH_ENV).unwrap_or_else(|| "packs".into());
+    let mut packs = Vec::new();
+    for root in std::env::split_paths(&search).filter(|root| root.is_dir()) {
+        packs.extend(discover(root)?);
+    }
+    Ok(packs)
+}
+
+impl RustG2P {
+    /// 从语言包目录创建转换器
+    pub fn load_language_pack(path: impl AsRef<Path>) -> Result<Self> {
+        LanguagePack::open(path)?.load()
+    }
+}
+
+/// 数据文件的相对路径改为相对于语言包目录
+fn resolve_paths(config: &mut G2PConfig, base: &Path) {
+    let resolve = |path: &mut String| {
+        if !path.is_empty() && Path::new(path.as_str()).is_relative() {
+            *path = base.join(path.as_str()).to_string_lossy().into_owned();
+        }
+    };
+    resolve(&mut config.data.dictionary);
+    resolve(&mut config.data.rules);
+    resolve(&mut config.data.irregular);
+    config.data.hunspell.iter_mut().for_each(resolve);
+    config.data.rewrite.iter_mut().for_each(resolve);
+}
+
+/// 检查词典、规则和不规则词汇层的发音只用到音素集中的音素，报告第一个例外
+fn check_phoneme_set(g2p: &RustG2P, phonemes: &[String]) -> Result<()> {
+    let allowed: HashSet<&str> = phonemes.iter().map(String::as_str).collect();
+    let outside = |symbol: &str| {
+        let symbol = symbol.trim_end_matches(|c: char| c.is_ascii_digit());
+        !symbol.is_empty() && !symbol.starts_with('$') && !allowed.contains(symbol)
+    };
+    
+    let mut found = None;
+    g2p.dictionary.for_each_entry(true, |word, readings| {
+        if let (None, Some(code)) = (&found, readings.iter().flat_map(|codes| codes.iter()).find(|code| outside(code.symbol()))) {
+            found = Some(format!("dictionary entry '{}' uses phoneme '{}'", word, code.symbol()));
+        }
+    });
+    g2p.irregular.for_each(|word, codes| {
+        if let (None, Some(code)) = (&found, codes.iter().find(|code| outside(code.symbol()))) {
+            found = Some(format!("irregular word '{}' uses phoneme '{}'", word, code.symbol()));
+        }
+    });
+    for (pattern, output) in g2p.rules_engine.outputs() {
+        if let (None, Some(symbol)) = (&found, output.iter().find(|symbol| outside(symbol))) {
+            found = Some(format!("rule '{}' outputs phoneme '{}'", pattern, symbol));
+        }
+    }
+    match found {
+        Some(message) => Err(invalid_data!("{}, which is not in the pack's phoneme set", message)),
+        None => Ok(()),
+    }
+}
+
+/// 读取音素集文件，`#` 开头的行为注释
+fn read_phoneme_set(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read phoneme set '{}'", path.display()))?;
+    let mut symbols = Vec::new();
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        phoneme::parse_arpabet(line).with_context(|| format!("Invalid phoneme '{}' in '{}'", line, path.display()))?;
+        symbols.push(line.trim_end_matches(|c: char| c.is_ascii_digit()).to_string());
+    }
+    Ok(symbols)
+}
@@ -1,4 +1,11 @@
+use crate::error::{invalid_data, G2PError, Result};
+use serde::Deserialize;
+use smallvec::SmallVec;
 use std::fmt;
+use std::sync::Arc;
+
+/// 转换过程中单个单词的音素序列，12个音素以内不分配堆内存；公开接口仍返回 `Vec<Phoneme>`
+pub(crate) type PhonemeSeq = SmallVec<[Phoneme; 12]>;
 
 /// 表示一个音素
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -90,7 +97,7 @@ impl Phoneme {
     /// 创建词边界标记
     pub fn word_boundary() -> Self {
         Self {
-            symbol: " ".to_string(),
+            symbol: WORD_BOUNDARY.to_string(),
             stress: StressLevel::Unstressed,
             features: PhonemeFeatures {
                 phoneme_type: PhonemeType::Special,
@@ -103,6 +110,47 @@ impl Phoneme {
         }
     }
     
+    /// 创建引号边界标记，用于在输出中标出引语的起止
+    pub fn quote_boundary() -> Self {
+        Self {
+            symbol: "\"".to_string(),
+            stress: StressLevel::Unstressed,
+            features: PhonemeFeatures::default(),
+        }
+    }
+    
+    /// 创建停顿标记，用于逗号、分号等句中停顿
+    pub fn pause() -> Self {
+        Self {
+            symbol: PAUSE.to_string(),
+            stress: StressLevel::Unstressed,
+            features: PhonemeFeatures::default(),
+        }
+    }
+    
+    /// 创建句子边界标记
+    pub fn sentence_boundary() -> Self {
+        Self {
+            symbol: SENTENCE.to_string(),
+            stress: StressLevel::Unstressed,
+            features: PhonemeFeatures::default(),
+        }
+    }
+    
+    /// 创建非语言声音的事件音素，如 `[laughter]`
+    pub fn event(name: &str) -> Self {
+        Self {
+            symbol: format!("[{}]", name),
+            stress: StressLevel::Unstressed,
+            features: PhonemeFeatures::default(),
+        }
+    }
+    
+    /// 是否为非语言声音的事件音素
+    pub fn is_event(&self) -> bool {
+        self.symbol.starts_with('[')
+    }
+    
     /// 解析重音标记
     fn parse_stress(symbol: &str) -> (String, StressLevel) {
         if symbol.ends_with('0') {
@@ -175,6 +223,349 @@ impl Phoneme {
     }
 }
 
+/// 词边界、停顿和句子边界标记的内部符号
+const WORD_BOUNDARY: &str = " ";
+const PAUSE: &str = ",";
+const SENTENCE: &str = ".";
+
+/// 输出中边界标记的写法，如Kaldi的 `sil`、`sp` 或 `<wb>`；空字符串表示不输出该标记
+///
+/// 只影响 `render_with`（和 `RustG2P::render`）的文本输出，音素序列中的标记不变。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoundarySymbols {
+    pub word: String,
+    pub pause: String,    // 逗号、分号等句中停顿，见 `RustG2P::with_pause_markers`
+    pub sentence: String, // 句末
+}
+
+impl Default for BoundarySymbols {
+    fn default() -> Self {
+        Self {
+            word: "|".to_string(),
+            pause: PAUSE.to_string(),
+            sentence: SENTENCE.to_string(),
+        }
+    }
+}
+
+impl BoundarySymbols {
+    /// 边界音素的写法，其他音素返回None
+    pub(crate) fn of(&self, phoneme: &Phoneme) -> Option<&str> {
+        match phoneme.symbol.as_str() {
+            WORD_BOUNDARY => Some(&self.word),
+            PAUSE => Some(&self.pause),
+            SENTENCE => Some(&self.sentence),
+            _ => None,
+        }
+    }
+}
+
+/// 音素的输出记法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Notation {
+    /// CMU词典形式：元音带重音数字，辅音不带
+    #[default]
+    Arpabet,
+    /// 不带重音数字的ARPAbet
+    ArpabetNoStress,
+    /// IPA，重读元音前加 `ˈ`/`ˌ`
+    Ipa,
+}
+
+/// `phone_string` 中重音的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StressMarks {
+    /// 不带重音数字，如 `HH AH L OW`
+    #[default]
+    Strip,
+    /// 元音都带重音数字，如 `HH AH0 L OW1`
+    Keep,
+    /// 只在主重音元音后加 `1`，如 `HH AH L OW1`
+    PrimaryOnly,
+}
+
+impl Notation {
+    /// 配置文件中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Notation::Arpabet => "arpabet",
+            Notation::ArpabetNoStress => "arpabet_no_stress",
+            Notation::Ipa => "ipa",
+        }
+    }
+    
+    /// 按 `as_str` 的名称解析
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Notation::Arpabet, Notation::ArpabetNoStress, Notation::Ipa]
+            .into_iter()
+            .find(|notation| notation.as_str() == name)
+    }
+}
+
+impl Phoneme {
+    /// 按指定记法输出单个音素，词边界输出为 `|`，引号边界、停顿和句子边界分别输出为 `"`、`,`、`.`
+    pub fn to_notation(&self, notation: Notation) -> String {
+        if self.symbol == WORD_BOUNDARY {
+            return "|".to_string();
+        }
+        match notation {
+            Notation::Arpabet if self.is_vowel() => {
+                let stress = match self.stress {
+                    StressLevel::Primary => 1,
+                    StressLevel::Secondary => 2,
+                    StressLevel::Unstressed => 0,
+                };
+                format!("{}{}", self.symbol, stress)
+            }
+            Notation::Arpabet | Notation::ArpabetNoStress => self.symbol.clone(),
+            Notation::Ipa => {
+                let ipa = arpabet_to_ipa(&self.symbol, &self.stress).unwrap_or(self.symbol.as_str());
+                match self.stress {
+                    StressLevel::Primary if self.is_vowel() => format!("ˈ{}", ipa),
+                    StressLevel::Secondary if self.is_vowel() => format!("ˌ{}", ipa),
+                    _ => ipa.to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// ARPAbet符号（不带重音数字）对应的IPA，AH和ER按是否重读区分
+pub fn arpabet_to_ipa(symbol: &str, stress: &StressLevel) -> Option<&'static str> {
+    let unstressed = *stress == StressLevel::Unstressed;
+    let ipa = match symbol {
+        "AA" => "ɑ",
+        "AE" => "æ",
+        "AH" if unstressed => "ə",
+        "AH" => "ʌ",
+        "AO" => "ɔ",
+        "AW" => "aʊ",
+        "AY" => "aɪ",
+        "EH" => "ɛ",
+        "ER" if unstressed => "ɚ",
+        "ER" => "ɝ",
+        "EY" => "eɪ",
+        "IH" => "ɪ",
+        "IY" => "i",
+        "OW" => "oʊ",
+        "OY" => "ɔɪ",
+        "UH" => "ʊ",
+        "UW" => "u",
+        "B" => "b",
+        "CH" => "tʃ",
+        "D" => "d",
+        "DH" => "ð",
+        "F" => "f",
+        "G" => "ɡ",
+        "HH" => "h",
+        "JH" => "dʒ",
+        "K" => "k",
+        "L" => "l",
+        "M" => "m",
+        "N" => "n",
+        "NG" => "ŋ",
+        "P" => "p",
+        "R" => "ɹ",
+        "S" => "s",
+        "SH" => "ʃ",
+        "T" => "t",
+        "TH" => "θ",
+        "V" => "v",
+        "W" => "w",
+        "Y" => "j",
+        "Z" => "z",
+        "ZH" => "ʒ",
+        "Q" => "ʔ",
+        _ => return None,
+    };
+    Some(ipa)
+}
+
+/// 解析空格分隔的ARPAbet音素串（如 `T EH1 S T`），不区分大小写
+pub fn parse_arpabet(text: &str) -> Result<Vec<Phoneme>> {
+    let phonemes: Vec<Phoneme> = text
+        .split_whitespace()
+        .map(|symbol| Phoneme::from_arpabet(&symbol.to_uppercase()))
+        .collect();
+    if phonemes.is_empty() {
+        return Err(invalid_data!("Empty phoneme string"));
+    }
+    if let Some(invalid) = phonemes.iter().find(|p| PhonemeCode::encode(p).is_none()) {
+        return Err(G2PError::InvalidPhoneme(invalid.symbol.clone()));
+    }
+    Ok(phonemes)
+}
+
+/// 词元音素所属的音素集，同一段输出中混有不同语言转换器的词元时据此区分
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PhonemeSet {
+    /// 某种语言（如 `en`、`en-GB`）的ARPAbet符号，不同语言中同一个符号不一定是同一个音
+    Arpabet(Arc<str>),
+    /// IPA符号，见 `project_ipa`
+    Ipa,
+}
+
+/// 把ARPAbet音素投影为IPA符号（如 `OW` → `oʊ`），重音仍记在 `stress` 中；没有对应IPA的符号（边界、事件标记）不变
+pub fn project_ipa(phonemes: &[Phoneme]) -> Vec<Phoneme> {
+    phonemes
+        .iter()
+        .map(|p| Phoneme {
+            symbol: arpabet_to_ipa(&p.symbol, &p.stress).map_or_else(|| p.symbol.clone(), str::to_string),
+            stress: p.stress.clone(),
+            features: p.features.clone(),
+        })
+        .collect()
+}
+
+/// 音素序列的IPA转写，音素之间不加空格，如 `həlˈoʊ`
+pub fn ipa_transcription(phonemes: &[Phoneme]) -> String {
+    phonemes.iter().map(|p| p.to_notation(Notation::Ipa)).collect()
+}
+
+/// 按指定记法输出音素序列，以空格分隔
+pub fn render(phonemes: &[Phoneme], notation: Notation) -> String {
+    render_with(phonemes, notation, &BoundarySymbols::default())
+}
+
+/// 与 `render` 相同，边界标记按 `symbols` 输出，写法为空字符串的标记省略
+pub fn render_with(phonemes: &[Phoneme], notation: Notation, symbols: &BoundarySymbols) -> String {
+    phonemes
+        .iter()
+        .filter_map(|p| match symbols.of(p) {
+            Some(symbol) => (!symbol.is_empty()).then(|| symbol.to_string()),
+            None => Some(p.to_notation(notation)),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 以空格连接的ARPAbet音素串，省略边界和事件等音素表之外的标记
+///
+/// 输出格式固定，不受记法和边界写法的配置影响，适合作为其他工具的输入。
+pub fn phone_string(phonemes: &[Phoneme], stress: StressMarks) -> String {
+    phonemes
+        .iter()
+        .filter_map(PhonemeCode::encode)
+        .map(|code| code.phone_symbol(stress))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 紧凑的单字节音素编码：低6位为符号编号，高2位为重音
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhonemeCode(u8);
+
+/// 可编码的ARPAbet符号，前15个为元音
+const CODE_SYMBOLS: [&str; 41] = [
+    "AA", "AE", "AH", "AO", "AW", "AY", "EH", "ER", "EY",
+    "IH", "IY", "OW", "OY", "UH", "UW",
+    "B", "CH", "D", "DH", "F", "G", "HH", "JH", "K", "L",
+    "M", "N", "NG", "P", "R", "S", "SH", "T", "TH", "V",
+    "W", "Y", "Z", "ZH",
+    "Q", "X",
+];
+
+const VOWEL_CODES: usize = 15;
+
+/// 带重音数字的元音符号，按 [符号][重音] 排列
+const STRESSED_VOWELS: [[&str; 3]; VOWEL_CODES] = [
+    ["AA0", "AA1", "AA2"], ["AE0", "AE1", "AE2"], ["AH0", "AH1", "AH2"],
+    ["AO0", "AO1", "AO2"], ["AW0", "AW1", "AW2"], ["AY0", "AY1", "AY2"],
+    ["EH0", "EH1", "EH2"], ["ER0", "ER1", "ER2"], ["EY0", "EY1", "EY2"],
+    ["IH0", "IH1", "IH2"], ["IY0", "IY1", "IY2"], ["OW0", "OW1", "OW2"],
+    ["OY0", "OY1", "OY2"], ["UH0", "UH1", "UH2"], ["UW0", "UW1", "UW2"],
+];
+
+impl PhonemeCode {
+    /// 编码音素，符号不在ARPAbet音素表中时返回None
+    pub fn encode(phoneme: &Phoneme) -> Option<Self> {
+        let id = CODE_SYMBOLS.iter().position(|s| *s == phoneme.symbol)?;
+        let stress = match phoneme.stress {
+            StressLevel::Unstressed => 0,
+            StressLevel::Primary => 1,
+            StressLevel::Secondary => 2,
+        };
+        Some(Self(id as u8 | stress << 6))
+    }
+    
+    /// 从原始字节还原编码，无效字节返回None
+    pub fn from_u8(byte: u8) -> Option<Self> {
+        let valid = usize::from(byte & 0x3f) < CODE_SYMBOLS.len() && byte >> 6 != 3;
+        valid.then_some(Self(byte))
+    }
+    
+    /// 所有可编码的音素（不带重音），元音在前
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..CODE_SYMBOLS.len() as u8).map(Self)
+    }
+    
+    pub fn to_u8(self) -> u8 {
+        self.0
+    }
+    
+    /// 不带重音的音素符号
+    pub fn symbol(self) -> &'static str {
+        CODE_SYMBOLS[usize::from(self.0 & 0x3f)]
+    }
+    
+    pub fn stress(self) -> StressLevel {
+        match self.0 >> 6 {
+            1 => StressLevel::Primary,
+            2 => StressLevel::Secondary,
+            _ => StressLevel::Unstressed,
+        }
+    }
+    
+    pub fn is_vowel(self) -> bool {
+        usize::from(self.0 & 0x3f) < VOWEL_CODES
+    }
+    
+    /// CMU词典形式的符号：元音带重音数字，辅音不带
+    pub fn arpabet(self) -> &'static str {
+        if self.is_vowel() {
+            STRESSED_VOWELS[usize::from(self.0 & 0x3f)][usize::from(self.0 >> 6)]
+        } else {
+            self.symbol()
+        }
+    }
+    
+    /// 音素串中的符号，重音数字按 `stress` 保留或去掉，见 `phone_string`
+    pub fn phone_symbol(self, stress: StressMarks) -> &'static str {
+        match (stress, self.stress()) {
+            (StressMarks::Keep, _) | (StressMarks::PrimaryOnly, StressLevel::Primary) => self.arpabet(),
+            _ => self.symbol(),
+        }
+    }
+    
+    /// 解码为完整的音素
+    pub fn decode(self) -> Phoneme {
+        Phoneme {
+            symbol: self.symbol().to_string(),
+            stress: self.stress(),
+            features: Phoneme::get_arpabet_features(self.symbol()),
+        }
+    }
+}
+
+/// 粗略检查音素序列是否符合英语音位配列：
+/// 必须含有元音，不能有相同辅音连续出现，词首辅音丛不超过3个，词尾不超过4个
+pub fn is_phonotactically_valid(phonemes: &[Phoneme]) -> bool {
+    if !phonemes.iter().any(|p| p.is_vowel()) {
+        return false;
+    }
+    
+    if phonemes.windows(2).any(|w| w[0].is_consonant() && w[0].symbol == w[1].symbol) {
+        return false;
+    }
+    
+    let onset = phonemes.iter().take_while(|p| p.is_consonant()).count();
+    let coda = phonemes.iter().rev().take_while(|p| p.is_consonant()).count();
+    onset <= 3 && coda <= 4
+}
+
 impl PhonemeFeatures {
     fn vowel(height: Height, backness: Backness) -> Self {
         Self {
@@ -218,8 +609,8 @@ impl fmt::Display for Phoneme {
             StressLevel::Unstressed => "0",
         };
         
-        if self.symbol == " " {
-            write!(f, " ")
+        if matches!(self.symbol.as_str(), WORD_BOUNDARY | PAUSE | SENTENCE | "\"") {
+            write!(f, "{}", self.symbol)
         } else {
             write!(f, "{}{}", self.symbol, stress_mark)
         }
@@ -0,0 +1,125 @@
+//! 数据文件加载报告
+//!
+//! 没有版本头的旧词典和规则文件按宽松模式解析：无法解析的行被跳过，错误的音素被修复或丢弃，
+//! 无效的优先级、条件和重音模板被忽略。`Dictionary::load_cmu_dict_with_report`、
+//! `RulesEngine::load_english_rules_with_report` 等函数把这些问题全部收集在 `LoadReport` 中，
+//! 与加载结果一起返回，工具可以据此列出并修正数据；不带报告的加载函数把摘要和前10个问题记入日志
+//! （`log` crate，摘要为info级别，问题为warn级别），不直接输出到stdout或stderr。
+//!
+//! 加载大文件时可以用 `Dictionary::load_cmu_dict_with_progress` 或 `G2PBuilder::on_progress` 接收 `LoadProgress`，
+//! 在界面上显示进度。
+
+use std::fmt;
+
+/// 问题的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IssueKind {
+    InvalidLine,    // 含有非ASCII字符、过长过短或没有字母，整行跳过
+    MalformedLine,  // 无法按文件格式拆分，整行跳过
+    InvalidEntry,   // 格式正确但内容无法使用（音素都无法解析等），整行跳过
+    FixedPhoneme,   // 错误的音素已修复，条目保留
+    SkippedPhoneme, // 无法解析的音素已丢弃，条目保留其余音素
+    IgnoredField,   // 无效的优先级、条件或重音模板已忽略，规则保留
+}
+
+/// 一个加载问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadIssue {
+    pub source: Option<String>, // 问题所在的被包含文件（`#include` 中的名称），主文件为None
+    pub line: usize,            // 行号，从1开始
+    pub kind: IssueKind,
+    pub message: String,
+    pub text: String, // 原始行
+}
+
+/// 一次加载的报告
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    pub lines: usize,      // 读取的行数，含被包含的文件
+    pub loaded: usize,     // 加载的条目数
+    pub duplicates: usize, // 词典中单词（忽略变体标记）已出现过的词条数，按 `DuplicatePolicy` 处理
+    pub issues: Vec<LoadIssue>,
+}
+
+/// 数据文件的加载进度
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadProgress {
+    /// 开始加载文件
+    Started { path: String },
+    /// 已解析的行数；词典分段并行解析，每完成一段报告一次
+    Lines { done: usize, total: usize },
+    /// 文件加载完成，`entries` 为词条数或规则数
+    Finished { path: String, entries: usize },
+}
+
+impl IssueKind {
+    /// 是否整行被跳过
+    pub fn skips_line(self) -> bool {
+        matches!(self, IssueKind::InvalidLine | IssueKind::MalformedLine | IssueKind::InvalidEntry)
+    }
+}
+
+impl LoadIssue {
+    pub(crate) fn new(line: usize, kind: IssueKind, message: impl Into<String>, text: &str) -> Self {
+        Self { source: None, line, kind, message: message.into(), text: text.to_string() }
+    }
+}
+
+impl fmt::Display for LoadIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{}:{}: ", source, self.line)?,
+            None => write!(f, "Line {}: ", self.line)?,
+        }
+        write!(f, "{}: '{}'", self.message, truncate(&self.text, 50))
+    }
+}
+
+impl LoadReport {
+    /// 是否没有任何问题
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 被跳过的行数
+    pub fn skipped_lines(&self) -> usize {
+        self.issues.iter().filter(|issue| issue.kind.skips_line()).count()
+    }
+
+    /// 某一类别的问题
+    pub fn of_kind(&self, kind: IssueKind) -> impl Iterator<Item = &LoadIssue> {
+        self.issues.iter().filter(move |issue| issue.kind == kind)
+    }
+
+    /// 渲染文本报告，每个问题一行
+    pub fn render(&self) -> String {
+        self.issues.iter().map(|issue| format!("{}\n", issue)).collect()
+    }
+
+    /// 并入被包含文件的报告，问题标上文件名（嵌套包含时保留最内层的文件名）
+    pub(crate) fn merge(&mut self, other: LoadReport, source: &str) {
+        self.lines += other.lines;
+        self.issues.extend(other.issues.into_iter().map(|mut issue| {
+            issue.source.get_or_insert_with(|| source.to_string());
+            issue
+        }));
+    }
+
+    /// 以警告级别记录前 `limit` 个问题
+    pub(crate) fn log_issues(&self, limit: usize) {
+        for issue in self.issues.iter().take(limit) {
+            log::warn!("{}", issue);
+        }
+        if self.issues.len() > limit {
+            log::warn!("{} more issues not shown", self.issues.len() - limit);
+        }
+    }
+}
+
+/// 截断字符串用于显示
+fn truncate(s: &str, max_len: usize) -> String {
+    match s.char_indices().nth(max_len) {
+        Some((end, _)) => format!("{}...", &s[..end]),
+        None => s.to_string(),
+    }
+}
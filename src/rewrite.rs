@@ -0,0 +1,206 @@
+//! 音素层面的改写规则
+//!
+//! 与按字母匹配的规则引擎不同，改写规则的模式和上下文都是音素，作用于转换后的音素序列，
+//! 用于口音转换、弱读和其他词汇后规则。规则文件每行一条：
+//!
+//! ```text
+//! #!g2p-rewrite 1
+//! # pattern|left_context|right_context|replacement|priority
+//! T|[vowel]|[vowel]|D|           # 元音间的T闪音化
+//! R||_|SILENT|                   # 非卷舌口音：词尾R不发音
+//! ```
+//!
+//! 音素写法：
+//! - `_`：词边界，在上下文中也匹配序列的两端
+//! - `[vowel]`、`[consonant]`：任意元音、任意辅音
+//! - 不带重音数字的音素忽略重音匹配，带数字时重音也须相同
+//! - 替换中的 `$n` 原样输出模式中第n个音素（从1开始），`SILENT` 表示删除
+//!
+//! 每个位置取优先级最高（默认为模式长度，相同时取先出现）的规则，上下文按改写前的序列判断，
+//! 替换结果不再参与匹配。需要多轮改写时加载多个规则文件依次应用。
+
+use crate::phoneme::{self, Notation, Phoneme};
+use crate::schema::{self, Capability, DataSchema};
+use crate::error::{invalid_data, Context, G2PError, Result};
+use std::fmt;
+use std::fs;
+
+/// 一条音素改写规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteRule {
+    pub pattern: Vec<String>,
+    pub left_context: Vec<String>,
+    pub right_context: Vec<String>,
+    pub replacement: Vec<String>, // 空表示删除
+    pub priority: usize,
+}
+
+/// 音素改写引擎
+#[derive(Debug, Clone, Default)]
+pub struct RewriteEngine {
+    rules: Vec<RewriteRule>, // 按优先级从高到低排列
+    schema: DataSchema,
+}
+
+impl RewriteEngine {
+    /// 加载改写规则文件
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rewrite rules file '{}'", path))?;
+        Self::parse(&content).with_context(|| format!("Failed to parse rewrite rules file '{}'", path))
+    }
+
+    /// 解析改写规则文本，任何无法识别的行都会报错
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut engine = Self { rules: Vec::new(), schema: schema::REWRITE_FORMAT.read_header(content)? };
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = engine.parse_rule(line).map_err(|e| G2PError::MalformedRule { line: i + 1, message: e.to_string() })?;
+            engine.rules.push(rule);
+        }
+        engine.rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+        Ok(engine)
+    }
+
+    fn parse_rule(&mut self, line: &str) -> Result<RewriteRule> {
+        // 行尾注释
+        let line = line.split_once(" #").map_or(line, |(rule, _)| rule).trim();
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() < 4 || parts.len() > 5 {
+            return Err(invalid_data!("expected pattern|left_context|right_context|replacement|priority: {}", line));
+        }
+        let items = |field: &str| field.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+        let pattern = items(parts[0]);
+        let left_context = items(parts[1]);
+        let right_context = items(parts[2]);
+        let replacement = if parts[3].trim() == "SILENT" { Vec::new() } else { items(parts[3]) };
+        if pattern.is_empty() {
+            return Err(invalid_data!("empty pattern"));
+        }
+
+        for item in pattern.iter().chain(&left_context).chain(&right_context) {
+            validate_item(item)?;
+            if item.starts_with('[') {
+                self.schema.record(Capability::Classes);
+            }
+        }
+        for item in &replacement {
+            if let Some(index) = item.strip_prefix('$') {
+                match index.parse::<usize>() {
+                    Ok(index) if (1..=pattern.len()).contains(&index) => self.schema.record(Capability::Captures),
+                    _ => return Err(invalid_data!("'{}' does not refer to a pattern position", item)),
+                }
+            } else if item != "_" {
+                phoneme::parse_arpabet(item)?;
+            }
+        }
+        let priority = match parts.get(4).map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            Some(priority) => {
+                self.schema.record(Capability::Weights);
+                priority.parse().map_err(|_| invalid_data!("invalid priority '{}'", priority))?
+            }
+            None => pattern.len(),
+        };
+        Ok(RewriteRule { pattern, left_context, right_context, replacement, priority })
+    }
+
+    /// 改写音素序列，没有规则匹配时原样返回
+    pub fn apply(&self, phonemes: &[Phoneme]) -> Vec<Phoneme> {
+        let mut out = Vec::with_capacity(phonemes.len());
+        let mut pos = 0;
+        while pos < phonemes.len() {
+            match self.rules.iter().find(|rule| rule.matches(phonemes, pos)) {
+                Some(rule) => {
+                    let end = pos + rule.pattern.len();
+                    replace(&rule.replacement, &phonemes[pos..end], &mut out);
+                    pos = end;
+                }
+                None => {
+                    out.push(phonemes[pos].clone());
+                    pos += 1;
+                }
+            }
+        }
+        out
+    }
+
+    /// 按优先级从高到低排列的规则
+    pub fn rules(&self) -> &[RewriteRule] {
+        &self.rules
+    }
+
+    /// 规则文件的版本和用到的特性
+    pub fn schema(&self) -> &DataSchema {
+        &self.schema
+    }
+}
+
+impl RewriteRule {
+    fn matches(&self, phonemes: &[Phoneme], start: usize) -> bool {
+        let end = start + self.pattern.len();
+        end <= phonemes.len()
+            && self.pattern.iter().zip(&phonemes[start..end]).all(|(item, phoneme)| item_matches(item, phoneme))
+            && self.left_context.iter().rev().enumerate().all(|(i, item)| {
+                context_matches(item, start.checked_sub(i + 1).and_then(|index| phonemes.get(index)))
+            })
+            && self.right_context.iter().enumerate().all(|(i, item)| context_matches(item, phonemes.get(end + i)))
+    }
+}
+
+impl fmt::Display for RewriteRule {
+    /// 形如 `[vowel] [T] [vowel] -> D (priority 1)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for item in &self.left_context {
+            write!(f, "{} ", item)?;
+        }
+        write!(f, "[{}]", self.pattern.join(" "))?;
+        for item in &self.right_context {
+            write!(f, " {}", item)?;
+        }
+        let replacement = if self.replacement.is_empty() { "SILENT".to_string() } else { self.replacement.join(" ") };
+        write!(f, " -> {} (priority {})", replacement, self.priority)
+    }
+}
+
+/// 检查模式或上下文中的音素写法
+pub(crate) fn validate_item(item: &str) -> Result<()> {
+    if !matches!(item, "_" | "[vowel]" | "[consonant]") {
+        phoneme::parse_arpabet(item)?;
+    }
+    Ok(())
+}
+
+/// 单个音素写法是否匹配，`_` 只匹配词边界
+pub(crate) fn item_matches(item: &str, phoneme: &Phoneme) -> bool {
+    match item {
+        "_" => phoneme.symbol == " ",
+        "[vowel]" => phoneme.is_vowel(),
+        "[consonant]" => phoneme.is_consonant(),
+        _ if item.ends_with(|c: char| c.is_ascii_digit()) => {
+            phoneme.to_notation(Notation::Arpabet).eq_ignore_ascii_case(item)
+        }
+        _ => phoneme.symbol.eq_ignore_ascii_case(item),
+    }
+}
+
+/// 上下文中的 `_` 也匹配序列两端
+fn context_matches(item: &str, phoneme: Option<&Phoneme>) -> bool {
+    match phoneme {
+        Some(phoneme) => item_matches(item, phoneme),
+        None => item == "_",
+    }
+}
+
+/// 输出替换结果，`$n` 引用 `matched` 中的第n个音素
+pub(crate) fn replace(replacement: &[String], matched: &[Phoneme], out: &mut Vec<Phoneme>) {
+    for item in replacement {
+        match item.strip_prefix('$').and_then(|index| index.parse::<usize>().ok()) {
+            Some(index) => out.push(matched[index - 1].clone()),
+            None if item == "_" => out.push(Phoneme::word_boundary()),
+            None => out.push(Phoneme::from_arpabet(&item.to_uppercase())),
+        }
+    }
+}
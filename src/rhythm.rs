@@ -0,0 +1,56 @@
+//! 节奏规则：消除跨词的重音冲突
+//!
+//! 英语中重读音节相邻时，前一个词的主重音会前移到它前面的重读音节上，
+//! 如 "thirteen men" 读作 `TH ER1 T IY2 N | M EH1 N`（THIRteen MEN）。
+//! 只处理整段音素序列，前一个词的最后一个元音和后一个词的第一个元音都带主重音时才算冲突。
+
+use crate::phoneme::{Phoneme, StressLevel};
+
+/// 对整段音素序列应用节奏规则，单词之间以词边界分隔
+///
+/// 前一个词须在主重音之前还有重读（主重音或次重音）的元音，否则无法前移，保持不变。
+pub fn apply_rhythm_rule(mut phonemes: Vec<Phoneme>) -> Vec<Phoneme> {
+    let words = word_ranges(&phonemes);
+    for pair in words.windows(2) {
+        let (first, second) = (pair[0].clone(), pair[1].clone());
+        // 中间只隔一个词边界才算相邻
+        if second.start != first.end + 1 {
+            continue;
+        }
+        let vowels: Vec<usize> = first.filter(|&i| phonemes[i].is_vowel()).collect();
+        let next_vowel = second.into_iter().find(|&i| phonemes[i].is_vowel());
+        let (Some(&last), Some(next)) = (vowels.last(), next_vowel) else {
+            continue;
+        };
+        if phonemes[last].stress != StressLevel::Primary || phonemes[next].stress != StressLevel::Primary {
+            continue;
+        }
+        let earlier = vowels[..vowels.len() - 1]
+            .iter()
+            .copied()
+            .find(|&i| phonemes[i].stress != StressLevel::Unstressed);
+        if let Some(earlier) = earlier {
+            phonemes[earlier].stress = StressLevel::Primary;
+            phonemes[last].stress = StressLevel::Secondary;
+        }
+    }
+    phonemes
+}
+
+/// 每个单词在序列中的范围
+fn word_ranges(phonemes: &[Phoneme]) -> Vec<std::ops::Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (i, phoneme) in phonemes.iter().enumerate() {
+        if phoneme.symbol == " " {
+            if i > start {
+                words.push(start..i);
+            }
+            start = i + 1;
+        }
+    }
+    if start < phonemes.len() {
+        words.push(start..phonemes.len());
+    }
+    words
+}
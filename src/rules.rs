@@ -1,13 +1,49 @@
-use crate::phoneme::Phoneme;
-use anyhow::Result;
+mod context;
+mod format;
+pub mod import;
+mod sandhi;
+mod stress;
+
+pub use format::{convert, RuleFormat, RuleSet};
+pub use sandhi::SandhiRule;
+
+use crate::golden::StableHasher;
+use crate::letters::letter_name;
+use crate::memory;
+use crate::phoneme::{Phoneme, StressLevel};
+use crate::report::LoadReport;
+use crate::schema::DataSchema;
+use crate::error::{invalid_data, Context, G2PError, Result};
+use context::ContextPattern;
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 规则转换的临时缓冲区，每个线程一份，避免每个单词重新分配
+#[derive(Default)]
+struct Scratch {
+    lowercase: String,
+    chars: Vec<char>,
+    phonemes: Vec<Phoneme>,
+    origins: Vec<usize>, // 每个音素所在规则匹配的起始字符位置
+}
+
+thread_local! {
+    static SCRATCH: RefCell<Scratch> = RefCell::new(Scratch::default());
+}
 
 /// 规则引擎
 pub struct RulesEngine {
     rules: Vec<Rule>,
     rule_groups: HashMap<char, Vec<usize>>, // 按首字母分组的规则索引
+    contexts: Vec<(Option<ContextPattern>, Option<ContextPattern>)>, // 编译后的左右上下文
     irregular_words: HashMap<String, Vec<String>>, // 不规则词汇
+    sandhi: Vec<SandhiRule>,                       // 跨词连读规则，按优先级排序
+    schema: DataSchema,                            // 规则文件的版本和用到的特性
+    assign_stress: bool,                           // 规则转换后指派主重音
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +54,26 @@ pub struct Rule {
     pub phonemes: Vec<String>,             // 输出音素
     pub priority: usize,                   // 优先级（模式长度）
     pub conditions: Vec<RuleCondition>,    // 额外条件
+    pub stress: Option<String>,            // 重音模板，如 `10`：依次改写输出中元音的重音，含主重音时单词中其他的主重音降为次重音
+}
+
+/// 带置信度的规则输出
+#[derive(Debug, Clone)]
+pub struct RuleOutput {
+    pub phonemes: Vec<Phoneme>,
+    pub confidence: f32,        // 0.0 ~ 1.0
+    pub irregular: bool,        // 来自不规则词汇表
+    pub default_letters: usize, // 使用默认映射的字母数
+    pub uncovered: Vec<usize>,  // 规则和默认映射都无法处理的字符位置（按字符计）
+}
+
+/// 规则转换过程中的一步，用于展示推导过程
+#[derive(Debug, Clone)]
+pub struct RuleStep {
+    pub position: usize,      // 起始字符位置
+    pub letters: String,      // 本步处理的字母
+    pub rule: Option<Rule>,   // 匹配的规则，None表示默认映射或无法处理
+    pub phonemes: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,171 +88,258 @@ pub enum RuleCondition {
 
 impl RulesEngine {
     /// 加载英语规则 - 仅从文件加载
+    ///
+    /// 规则文件可以用 `#include "suffixes.rules"` 引入其他规则文件，路径相对于所在文件，
+    /// 被包含的规则插入指令所在的位置。
     pub fn load_english_rules(rules_path: &str) -> Result<Self> {
-        let mut engine = Self {
+        Ok(Self::load_english_rules_with_report(rules_path)?.0)
+    }
+    
+    /// 加载英语规则，没有版本头的文件中跳过的行和忽略的字段在报告中返回
+    pub fn load_english_rules_with_report(rules_path: &str) -> Result<(Self, LoadReport)> {
+        let (rule_set, report) = Self::read_pipe_file(Path::new(rules_path), &mut Vec::new())?;
+        Ok((Self::from_rule_set(rule_set), report))
+    }
+    
+    /// 从文本解析英语规则（格式同 `load_english_rules`，但不能包含其他文件）
+    pub fn parse_english_rules(content: &str) -> Result<Self> {
+        Ok(Self::parse_english_rules_with_report(content)?.0)
+    }
+    
+    /// 从文本解析英语规则，跳过的行和忽略的字段在报告中返回
+    pub fn parse_english_rules_with_report(content: &str) -> Result<(Self, LoadReport)> {
+        let (rule_set, report) = format::parse_pipe(content)?;
+        Ok((Self::from_rule_set(rule_set), report))
+    }
+    
+    /// 加载NRL/Elovitz格式的规则文件
+    pub fn load_nrl_rules(rules_path: &str) -> Result<Self> {
+        let content = Self::read_rules_file(Path::new(rules_path))?;
+        Ok(Self::from_rules(import::parse_nrl(&content)?))
+    }
+    
+    /// 加载Festival LTS格式的规则文件
+    pub fn load_festival_rules(rules_path: &str) -> Result<Self> {
+        let content = Self::read_rules_file(Path::new(rules_path))?;
+        Ok(Self::from_rules(import::parse_festival(&content)?))
+    }
+    
+    /// 从规则列表构建引擎
+    pub fn from_rules(rules: Vec<Rule>) -> Self {
+        Self::from_rule_set(RuleSet { rules, ..RuleSet::default() })
+    }
+    
+    /// 从规则集合（含不规则词汇）构建引擎
+    pub fn from_rule_set(rule_set: RuleSet) -> Self {
+        let mut engine = Self::empty();
+        engine.rules = rule_set.rules;
+        engine.irregular_words = rule_set.irregular_words.into_iter().collect();
+        engine.sandhi = rule_set.sandhi;
+        engine.sandhi.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+        engine.schema = rule_set.schema;
+        engine.rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+        engine.build_index();
+        engine
+    }
+    
+    fn empty() -> Self {
+        Self {
             rules: Vec::new(),
             rule_groups: HashMap::new(),
+            contexts: Vec::new(),
             irregular_words: HashMap::new(),
-        };
-        
-        // 从文件加载规则和不规则词汇
-        let content = fs::read_to_string(rules_path)
-            .map_err(|e| anyhow::anyhow!("Failed to read rules file '{}': {}", rules_path, e))?;
-        engine.parse_rules(&content)?;
-        
-        engine.build_index();
-        
-        Ok(engine)
+            sandhi: Vec::new(),
+            schema: DataSchema::default(),
+            assign_stress: true,
+        }
     }
     
-    /// 解析规则文件
-    fn parse_rules(&mut self, content: &str) -> Result<()> {
-        for line in content.lines() {
-            let line = line.trim();
-            
-            // 跳过空行和注释行
-            if line.is_empty() || line.starts_with('#') || line.starts_with("=") {
-                continue;
-            }
-            
-            // 处理不规则词汇
-            if line.starts_with("IRREGULAR|") {
-                self.parse_irregular_word(line)?;
-                continue;
-            }
-            
-            // 解析常规规则，格式：pattern|left_context|right_context|phonemes|priority|conditions
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() < 4 {
-                continue; // 跳过格式不正确的行
-            }
-            
-            let pattern = parts[0].to_string();
-            let left_context = if parts.len() > 1 && !parts[1].is_empty() {
-                Some(parts[1].to_string())
-            } else {
-                None
-            };
-            let right_context = if parts.len() > 2 && !parts[2].is_empty() {
-                Some(parts[2].to_string())
-            } else {
-                None
-            };
-            
-            // 解析音素列表（用空格分隔）
-            let phonemes: Vec<String> = if parts[3] == "SILENT" {
-                Vec::new() // 静音规则
-            } else {
-                parts[3]
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect()
-            };
-            
-            // 解析优先级
-            let priority = if parts.len() > 4 && !parts[4].is_empty() {
-                parts[4].parse::<usize>().unwrap_or(pattern.len())
-            } else {
-                pattern.len()
-            };
-            
-            // 解析条件
-            let conditions = if parts.len() > 5 && !parts[5].is_empty() {
-                self.parse_conditions(parts[5])?
-            } else {
-                Vec::new()
-            };
-            
-            let rule = Rule {
-                pattern,
-                left_context,
-                right_context,
-                phonemes,
-                priority,
-                conditions,
-            };
-            
-            self.rules.push(rule);
+    fn read_rules_file(rules_path: &Path) -> Result<String> {
+        fs::read_to_string(rules_path)
+            .with_context(|| format!("Failed to read rules file '{}'", rules_path.display()))
+    }
+    
+    /// 解析竖线格式的规则文件及其包含的文件，`stack` 为正在解析的文件，用于发现循环包含
+    fn read_pipe_file(path: &Path, stack: &mut Vec<PathBuf>) -> Result<(RuleSet, LoadReport)> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if stack.contains(&canonical) {
+            return Err(invalid_data!("Include cycle: '{}' is already being loaded", path.display()));
         }
+        let content = Self::read_rules_file(path)?;
         
-        // 按优先级排序，优先级高的在前
-        self.rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        stack.push(canonical);
+        let base = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let rule_set = format::parse_pipe_with(&content, &mut |name| Self::read_pipe_file(&base.join(name), stack));
+        stack.pop();
         
-        Ok(())
-    }
-    
-    /// 解析不规则词汇行
-    fn parse_irregular_word(&mut self, line: &str) -> Result<()> {
-        // 格式：IRREGULAR|word|phoneme1 phoneme2 phoneme3
-        let parts: Vec<&str> = line.split('|').collect();
-        if parts.len() >= 3 {
-            let word = parts[1].trim().to_lowercase();
-            let phonemes: Vec<String> = parts[2]
-                .split_whitespace()
-                .map(|s| s.to_string())
-                .collect();
-            
-            if !word.is_empty() && !phonemes.is_empty() {
-                self.irregular_words.insert(word, phonemes);
-            }
-        }
-        Ok(())
+        rule_set.with_context(|| format!("Failed to parse rules file '{}'", path.display()))
+    }
+    
+    /// 应用规则到单词
+    ///
+    /// 规则模式可以包含撇号和数字（如 `n't`）；没有规则匹配时撇号不发音，数字按名称逐个读（`mp3` 读作 M P three）。
+    /// 不规则词汇以外的单词转换后指派唯一的主重音，见 `set_stress_assignment`。
+    pub fn apply_rules(&self, word: &str) -> Result<Vec<Phoneme>> {
+        Ok(self.apply_rules_scored(word)?.phonemes)
+    }
+    
+    /// 应用规则到单词，并给出结果的置信度
+    ///
+    /// 每个字母按其来源计分后取平均：不规则词汇和带上下文/多字母规则为1.0，
+    /// 无上下文的单元音字母规则为0.5，默认映射为0.25，无法处理的字符为0；撇号和数字的读法是确定的，计为1.0。
+    pub fn apply_rules_scored(&self, word: &str) -> Result<RuleOutput> {
+        self.run_rules(word, None, None)
+    }
+    
+    /// 应用规则并记录每一步匹配的规则；不规则词汇没有推导步骤
+    pub fn trace_rules(&self, word: &str) -> Result<(RuleOutput, Vec<RuleStep>)> {
+        let mut steps = Vec::new();
+        let output = self.run_rules(word, Some(&mut steps), None)?;
+        Ok((output, steps))
     }
     
-    /// 解析条件字符串
-    fn parse_conditions(&self, conditions_str: &str) -> Result<Vec<RuleCondition>> {
-        let mut conditions = Vec::new();
+    /// 应用规则并给出至多 `n` 个候选读音，首个候选与 `apply_rules_scored` 相同
+    ///
+    /// 某一位置有多条优先级相同、输出不同的规则同时匹配时，规则文件中靠前的规则给出首选读音，
+    /// 其余规则各给出一个备选读音（每个备选只替换一处）。备选按置信度从高到低排列，读音相同的只保留一个。
+    pub fn apply_rules_nbest(&self, word: &str, n: usize) -> Result<Vec<RuleOutput>> {
+        let (primary, steps) = self.trace_rules(word)?;
+        let mut candidates = vec![primary];
+        if candidates[0].irregular {
+            candidates.truncate(n);
+            return Ok(candidates);
+        }
         
-        for condition in conditions_str.split(',') {
-            let condition = condition.trim();
-            match condition {
-                "START" => conditions.push(RuleCondition::WordStart),
-                "END" => conditions.push(RuleCondition::WordEnd),
-                "VOWEL_BEFORE" => conditions.push(RuleCondition::BeforeVowel),
-                "VOWEL_AFTER" => conditions.push(RuleCondition::AfterVowel),
-                "word_start" => conditions.push(RuleCondition::WordStart),
-                "word_end" => conditions.push(RuleCondition::WordEnd),
-                "before_vowel" => conditions.push(RuleCondition::BeforeVowel),
-                "after_vowel" => conditions.push(RuleCondition::AfterVowel),
-                "stressed" => conditions.push(RuleCondition::Stressed),
-                "unstressed" => conditions.push(RuleCondition::Unstressed),
-                _ => {} // 忽略未知条件
+        let word_chars: Vec<char> = word.chars().map(rule_char).collect();
+        let mut alternates = Vec::new();
+        for step in &steps {
+            let Some(chosen) = step.rule.as_ref().and_then(|_| self.find_best_rule(&word_chars, step.position).ok()) else {
+                continue;
+            };
+            for rule_idx in self.tied_rules(&word_chars, step.position, chosen) {
+                let output = self.run_rules(word, None, Some((step.position, rule_idx)))?;
+                if candidates.iter().chain(&alternates).all(|c: &RuleOutput| c.phonemes != output.phonemes) {
+                    alternates.push(output);
+                }
             }
         }
-        
-        Ok(conditions)
+        alternates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        candidates.extend(alternates);
+        candidates.truncate(n);
+        Ok(candidates)
     }
     
-    /// 应用规则到单词
-    pub fn apply_rules(&self, word: &str) -> Result<Vec<Phoneme>> {
+    fn run_rules(&self, word: &str, trace: Option<&mut Vec<RuleStep>>, forced: Option<(usize, usize)>) -> Result<RuleOutput> {
+        // 取出本线程的缓冲区，转换完再放回；嵌套调用时取到的是空缓冲区，不会冲突
+        let mut scratch = SCRATCH.with(RefCell::take);
+        let output = self.run_rules_with(word, trace, forced, &mut scratch);
+        SCRATCH.with(|cell| cell.replace(scratch));
+        output
+    }
+    
+    /// `forced` 为 `(位置, 规则索引)` 时，该位置改用指定的规则
+    fn run_rules_with(
+        &self,
+        word: &str,
+        mut trace: Option<&mut Vec<RuleStep>>,
+        forced: Option<(usize, usize)>,
+        scratch: &mut Scratch,
+    ) -> Result<RuleOutput> {
+        let Scratch { lowercase, chars: word_chars, phonemes, origins } = scratch;
+        
         // 首先检查不规则词汇
-        if let Some(phonemes) = self.irregular_words.get(&word.to_lowercase()) {
-            return Ok(phonemes.iter().map(|p| Phoneme::from_arpabet(p)).collect());
+        lowercase.clear();
+        lowercase.extend(word.chars().map(rule_char).flat_map(char::to_lowercase));
+        if let Some(phonemes) = self.irregular_words.get(lowercase.as_str()) {
+            return Ok(RuleOutput {
+                phonemes: phonemes.iter().map(|p| Phoneme::from_arpabet(p)).collect(),
+                confidence: 1.0,
+                irregular: true,
+                default_letters: 0,
+                uncovered: Vec::new(),
+            });
         }
         
-        let mut phonemes = Vec::new();
+        phonemes.clear();
+        origins.clear();
+        let mut templated = false;
         let mut pos = 0;
-        let word_chars: Vec<char> = word.chars().collect();
+        let mut score = 0.0;
+        let mut default_letters = 0;
+        let mut uncovered = Vec::new();
+        word_chars.clear();
+        word_chars.extend(word.chars().map(rule_char));
         
         while pos < word_chars.len() {
-            match self.find_best_rule(&word_chars, pos) {
-                Ok(rule) => {
+            let best = match forced {
+                Some((at, rule_idx)) if at == pos => Ok(rule_idx),
+                _ => self.find_best_rule(word_chars, pos),
+            };
+            match best {
+                Ok(rule_idx) => {
+                    let rule = &self.rules[rule_idx];
+                    let output = self.rule_output(rule_idx, word_chars, pos);
+                    
                     // 添加规则输出的音素
-                    for phoneme_str in &rule.phonemes {
+                    let start = phonemes.len();
+                    for phoneme_str in output.iter() {
                         if !phoneme_str.is_empty() {
                             phonemes.push(Phoneme::from_arpabet(phoneme_str));
                         }
                     }
+                    if let Some(template) = &rule.stress {
+                        Self::apply_stress_template(phonemes, start, template);
+                        templated |= template.contains('1');
+                    }
+                    templated |= rule.phonemes.iter().any(|p| p.starts_with('$') && p.ends_with('1'));
+                    origins.resize(phonemes.len(), pos);
                     
                     // 前进位置
-                    pos += rule.pattern.chars().count();
+                    let len = rule.pattern.chars().count();
+                    if let Some(steps) = trace.as_deref_mut() {
+                        steps.push(RuleStep {
+                            position: pos,
+                            letters: word_chars[pos..pos + len].iter().collect(),
+                            rule: Some(rule.clone()),
+                            phonemes: output.iter().filter(|p| !p.is_empty()).cloned().collect(),
+                        });
+                    }
+                    score += Self::rule_confidence(rule) * len as f32;
+                    pos += len;
                 }
                 Err(_) => {
-                    // 如果找不到规则，使用默认处理
+                    // 如果找不到规则，使用默认处理：撇号不发音，数字读作数字名称，字母使用默认映射
                     let current_char = word_chars[pos];
-                    
-                    if let Some(default_phoneme) = Self::get_default_phoneme(current_char) {
-                        phonemes.push(Phoneme::from_arpabet(&default_phoneme));
+                    let default_phonemes: Vec<String> = match current_char {
+                        '\'' => {
+                            score += 1.0;
+                            Vec::new()
+                        }
+                        digit if digit.is_ascii_digit() => {
+                            score += 1.0;
+                            letter_name(digit).unwrap_or_default().iter().map(|p| p.to_string()).collect()
+                        }
+                        letter => match Self::get_default_phoneme(letter) {
+                            Some(default_phoneme) => {
+                                score += 0.25;
+                                default_letters += 1;
+                                vec![default_phoneme]
+                            }
+                            None => {
+                                uncovered.push(pos);
+                                Vec::new()
+                            }
+                        },
+                    };
+                    phonemes.extend(default_phonemes.iter().map(|p| Phoneme::from_arpabet(p)));
+                    origins.resize(phonemes.len(), pos);
+                    if let Some(steps) = trace.as_deref_mut() {
+                        steps.push(RuleStep {
+                            position: pos,
+                            letters: current_char.to_string(),
+                            rule: None,
+                            phonemes: default_phonemes,
+                        });
                     }
                     
                     pos += 1;
@@ -204,7 +347,62 @@ impl RulesEngine {
             }
         }
         
-        Ok(phonemes)
+        let confidence = if word_chars.is_empty() {
+            1.0
+        } else {
+            score / word_chars.len() as f32
+        };
+        
+        if self.assign_stress && !templated {
+            stress::assign_stress(word_chars, phonemes, origins);
+        }
+        
+        // 按实际长度分配一次，缓冲区保留容量
+        let mut output = Vec::with_capacity(phonemes.len());
+        output.append(phonemes);
+        Ok(RuleOutput {
+            phonemes: output,
+            confidence,
+            irregular: false,
+            default_letters,
+            uncovered,
+        })
+    }
+    
+    /// 按重音模板改写 `start` 之后的元音；模板含主重音时，之前的主重音降为次重音
+    fn apply_stress_template(phonemes: &mut [Phoneme], start: usize, template: &str) {
+        let levels: Vec<StressLevel> = template
+            .chars()
+            .map(|digit| match digit {
+                '1' => StressLevel::Primary,
+                '2' => StressLevel::Secondary,
+                _ => StressLevel::Unstressed,
+            })
+            .collect();
+        let vowels = phonemes[start..].iter_mut().filter(|p| p.is_vowel());
+        for (phoneme, level) in vowels.zip(&levels) {
+            phoneme.stress = level.clone();
+        }
+        if levels.contains(&StressLevel::Primary) {
+            for phoneme in &mut phonemes[..start] {
+                if phoneme.stress == StressLevel::Primary {
+                    phoneme.stress = StressLevel::Secondary;
+                }
+            }
+        }
+    }
+    
+    /// 单条规则的置信度：单个元音字母的无上下文规则最不可靠
+    fn rule_confidence(rule: &Rule) -> f32 {
+        let has_context = rule.left_context.is_some() || rule.right_context.is_some() || !rule.conditions.is_empty();
+        let single_vowel = rule.pattern.chars().count() == 1
+            && rule.pattern.chars().all(|c| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y'));
+        
+        if single_vowel && !has_context {
+            0.5
+        } else {
+            1.0
+        }
     }
     
     /// 获取字符的默认音素
@@ -240,54 +438,101 @@ impl RulesEngine {
         }
     }
     
-    /// 查找最佳匹配规则
-    fn find_best_rule(&self, word: &[char], pos: usize) -> Result<&Rule> {
+    /// 查找最佳匹配规则，返回规则索引
+    fn find_best_rule(&self, word: &[char], pos: usize) -> Result<usize> {
         let current_char = word[pos];
-        let mut best_rule: Option<&Rule> = None;
+        let mut best_rule: Option<usize> = None;
         let mut best_priority = 0;
         
         // 获取当前字符的候选规则
         if let Some(rule_indices) = self.rule_groups.get(&current_char) {
             for &rule_idx in rule_indices {
-                let rule = &self.rules[rule_idx];
-                
-                if let Some(priority) = self.rule_matches(rule, word, pos) {
+                if let Some(priority) = self.rule_matches(rule_idx, word, pos) {
                     if priority > best_priority {
                         best_priority = priority;
-                        best_rule = Some(rule);
+                        best_rule = Some(rule_idx);
                     }
                 }
             }
         }
         
-        best_rule.ok_or_else(|| {
-            anyhow::anyhow!("No rule found for character '{}' at position {}", word[pos], pos)
-        })
+        best_rule.ok_or(G2PError::NoRule { character: word[pos], position: pos })
+    }
+    
+    /// 与选中的规则优先级相同、同时匹配且输出不同的其他规则，按规则文件中的顺序
+    fn tied_rules(&self, word: &[char], pos: usize, chosen: usize) -> Vec<usize> {
+        let rule = &self.rules[chosen];
+        let output = self.rule_output(chosen, word, pos);
+        self.rule_groups
+            .get(&word[pos])
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&idx| idx != chosen && self.rule_matches(idx, word, pos) == Some(rule.priority))
+            .filter(|&idx| self.rule_output(idx, word, pos) != output || self.rules[idx].stress != rule.stress)
+            .collect()
+    }
+    
+    /// 规则输出的音素，`$name` 替换为上下文捕获到的字母的默认音素
+    ///
+    /// 占位符后的数字改写元音的重音（`$v1`），引用不存在的捕获时不输出。
+    fn rule_output(&self, rule_idx: usize, word: &[char], pos: usize) -> Cow<'_, [String]> {
+        let rule = &self.rules[rule_idx];
+        if !rule.phonemes.iter().any(|p| p.starts_with('$')) {
+            return Cow::Borrowed(&rule.phonemes);
+        }
+        
+        let (left_ctx, right_ctx) = &self.contexts[rule_idx];
+        let end = pos + rule.pattern.chars().count();
+        let mut captures = Vec::new();
+        captures.extend(left_ctx.as_ref().and_then(|ctx| ctx.captures(word, pos)).into_iter().flatten());
+        captures.extend(right_ctx.as_ref().and_then(|ctx| ctx.captures(word, end)).into_iter().flatten());
+        
+        let resolve = |placeholder: &str| {
+            let name = placeholder.trim_end_matches(|c: char| c.is_ascii_digit());
+            let stress = &placeholder[name.len()..];
+            let (_, letter) = captures.iter().find(|(capture, _)| capture == name)?;
+            let phoneme = Self::get_default_phoneme(*letter)?;
+            match phoneme.strip_suffix(|c: char| c.is_ascii_digit()) {
+                Some(vowel) if !stress.is_empty() => Some(format!("{}{}", vowel, stress)),
+                _ => Some(phoneme),
+            }
+        };
+        Cow::Owned(
+            rule.phonemes
+                .iter()
+                .filter_map(|p| match p.strip_prefix('$') {
+                    Some(placeholder) => resolve(placeholder),
+                    None => Some(p.clone()),
+                })
+                .collect(),
+        )
     }
     
     /// 检查规则是否匹配
-    fn rule_matches(&self, rule: &Rule, word: &[char], pos: usize) -> Option<usize> {
+    fn rule_matches(&self, rule_idx: usize, word: &[char], pos: usize) -> Option<usize> {
+        let rule = &self.rules[rule_idx];
+        let (left_ctx, right_ctx) = &self.contexts[rule_idx];
+        
         // 1. 检查模式匹配
-        let pattern_chars: Vec<char> = rule.pattern.chars().collect();
-        if pos + pattern_chars.len() > word.len() {
+        let pattern_len = rule.pattern.chars().count();
+        if pos + pattern_len > word.len() {
             return None;
         }
         
-        for (i, &pattern_char) in pattern_chars.iter().enumerate() {
-            if word[pos + i].to_ascii_lowercase() != pattern_char {
-                return None;
-            }
+        if !rule.pattern.chars().zip(&word[pos..]).all(|(pattern_char, c)| c.to_ascii_lowercase() == pattern_char) {
+            return None;
         }
         
         // 2. 检查上下文条件
-        if let Some(ref right_ctx) = rule.right_context {
-            if !self.check_right_context(right_ctx, word, pos + pattern_chars.len()) {
+        if let Some(right_ctx) = right_ctx {
+            if !right_ctx.matches(word, pos + pattern_len) {
                 return None;
             }
         }
         
-        if let Some(ref left_ctx) = rule.left_context {
-            if !self.check_left_context(left_ctx, word, pos) {
+        if let Some(left_ctx) = left_ctx {
+            if !left_ctx.matches(word, pos) {
                 return None;
             }
         }
@@ -302,47 +547,6 @@ impl RulesEngine {
         Some(rule.priority)
     }
     
-    /// 检查右上下文
-    fn check_right_context(&self, context: &str, word: &[char], pos: usize) -> bool {
-        if context == "END" {
-            return pos >= word.len(); // 词尾
-        }
-        
-        let context_chars: Vec<char> = context.chars().collect();
-        if pos + context_chars.len() > word.len() {
-            return false;
-        }
-        
-        for (i, &ctx_char) in context_chars.iter().enumerate() {
-            if word[pos + i].to_ascii_lowercase() != ctx_char {
-                return false;
-            }
-        }
-        
-        true
-    }
-    
-    /// 检查左上下文
-    fn check_left_context(&self, context: &str, word: &[char], pos: usize) -> bool {
-        if context == "START" {
-            return pos == 0; // 词首
-        }
-        
-        let context_chars: Vec<char> = context.chars().collect();
-        if context_chars.len() > pos {
-            return false;
-        }
-        
-        let start = pos - context_chars.len();
-        for (i, &ctx_char) in context_chars.iter().enumerate() {
-            if word[start + i].to_ascii_lowercase() != ctx_char {
-                return false;
-            }
-        }
-        
-        true
-    }
-    
     /// 检查条件
     fn check_condition(&self, condition: &RuleCondition, word: &[char], pos: usize) -> bool {
         match condition {
@@ -373,6 +577,14 @@ impl RulesEngine {
     
     /// 构建规则索引
     fn build_index(&mut self) {
+        self.contexts = self.rules
+            .iter()
+            .map(|rule| (
+                rule.left_context.as_deref().map(ContextPattern::left),
+                rule.right_context.as_deref().map(ContextPattern::right),
+            ))
+            .collect();
+        
         for (idx, rule) in self.rules.iter().enumerate() {
             if let Some(first_char) = rule.pattern.chars().next() {
                 self.rule_groups
@@ -383,8 +595,127 @@ impl RulesEngine {
         }
     }
     
+    /// 估算规则集的堆内存占用（字节）
+    pub fn heap_size(&self) -> usize {
+        let rules: usize = self.rules
+            .iter()
+            .map(|rule| {
+                rule.pattern.capacity()
+                    + rule.left_context.as_ref().map_or(0, String::capacity)
+                    + rule.right_context.as_ref().map_or(0, String::capacity)
+                    + memory::strings_heap(&rule.phonemes)
+                    + rule.conditions.capacity() * std::mem::size_of::<RuleCondition>()
+            })
+            .sum();
+        let groups: usize = self.rule_groups
+            .values()
+            .map(|indices| indices.capacity() * std::mem::size_of::<usize>())
+            .sum();
+        let irregular: usize = self.irregular_words
+            .iter()
+            .map(|(word, phonemes)| word.capacity() + memory::strings_heap(phonemes))
+            .sum();
+        let sandhi: usize = self.sandhi
+            .iter()
+            .map(|rule| memory::strings_heap(&rule.pattern) + memory::strings_heap(&rule.replacement))
+            .sum();
+        
+        self.rules.capacity() * std::mem::size_of::<Rule>()
+            + rules
+            + memory::map_heap(&self.rule_groups)
+            + groups
+            + self.contexts.capacity() * std::mem::size_of::<(Option<ContextPattern>, Option<ContextPattern>)>()
+            + memory::map_heap(&self.irregular_words)
+            + irregular
+            + self.sandhi.capacity() * std::mem::size_of::<SandhiRule>()
+            + sandhi
+    }
+    
     /// 获取规则数量
     pub fn rule_count(&self) -> usize {
         self.rules.len()
     }
-}
\ No newline at end of file
+    
+    /// 对整段文本的音素序列应用跨词连读规则，没有连读规则时原样返回
+    pub fn apply_sandhi(&self, phonemes: Vec<Phoneme>) -> Vec<Phoneme> {
+        sandhi::apply(&self.sandhi, phonemes)
+    }
+    
+    /// 是否在规则转换后为单词指派唯一的主重音（默认开启），规则见 `stress` 模块
+    pub fn set_stress_assignment(&mut self, enabled: bool) {
+        self.assign_stress = enabled;
+    }
+    
+    /// 加入跨词连读规则，与已有规则一起按优先级排列
+    pub fn add_sandhi_rules(&mut self, rules: impl IntoIterator<Item = SandhiRule>) {
+        self.sandhi.extend(rules);
+        self.sandhi.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+    }
+    
+    /// 跨词连读规则，按优先级从高到低排列
+    pub fn sandhi_rules(&self) -> &[SandhiRule] {
+        &self.sandhi
+    }
+    
+    /// 规则和不规则词汇的输出，依次给出模式或单词和输出的音素；静音规则的输出为空字符串，`$` 开头的是捕获引用
+    pub(crate) fn outputs(&self) -> impl Iterator<Item = (&str, &[String])> {
+        let rules = self.rules.iter().map(|rule| (rule.pattern.as_str(), rule.phonemes.as_slice()));
+        rules.chain(self.irregular_words.iter().map(|(word, phonemes)| (word.as_str(), phonemes.as_slice())))
+    }
+    
+    /// 规则文件的版本和用到的特性；由NRL、Festival或规则列表构建时为默认值
+    pub fn schema(&self) -> &DataSchema {
+        &self.schema
+    }
+    
+    /// 按匹配顺序写入全部规则和排序后的不规则词汇
+    pub(crate) fn fingerprint(&self, hasher: &mut StableHasher) {
+        for rule in &self.rules {
+            hasher.write_field(&rule.to_pipe_line());
+        }
+        for rule in &self.sandhi {
+            hasher.write_field(&rule.to_pipe_line());
+        }
+        let mut words: Vec<(&String, &Vec<String>)> = self.irregular_words.iter().collect();
+        words.sort();
+        for (word, phonemes) in words {
+            hasher.write_field(word);
+            hasher.write_field(&phonemes.join(" "));
+        }
+        hasher.write_field(if self.assign_stress { "stress-assignment" } else { "no-stress-assignment" });
+    }
+}
+
+/// 规则匹配前统一字符写法：弯撇号（`’`、`ʼ`、`‘`）换成直撇号，规则文件中只需写 `'`
+fn rule_char(c: char) -> char {
+    match c {
+        '’' | 'ʼ' | '‘' => '\'',
+        c => c,
+    }
+}
+
+impl fmt::Display for Rule {
+    /// 形如 `#[e]_ -> SILENT (priority 2, word_end)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]{} -> ",
+            self.left_context.as_deref().unwrap_or(""),
+            self.pattern,
+            self.right_context.as_deref().unwrap_or("")
+        )?;
+        if self.phonemes.is_empty() {
+            write!(f, "SILENT")?;
+        } else {
+            write!(f, "{}", self.phonemes.join(" "))?;
+        }
+        write!(f, " (priority {}", self.priority)?;
+        for condition in &self.conditions {
+            write!(f, ", {}", format::condition_name(condition))?;
+        }
+        if let Some(stress) = &self.stress {
+            write!(f, ", stress {}", stress)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,253 @@
+/// 规则上下文模式
+///
+/// 支持的语法（兼容NRL/Elovitz规则的字符类符号）：
+/// - 字母：字面匹配
+/// - `_`：词边界
+/// - `#`：一个或多个元音
+/// - `:`：零个或多个辅音
+/// - `^`：一个辅音
+/// - `*`：一个或多个辅音
+/// - `.`：一个浊辅音
+/// - `+`：一个前元音 (e, i, y)
+/// - `$`：一个辅音后接 e 或 i
+/// - `%`：后缀 (er, e, es, ed, ing, ely)
+/// - `&`：咝音 (s, c, g, z, x, j, ch, sh)
+/// - `@`：影响后续u的辅音 (t, s, r, d, l, z, n, j, th, ch, sh)
+/// - `{abc}`：字符集合中的一个，`{abc}*` 表示零个或多个
+/// - `<name:x>`：命名捕获，`x` 为匹配单个字母的元素（字母、`^`、`.`、`+`、`{abc}`），
+///   规则输出中的 `$name` 引用捕获到的字母
+#[derive(Debug, Clone)]
+pub struct ContextPattern {
+    items: Vec<ContextItem>,
+    backward: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum ContextItem {
+    Literal(char),
+    Boundary,
+    Set(CharSet, Repeat),
+    Alternatives(&'static [&'static str]),
+    Capture(String, Box<ContextItem>),
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum CharSet {
+    Vowel,
+    Consonant,
+    Voiced,
+    Front,
+    Custom(Vec<char>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Repeat {
+    One,
+    ZeroOrMore,
+    OneOrMore,
+}
+
+const SUFFIXES: &[&str] = &["er", "e", "es", "ed", "ing", "ely"];
+const SIBILANTS: &[&str] = &["s", "c", "g", "z", "x", "j", "ch", "sh"];
+const U_INFLUENCERS: &[&str] = &["t", "s", "r", "d", "l", "z", "n", "j", "th", "ch", "sh"];
+
+impl ContextPattern {
+    /// 解析左上下文（从模式起点向左匹配）
+    pub fn left(context: &str) -> Self {
+        if context == "START" {
+            return Self { items: vec![ContextItem::Boundary], backward: true };
+        }
+        let mut items = Self::parse_items(context);
+        items.reverse();
+        Self { items, backward: true }
+    }
+
+    /// 解析右上下文（从模式终点向右匹配）
+    pub fn right(context: &str) -> Self {
+        if context == "END" {
+            return Self { items: vec![ContextItem::Boundary], backward: false };
+        }
+        Self { items: Self::parse_items(context), backward: false }
+    }
+
+    /// 按书写顺序解析上下文元素
+    pub(super) fn parse_items(context: &str) -> Vec<ContextItem> {
+        let chars: Vec<char> = context.chars().collect();
+        let mut items = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let item = match chars[i] {
+                '_' => ContextItem::Boundary,
+                '#' => ContextItem::Set(CharSet::Vowel, Repeat::OneOrMore),
+                ':' => ContextItem::Set(CharSet::Consonant, Repeat::ZeroOrMore),
+                '^' => ContextItem::Set(CharSet::Consonant, Repeat::One),
+                '*' => ContextItem::Set(CharSet::Consonant, Repeat::OneOrMore),
+                '.' => ContextItem::Set(CharSet::Voiced, Repeat::One),
+                '+' => ContextItem::Set(CharSet::Front, Repeat::One),
+                '$' => {
+                    items.push(ContextItem::Set(CharSet::Consonant, Repeat::One));
+                    ContextItem::Set(CharSet::Custom(vec!['e', 'i']), Repeat::One)
+                }
+                '%' => ContextItem::Alternatives(SUFFIXES),
+                '&' => ContextItem::Alternatives(SIBILANTS),
+                '@' => ContextItem::Alternatives(U_INFLUENCERS),
+                '{' => match chars[i + 1..].iter().position(|&c| c == '}') {
+                    Some(len) => {
+                        let set: Vec<char> = chars[i + 1..i + 1 + len]
+                            .iter()
+                            .map(|c| c.to_ascii_lowercase())
+                            .collect();
+                        i += len + 1;
+                        let repeat = if chars.get(i + 1) == Some(&'*') {
+                            i += 1;
+                            Repeat::ZeroOrMore
+                        } else {
+                            Repeat::One
+                        };
+                        ContextItem::Set(CharSet::Custom(set), repeat)
+                    }
+                    None => ContextItem::Literal('{'), // 未闭合的集合按字面处理
+                },
+                '<' => match Self::parse_capture(&chars[i + 1..]) {
+                    Some((capture, len)) => {
+                        i += len + 1;
+                        capture
+                    }
+                    None => ContextItem::Literal('<'), // 不合法的捕获按字面处理
+                },
+                c => ContextItem::Literal(c),
+            };
+            items.push(item);
+            i += 1;
+        }
+
+        items
+    }
+
+    /// 解析 `<` 之后的 `name:x>`，返回捕获元素和消耗的字符数（含 `>`）
+    fn parse_capture(chars: &[char]) -> Option<(ContextItem, usize)> {
+        let end = chars.iter().position(|&c| c == '>')?;
+        let colon = chars[..end].iter().position(|&c| c == ':')?;
+        let name: String = chars[..colon].iter().collect();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let inner: String = chars[colon + 1..end].iter().collect();
+        match Self::parse_items(&inner).as_slice() {
+            [item @ (ContextItem::Literal(_) | ContextItem::Set(_, Repeat::One))] => {
+                Some((ContextItem::Capture(name, Box::new(item.clone())), end + 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// 上下文中命名捕获的名称
+    pub(super) fn capture_names(context: &str) -> Vec<String> {
+        Self::parse_items(context)
+            .into_iter()
+            .filter_map(|item| match item {
+                ContextItem::Capture(name, _) => Some(name),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 检查上下文是否匹配，`pos` 为左上下文的模式起点或右上下文的模式终点
+    pub fn matches(&self, word: &[char], pos: usize) -> bool {
+        self.captures(word, pos).is_some()
+    }
+
+    /// 匹配上下文并返回命名捕获到的字母，不匹配时返回 `None`
+    pub fn captures(&self, word: &[char], pos: usize) -> Option<Vec<(String, char)>> {
+        let view = View { word, origin: pos, backward: self.backward };
+        let mut captures = Vec::new();
+        Self::match_from(&self.items, &view, 0, &mut captures).then_some(captures)
+    }
+
+    /// 捕获只在其后的元素全部匹配成功时记录，回溯失败的分支不会留下捕获
+    fn match_from(items: &[ContextItem], view: &View, at: usize, captures: &mut Vec<(String, char)>) -> bool {
+        let Some((first, rest)) = items.split_first() else {
+            return true;
+        };
+
+        match first {
+            ContextItem::Literal(c) => view.at(at) == Some(*c) && Self::match_from(rest, view, at + 1, captures),
+            ContextItem::Boundary => view.at(at).is_none() && Self::match_from(rest, view, at, captures),
+            ContextItem::Capture(name, item) => match view.at(at) {
+                Some(c) if item.matches_char(c) && Self::match_from(rest, view, at + 1, captures) => {
+                    captures.push((name.clone(), c));
+                    true
+                }
+                _ => false,
+            },
+            ContextItem::Set(set, repeat) => {
+                let mut run = 0;
+                while view.at(at + run).is_some_and(|c| set.contains(c)) {
+                    run += 1;
+                }
+                let (min, max) = match repeat {
+                    Repeat::One => (1, run.min(1)),
+                    Repeat::ZeroOrMore => (0, run),
+                    Repeat::OneOrMore => (1, run),
+                };
+                // 贪婪匹配并回溯
+                (min..=max).rev().any(|n| Self::match_from(rest, view, at + n, captures))
+            }
+            ContextItem::Alternatives(options) => options.iter().any(|option| {
+                let len = option.len();
+                let matched = if view.backward {
+                    option.chars().rev().enumerate().all(|(i, c)| view.at(at + i) == Some(c))
+                } else {
+                    option.chars().enumerate().all(|(i, c)| view.at(at + i) == Some(c))
+                };
+                matched && Self::match_from(rest, view, at + len, captures)
+            }),
+        }
+    }
+}
+
+impl ContextItem {
+    /// 匹配单个字母的元素是否匹配 `ch`
+    fn matches_char(&self, ch: char) -> bool {
+        match self {
+            ContextItem::Literal(c) => *c == ch,
+            ContextItem::Set(set, Repeat::One) => set.contains(ch),
+            _ => false,
+        }
+    }
+}
+
+impl CharSet {
+    fn contains(&self, ch: char) -> bool {
+        match self {
+            CharSet::Vowel => is_vowel(ch),
+            CharSet::Consonant => ch.is_ascii_alphabetic() && !is_vowel(ch),
+            CharSet::Voiced => matches!(ch, 'b' | 'd' | 'v' | 'g' | 'j' | 'l' | 'm' | 'n' | 'r' | 'w' | 'z'),
+            CharSet::Front => matches!(ch, 'e' | 'i' | 'y'),
+            CharSet::Custom(chars) => chars.contains(&ch),
+        }
+    }
+}
+
+fn is_vowel(ch: char) -> bool {
+    matches!(ch, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// 单词在某个方向上的视图
+struct View<'a> {
+    word: &'a [char],
+    origin: usize,
+    backward: bool,
+}
+
+impl View<'_> {
+    fn at(&self, offset: usize) -> Option<char> {
+        let index = if self.backward {
+            self.origin.checked_sub(offset + 1)?
+        } else {
+            self.origin + offset
+        };
+        self.word.get(index).map(|c| c.to_ascii_lowercase())
+    }
+}
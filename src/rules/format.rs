@@ -0,0 +1,511 @@
+//! 规则文件格式之间的转换
+
+use super::context::{CharSet, ContextItem, ContextPattern, Repeat};
+use super::{import, Rule, RuleCondition, SandhiRule};
+use crate::report::{IssueKind, LoadIssue, LoadReport};
+use crate::schema::{self, Capability, DataSchema};
+use crate::error::{invalid_data, G2PError, Result};
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// 支持的规则文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleFormat {
+    /// 本crate的竖线分隔格式：pattern|left_context|right_context|phonemes|priority|conditions|stress
+    Pipe,
+    /// NRL/Elovitz格式：left[pattern]right=PHONEMES
+    Nrl,
+    /// Festival LTS规则集
+    Festival,
+}
+
+/// 解析后的规则集合
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+    pub irregular_words: Vec<(String, Vec<String>)>, // 不规则词汇，保持文件顺序
+    pub sandhi: Vec<SandhiRule>,                     // 跨词连读规则，保持文件顺序
+    pub schema: DataSchema,                          // 竖线格式的版本和用到的特性，其他格式为默认值
+}
+
+impl RuleSet {
+    /// 按指定格式解析规则文本
+    pub fn parse(content: &str, format: RuleFormat) -> Result<Self> {
+        match format {
+            RuleFormat::Pipe => Ok(parse_pipe(content)?.0),
+            RuleFormat::Nrl => Ok(Self { rules: import::parse_nrl(content)?, ..Self::default() }),
+            RuleFormat::Festival => Ok(Self { rules: import::parse_festival(content)?, ..Self::default() }),
+        }
+    }
+
+    /// 按指定格式输出规则文本
+    pub fn render(&self, format: RuleFormat) -> Result<String> {
+        match format {
+            RuleFormat::Pipe => Ok(self.render_pipe()),
+            RuleFormat::Nrl => self.render_nrl(),
+            RuleFormat::Festival => self.render_festival(),
+        }
+    }
+
+    /// 按优先级从高到低排列的规则（不改变同优先级规则的相对顺序）
+    fn ordered_rules(&self) -> Vec<&Rule> {
+        let mut rules: Vec<&Rule> = self.rules.iter().collect();
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+        rules
+    }
+
+    fn render_pipe(&self) -> String {
+        let mut out = format!(
+            "#!g2p-rules {}\n# Format: pattern|left_context|right_context|phonemes|priority|conditions\n",
+            schema::RULES_VERSION
+        );
+
+        for (word, phonemes) in &self.irregular_words {
+            let _ = writeln!(out, "IRREGULAR|{}|{}", word, phonemes.join(" "));
+        }
+
+        for rule in self.ordered_rules() {
+            let _ = writeln!(out, "{}", rule.to_pipe_line());
+        }
+
+        for rule in &self.sandhi {
+            let _ = writeln!(out, "{}", rule.to_pipe_line());
+        }
+
+        out
+    }
+
+    /// 外部格式只能表示单词内的规则
+    fn check_no_sandhi(&self) -> Result<()> {
+        match self.sandhi.first() {
+            Some(rule) => Err(invalid_data!("Sandhi rule '{}' cannot be represented in this format", rule)),
+            None => Ok(()),
+        }
+    }
+
+    fn render_nrl(&self) -> Result<String> {
+        self.check_no_sandhi()?;
+        let mut out = String::new();
+
+        for (word, phonemes) in &self.irregular_words {
+            let _ = writeln!(out, " [{}] ={}", word.to_uppercase(), phonemes.join(" "));
+        }
+
+        for rule in self.ordered_rules() {
+            let (left, right) = external_contexts(rule)?;
+            let left = nrl_context(&left, true).map_err(|e| invalid_data!("Rule '{}': {}", rule.pattern, e))?;
+            let right = nrl_context(&right, false).map_err(|e| invalid_data!("Rule '{}': {}", rule.pattern, e))?;
+            let _ = writeln!(out, "{}[{}]{}={}", left, rule.pattern.to_uppercase(), right, rule.phonemes.join(" "));
+        }
+
+        Ok(out)
+    }
+
+    fn render_festival(&self) -> Result<String> {
+        self.check_no_sandhi()?;
+        let mut sets = FestivalSets::default();
+        let mut body = String::new();
+
+        for (word, phonemes) in &self.irregular_words {
+            let _ = writeln!(body, "  ( # [ {} ] # = {} )", spaced(word), festival_phones(phonemes));
+        }
+
+        for rule in self.ordered_rules() {
+            let (left, right) = external_contexts(rule)?;
+            let left = sets.context(&left, true).map_err(|e| invalid_data!("Rule '{}': {}", rule.pattern, e))?;
+            let right = sets.context(&right, false).map_err(|e| invalid_data!("Rule '{}': {}", rule.pattern, e))?;
+            let _ = writeln!(
+                body,
+                "  ( {}[ {} ] {}= {} )",
+                left.iter().map(|t| format!("{} ", t)).collect::<String>(),
+                spaced(&rule.pattern),
+                right.iter().map(|t| format!("{} ", t)).collect::<String>(),
+                festival_phones(&rule.phonemes),
+            );
+        }
+
+        let mut out = String::from("(lts.ruleset\n converted\n (");
+        for (name, chars) in &sets.definitions {
+            let _ = write!(out, "\n  ({} {})", name, spaced(chars));
+        }
+        out.push_str(")\n (\n");
+        out.push_str(&body);
+        out.push_str(" ))\n");
+
+        Ok(out)
+    }
+}
+
+impl Rule {
+    /// 竖线分隔格式的一行规则
+    pub fn to_pipe_line(&self) -> String {
+        let phonemes = if self.phonemes.is_empty() {
+            "SILENT".to_string()
+        } else {
+            self.phonemes.join(" ")
+        };
+        let conditions: Vec<&str> = self.conditions.iter().map(condition_name).collect();
+        let mut line = format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.pattern,
+            self.left_context.as_deref().unwrap_or(""),
+            self.right_context.as_deref().unwrap_or(""),
+            phonemes,
+            self.priority,
+            conditions.join(","),
+        );
+        if let Some(stress) = &self.stress {
+            let _ = write!(line, "|{}", stress);
+        }
+        line
+    }
+}
+
+/// 在格式之间转换规则文本
+pub fn convert(input: &str, input_format: RuleFormat, output_format: RuleFormat) -> Result<String> {
+    RuleSet::parse(input, input_format)?.render(output_format)
+}
+
+/// 解析竖线分隔格式的规则文本，文本中不能有 `#include` 指令
+pub(super) fn parse_pipe(content: &str) -> Result<(RuleSet, LoadReport)> {
+    parse_pipe_with(content, &mut |name| {
+        Err(invalid_data!("cannot include \"{}\" when parsing rules from text; load the rules file instead", name))
+    })
+}
+
+/// 解析竖线分隔格式的规则文本，`#include "file"` 指令交给 `include` 解析，结果插入指令所在的位置
+///
+/// 带版本头的文件按严格模式解析，无法识别的行、优先级和条件都会报错；没有版本头的旧文件跳过这些内容，
+/// 跳过的内容记入返回的报告。
+pub(super) fn parse_pipe_with(
+    content: &str,
+    include: &mut dyn FnMut(&str) -> Result<(RuleSet, LoadReport)>,
+) -> Result<(RuleSet, LoadReport)> {
+    let mut rule_set = RuleSet { schema: schema::RULES_FORMAT.read_header(content)?, ..RuleSet::default() };
+    let strict = rule_set.schema.is_versioned();
+    let mut report = LoadReport::default();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        let line_no = i + 1;
+        report.lines += 1;
+        let mut issue = |kind, message: String| report.issues.push(LoadIssue::new(line_no, kind, message, line));
+
+        if let Some(target) = line.strip_prefix("#include") {
+            let name = target
+                .trim()
+                .strip_prefix('"')
+                .and_then(|target| target.strip_suffix('"'))
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| G2PError::MalformedRule { line: line_no, message: format!("malformed include directive: {}", line) })?;
+            let (included, included_report) = include(name).map_err(|e| G2PError::MalformedRule {
+                line: line_no,
+                message: e.chain().to_string(),
+            })?;
+            report.merge(included_report, name);
+            rule_set.rules.extend(included.rules);
+            rule_set.irregular_words.extend(included.irregular_words);
+            rule_set.sandhi.extend(included.sandhi);
+            rule_set.schema.merge(&included.schema);
+            rule_set.schema.record(Capability::Includes);
+            continue;
+        }
+
+        // 跳过空行、注释行和已由 `read_header` 处理的指令
+        if line.is_empty() || line.starts_with('#') || line.starts_with('=') {
+            continue;
+        }
+
+        // 处理跨词连读规则，格式：SANDHI|pattern|replacement|priority
+        if let Some(fields) = line.strip_prefix("SANDHI|") {
+            match SandhiRule::parse(fields) {
+                Ok(rule) => {
+                    rule_set.sandhi.push(rule);
+                    rule_set.schema.record(Capability::Sandhi);
+                }
+                Err(e) if strict => return Err(G2PError::MalformedRule { line: line_no, message: e.to_string() }),
+                Err(e) => issue(IssueKind::MalformedLine, e.to_string()),
+            }
+            continue;
+        }
+
+        // 处理不规则词汇，格式：IRREGULAR|word|phoneme1 phoneme2 phoneme3
+        if line.starts_with("IRREGULAR|") {
+            let parts: Vec<&str> = line.split('|').collect();
+            if parts.len() >= 3 {
+                let word = parts[1].trim().to_lowercase();
+                let phonemes: Vec<String> = parts[2]
+                    .split_whitespace()
+                    .map(|s| s.to_string())
+                    .collect();
+
+                if !word.is_empty() && !phonemes.is_empty() {
+                    rule_set.irregular_words.push((word, phonemes));
+                    rule_set.schema.record(Capability::Irregular);
+                    continue;
+                }
+            }
+            if strict {
+                return Err(G2PError::MalformedRule { line: line_no, message: format!("malformed irregular word: {}", line) });
+            }
+            issue(IssueKind::MalformedLine, "malformed irregular word".to_string());
+            continue;
+        }
+
+        // 解析常规规则，格式：pattern|left_context|right_context|phonemes|priority|conditions|stress
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() < 4 || (strict && (parts.len() > 7 || parts[0].is_empty())) {
+            if strict {
+                return Err(G2PError::MalformedRule { line: line_no, message: format!("malformed rule: {}", line) });
+            }
+            issue(IssueKind::MalformedLine, "malformed rule".to_string());
+            continue; // 跳过格式不正确的行
+        }
+
+        let pattern = parts[0].to_string();
+        let left_context = if !parts[1].is_empty() {
+            Some(parts[1].to_string())
+        } else {
+            None
+        };
+        let right_context = if !parts[2].is_empty() {
+            Some(parts[2].to_string())
+        } else {
+            None
+        };
+        if parts[1].contains('{') || parts[2].contains('{') {
+            rule_set.schema.record(Capability::Classes);
+        }
+        let captures: Vec<String> = [parts[1], parts[2]].into_iter().flat_map(ContextPattern::capture_names).collect();
+        if !captures.is_empty() {
+            rule_set.schema.record(Capability::Captures);
+        }
+
+        // 解析音素列表（用空格分隔）
+        let phonemes: Vec<String> = if parts[3] == "SILENT" {
+            Vec::new() // 静音规则
+        } else {
+            parts[3]
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect()
+        };
+        let unknown = phonemes.iter().filter_map(|p| p.strip_prefix('$')).find(|placeholder| {
+            let name = placeholder.trim_end_matches(|c: char| c.is_ascii_digit());
+            !captures.iter().any(|capture| capture == name)
+        });
+        if let Some(placeholder) = unknown {
+            let message = format!("'${}' does not refer to a named capture", placeholder);
+            if strict {
+                return Err(G2PError::MalformedRule { line: line_no, message: message.to_string() });
+            }
+            issue(IssueKind::IgnoredField, message);
+        }
+
+        // 解析优先级
+        let priority = if parts.len() > 4 && !parts[4].is_empty() {
+            rule_set.schema.record(Capability::Weights);
+            match parts[4].parse::<usize>() {
+                Ok(priority) => priority,
+                Err(_) if strict => return Err(G2PError::MalformedRule { line: line_no, message: format!("invalid priority '{}'", parts[4]) }),
+                Err(_) => {
+                    issue(IssueKind::IgnoredField, format!("invalid priority '{}'", parts[4]));
+                    pattern.len()
+                }
+            }
+        } else {
+            pattern.len()
+        };
+
+        // 解析条件
+        let conditions = if parts.len() > 5 && !parts[5].is_empty() {
+            rule_set.schema.record(Capability::Conditions);
+            let (conditions, unknown) = parse_conditions(parts[5]);
+            for condition in unknown {
+                let message = format!("unknown condition '{}'", condition);
+                if strict {
+                    return Err(G2PError::MalformedRule { line: line_no, message: message.to_string() });
+                }
+                issue(IssueKind::IgnoredField, message);
+            }
+            conditions
+        } else {
+            Vec::new()
+        };
+
+        // 解析重音模板，每个元音一位数字
+        let stress = match parts.get(6).map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            Some(template) if template.chars().all(|c| matches!(c, '0'..='2')) => {
+                rule_set.schema.record(Capability::Stress);
+                Some(template.to_string())
+            }
+            Some(template) if strict => {
+                return Err(G2PError::MalformedRule { line: line_no, message: format!("invalid stress template '{}'", template) })
+            }
+            Some(template) => {
+                issue(IssueKind::IgnoredField, format!("invalid stress template '{}'", template));
+                None
+            }
+            None => None,
+        };
+
+        rule_set.rules.push(Rule {
+            pattern,
+            left_context,
+            right_context,
+            phonemes,
+            priority,
+            conditions,
+            stress,
+        });
+    }
+
+    report.loaded = rule_set.rules.len() + rule_set.irregular_words.len() + rule_set.sandhi.len();
+    Ok((rule_set, report))
+}
+
+/// 解析条件字符串，返回识别的条件和无法识别的条件名
+fn parse_conditions(conditions_str: &str) -> (Vec<RuleCondition>, Vec<&str>) {
+    let mut conditions = Vec::new();
+    let mut unknown = Vec::new();
+
+    for condition in conditions_str.split(',') {
+        let condition = condition.trim();
+        match condition {
+            "START" => conditions.push(RuleCondition::WordStart),
+            "END" => conditions.push(RuleCondition::WordEnd),
+            "VOWEL_BEFORE" => conditions.push(RuleCondition::BeforeVowel),
+            "VOWEL_AFTER" => conditions.push(RuleCondition::AfterVowel),
+            "word_start" => conditions.push(RuleCondition::WordStart),
+            "word_end" => conditions.push(RuleCondition::WordEnd),
+            "before_vowel" => conditions.push(RuleCondition::BeforeVowel),
+            "after_vowel" => conditions.push(RuleCondition::AfterVowel),
+            "stressed" => conditions.push(RuleCondition::Stressed),
+            "unstressed" => conditions.push(RuleCondition::Unstressed),
+            _ => unknown.push(condition),
+        }
+    }
+
+    (conditions, unknown)
+}
+
+pub(super) fn condition_name(condition: &RuleCondition) -> &'static str {
+    match condition {
+        RuleCondition::WordStart => "START",
+        RuleCondition::WordEnd => "END",
+        RuleCondition::BeforeVowel => "VOWEL_BEFORE",
+        RuleCondition::AfterVowel => "VOWEL_AFTER",
+        RuleCondition::Stressed => "stressed",
+        RuleCondition::Unstressed => "unstressed",
+    }
+}
+
+/// 外部格式没有条件字段，将条件折算进左右上下文
+fn external_contexts(rule: &Rule) -> Result<(String, String)> {
+    if rule.stress.is_some() {
+        return Err(invalid_data!("Rule '{}': stress templates cannot be represented in this format", rule.pattern));
+    }
+    let mut left = rule.left_context.clone().unwrap_or_default();
+    let mut right = rule.right_context.clone().unwrap_or_default();
+    let single_char = rule.pattern.chars().count() == 1;
+
+    for condition in &rule.conditions {
+        match condition {
+            RuleCondition::WordStart if left != "START" => left.insert(0, '_'),
+            RuleCondition::WordEnd if right.is_empty() => right.push('_'),
+            RuleCondition::AfterVowel if left != "START" => left.push_str("{aeiouy}"),
+            RuleCondition::BeforeVowel if single_char && right != "END" => right.insert_str(0, "{aeiouy}"),
+            RuleCondition::WordStart | RuleCondition::Stressed | RuleCondition::Unstressed => {} // 已满足或引擎未使用
+            other => {
+                return Err(invalid_data!(
+                    "Rule '{}': condition {:?} cannot be represented in this format",
+                    rule.pattern,
+                    other
+                ))
+            }
+        }
+    }
+
+    Ok((left, right))
+}
+
+/// 转换为NRL上下文：除 `{...}` 集合外，本crate的上下文语法与NRL一致
+fn nrl_context(context: &str, left: bool) -> Result<String> {
+    if (left && context == "START") || (!left && context == "END") {
+        return Ok(" ".to_string());
+    }
+    if context.contains('{') {
+        return Err(invalid_data!("character sets cannot be represented in NRL rules"));
+    }
+    if !ContextPattern::capture_names(context).is_empty() {
+        return Err(invalid_data!("named captures cannot be represented in NRL rules"));
+    }
+    Ok(context.replace('_', " ").to_uppercase())
+}
+
+/// Festival输出时需要定义的字符集合
+#[derive(Default)]
+struct FestivalSets {
+    definitions: Vec<(String, String)>,
+}
+
+impl FestivalSets {
+    fn name_for(&mut self, name: &str, chars: &str) -> String {
+        if let Some((existing, _)) = self.definitions.iter().find(|(_, c)| c == chars) {
+            return existing.clone();
+        }
+        let name = if self.definitions.iter().any(|(n, _)| n == name) {
+            format!("{}{}", name, self.definitions.len())
+        } else {
+            name.to_string()
+        };
+        self.definitions.push((name.clone(), chars.to_string()));
+        name
+    }
+
+    fn context(&mut self, context: &str, left: bool) -> Result<Vec<String>> {
+        if (left && context == "START") || (!left && context == "END") {
+            return Ok(vec!["#".to_string()]);
+        }
+
+        let mut tokens = Vec::new();
+        for item in ContextPattern::parse_items(context) {
+            match item {
+                ContextItem::Literal(c) => tokens.push(c.to_string()),
+                ContextItem::Boundary => tokens.push("#".to_string()),
+                ContextItem::Set(set, repeat) => {
+                    let name = match &set {
+                        CharSet::Vowel => self.name_for("V", "aeiou"),
+                        CharSet::Consonant => self.name_for("C", "bcdfghjklmnpqrstvwxyz"),
+                        CharSet::Voiced => self.name_for("VOICED", "bdvgjlmnrwz"),
+                        CharSet::Front => self.name_for("FRONT", "eiy"),
+                        CharSet::Custom(chars) => self.name_for("SET", &chars.iter().collect::<String>()),
+                    };
+                    match repeat {
+                        Repeat::One => tokens.push(name),
+                        Repeat::ZeroOrMore => tokens.extend([name, "*".to_string()]),
+                        Repeat::OneOrMore => tokens.extend([name.clone(), name, "*".to_string()]),
+                    }
+                }
+                ContextItem::Alternatives(_) => {
+                    return Err(invalid_data!("multi-letter alternatives (%, &, @) cannot be represented in Festival rules"))
+                }
+                ContextItem::Capture(..) => return Err(invalid_data!("named captures cannot be represented in Festival rules")),
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+fn spaced(text: &str) -> String {
+    text.chars().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+fn festival_phones(phonemes: &[String]) -> String {
+    if phonemes.is_empty() {
+        "_epsilon_".to_string()
+    } else {
+        phonemes.iter().map(|p| p.to_lowercase()).collect::<Vec<_>>().join(" ")
+    }
+}
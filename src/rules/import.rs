@@ -0,0 +1,310 @@
+//! 经典字母到音素(LTS)规则格式的导入
+//!
+//! - NRL/Elovitz规则：每行 `left[pattern]right=PHONEMES`，左右上下文中的空格表示词边界
+//! - Festival LTS规则：`(lts.ruleset name (sets...) (rules...))` 形式的scheme文件
+//!
+//! 两种格式都按"先出现先匹配"的语义，导入时按出现顺序分配递减的优先级。
+
+use super::Rule;
+use crate::error::{invalid_data, G2PError, Result};
+use std::collections::HashMap;
+
+/// NRL规则使用的音素符号（另接受ARPAbet的JH），切分时取最长匹配
+const NRL_PHONEMES: &[&str] = &[
+    "IY", "IH", "EY", "EH", "AE", "AA", "AO", "OW", "UH", "UW", "ER", "AX", "AH", "AY", "AW", "OY",
+    "TH", "DH", "SH", "ZH", "HH", "NG", "CH", "JH", "WH",
+    "P", "B", "T", "D", "K", "G", "F", "V", "S", "Z", "M", "N", "L", "W", "Y", "R", "J",
+];
+
+/// 解析NRL/Elovitz格式的规则文本
+pub fn parse_nrl(content: &str) -> Result<Vec<Rule>> {
+    let mut parsed = Vec::new();
+
+    for (line_no, raw_line) in content.lines().enumerate() {
+        let line = raw_line.trim_end_matches(['\r', '\n']);
+        if line.trim().is_empty() || line.trim_start().starts_with("//") || line.trim_start().starts_with(';') {
+            continue;
+        }
+
+        let (lhs, phonemes) = line
+            .rsplit_once('=')
+            .ok_or_else(|| G2PError::MalformedRule { line: line_no + 1, message: format!("missing '=' in NRL rule '{}'", line) })?;
+        let open = lhs
+            .find('[')
+            .ok_or_else(|| G2PError::MalformedRule { line: line_no + 1, message: format!("missing '[' in NRL rule '{}'", line) })?;
+        let close = lhs
+            .rfind(']')
+            .filter(|&close| close > open)
+            .ok_or_else(|| G2PError::MalformedRule { line: line_no + 1, message: format!("missing ']' in NRL rule '{}'", line) })?;
+
+        let pattern = lhs[open + 1..close].to_lowercase();
+        if pattern.is_empty() {
+            return Err(G2PError::MalformedRule { line: line_no + 1, message: format!("empty pattern in NRL rule '{}'", line) });
+        }
+
+        let phonemes = split_nrl_phonemes(phonemes.trim())
+            .map_err(|e| G2PError::MalformedRule { line: line_no + 1, message: e.to_string() })?;
+
+        parsed.push(Rule {
+            pattern,
+            left_context: nrl_context(&lhs[..open]),
+            right_context: nrl_context(&lhs[close + 1..]),
+            phonemes,
+            priority: 0,
+            conditions: Vec::new(),
+            stress: None,
+        });
+    }
+
+    Ok(assign_order_priorities(parsed))
+}
+
+/// 将NRL上下文转换为规则上下文（空格即词边界）
+fn nrl_context(context: &str) -> Option<String> {
+    if context.is_empty() {
+        None
+    } else {
+        Some(context.replace(' ', "_").to_lowercase())
+    }
+}
+
+/// 将NRL音素串切分为ARPAbet音素，兼容空格分隔和连写两种形式，音素后可带重音数字
+fn split_nrl_phonemes(phonemes: &str) -> Result<Vec<String>> {
+    let mut result = Vec::new();
+
+    for chunk in phonemes.split_whitespace() {
+        let upper = chunk.to_uppercase();
+        let mut rest = upper.as_str();
+        while !rest.is_empty() {
+            let symbol = NRL_PHONEMES
+                .iter()
+                .filter(|symbol| rest.starts_with(*symbol))
+                .max_by_key(|symbol| symbol.len())
+                .ok_or_else(|| invalid_data!("unknown NRL phoneme in '{}'", chunk))?;
+            rest = &rest[symbol.len()..];
+
+            let stress = rest.chars().next().filter(|c| c.is_ascii_digit());
+            if stress.is_some() {
+                rest = &rest[1..];
+            }
+            result.push(nrl_to_arpabet(symbol, stress));
+        }
+    }
+
+    Ok(result)
+}
+
+fn nrl_to_arpabet(symbol: &str, stress: Option<char>) -> String {
+    let (base, default_stress) = match symbol {
+        "AX" => ("AH", Some('0')),
+        "J" => ("JH", None),
+        "WH" => ("W", None),
+        other => (other, None),
+    };
+    match stress.or(default_stress) {
+        Some(digit) => format!("{}{}", base, digit),
+        None => base.to_string(),
+    }
+}
+
+/// 解析Festival LTS规则集
+pub fn parse_festival(content: &str) -> Result<Vec<Rule>> {
+    let exprs = SExpr::parse_all(content)?;
+    let ruleset = exprs
+        .iter()
+        .find_map(|expr| match expr {
+            SExpr::List(items) if items.first().and_then(SExpr::atom) == Some("lts.ruleset") => Some(items),
+            _ => None,
+        })
+        .ok_or_else(|| invalid_data!("No lts.ruleset found in Festival rule file"))?;
+
+    if ruleset.len() < 4 {
+        return Err(invalid_data!("lts.ruleset expects a name, a set list and a rule list"));
+    }
+
+    let mut sets: HashMap<&str, String> = HashMap::new();
+    for set in ruleset[2].list().unwrap_or(&[]) {
+        let members = set.list().ok_or_else(|| invalid_data!("Festival set definition must be a list"))?;
+        let (name, chars) = members
+            .split_first()
+            .ok_or_else(|| invalid_data!("Empty Festival set definition"))?;
+        let name = name.atom().ok_or_else(|| invalid_data!("Festival set name must be an atom"))?;
+        let chars: String = chars.iter().filter_map(SExpr::atom).collect::<Vec<_>>().concat();
+        sets.insert(name, chars.to_lowercase());
+    }
+
+    let rules = ruleset[3].list().ok_or_else(|| invalid_data!("Festival rules must be a list"))?;
+    let mut parsed = Vec::new();
+    for (index, rule) in rules.iter().enumerate() {
+        let tokens: Vec<&str> = rule
+            .list()
+            .ok_or_else(|| invalid_data!("Festival rule {} is not a list", index + 1))?
+            .iter()
+            .map(|t| t.atom().ok_or_else(|| invalid_data!("Festival rule {} has nested lists", index + 1)))
+            .collect::<Result<_>>()?;
+        parsed.push(festival_rule(&tokens, &sets).map_err(|e| invalid_data!("Festival rule {}: {}", index + 1, e))?);
+    }
+
+    Ok(assign_order_priorities(parsed))
+}
+
+/// 转换单条Festival规则：`( LC [ A ] RC = PHONES )`
+fn festival_rule(tokens: &[&str], sets: &HashMap<&str, String>) -> Result<Rule> {
+    let open = tokens.iter().position(|&t| t == "[").ok_or_else(|| invalid_data!("missing '['"))?;
+    let close = tokens.iter().position(|&t| t == "]").filter(|&c| c > open).ok_or_else(|| invalid_data!("missing ']'"))?;
+    let equals = tokens.iter().position(|&t| t == "=").filter(|&e| e > close).ok_or_else(|| invalid_data!("missing '='"))?;
+
+    let pattern: String = tokens[open + 1..close].concat().to_lowercase();
+    if pattern.is_empty() {
+        return Err(invalid_data!("empty pattern"));
+    }
+
+    let phonemes = tokens[equals + 1..]
+        .iter()
+        .filter(|&&t| t != "_epsilon_")
+        .flat_map(|t| t.split('-'))
+        .map(|t| match t.to_uppercase().as_str() {
+            "AX" => "AH0".to_string(),
+            upper => upper.to_string(),
+        })
+        .collect();
+
+    Ok(Rule {
+        pattern,
+        left_context: festival_context(&tokens[..open], sets),
+        right_context: festival_context(&tokens[close + 1..equals], sets),
+        phonemes,
+        priority: 0,
+        conditions: Vec::new(),
+        stress: None,
+    })
+}
+
+/// 将Festival上下文转换为规则上下文：`#` 为词边界，集合名展开为 `{...}`，`*` 表示零个或多个
+fn festival_context(tokens: &[&str], sets: &HashMap<&str, String>) -> Option<String> {
+    let mut context = String::new();
+    for &token in tokens {
+        match token {
+            "#" => context.push('_'),
+            "*" => match context.pop() {
+                Some('}') => context.push_str("}*"),
+                Some(literal) => {
+                    context.push('{');
+                    context.push(literal);
+                    context.push_str("}*");
+                }
+                None => {}
+            },
+            _ => match sets.get(token) {
+                Some(chars) => {
+                    context.push('{');
+                    context.push_str(chars);
+                    context.push('}');
+                }
+                None => context.push_str(&token.to_lowercase()),
+            },
+        }
+    }
+
+    if context.is_empty() {
+        None
+    } else {
+        Some(context)
+    }
+}
+
+/// 按出现顺序分配递减的优先级，保持"先出现先匹配"的语义
+fn assign_order_priorities(mut rules: Vec<Rule>) -> Vec<Rule> {
+    let count = rules.len();
+    for (index, rule) in rules.iter_mut().enumerate() {
+        rule.priority = count - index;
+    }
+    rules
+}
+
+/// 最小的S表达式解析器，仅用于Festival规则文件
+#[derive(Debug)]
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    fn parse_all(content: &str) -> Result<Vec<SExpr>> {
+        let tokens = Self::tokenize(content);
+        let mut pos = 0;
+        let mut exprs = Vec::new();
+        while pos < tokens.len() {
+            exprs.push(Self::parse(&tokens, &mut pos)?);
+        }
+        Ok(exprs)
+    }
+
+    fn tokenize(content: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        for line in content.lines() {
+            let line = match line.find(';') {
+                Some(pos) => &line[..pos],
+                None => line,
+            };
+            let mut current = String::new();
+            for ch in line.chars() {
+                match ch {
+                    '(' | ')' | '[' | ']' => {
+                        if !current.is_empty() {
+                            tokens.push(std::mem::take(&mut current));
+                        }
+                        tokens.push(ch.to_string());
+                    }
+                    '"' => {}
+                    c if c.is_whitespace() => {
+                        if !current.is_empty() {
+                            tokens.push(std::mem::take(&mut current));
+                        }
+                    }
+                    c => current.push(c),
+                }
+            }
+            if !current.is_empty() {
+                tokens.push(current);
+            }
+        }
+        tokens
+    }
+
+    fn parse(tokens: &[String], pos: &mut usize) -> Result<SExpr> {
+        let token = tokens.get(*pos).ok_or_else(|| invalid_data!("Unexpected end of Festival rule file"))?;
+        *pos += 1;
+        match token.as_str() {
+            "(" => {
+                let mut items = Vec::new();
+                loop {
+                    match tokens.get(*pos).map(String::as_str) {
+                        Some(")") => {
+                            *pos += 1;
+                            return Ok(SExpr::List(items));
+                        }
+                        Some(_) => items.push(Self::parse(tokens, pos)?),
+                        None => return Err(invalid_data!("Unbalanced parentheses in Festival rule file")),
+                    }
+                }
+            }
+            ")" => Err(invalid_data!("Unexpected ')' in Festival rule file")),
+            atom => Ok(SExpr::Atom(atom.to_string())),
+        }
+    }
+
+    fn atom(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(atom) => Some(atom),
+            SExpr::List(_) => None,
+        }
+    }
+
+    fn list(&self) -> Option<&[SExpr]> {
+        match self {
+            SExpr::List(items) => Some(items),
+            SExpr::Atom(_) => None,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! 跨词的连读音变（sandhi）规则
+//!
+//! 规则文件中的 `SANDHI|pattern|replacement|priority` 行作用于整段文本的音素序列：
+//!
+//! - `_`：词边界，引号边界不算词边界，规则不会跨越引语
+//! - `[vowel]`、`[consonant]`：任意元音、任意辅音
+//! - 不带重音数字的音素忽略重音匹配，带数字时重音也须相同
+//! - 替换中的 `$n` 原样输出模式中第n个位置匹配到的音素（从1开始）
+//!
+//! 例如 `SANDHI|T _ Y|CH _|` 把 "got you" 读作 `G AA1 CH | UW1`。
+
+use crate::phoneme::{self, Phoneme};
+use crate::rewrite;
+use crate::error::{invalid_data, Result};
+use std::fmt;
+
+/// 一条连读规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandhiRule {
+    pub pattern: Vec<String>,
+    pub replacement: Vec<String>,
+    pub priority: usize, // 同一位置有多条规则匹配时取优先级最高的，默认为模式长度
+}
+
+/// 非卷舌口音的连接R和插入R：词尾的R移到后一个元音开头的词首（far away），以ER结尾的词后补上R（better off），
+/// 词尾的非高元音后插入R（law and order），配合删除词尾R的改写规则使用
+const LINKING_R: &[&str] = &["R _ [vowel]|_ R $3|", "ER _ [vowel]|$1 _ R $3|", "AA _ [vowel]|$1 _ R $3|", "AO _ [vowel]|$1 _ R $3|", "AH _ [vowel]|$1 _ R $3|"];
+
+impl SandhiRule {
+    /// 非卷舌口音的连接R和插入R规则
+    pub fn linking_r() -> Vec<Self> {
+        LINKING_R.iter().map(|line| Self::parse(line).expect("built-in sandhi rule")).collect()
+    }
+
+    /// 解析 `SANDHI|` 之后的部分
+    pub(super) fn parse(fields: &str) -> Result<Self> {
+        let parts: Vec<&str> = fields.split('|').collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(invalid_data!("expected SANDHI|pattern|replacement|priority"));
+        }
+        let pattern: Vec<String> = parts[0].split_whitespace().map(str::to_string).collect();
+        let replacement: Vec<String> = parts[1].split_whitespace().map(str::to_string).collect();
+        if pattern.is_empty() {
+            return Err(invalid_data!("empty sandhi pattern"));
+        }
+        for item in &pattern {
+            rewrite::validate_item(item)?;
+        }
+        for item in &replacement {
+            if let Some(index) = item.strip_prefix('$') {
+                match index.parse::<usize>() {
+                    Ok(index) if (1..=pattern.len()).contains(&index) => {}
+                    _ => return Err(invalid_data!("'{}' does not refer to a pattern position", item)),
+                }
+            } else if item != "_" {
+                phoneme::parse_arpabet(item)?;
+            }
+        }
+        let priority = match parts.get(2).map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            Some(priority) => priority.parse().map_err(|_| invalid_data!("invalid priority '{}'", priority))?,
+            None => pattern.len(),
+        };
+        Ok(Self { pattern, replacement, priority })
+    }
+
+    /// 竖线分隔格式的一行规则
+    pub fn to_pipe_line(&self) -> String {
+        format!("SANDHI|{}|{}|{}", self.pattern.join(" "), self.replacement.join(" "), self.priority)
+    }
+
+    /// 模式是否从 `start` 开始匹配
+    fn matches(&self, phonemes: &[Phoneme], start: usize) -> bool {
+        self.pattern.len() <= phonemes.len() - start
+            && self.pattern.iter().zip(&phonemes[start..]).all(|(item, phoneme)| rewrite::item_matches(item, phoneme))
+    }
+}
+
+impl fmt::Display for SandhiRule {
+    /// 形如 `T _ Y -> CH _ (priority 3)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {} (priority {})", self.pattern.join(" "), self.replacement.join(" "), self.priority)
+    }
+}
+
+/// 从左到右扫描音素序列，每个位置应用优先级最高的规则；替换结果不再参与匹配
+///
+/// `rules` 须已按优先级从高到低排列。
+pub(super) fn apply(rules: &[SandhiRule], phonemes: Vec<Phoneme>) -> Vec<Phoneme> {
+    if rules.is_empty() {
+        return phonemes;
+    }
+    let mut out = Vec::with_capacity(phonemes.len());
+    let mut pos = 0;
+    while pos < phonemes.len() {
+        match rules.iter().find(|rule| rule.matches(&phonemes, pos)) {
+            Some(rule) => {
+                let end = pos + rule.pattern.len();
+                rewrite::replace(&rule.replacement, &phonemes[pos..end], &mut out);
+                pos = end;
+            }
+            None => {
+                out.push(phonemes[pos].clone());
+                pos += 1;
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,158 @@
+//! 规则读音的重音指派
+//!
+//! 单元音字母的规则不带重音，元音字母组合的规则都带主重音，规则得到的读音往往没有主重音或有多个主重音。
+//! 规则转换之后按单词的拼写和音节数确定唯一的主重音：
+//!
+//! 1. 重读后缀（-ee、-eer、-ese、-ette、-esque、-ique）本身重读：`employee`、`cassette`
+//! 2. -tion、-ity、-ic、-ical、-ian、-ious、-logy 等后缀前的音节重读：`nation`、`ability`、`biology`
+//! 3. -ness、-less、-ment、-ful、-ly、-ing 等后缀不影响重音，去掉后按剩余部分判断：`carelessness`
+//! 4. 否则一到两个音节的单词重读第一个音节；三个及以上音节时，倒数第二个音节为长元音（规则标了主重音）则重读它，
+//!    否则重读倒数第三个音节
+//!
+//! 其他的主重音降为次重音。规则的重音模板或 `$name1` 捕获给出了主重音时不做指派。
+//!
+//! 这些规则只看拼写和音节数，例外很多：`tomato`、`banana` 这样倒数第二个音节重读的单词会读成第一个音节重读。
+
+use crate::phoneme::{Phoneme, StressLevel};
+
+/// 后缀对重音位置的影响
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SuffixClass {
+    Stressed,    // 后缀本身重读
+    PreStressed, // 后缀前的音节重读
+    Neutral,     // 不影响重音
+}
+
+use SuffixClass::*;
+
+/// 影响重音的后缀，有多个后缀匹配时取最长的
+const SUFFIXES: &[(&str, SuffixClass)] = &[
+    ("ee", Stressed),
+    ("ees", Stressed),
+    ("eer", Stressed),
+    ("eers", Stressed),
+    ("ese", Stressed),
+    ("ette", Stressed),
+    ("ettes", Stressed),
+    ("esque", Stressed),
+    ("ique", Stressed),
+    ("tion", PreStressed),
+    ("tions", PreStressed),
+    ("sion", PreStressed),
+    ("sions", PreStressed),
+    ("cian", PreStressed),
+    ("ian", PreStressed),
+    ("ians", PreStressed),
+    ("ity", PreStressed),
+    ("ities", PreStressed),
+    ("ic", PreStressed),
+    ("ics", PreStressed),
+    ("ical", PreStressed),
+    ("ically", PreStressed),
+    ("ial", PreStressed),
+    ("ially", PreStressed),
+    ("ious", PreStressed),
+    ("eous", PreStressed),
+    ("uous", PreStressed),
+    ("logy", PreStressed),
+    ("logist", PreStressed),
+    ("graphy", PreStressed),
+    ("nomy", PreStressed),
+    ("metry", PreStressed),
+    ("pathy", PreStressed),
+    ("cracy", PreStressed),
+    ("ness", Neutral),
+    ("less", Neutral),
+    ("ment", Neutral),
+    ("ments", Neutral),
+    ("ful", Neutral),
+    ("ly", Neutral),
+    ("ing", Neutral),
+    ("ings", Neutral),
+    ("ed", Neutral),
+    ("er", Neutral),
+    ("ers", Neutral),
+    ("est", Neutral),
+    ("ism", Neutral),
+    ("ist", Neutral),
+    ("ists", Neutral),
+    ("hood", Neutral),
+    ("ship", Neutral),
+    ("dom", Neutral),
+];
+
+/// 为规则读音指派主重音，`origins[i]` 为 `phonemes[i]` 所在规则匹配的起始字符位置
+pub(super) fn assign_stress(letters: &[char], phonemes: &mut [Phoneme], origins: &[usize]) {
+    let vowels: Vec<(usize, usize)> = phonemes
+        .iter()
+        .zip(origins)
+        .enumerate()
+        .filter(|(_, (phoneme, _))| phoneme.is_vowel())
+        .map(|(idx, (_, &origin))| (idx, origin))
+        .collect();
+
+    let Some(target) = stressed_vowel(letters, &vowels, phonemes) else {
+        return;
+    };
+    for (idx, phoneme) in phonemes.iter_mut().enumerate() {
+        if idx == target {
+            phoneme.stress = StressLevel::Primary;
+        } else if phoneme.stress == StressLevel::Primary {
+            phoneme.stress = StressLevel::Secondary;
+        }
+    }
+}
+
+/// 应重读的元音在 `phonemes` 中的位置；`vowels` 为各元音的位置和起始字符位置
+fn stressed_vowel(letters: &[char], vowels: &[(usize, usize)], phonemes: &[Phoneme]) -> Option<usize> {
+    // 逐个去掉不影响重音的后缀，词干中至少留一个元音字母
+    let mut end = letters.len();
+    while let Some((suffix, class)) = longest_suffix(&letters[..end]) {
+        let start = end - suffix.len();
+        let before: Vec<(usize, usize)> = vowels.iter().copied().filter(|&(_, origin)| origin < start).collect();
+        if !letters[..start].iter().any(|c| matches!(c.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u' | 'y')) {
+            break;
+        }
+        match class {
+            Stressed => {
+                if let Some(&(idx, _)) = vowels.iter().find(|&&(_, origin)| origin >= start && origin < end) {
+                    return Some(idx);
+                }
+            }
+            PreStressed => {
+                if let Some(&(idx, _)) = before.last() {
+                    return Some(idx);
+                }
+            }
+            Neutral => {
+                if before.is_empty() {
+                    break;
+                }
+                end = start;
+                continue;
+            }
+        }
+        break;
+    }
+
+    let stem: Vec<usize> = vowels.iter().filter(|&&(_, origin)| origin < end).map(|&(idx, _)| idx).collect();
+    let stem = if stem.is_empty() { vowels.iter().map(|&(idx, _)| idx).collect() } else { stem };
+    match stem.len() {
+        0 => None,
+        1 | 2 => Some(stem[0]),
+        n if phonemes[stem[n - 2]].stress == StressLevel::Primary => Some(stem[n - 2]),
+        n => Some(stem[n - 3]),
+    }
+}
+
+/// `letters` 末尾最长的已知后缀
+fn longest_suffix(letters: &[char]) -> Option<(&'static str, SuffixClass)> {
+    SUFFIXES
+        .iter()
+        .copied()
+        .filter(|(suffix, _)| {
+            letters.len() > suffix.len()
+                && letters[letters.len() - suffix.len()..].iter().map(char::to_ascii_lowercase).eq(suffix.chars())
+        })
+        .max_by_key(|(suffix, _)| suffix.len())
+}
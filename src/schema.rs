@@ -0,0 +1,207 @@
+//! 数据文件的版本头和特性声明
+//!
+//! 规则文件以 `#!g2p-rules 1` 开头，词典文件以 `;;;!g2p-lexicon 1` 开头，之后可用
+//! `requires` 行声明文件需要的特性：
+//!
+//! ```text
+//! #!g2p-rules 1
+//! #!requires classes, weights
+//! ```
+//!
+//! 带版本头的文件按严格模式解析：版本更高、声明了不支持的特性、含有无法识别的行或指令时拒绝加载，
+//! 而不是跳过。没有版本头的旧文件保持原来的宽松解析。
+
+use crate::error::{invalid_data, Result};
+use std::fmt;
+
+/// 支持的规则文件版本
+pub const RULES_VERSION: u32 = 1;
+
+/// 支持的音素改写规则文件版本
+pub const REWRITE_VERSION: u32 = 1;
+
+/// 支持的词典文件版本
+pub const LEXICON_VERSION: u32 = 1;
+
+/// 数据文件可能用到的特性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Capability {
+    Classes,    // 上下文中的字符类 `{aeiou}`
+    Weights,    // 显式优先级列
+    Conditions, // 条件列
+    Irregular,  // IRREGULAR 行
+    Includes,   // `#include` 指令
+    Captures,   // 上下文中的命名捕获 `<v:{aeiou}>` 和输出中的 `$v`
+    Sandhi,     // SANDHI 行
+    Variants,   // 词典中的多读音 `WORD(2)`
+    Stress,     // 重音模板列
+}
+
+impl Capability {
+    const ALL: [Capability; 9] = [
+        Capability::Classes,
+        Capability::Weights,
+        Capability::Conditions,
+        Capability::Irregular,
+        Capability::Includes,
+        Capability::Captures,
+        Capability::Sandhi,
+        Capability::Variants,
+        Capability::Stress,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Classes => "classes",
+            Capability::Weights => "weights",
+            Capability::Conditions => "conditions",
+            Capability::Irregular => "irregular",
+            Capability::Includes => "includes",
+            Capability::Captures => "captures",
+            Capability::Sandhi => "sandhi",
+            Capability::Variants => "variants",
+            Capability::Stress => "stress",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|capability| capability.as_str() == name)
+    }
+
+    /// 特性集合的位掩码，供二进制格式保存
+    pub(crate) fn to_bits(capabilities: &[Capability]) -> u16 {
+        capabilities.iter().fold(0, |bits, &capability| bits | 1 << capability as u16)
+    }
+
+    /// 由位掩码还原特性，按固定顺序排列；含有未知的位时报错
+    pub(crate) fn from_bits(bits: u16) -> Result<Vec<Capability>> {
+        let capabilities: Vec<Capability> = Self::ALL.into_iter().filter(|&capability| bits & 1 << capability as u16 != 0).collect();
+        if Self::to_bits(&capabilities) != bits {
+            return Err(invalid_data!("Unknown capability bits {:#06x}", bits));
+        }
+        Ok(capabilities)
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 数据文件的版本及其声明和实际用到的特性
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataSchema {
+    pub version: Option<u32>,        // 没有版本头的旧文件为 None
+    pub declared: Vec<Capability>,   // `requires` 声明的特性
+    pub used: Vec<Capability>,       // 加载时检测到的特性，按固定顺序排列
+}
+
+impl DataSchema {
+    /// 文件是否带版本头（按严格模式解析）
+    pub fn is_versioned(&self) -> bool {
+        self.version.is_some()
+    }
+
+    pub fn uses(&self, capability: Capability) -> bool {
+        self.used.contains(&capability)
+    }
+
+    pub(crate) fn record(&mut self, capability: Capability) {
+        if let Err(index) = self.used.binary_search(&capability) {
+            self.used.insert(index, capability);
+        }
+    }
+
+    /// 合并被包含文件用到的特性
+    pub(crate) fn merge(&mut self, other: &DataSchema) {
+        for &capability in &other.used {
+            self.record(capability);
+        }
+    }
+}
+
+/// 文件格式说明：指令前缀、格式名、支持的版本和特性
+pub(crate) struct Format {
+    pub prefix: &'static str,
+    pub name: &'static str,
+    pub version: u32,
+    pub capabilities: &'static [Capability],
+}
+
+pub(crate) const RULES_FORMAT: Format = Format {
+    prefix: "#!",
+    name: "g2p-rules",
+    version: RULES_VERSION,
+    capabilities: &[
+        Capability::Classes,
+        Capability::Weights,
+        Capability::Conditions,
+        Capability::Irregular,
+        Capability::Includes,
+        Capability::Captures,
+        Capability::Sandhi,
+        Capability::Stress,
+    ],
+};
+
+pub(crate) const REWRITE_FORMAT: Format = Format {
+    prefix: "#!",
+    name: "g2p-rewrite",
+    version: REWRITE_VERSION,
+    capabilities: &[Capability::Classes, Capability::Weights, Capability::Captures],
+};
+
+pub(crate) const LEXICON_FORMAT: Format = Format {
+    prefix: ";;;!",
+    name: "g2p-lexicon",
+    version: LEXICON_VERSION,
+    capabilities: &[Capability::Variants],
+};
+
+impl Format {
+    /// 解析文件开头的版本头和 `requires` 行，其余行不检查
+    pub fn read_header(&self, content: &str) -> Result<DataSchema> {
+        let mut schema = DataSchema::default();
+        let mut first = true;
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some(directive) = line.strip_prefix(self.prefix) else {
+                break;
+            };
+            let (name, value) = directive.split_once(char::is_whitespace).unwrap_or((directive, ""));
+            match name {
+                _ if name == self.name && first => schema.version = Some(self.parse_version(value.trim())?),
+                "requires" if schema.is_versioned() => {
+                    for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                        let capability = Capability::from_name(name)
+                            .filter(|capability| self.capabilities.contains(capability))
+                            .ok_or_else(|| invalid_data!("{} file requires unsupported feature '{}'", self.name, name))?;
+                        schema.declared.push(capability);
+                    }
+                }
+                _ => return Err(invalid_data!("Unknown {} directive on line {}: {}", self.name, i + 1, line)),
+            }
+            first = false;
+        }
+        Ok(schema)
+    }
+
+    fn parse_version(&self, value: &str) -> Result<u32> {
+        let version: u32 = value
+            .parse()
+            .map_err(|_| invalid_data!("Invalid {} version: '{}'", self.name, value))?;
+        if version == 0 || version > self.version {
+            return Err(invalid_data!(
+                "{} version {} is not supported (this build reads up to version {})",
+                self.name,
+                version,
+                self.version
+            ));
+        }
+        Ok(version)
+    }
+}
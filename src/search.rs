@@ -0,0 +1,136 @@
+//! 按读音检索文档：用户按发音拼写人名等单词时（如用 "Kristen" 查 "Kirsten"）也能找到
+//!
+//! 每个单词转换为音素键：去掉重音，所有元音记为同一个符号，连续相同的符号合并。
+//! 查询时比较音素键的编辑距离，因此拼写不同而读音相近的单词可以互相匹配。
+
+use crate::eval;
+use crate::RustG2P;
+use crate::error::Result;
+use std::collections::HashMap;
+
+/// 音素键中代表任意元音的符号
+const VOWEL: &str = "@";
+
+/// 按读音检索的文档索引
+#[derive(Debug, Clone, Default)]
+pub struct PhoneticIndex {
+    documents: Vec<String>,
+    postings: HashMap<Vec<String>, Vec<Posting>>, // 音素键 → 出现的位置，按文档编号排列
+}
+
+#[derive(Debug, Clone)]
+struct Posting {
+    document: usize,
+    word: String,
+}
+
+/// 一条检索结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub document: usize,
+    pub distance: usize,      // 每个查询词与最接近的单词的音素键距离之和
+    pub matched: Vec<String>, // 与每个查询词匹配的文档单词
+}
+
+impl PhoneticIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 用一组文档建立索引，文档编号为其在列表中的位置
+    pub fn build<S: AsRef<str>>(g2p: &RustG2P, documents: &[S]) -> Result<Self> {
+        let mut index = Self::new();
+        for document in documents {
+            index.add(g2p, document.as_ref())?;
+        }
+        Ok(index)
+    }
+
+    /// 加入一篇文档，返回文档编号
+    pub fn add(&mut self, g2p: &RustG2P, document: &str) -> Result<usize> {
+        let id = self.documents.len();
+        for (word, key) in phonetic_keys(g2p, document)? {
+            let postings = self.postings.entry(key).or_default();
+            if !postings.iter().any(|posting| posting.document == id && posting.word == word) {
+                postings.push(Posting { document: id, word });
+            }
+        }
+        self.documents.push(document.to_string());
+        Ok(id)
+    }
+
+    /// 检索读音相近的文档：查询中的每个词都须在文档中有音素键距离不超过 `max_distance` 的单词
+    ///
+    /// 结果按距离从小到大排列，距离相同时按文档编号排列。
+    pub fn search(&self, g2p: &RustG2P, query: &str, max_distance: usize) -> Result<Vec<SearchHit>> {
+        let query = phonetic_keys(g2p, query)?;
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 每篇文档中与每个查询词最接近的单词
+        let mut best: HashMap<usize, Vec<Option<(usize, &str)>>> = HashMap::new();
+        for (i, (_, query_key)) in query.iter().enumerate() {
+            for (key, postings) in &self.postings {
+                let distance = eval::edit_distance(query_key, key);
+                if distance > max_distance {
+                    continue;
+                }
+                for posting in postings {
+                    let slots = best.entry(posting.document).or_insert_with(|| vec![None; query.len()]);
+                    let closer = slots[i].is_none_or(|(d, word)| (distance, posting.word.as_str()) < (d, word));
+                    if closer {
+                        slots[i] = Some((distance, &posting.word));
+                    }
+                }
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = best
+            .into_iter()
+            .filter_map(|(document, slots)| {
+                let slots: Option<Vec<(usize, &str)>> = slots.into_iter().collect();
+                slots.map(|slots| SearchHit {
+                    document,
+                    distance: slots.iter().map(|(distance, _)| distance).sum(),
+                    matched: slots.iter().map(|(_, word)| word.to_string()).collect(),
+                })
+            })
+            .collect();
+        hits.sort_by_key(|hit| (hit.distance, hit.document));
+        Ok(hits)
+    }
+
+    /// 按编号取文档原文
+    pub fn document(&self, id: usize) -> Option<&str> {
+        self.documents.get(id).map(String::as_str)
+    }
+
+    /// 文档数
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+}
+
+/// 文本中每个发音单词及其音素键
+fn phonetic_keys(g2p: &RustG2P, text: &str) -> Result<Vec<(String, Vec<String>)>> {
+    Ok(g2p
+        .text_to_words(text)?
+        .into_iter()
+        .filter(|word| !word.phonemes.is_empty())
+        .map(|word| {
+            let mut key: Vec<String> = Vec::with_capacity(word.phonemes.len());
+            for phoneme in &word.phonemes {
+                let symbol = if phoneme.is_vowel() { VOWEL } else { phoneme.symbol.as_str() };
+                if key.last().map(String::as_str) != Some(symbol) {
+                    key.push(symbol.to_string());
+                }
+            }
+            (word.word, key)
+        })
+        .collect())
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 运行时统计快照
+#[derive(Debug, Clone)]
+pub struct G2PStats {
+    pub dict_entries: usize,
+    pub rule_count: usize,
+    pub words_converted: u64,
+    pub dictionary_hits: u64,
+    pub irregular_hits: u64,    // 不规则词汇层和规则文件中不规则词汇的命中
+    pub stem_hits: u64,         // 还原词干后在词典中找到的派生词
+    pub rule_words: u64,        // 经规则转换的单词
+    pub default_letters: u64,   // 没有匹配规则、使用默认映射的字母
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub rule_cache_hits: u64,   // 规则结果缓存的命中，见 `RustG2P::with_rule_cache`
+    pub rule_cache_misses: u64,
+    pub words_by_language: HashMap<String, u64>,
+}
+
+impl G2PStats {
+    /// 未登录词（词典未收录）所占比例
+    pub fn oov_rate(&self) -> f64 {
+        if self.words_converted == 0 {
+            0.0
+        } else {
+            (self.words_converted - self.dictionary_hits) as f64 / self.words_converted as f64
+        }
+    }
+    
+    /// 规则结果缓存的命中率，没有查询过缓存时为0
+    pub fn rule_cache_hit_rate(&self) -> f64 {
+        let lookups = self.rule_cache_hits + self.rule_cache_misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.rule_cache_hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// 实时计数器，只使用原子操作，读取和更新都不加锁
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    pub words: AtomicU64,
+    pub dictionary_hits: AtomicU64,
+    pub irregular_hits: AtomicU64,
+    pub stem_hits: AtomicU64,
+    pub rule_words: AtomicU64,
+    pub default_letters: AtomicU64,
+    pub cache_hits: AtomicU64,
+    pub cache_misses: AtomicU64,
+    pub rule_cache_hits: AtomicU64,
+    pub rule_cache_misses: AtomicU64,
+}
+
+impl Counters {
+    pub fn add(counter: &AtomicU64, n: u64) {
+        counter.fetch_add(n, Ordering::Relaxed);
+    }
+    
+    /// 生成快照；词典和规则规模由调用方填写
+    pub fn snapshot(&self, language: &str, dict_entries: usize, rule_count: usize) -> G2PStats {
+        let words = self.words.load(Ordering::Relaxed);
+        G2PStats {
+            dict_entries,
+            rule_count,
+            words_converted: words,
+            dictionary_hits: self.dictionary_hits.load(Ordering::Relaxed),
+            irregular_hits: self.irregular_hits.load(Ordering::Relaxed),
+            stem_hits: self.stem_hits.load(Ordering::Relaxed),
+            rule_words: self.rule_words.load(Ordering::Relaxed),
+            default_letters: self.default_letters.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            rule_cache_hits: self.rule_cache_hits.load(Ordering::Relaxed),
+            rule_cache_misses: self.rule_cache_misses.load(Ordering::Relaxed),
+            words_by_language: HashMap::from([(language.to_string(), words)]),
+        }
+    }
+    
+    pub fn reset(&self) {
+        for counter in [
+            &self.words,
+            &self.dictionary_hits,
+            &self.irregular_hits,
+            &self.stem_hits,
+            &self.rule_words,
+            &self.default_letters,
+            &self.cache_hits,
+            &self.cache_misses,
+            &self.rule_cache_hits,
+            &self.rule_cache_misses,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
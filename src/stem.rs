@@ -0,0 +1,171 @@
+//! 按英语拼写规则还原派生词的词干
+//!
+//! 词典未收录的派生词（"running"、"hoped"、"happier"）先去掉常见后缀，按拼写规则还原词干
+//! （去掉双写的辅音、补回不发音的 e、i 改回 y），词干在词典中时用词干的读音加后缀读音，
+//! 不再交给规则引擎。设置了 Hunspell 词缀词典（`with_affix_dictionary`）时先按词缀词典分析。
+
+use crate::phoneme::Phoneme;
+use crate::RustG2P;
+
+/// 可还原的后缀
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suffix {
+    Ing,
+    Ed,
+    S, // 复数和第三人称单数的 -s、-es
+    Er,
+    Est,
+    Ly,
+}
+
+/// 一个可能的词干
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StemCandidate {
+    pub stem: String,
+    pub suffix: Suffix,
+}
+
+/// 后缀的拼写，同一后缀的多种写法按从长到短排列
+const SUFFIXES: &[(&str, Suffix)] = &[
+    ("ing", Suffix::Ing),
+    ("ed", Suffix::Ed),
+    ("es", Suffix::S),
+    ("s", Suffix::S),
+    ("est", Suffix::Est),
+    ("er", Suffix::Er),
+    ("ly", Suffix::Ly),
+];
+
+impl Suffix {
+    /// 按后缀的写法识别，包括去掉了词干结尾 e 的写法（hope + d）
+    pub fn from_spelling(spelling: &str) -> Option<Self> {
+        match spelling {
+            "ing" => Some(Suffix::Ing),
+            "ed" | "d" => Some(Suffix::Ed),
+            "s" | "es" => Some(Suffix::S),
+            "er" | "r" => Some(Suffix::Er),
+            "est" | "st" => Some(Suffix::Est),
+            "ly" => Some(Suffix::Ly),
+            _ => None,
+        }
+    }
+
+    /// 后缀的读音，-ed 和 -s 随词干最后一个音素变化
+    pub fn phonemes(self, stem: &[Phoneme]) -> Vec<Phoneme> {
+        let last = stem.last().map_or("", |p| p.symbol.as_str());
+        let voiceless = matches!(last, "P" | "T" | "K" | "F" | "TH" | "S" | "SH" | "CH");
+        let arpabet = match self {
+            Suffix::Ing => "IH0 NG",
+            Suffix::Ed if matches!(last, "T" | "D") => "IH0 D",
+            Suffix::Ed if voiceless => "T",
+            Suffix::Ed => "D",
+            Suffix::S if matches!(last, "S" | "Z" | "SH" | "ZH" | "CH" | "JH") => "IH0 Z",
+            Suffix::S if voiceless => "S",
+            Suffix::S => "Z",
+            Suffix::Er => "ER0",
+            Suffix::Est => "AH0 S T",
+            Suffix::Ly => "L IY0",
+        };
+        arpabet.split_whitespace().map(Phoneme::from_arpabet).collect()
+    }
+}
+
+/// 按拼写规则列出可能的词干，越可能的越靠前
+///
+/// - 双写的辅音去掉一个：running → run（ll、ss、ff、zz 先保留：falling → fall）
+/// - 辅音-元音-辅音结尾时补回 e：hoped → hope，其他词干也会在最后尝试补 e：larger → large
+/// - 结尾的 i 改回 y：happier → happy、tried → try
+pub fn stem_candidates(word: &str) -> Vec<StemCandidate> {
+    let word = word.to_lowercase();
+    let mut candidates = Vec::new();
+    for &(spelling, suffix) in SUFFIXES {
+        let Some(base) = word.strip_suffix(spelling) else {
+            continue;
+        };
+        let letters: Vec<char> = base.chars().collect();
+        if letters.len() < 2 || !letters.iter().any(|&c| is_vowel(c)) {
+            continue;
+        }
+        let last = letters[letters.len() - 1];
+        match spelling {
+            // -es 只跟在咝音、o 和改写的 i 后面，-s 不跟在 s 后面
+            "es" if !(base.ends_with(['s', 'x', 'z', 'o', 'i']) || base.ends_with("ch") || base.ends_with("sh")) => continue,
+            "s" if last == 's' => continue,
+            _ => {}
+        }
+
+        let mut stems = Vec::new();
+        if last == 'i' {
+            stems.push(format!("{}y", &base[..base.len() - 1]));
+        } else if letters.len() >= 3 && last == letters[letters.len() - 2] && !is_vowel(last) {
+            let undoubled = base[..base.len() - 1].to_string();
+            if matches!(last, 'l' | 's' | 'f' | 'z') {
+                stems.extend([base.to_string(), undoubled]);
+            } else {
+                stems.extend([undoubled, base.to_string()]);
+            }
+        } else if ends_with_cvc(&letters) && spelling != "s" && spelling != "ly" {
+            stems.extend([format!("{}e", base), base.to_string()]);
+        } else {
+            stems.push(base.to_string());
+            if spelling != "s" && spelling != "ly" {
+                stems.push(format!("{}e", base));
+            }
+        }
+        candidates.extend(stems.into_iter().map(|stem| StemCandidate { stem, suffix }));
+    }
+    candidates
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// 是否以辅音-元音-辅音结尾（最后的辅音不是 w、x、y）
+fn ends_with_cvc(letters: &[char]) -> bool {
+    match letters {
+        [.., a, b, c] => !is_vowel(*a) && is_vowel(*b) && !is_vowel(*c) && !matches!(c, 'w' | 'x' | 'y'),
+        _ => false,
+    }
+}
+
+impl RustG2P {
+    /// 还原派生词的词干，词干在用户词典层、主词典或不规则词汇层中时返回词干和派生词的读音
+    ///
+    /// 先按词缀词典分析，再按英语拼写规则；词缀词典给出的前后缀不是常见英语后缀时按规则读。
+    pub fn recover_stem(&self, word: &str) -> Option<(String, Vec<Phoneme>)> {
+        let analyses = self.affixes.as_ref().map(|affixes| affixes.analyze(word)).unwrap_or_default();
+        let from_affixes = analyses.into_iter().find_map(|analysis| {
+            let stem = self.stem_phonemes(&analysis.stem)?;
+            let mut phonemes = match analysis.prefix.as_deref().filter(|prefix| !prefix.is_empty()) {
+                Some(prefix) => self.rules_engine.apply_rules(prefix).ok()?,
+                None => Vec::new(),
+            };
+            phonemes.extend_from_slice(&stem);
+            if let Some(suffix) = analysis.suffix.as_deref().filter(|suffix| !suffix.is_empty()) {
+                match Suffix::from_spelling(suffix) {
+                    Some(known) => phonemes.extend(known.phonemes(&stem)),
+                    None => phonemes.extend(self.rules_engine.apply_rules(suffix).ok()?),
+                }
+            }
+            Some((analysis.stem, phonemes))
+        });
+
+        from_affixes.or_else(|| {
+            stem_candidates(word).into_iter().find_map(|candidate| {
+                let mut phonemes = self.stem_phonemes(&candidate.stem)?;
+                phonemes.extend(candidate.suffix.phonemes(&phonemes));
+                Some((candidate.stem, phonemes))
+            })
+        })
+    }
+
+    /// 词干的读音，不计入统计
+    fn stem_phonemes(&self, stem: &str) -> Option<Vec<Phoneme>> {
+        let codes = self
+            .lookup_user(stem)
+            .or_else(|| self.dictionary.lookup_ref(stem).map(<[_]>::to_vec))
+            .or_else(|| self.irregular.lookup_codes(stem))?;
+        Some(codes.iter().map(|code| code.decode()).collect())
+    }
+}
@@ -0,0 +1,90 @@
+//! 长文本的流式转换
+//!
+//! `RustG2P::phonemize_iter` 按空行把文本切成段落，段落内再切分句子，每次只转换一个句子，
+//! 所以内存占用只与最长的段落有关。句子不跨越段落，逐段切分的结果与整段文本相同。
+
+use crate::phoneme::Phoneme;
+use crate::text::Span;
+use crate::RustG2P;
+use crate::error::Result;
+
+/// 逐个产生音素的迭代器，见 `RustG2P::phonemize_iter`
+pub struct PhonemeStream<'a> {
+    g2p: &'a RustG2P,
+    rest: &'a str,                                  // 尚未切分的文本
+    sentences: std::vec::IntoIter<Vec<Span<'a>>>,   // 当前段落中尚未转换的句子
+    phonemes: std::vec::IntoIter<Phoneme>,          // 当前句子中尚未产生的音素
+    failed: bool,
+}
+
+impl<'a> PhonemeStream<'a> {
+    pub(crate) fn new(g2p: &'a RustG2P, text: &'a str) -> Self {
+        Self {
+            g2p,
+            rest: text,
+            sentences: Vec::new().into_iter(),
+            phonemes: Vec::new().into_iter(),
+            failed: false,
+        }
+    }
+
+    /// 切下下一个段落，包括其后的空行
+    fn next_paragraph(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let mut end = 0;
+        let mut has_text = false;
+        for line in self.rest.split_inclusive('\n') {
+            end += line.len();
+            if !line.trim().is_empty() {
+                has_text = true;
+            } else if has_text {
+                break;
+            }
+        }
+        let (paragraph, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(paragraph)
+    }
+
+    /// 转换下一个句子，文本结束时返回None
+    fn next_sentence(&mut self) -> Option<Result<Vec<Phoneme>>> {
+        loop {
+            if let Some(sentence) = self.sentences.next() {
+                let mut tokens = Vec::new();
+                return Some(
+                    self.g2p
+                        .push_sentence(sentence, &mut tokens)
+                        .and_then(|()| self.g2p.phonemes(tokens)),
+                );
+            }
+            let paragraph = self.next_paragraph()?;
+            self.sentences = self.g2p.text_processor.sentences(paragraph).into_iter();
+        }
+    }
+}
+
+impl Iterator for PhonemeStream<'_> {
+    type Item = Result<Phoneme>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(phoneme) = self.phonemes.next() {
+                return Some(Ok(phoneme));
+            }
+            if self.failed {
+                return None;
+            }
+            match self.next_sentence()? {
+                Ok(phonemes) => self.phonemes = phonemes.into_iter(),
+                Err(error) => {
+                    self.failed = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for PhonemeStream<'_> {}
@@ -0,0 +1,184 @@
+//! 字幕文件（SRT、WebVTT）的逐条转换
+//!
+//! 按空行把文件切分为块，含有 `-->` 时间行的块是一条字幕，之前的行为编号或标识，之后的行为文本；
+//! WebVTT 的文件头、`NOTE`、`STYLE` 等没有时间行的块跳过。字幕文本中的 `<i>`、`<v Speaker>` 标签和
+//! `{\an8}` 这样的样式代码在转换前去掉。转换结果保留原来的时间戳，可以按原格式写回（文本换成音素），
+//! 供配音和口型同步使用：
+//!
+//! ```text
+//! 1
+//! 00:00:01,000 --> 00:00:02,500
+//! HH AH0 L OW1 | W ER1 L D
+//! ```
+
+use crate::phoneme::Phoneme;
+use crate::RustG2P;
+use crate::error::{invalid_data, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// 字幕格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+/// 一条字幕
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    pub id: Option<String>, // SRT的序号或WebVTT的标识
+    pub start: Duration,
+    pub end: Duration,
+    pub settings: String, // WebVTT时间行中的设置（`align:start` 等），SRT为空
+    pub text: String,     // 原始文本，多行以换行分隔
+}
+
+/// 一条字幕的转换结果
+#[derive(Debug, Clone)]
+pub struct CuePhonemes {
+    pub cue: Cue,
+    pub phonemes: Vec<Phoneme>, // 整条字幕的音素序列，词间有词边界，结尾没有
+}
+
+impl SubtitleFormat {
+    /// 以 `WEBVTT` 开头的文件为WebVTT，否则按SRT处理
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start_matches('\u{feff}').starts_with("WEBVTT") {
+            SubtitleFormat::Vtt
+        } else {
+            SubtitleFormat::Srt
+        }
+    }
+}
+
+/// 解析SRT或WebVTT文本，按出现顺序返回字幕
+pub fn parse(content: &str) -> Result<Vec<Cue>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut cues = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    for line in content.lines().chain(std::iter::once("")) {
+        if !line.trim().is_empty() {
+            block.push(line);
+            continue;
+        }
+        if let Some(timing) = block.iter().position(|line| line.contains("-->")) {
+            let (start, end, settings) = parse_timing(block[timing])
+                .map_err(|e| invalid_data!("Cue {}: {}", cues.len() + 1, e))?;
+            cues.push(Cue {
+                id: (timing > 0).then(|| block[..timing].join(" ").trim().to_string()),
+                start,
+                end,
+                settings,
+                text: block[timing + 1..].join("\n"),
+            });
+        }
+        block.clear();
+    }
+    Ok(cues)
+}
+
+/// 解析时间行 `start --> end [settings]`
+fn parse_timing(line: &str) -> Result<(Duration, Duration, String)> {
+    let (start, rest) = line.split_once("-->").unwrap_or((line, ""));
+    let mut rest = rest.split_whitespace();
+    let end = rest.next().ok_or_else(|| invalid_data!("missing end time in '{}'", line.trim()))?;
+    let settings = rest.collect::<Vec<_>>().join(" ");
+    Ok((parse_timestamp(start.trim())?, parse_timestamp(end)?, settings))
+}
+
+/// 解析 `HH:MM:SS,mmm`、`HH:MM:SS.mmm` 或 `MM:SS.mmm`
+fn parse_timestamp(stamp: &str) -> Result<Duration> {
+    let invalid = || invalid_data!("invalid timestamp '{}'", stamp);
+    let (clock, millis) = stamp.split_once([',', '.']).ok_or_else(invalid)?;
+    let fields: Vec<u64> = clock
+        .split(':')
+        .map(|field| field.parse().map_err(|_| invalid()))
+        .collect::<Result<_>>()?;
+    let seconds = match fields[..] {
+        [hours, minutes, seconds] => hours * 3600 + minutes * 60 + seconds,
+        [minutes, seconds] => minutes * 60 + seconds,
+        _ => return Err(invalid()),
+    };
+    if millis.len() != 3 {
+        return Err(invalid());
+    }
+    let millis: u64 = millis.parse().map_err(|_| invalid())?;
+    Ok(Duration::from_millis(seconds * 1000 + millis))
+}
+
+/// 去掉字幕文本中的标签和样式代码
+pub fn plain_text(text: &str) -> String {
+    lazy_static! {
+        static ref TAG_RE: Regex = Regex::new(r"<[^>]*>|\{\\[^}]*\}").unwrap();
+    }
+    TAG_RE.replace_all(text, "").into_owned()
+}
+
+/// 按指定格式输出字幕，SRT按顺序重新编号
+pub fn write(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    for (i, cue) in cues.iter().enumerate() {
+        match format {
+            SubtitleFormat::Srt => {
+                let _ = writeln!(out, "{}", i + 1);
+            }
+            SubtitleFormat::Vtt => {
+                if let Some(id) = &cue.id {
+                    let _ = writeln!(out, "{}", id);
+                }
+            }
+        }
+        let _ = write!(out, "{} --> {}", format_timestamp(cue.start, format), format_timestamp(cue.end, format));
+        if format == SubtitleFormat::Vtt && !cue.settings.is_empty() {
+            let _ = write!(out, " {}", cue.settings);
+        }
+        let _ = writeln!(out, "\n{}\n", cue.text);
+    }
+    out
+}
+
+/// SRT为 `00:00:01,000`，WebVTT为 `00:00:01.000`
+pub fn format_timestamp(time: Duration, format: SubtitleFormat) -> String {
+    let millis = time.as_millis();
+    let separator = if format == SubtitleFormat::Srt { ',' } else { '.' };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+impl RustG2P {
+    /// 解析字幕文件并逐条转换，保留原来的时间戳
+    pub fn phonemize_subtitles(&self, content: &str) -> Result<Vec<CuePhonemes>> {
+        parse(content)?
+            .into_iter()
+            .map(|cue| {
+                let mut phonemes = self.text_to_phonemes(&plain_text(&cue.text))?;
+                if phonemes.last().is_some_and(|p| p.symbol == " ") {
+                    phonemes.pop();
+                }
+                Ok(CuePhonemes { cue, phonemes })
+            })
+            .collect()
+    }
+
+    /// 转换字幕文件，按原格式输出，每条字幕的文本换成按配置记法输出的音素
+    pub fn phonemize_subtitle_file(&self, content: &str) -> Result<String> {
+        let cues: Vec<Cue> = self
+            .phonemize_subtitles(content)?
+            .into_iter()
+            .map(|result| Cue { text: self.render(&result.phonemes), ..result.cue })
+            .collect();
+        Ok(write(&cues, SubtitleFormat::detect(content)))
+    }
+}
@@ -0,0 +1,77 @@
+//! 按音素切分音节
+//!
+//! 每个元音是一个音节核。元音之间的辅音按最大首音原则划分：在英语允许的词首辅音丛范围内，
+//! 尽量多地归入后一个音节的首音，其余归入前一个音节的尾音。
+
+use crate::phoneme::Phoneme;
+
+/// 一个音节在音素序列中的范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syllable {
+    pub start: usize,
+    pub nucleus: Option<usize>, // 音节核（元音）的位置，没有元音的单词（如 "hmm"）为 None
+    pub end: usize,             // 不含
+}
+
+impl Syllable {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// 允许出现在词首的双辅音丛
+const ONSETS_2: &[(&str, &str)] = &[
+    ("P", "R"), ("P", "L"), ("B", "R"), ("B", "L"), ("T", "R"), ("D", "R"), ("K", "R"), ("K", "L"),
+    ("G", "R"), ("G", "L"), ("F", "R"), ("F", "L"), ("TH", "R"), ("SH", "R"), ("V", "R"),
+    ("S", "P"), ("S", "T"), ("S", "K"), ("S", "M"), ("S", "N"), ("S", "L"), ("S", "W"), ("S", "F"),
+    ("T", "W"), ("D", "W"), ("K", "W"), ("G", "W"), ("TH", "W"),
+    ("P", "Y"), ("B", "Y"), ("K", "Y"), ("G", "Y"), ("F", "Y"), ("V", "Y"), ("M", "Y"), ("HH", "Y"),
+];
+
+/// 允许出现在词首的三辅音丛
+const ONSETS_3: &[(&str, &str, &str)] = &[
+    ("S", "P", "R"), ("S", "P", "L"), ("S", "T", "R"), ("S", "K", "R"), ("S", "K", "W"), ("S", "K", "L"),
+    ("S", "P", "Y"), ("S", "K", "Y"),
+];
+
+/// 辅音序列能否作为音节首音
+pub fn is_legal_onset(consonants: &[Phoneme]) -> bool {
+    match consonants {
+        [] => true,
+        [c] => c.symbol != "NG",
+        [a, b] => ONSETS_2.contains(&(a.symbol.as_str(), b.symbol.as_str())),
+        [a, b, c] => ONSETS_3.contains(&(a.symbol.as_str(), b.symbol.as_str(), c.symbol.as_str())),
+        _ => false,
+    }
+}
+
+/// 把单个单词的音素切分为音节；词边界等特殊符号按辅音处理，多个单词应分别切分
+pub fn syllabify(phonemes: &[Phoneme]) -> Vec<Syllable> {
+    let nuclei: Vec<usize> = (0..phonemes.len()).filter(|&i| phonemes[i].is_vowel()).collect();
+    if nuclei.is_empty() {
+        return if phonemes.is_empty() {
+            Vec::new()
+        } else {
+            vec![Syllable { start: 0, nucleus: None, end: phonemes.len() }]
+        };
+    }
+
+    let mut syllables = Vec::with_capacity(nuclei.len());
+    let mut start = 0;
+    for (k, &nucleus) in nuclei.iter().enumerate() {
+        let end = match nuclei.get(k + 1) {
+            // 两个元音之间的辅音：最长的合法首音归后一个音节
+            Some(&next) => (nucleus + 1..=next)
+                .find(|&split| is_legal_onset(&phonemes[split..next]))
+                .unwrap_or(next),
+            None => phonemes.len(),
+        };
+        syllables.push(Syllable { start, nucleus: Some(nucleus), end });
+        start = end;
+    }
+    syllables
+}
@@ -1,39 +1,569 @@
-use anyhow::Result;
+use crate::error::Result;
 use regex::Regex;
 use lazy_static::lazy_static;
+use crate::address;
+use crate::lang::CaseRules;
+use crate::markup;
+use crate::numbers::{self, EnglishNumbers, NumberLocale, NumberReading, NumberWords};
+use crate::translit::{self, Script};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// 文本预处理器
 pub struct TextProcessor {
-    number_words: HashMap<&'static str, &'static str>,
     abbreviations: HashMap<&'static str, &'static str>,
+    options: NormalizeOptions,
+    number_words: Option<Arc<dyn NumberWords>>, // 数字和列表编号的读法，没有时不展开数字
 }
 
+/// 按斜杠音素片段切分后的输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'t> {
+    Text(&'t str),
+    Phonemic(&'t str), // 斜杠内的音素串，不含斜杠
+    Letters(&'t str),  // 按字母名称拼读的片段，如 `e.g.`、`B`
+    Event(&'t str),    // 非语言声音标记，如 `[laughter]`、`(coughs)`，不含括号
+    Filler(&'t str),   // 填充词，如 `um`、`mm-hmm`，不含前后的标点
+}
+
+/// 句子中引号内外的片段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Span<'t> {
+    Narration(&'t str), // 引号外的叙述和说话人标记
+    Quote(&'t str),     // 引号内的文本，不含引号
+}
+
+/// 标准化之前检测到的词元类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    Word,
+    Number,      // `25`、`3.5`
+    Ordinal,     // `1st`、`22nd`
+    Acronym,     // `NASA`，以及按字母拼读的 `U.S.`、`plan B`
+    Url,
+    Punctuation, // 不发音
+    Emoji,       // 不发音
+    NonVerbal,   // 非语言声音标记 `[laughter]`，输出事件音素或不发音
+    Filler,      // 填充词 `um`、`uh-huh`
+}
+
+impl TokenKind {
+    /// 与序列化结果相同的小写名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenKind::Word => "word",
+            TokenKind::Number => "number",
+            TokenKind::Ordinal => "ordinal",
+            TokenKind::Acronym => "acronym",
+            TokenKind::Url => "url",
+            TokenKind::Punctuation => "punctuation",
+            TokenKind::Emoji => "emoji",
+            TokenKind::NonVerbal => "non_verbal",
+            TokenKind::Filler => "filler",
+        }
+    }
+    
+    /// 是否产生读音；标点和表情只出现在 `text_to_tokens` 的结果中
+    pub fn is_spoken(self) -> bool {
+        !matches!(self, TokenKind::Punctuation | TokenKind::Emoji | TokenKind::NonVerbal)
+    }
+}
+
+/// 原文的大小写形式，字幕等渲染器据此还原显示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Casing {
+    Lower,       // `hello`
+    Capitalized, // `Hello`
+    Upper,       // `HELLO`、`NASA`
+    Mixed,       // `iPhone`、`McDonald`
+    Uncased,     // 没有字母，如 `25`、`!`
+}
+
+impl Casing {
+    /// 按文本中的字母判断大小写形式，只有一个大写字母时为 `Capitalized`
+    pub fn of(text: &str) -> Self {
+        let mut letters = text.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = letters.next() else {
+            return Casing::Uncased;
+        };
+        let rest: Vec<char> = letters.collect();
+        let rest_lower = rest.iter().all(|c| !c.is_uppercase());
+        if first.is_uppercase() && rest_lower {
+            Casing::Capitalized
+        } else if !first.is_uppercase() && rest_lower {
+            Casing::Lower
+        } else if first.is_uppercase() && rest.iter().all(|c| !c.is_lowercase()) {
+            Casing::Upper
+        } else {
+            Casing::Mixed
+        }
+    }
+}
+
+/// 按空白切分并检测类别，词首词尾的标点作为单独的词元
+///
+/// 网址整体作为一个词元，只切掉结尾的标点。连续的标点（`?!`、`...`）合为一个词元。
+pub fn classify_tokens(text: &str) -> Vec<(&str, TokenKind)> {
+    let mut tokens = Vec::new();
+    for chunk in text.split_whitespace() {
+        let core = chunk.trim_end_matches(is_punctuation);
+        let (leading, core) = if is_url(core) {
+            ("", core)
+        } else {
+            let start = core.len() - core.trim_start_matches(is_punctuation).len();
+            core.split_at(start)
+        };
+        let trailing = &chunk[leading.len() + core.len()..];
+        
+        for (part, kind) in [
+            (leading, TokenKind::Punctuation),
+            (core, classify(core)),
+            (trailing, TokenKind::Punctuation),
+        ] {
+            if !part.is_empty() {
+                tokens.push((part, kind));
+            }
+        }
+    }
+    tokens
+}
+
+/// 去掉标点后的单个词元的类别
+fn classify(token: &str) -> TokenKind {
+    lazy_static! {
+        static ref NUMBER_RE: Regex = Regex::new(r"^\d+(?:[.,]\d+)*$").unwrap();
+        static ref ORDINAL_RE: Regex = Regex::new(r"(?i)^\d+(?:st|nd|rd|th)$").unwrap();
+    }
+    
+    if is_url(token) {
+        TokenKind::Url
+    } else if NUMBER_RE.is_match(token) {
+        TokenKind::Number
+    } else if ORDINAL_RE.is_match(token) {
+        TokenKind::Ordinal
+    } else if token.chars().count() > 1 && token.chars().all(|c| c.is_ascii_uppercase()) {
+        TokenKind::Acronym
+    } else if token.chars().all(is_emoji) {
+        TokenKind::Emoji
+    } else {
+        TokenKind::Word
+    }
+}
+
+fn is_url(token: &str) -> bool {
+    token.contains("://") || token.to_lowercase().starts_with("www.")
+}
+
+/// 朗读时在此停顿的句中标点
+pub(crate) fn is_clause_break(c: char) -> bool {
+    matches!(c, ',' | ';' | ':' | '—' | '–' | '(' | ')')
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || matches!(c, '“' | '”' | '‘' | '’' | '—' | '–' | '…' | '«' | '»' | '¡' | '¿')
+}
+
+/// 常见的表情符号区段，包括肤色修饰和零宽连接符
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x200D)
+}
+
+/// 把输入切分为普通文本和斜杠包围的音素片段（如 `/T EH1 S T/`）
+///
+/// 开头的斜杠须在文本开头或空白之后，结尾的斜杠之后不能紧跟字母或数字，
+/// 以免把 `and/or` 之类的写法当成音素。
+pub fn split_phonemic(text: &str) -> Vec<Segment<'_>> {
+    lazy_static! {
+        static ref PHONEMIC_RE: Regex = Regex::new(r"(?:^|\s)/([^/\n]+)/").unwrap();
+    }
+    
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for caps in PHONEMIC_RE.captures_iter(text) {
+        let inner = caps.get(1).unwrap();
+        let end = inner.end() + 1;
+        if text[end..].starts_with(|c: char| c.is_alphanumeric()) {
+            continue;
+        }
+        
+        let start = inner.start() - 1;
+        if start > last {
+            segments.push(Segment::Text(&text[last..start]));
+        }
+        segments.push(Segment::Phonemic(inner.as_str()));
+        last = end;
+    }
+    if last < text.len() {
+        segments.push(Segment::Text(&text[last..]));
+    }
+    segments
+}
+
+/// 把文本中应逐个读字母名称的片段切分出来
+///
+/// 包括带点的字母序列（`e.g.`、`a.k.a.`、`U.S.`）和单独出现的字母（`plan B`）。
+/// 小写的 `a` 是冠词，只有大写的 `A` 位于句末或标点前时才按字母读；
+/// `I` 的代词读音与字母名称相同，不需要区分。
+pub fn split_letter_sequences(text: &str) -> Vec<Segment<'_>> {
+    lazy_static! {
+        static ref LETTERS_RE: Regex = Regex::new(r#"(?:^|[\s("])((?:[A-Za-z]\.)+[A-Za-z]\.?|[A-Za-z])"#).unwrap();
+    }
+    
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for caps in LETTERS_RE.captures_iter(text) {
+        let letters = caps.get(1).unwrap();
+        let next = text[letters.end()..].chars().next();
+        if next.is_some_and(|c| c.is_alphanumeric() || matches!(c, '\'' | '’' | '-')) {
+            continue;
+        }
+        let article = match letters.as_str() {
+            "a" => true,
+            "A" => !matches!(next, None | Some('.' | ',' | '!' | '?' | ';' | ':' | ')')),
+            _ => false,
+        };
+        if article {
+            continue;
+        }
+        
+        if letters.start() > last {
+            segments.push(Segment::Text(&text[last..letters.start()]));
+        }
+        segments.push(Segment::Letters(letters.as_str()));
+        last = letters.end();
+    }
+    if last < text.len() {
+        segments.push(Segment::Text(&text[last..]));
+    }
+    segments
+}
+
+/// 非语言声音的规范名称及其常见写法
+const NON_VERBAL_EVENTS: &[(&str, &[&str])] = &[
+    ("laughter", &["laughter", "laugh", "laughs", "laughing", "chuckle", "chuckles", "giggle", "giggles"]),
+    ("sigh", &["sigh", "sighs", "sighing"]),
+    ("cough", &["cough", "coughs", "coughing"]),
+    ("breath", &["breath", "breathes", "breathing", "inhale", "inhales", "exhale", "exhales"]),
+    ("sniff", &["sniff", "sniffs", "sniffing"]),
+    ("throat_clear", &["clears throat", "clearing throat", "throat clear", "throat clearing"]),
+    ("cry", &["cry", "cries", "crying", "sob", "sobs"]),
+    ("yawn", &["yawn", "yawns", "yawning"]),
+    ("applause", &["applause", "clapping", "claps"]),
+    ("noise", &["noise"]),
+    ("music", &["music"]),
+];
+
+/// 非语言声音标记的规范名称：常见写法归并（`laughs` → `laughter`），
+/// 方括号中的其他内容（`[inaudible]`）转为小写、空白换成下划线；圆括号只识别常见写法
+pub fn non_verbal_name(inner: &str, bracketed: bool) -> Option<String> {
+    let key = inner.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    match NON_VERBAL_EVENTS.iter().find(|(_, spellings)| spellings.contains(&key.as_str())) {
+        Some((name, _)) => Some(name.to_string()),
+        None if bracketed => Some(key.replace(' ', "_")),
+        None => None,
+    }
+}
+
+/// 把ASR转写中的非语言声音标记切分出来：方括号内的任意单词（`[laughter]`、`[inaudible]`），
+/// 以及圆括号内的常见写法（`(coughs)`）；Markdown链接 `[text](url)` 不算
+pub fn split_events(text: &str) -> Vec<Segment<'_>> {
+    lazy_static! {
+        static ref EVENT_RE: Regex = Regex::new(r"(?:^|\s)(\[([A-Za-z][A-Za-z _-]*)\]|\(([A-Za-z][A-Za-z ]*)\))").unwrap();
+    }
+    
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for caps in EVENT_RE.captures_iter(text) {
+        let marker = caps.get(1).unwrap();
+        if text[marker.end()..].starts_with(|c: char| c.is_alphanumeric() || c == '(') {
+            continue;
+        }
+        let (inner, bracketed) = match caps.get(2) {
+            Some(inner) => (inner.as_str(), true),
+            None => (caps.get(3).unwrap().as_str(), false),
+        };
+        if non_verbal_name(inner, bracketed).is_none() {
+            continue;
+        }
+        
+        if marker.start() > last {
+            segments.push(Segment::Text(&text[last..marker.start()]));
+        }
+        segments.push(Segment::Event(inner));
+        last = marker.end();
+    }
+    if last < text.len() {
+        segments.push(Segment::Text(&text[last..]));
+    }
+    segments
+}
+
+/// 填充词：折叠重复字母后的写法、规范写法、读音
+const FILLERS: &[(&str, &str, &str)] = &[
+    ("uh", "uh", "AH1"),
+    ("um", "um", "AH1 M"),
+    ("uhm", "um", "AH1 M"),
+    ("er", "uh", "ER1"),
+    ("erm", "um", "ER1 M"),
+    ("hm", "hmm", "HH M"),
+    ("mhm", "mm-hmm", "M HH M"),
+    ("m-hm", "mm-hmm", "M HH M"),
+    ("uh-huh", "uh-huh", "AH0 HH AH1"),
+];
+
+/// 在填充词表中查找，拉长的写法（`ummm`、`hmmm`）折叠重复字母后匹配；单个字母和全大写的缩写（`ER`、`HM`）不算，
+/// 折叠后为 `er` 的写法是普通单词 `err`
+fn filler_entry(word: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    if word.len() < 2 || !word.chars().all(|c| c.is_ascii_alphabetic() || c == '-') {
+        return None;
+    }
+    if !word.chars().any(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    let lower = word.to_ascii_lowercase();
+    let mut key = lower.clone().into_bytes();
+    key.dedup();
+    if key != lower.as_bytes() && key == b"er" {
+        return None;
+    }
+    FILLERS.iter().find(|(spelling, ..)| spelling.as_bytes() == key)
+}
+
+/// 填充词的写法和ARPAbet读音：`Keep` 保留原写法，`Normalize` 换成规范写法（`ummm`、`erm` → `um`）；
+/// 不是填充词时返回 None
+pub fn filler(word: &str, style: DisfluencyStyle) -> Option<(String, &'static str)> {
+    let &(_, canonical, pronunciation) = filler_entry(word)?;
+    match style {
+        DisfluencyStyle::Normalize => Some((canonical.to_string(), filler_entry(canonical)?.2)),
+        _ => Some((word.to_lowercase(), pronunciation)),
+    }
+}
+
+/// 切分出填充词，前后的标点留在 `Text` 片段中
+pub fn split_fillers(text: &str) -> Vec<Segment<'_>> {
+    lazy_static! {
+        static ref CHUNK_RE: Regex = Regex::new(r"\S+").unwrap();
+    }
+    
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for chunk in CHUNK_RE.find_iter(text) {
+        let word = chunk.as_str().trim_matches(|c: char| !c.is_alphabetic());
+        if filler_entry(word).is_none() {
+            continue;
+        }
+        let start = chunk.start() + chunk.as_str().find(word).unwrap();
+        if start > last {
+            segments.push(Segment::Text(&text[last..start]));
+        }
+        segments.push(Segment::Filler(word));
+        last = start + word.len();
+    }
+    if last < text.len() {
+        segments.push(Segment::Text(&text[last..]));
+    }
+    segments
+}
+
+/// 在文字不同的相邻字母、数字之间拆开（"Ωmega3" → "Ω"、"mega"、"3"），各段分别标准化和转换，
+/// 数字不再粘在单词上被丢掉；数字后的序数词后缀和复数 s（`1st`、`1980s`）不拆
+pub fn split_scripts(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+    let mut previous = None;
+    for (i, c) in text.char_indices() {
+        let script = translit::script_of(c);
+        if let (Some(before), Some(after)) = (previous, script) {
+            if before != after && !(before == Script::Digit && is_number_suffix(&text[i..])) {
+                segments.push(Segment::Text(&text[last..i]));
+                last = i;
+            }
+        }
+        previous = script;
+    }
+    if last < text.len() {
+        segments.push(Segment::Text(&text[last..]));
+    }
+    segments
+}
+
+/// 数字后面紧跟的字母是否为序数词后缀或复数 s
+fn is_number_suffix(rest: &str) -> bool {
+    let suffix = rest.split(|c: char| !c.is_alphabetic()).next().unwrap_or_default().to_lowercase();
+    matches!(suffix.as_str(), "st" | "nd" | "rd" | "th" | "s")
+}
+
+/// 后面的文本是否像新句子的开头
+fn starts_sentence(text: &str) -> bool {
+    text.trim_start()
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_uppercase() || matches!(c, '"' | '“' | '(' | '\''))
+}
+
+fn push_span<'t>(spans: &mut Vec<Span<'t>>, span: Span<'t>) {
+    let span = match span {
+        Span::Narration(text) => Span::Narration(text.trim()),
+        Span::Quote(text) => Span::Quote(text.trim()),
+    };
+    if !matches!(span, Span::Narration("") | Span::Quote("")) {
+        spans.push(span);
+    }
+}
+
+fn push_sentence<'t>(sentences: &mut Vec<Vec<Span<'t>>>, spans: &mut Vec<Span<'t>>) {
+    if !spans.is_empty() {
+        sentences.push(std::mem::take(spans));
+    }
+}
+
+/// 处理行首的列表标记，使编号和每一项都自成一句，不与下一项连读
+///
+/// 编号（`1.`、`2)`）按 `style` 读作 `One.` 或 `First.`，项目符号（`-`、`*`、`•`）去掉，
+/// 没有句末标点的列表项补上句号：`1. Buy milk → One. Buy milk.`。只处理行首，句中的 `1.` 不受影响。
+/// 编号按 `number_words` 读，性和格由列表项的第一个单词决定。
+pub fn expand_list_items<'t>(text: &'t str, style: ListStyle, number_words: &dyn NumberWords) -> Cow<'t, str> {
+    lazy_static! {
+        static ref LIST_ITEM_RE: Regex = Regex::new(r"(?m)^([ \t]*)(?:(\d{1,2})[.)]|[-*•·])[ \t]+([^\r\n]*?)([ \t]*)$").unwrap();
+    }
+    
+    LIST_ITEM_RE.replace_all(text, |caps: &regex::Captures| {
+        let mut item = caps[1].to_string();
+        if let Some(number) = caps.get(2) {
+            let number: u64 = number.as_str().parse().unwrap_or_default();
+            let following = caps[3].split_whitespace().next().map(|word| word.to_lowercase());
+            let agreement = number_words.agreement(following.as_deref());
+            let words = match style {
+                ListStyle::Cardinal => number_words.cardinal(number, agreement),
+                ListStyle::Ordinal => number_words.ordinal(number, agreement),
+            };
+            // 首字母大写，使编号后断句
+            let mut chars = words.chars();
+            item.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+            item.push_str(chars.as_str());
+            item.push_str(". ");
+        }
+        item.push_str(&caps[3]);
+        if !caps[3].is_empty() && !caps[3].ends_with(['.', '!', '?', ':', ';']) {
+            item.push('.');
+        }
+        item
+    })
+}
+
+/// 对可能已改写过的文本再应用一步改写，没有改动时不复制
+fn rewrite<'t>(text: Cow<'t, str>, pass: impl Fn(&str) -> Cow<'_, str>) -> Cow<'t, str> {
+    match text {
+        Cow::Borrowed(text) => pass(text),
+        Cow::Owned(text) => match pass(&text) {
+            Cow::Borrowed(_) => Cow::Owned(text),
+            Cow::Owned(rewritten) => Cow::Owned(rewritten),
+        },
+    }
+}
+
+/// 文本标准化的各步骤开关
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    pub expand_abbreviations: bool,
+    pub expand_numbers: bool,
+    pub strip_punctuation: bool,
+    pub strip_markup: bool, // 去掉Markdown和HTML标记
+    pub latin_style: LatinStyle, // 仅在 expand_abbreviations 时生效
+    pub number_locale: NumberLocale,
+    pub list_style: ListStyle, // 仅在 expand_numbers 时生效
+    pub non_verbal: NonVerbalStyle,
+    pub disfluency: DisfluencyStyle,
+    pub case_rules: CaseRules, // 转小写的规则，未登录词交给规则引擎前也按此转换
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            expand_abbreviations: true,
+            expand_numbers: true,
+            strip_punctuation: true,
+            strip_markup: false,
+            latin_style: LatinStyle::default(),
+            number_locale: NumberLocale::default(),
+            list_style: ListStyle::default(),
+            non_verbal: NonVerbalStyle::default(),
+            disfluency: DisfluencyStyle::default(),
+            case_rules: CaseRules::default(),
+        }
+    }
+}
+
+/// 转写中非语言声音标记（`[laughter]`、`(coughs)`）的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NonVerbalStyle {
+    /// 输出事件音素 `Phoneme::event`，如 `[laughter]`
+    #[default]
+    Event,
+    /// 不发音，只在 `text_to_tokens` 的结果中保留
+    Tagged,
+    /// 按普通文字读
+    Literal,
+}
+
+/// 转写中填充词（`um`、`uh`、`mm-hmm`）的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisfluencyStyle {
+    /// 保留原写法，使用填充词表中的读音
+    #[default]
+    Keep,
+    /// 换成规范写法及其读音，如 `ummm`、`erm` → `um`
+    Normalize,
+    /// 去掉填充词
+    Drop,
+}
+
+/// 拉丁缩写（`e.g.`、`i.e.`、`et al.` 等）的读法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatinStyle {
+    /// 读作对应的英文：`e.g.` → for example
+    #[default]
+    Expand,
+    /// 按字母读：`e.g.` → E G；没有字母读法的（`et al.`、`etc.`）按原词读
+    Letters,
+}
+
+/// 行首列表编号（`1.`、`2)`）的读法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListStyle {
+    /// `1. → One.`
+    #[default]
+    Cardinal,
+    /// `1. → First.`
+    Ordinal,
+}
+
+/// 拉丁缩写不展开时的读法
+enum LatinReading {
+    Letters,
+    Words(&'static str),
+}
+
+/// 拉丁缩写：(写法, 展开, 不展开时的读法)
+const LATIN_ABBREVIATIONS: [(&str, &str, LatinReading); 6] = [
+    ("e.g.", "for example", LatinReading::Letters),
+    ("i.e.", "that is", LatinReading::Letters),
+    ("cf.", "compare", LatinReading::Letters),
+    ("viz.", "namely", LatinReading::Words("viz")),
+    ("et al.", "and others", LatinReading::Words("et al")),
+    ("etc.", "etcetera", LatinReading::Words("etcetera")),
+];
+
 impl TextProcessor {
     pub fn new() -> Self {
-        let mut number_words = HashMap::new();
-        number_words.insert("0", "zero");
-        number_words.insert("1", "one");
-        number_words.insert("2", "two");
-        number_words.insert("3", "three");
-        number_words.insert("4", "four");
-        number_words.insert("5", "five");
-        number_words.insert("6", "six");
-        number_words.insert("7", "seven");
-        number_words.insert("8", "eight");
-        number_words.insert("9", "nine");
-        number_words.insert("10", "ten");
-        number_words.insert("11", "eleven");
-        number_words.insert("12", "twelve");
-        number_words.insert("13", "thirteen");
-        number_words.insert("14", "fourteen");
-        number_words.insert("15", "fifteen");
-        number_words.insert("16", "sixteen");
-        number_words.insert("17", "seventeen");
-        number_words.insert("18", "eighteen");
-        number_words.insert("19", "nineteen");
-        number_words.insert("20", "twenty");
-        
         let mut abbreviations = HashMap::new();
         abbreviations.insert("dr.", "doctor");
         abbreviations.insert("mr.", "mister");
@@ -43,88 +573,332 @@ impl TextProcessor {
         abbreviations.insert("st.", "street");
         abbreviations.insert("ave.", "avenue");
         abbreviations.insert("blvd.", "boulevard");
-        abbreviations.insert("etc.", "etcetera");
         abbreviations.insert("vs.", "versus");
         
         Self {
-            number_words,
             abbreviations,
+            options: NormalizeOptions::default(),
+            number_words: Some(Arc::new(EnglishNumbers)),
         }
     }
     
-    /// 文本标准化
-    pub fn normalize(&self, text: &str) -> Result<String> {
-        let mut result = text.to_string();
+    /// 设置标准化步骤开关
+    pub fn with_options(mut self, options: NormalizeOptions) -> Self {
+        self.options = options;
+        self
+    }
+    
+    /// 当前的标准化设置
+    pub fn options(&self) -> &NormalizeOptions {
+        &self.options
+    }
+    
+    /// 设置数字和列表编号的读法，默认为 `EnglishNumbers`
+    ///
+    /// 时间、范围、小数、版本号等固定格式仍按英语读。
+    pub fn with_number_words(mut self, number_words: Arc<dyn NumberWords>) -> Self {
+        self.number_words = Some(number_words);
+        self
+    }
+    
+    /// 不展开数字，用于还没有数词读法的语言；`expand_numbers` 随之失效
+    pub fn without_number_words(mut self) -> Self {
+        self.number_words = None;
+        self
+    }
+    
+    /// 分段之前需要原始文本的改写：标记随 `strip_markup` 去掉，地址缩写随 `expand_abbreviations`，列表编号、金额、科学计数法、时间、范围、带符号和单位的量以及带点的数字随 `expand_numbers`
+    pub fn preprocess<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        let mut text = Cow::Borrowed(text);
+        if self.options.strip_markup {
+            text = rewrite(text, markup::strip_markup);
+        }
+        if self.options.expand_abbreviations {
+            text = rewrite(text, address::expand_addresses);
+        }
+        if let Some(number_words) = self.number_words() {
+            text = rewrite(text, |text| expand_list_items(text, self.options.list_style, number_words));
+            let locale = self.options.number_locale;
+            text = rewrite(text, |text| numbers::normalize_separators(text, locale));
+            text = rewrite(text, numbers::expand_currency);
+            text = rewrite(text, numbers::expand_scientific);
+            text = rewrite(text, numbers::expand_times);
+            text = rewrite(text, numbers::expand_ranges);
+            text = rewrite(text, numbers::expand_quantities);
+            text = rewrite(text, numbers::expand_dotted);
+        }
+        text
+    }
+    
+    /// 按句子切分，每个句子再切成引号内外的片段
+    ///
+    /// 引号外的 `.`、`!`、`?` 后面跟着大写字母、引号或文本结尾时断句，缩写（`Dr.`）和单个字母后不断句。
+    /// 引号内以句末标点结尾时，引号后面不是说话人标记才断句：`"Stop!" he said.` 是一个句子，
+    /// `He said, "Stop!" Then he left.` 是两个句子。支持直引号 `"` 和弯引号 `“”`，未闭合的引号延续到文本结尾。
+    pub fn sentences<'t>(&self, text: &'t str) -> Vec<Vec<Span<'t>>> {
+        let mut sentences = Vec::new();
+        let mut spans = Vec::new();
+        let mut start = 0;
+        let mut closing = None; // 当前所在引语的闭合引号
         
+        for (i, c) in text.char_indices() {
+            let end = i + c.len_utf8();
+            match (closing, c) {
+                (None, '"' | '“') => {
+                    push_span(&mut spans, Span::Narration(&text[start..i]));
+                    closing = Some(if c == '“' { '”' } else { '"' });
+                    start = end;
+                }
+                (Some(quote), _) if c == quote => {
+                    let inner = &text[start..i];
+                    push_span(&mut spans, Span::Quote(inner));
+                    closing = None;
+                    start = end;
+                    if inner.trim_end().ends_with(['.', '!', '?']) && starts_sentence(&text[end..]) {
+                        push_sentence(&mut sentences, &mut spans);
+                    }
+                }
+                (None, '.' | '!' | '?') if self.ends_sentence(&text[start..i], c, &text[end..]) => {
+                    push_span(&mut spans, Span::Narration(&text[start..end]));
+                    start = end;
+                    push_sentence(&mut sentences, &mut spans);
+                }
+                _ => {}
+            }
+        }
+        let rest = &text[start..];
+        push_span(&mut spans, if closing.is_some() { Span::Quote(rest) } else { Span::Narration(rest) });
+        push_sentence(&mut sentences, &mut spans);
+        sentences
+    }
+    
+    /// 句末标点后是否断句
+    fn ends_sentence(&self, before: &str, punctuation: char, after: &str) -> bool {
+        if !after.is_empty() && !after.starts_with(char::is_whitespace) || !starts_sentence(after) {
+            return false;
+        }
+        if punctuation != '.' {
+            return true;
+        }
+        let word = before.rsplit(char::is_whitespace).next().unwrap_or_default().to_lowercase();
+        word.chars().count() > 1 && !self.abbreviations.contains_key(format!("{}.", word).as_str())
+    }
+    
+    /// 按配置处理非语言声音标记、填充词、拉丁缩写和字母序列，切分出需要特殊读法的片段
+    ///
+    /// 返回的 `Text` 片段仍需经过 `normalize`；展开后的拉丁缩写也作为 `Text` 片段返回。
+    pub fn segments<'t>(&self, text: &'t str) -> Vec<Segment<'t>> {
+        let events = if self.options.non_verbal == NonVerbalStyle::Literal {
+            vec![Segment::Text(text)]
+        } else {
+            split_events(text)
+        };
+        
+        events
+            .into_iter()
+            .flat_map(|segment| match segment {
+                Segment::Text(text) => split_fillers(text),
+                other => vec![other],
+            })
+            .flat_map(|segment| match segment {
+                Segment::Text(text) if self.options.expand_abbreviations => self.split_latin_abbreviations(text),
+                other => vec![other],
+            })
+            .flat_map(|segment| match segment {
+                Segment::Text(text) => split_scripts(text),
+                other => vec![other],
+            })
+            .flat_map(|segment| match segment {
+                Segment::Text(text) => split_letter_sequences(text),
+                other => vec![other],
+            })
+            .collect()
+    }
+    
+    /// 按 `latin_style` 替换拉丁缩写，不区分大小写
+    fn split_latin_abbreviations<'t>(&self, text: &'t str) -> Vec<Segment<'t>> {
+        lazy_static! {
+            static ref LATIN_RE: Regex = {
+                let alternatives: Vec<String> = LATIN_ABBREVIATIONS
+                    .iter()
+                    .map(|(abbrev, _, _)| regex::escape(abbrev).replace(' ', r"\s+"))
+                    .collect();
+                Regex::new(&format!(r#"(?i)(?:^|[\s("])({})"#, alternatives.join("|"))).unwrap()
+            };
+            static ref SPACES_RE: Regex = Regex::new(r"\s+").unwrap();
+        }
+        
+        let mut segments = Vec::new();
+        let mut last = 0;
+        for caps in LATIN_RE.captures_iter(text) {
+            let found = caps.get(1).unwrap();
+            if text[found.end()..].starts_with(|c: char| c.is_alphanumeric()) {
+                continue;
+            }
+            let key = SPACES_RE.replace_all(&found.as_str().to_lowercase(), " ").into_owned();
+            let Some((_, expansion, reading)) = LATIN_ABBREVIATIONS.iter().find(|(abbrev, _, _)| *abbrev == key) else {
+                continue;
+            };
+            
+            if found.start() > last {
+                segments.push(Segment::Text(&text[last..found.start()]));
+            }
+            segments.push(match (self.options.latin_style, reading) {
+                (LatinStyle::Expand, _) => Segment::Text(expansion),
+                (LatinStyle::Letters, LatinReading::Letters) => Segment::Letters(found.as_str()),
+                (LatinStyle::Letters, LatinReading::Words(words)) => Segment::Text(words),
+            });
+            last = found.end();
+        }
+        if last < text.len() {
+            segments.push(Segment::Text(&text[last..]));
+        }
+        segments
+    }
+    
+    /// 文本标准化
+    ///
+    /// 每一步没有改动时沿用上一步的结果，不复制文本；已经标准化的文本（小写、单个空格分隔）直接借用输入。
+    pub fn normalize<'t>(&self, text: &'t str) -> Result<Cow<'t, str>> {
+        self.normalize_in_context(text, &[], &[])
+    }
+    
+    /// 标准化文本中的一段，`previous`（由近及远）和 `following` 为这一段前后的原文单词，
+    /// 段首段尾的数字按它们选择读法和性、格，与整段标准化的结果一致
+    pub fn normalize_in_context<'t>(&self, text: &'t str, previous: &[&str], following: &[&str]) -> Result<Cow<'t, str>> {
         // 1. 转小写
-        result = result.to_lowercase();
+        let mut result = self.options.case_rules.lowercase(text);
         
         // 2. 处理缩写
-        result = self.expand_abbreviations(&result);
+        if self.options.expand_abbreviations {
+            result = rewrite(result, |text| self.expand_abbreviations(text));
+        }
         
         // 3. 处理数字
-        result = self.expand_numbers(&result);
+        if let Some(number_words) = self.number_words() {
+            let lowercase = |words: &[&str]| -> Vec<String> {
+                words.iter().map(|word| self.options.case_rules.lowercase(word).into_owned()).collect()
+            };
+            let (previous, following) = (lowercase(previous), lowercase(following));
+            result = rewrite(result, |text| self.expand_numbers(number_words, text, &previous, &following));
+        }
         
         // 4. 清理标点符号
-        result = self.clean_punctuation(&result);
+        if self.options.strip_punctuation {
+            result = rewrite(result, |text| self.clean_punctuation(text));
+        }
         
         // 5. 标准化空格
-        result = self.normalize_whitespace(&result);
+        result = rewrite(result, |text| self.normalize_whitespace(text));
         
         Ok(result)
     }
     
     /// 分词
     pub fn tokenize(&self, text: &str) -> Result<Vec<String>> {
-        let words: Vec<String> = text
-            .split_whitespace()
-            .filter(|word| !word.is_empty())
-            .map(|word| word.trim_matches(|c: char| !c.is_alphabetic()))
-            .filter(|word| !word.is_empty())
+        let words: Vec<String> = self.tokens(text)
             .map(|word| word.to_string())
             .collect();
         
         Ok(words)
     }
     
-    /// 展开缩写
-    fn expand_abbreviations(&self, text: &str) -> String {
-        let mut result = text.to_string();
-        
-        for (abbrev, expansion) in &self.abbreviations {
-            result = result.replace(abbrev, expansion);
+    /// 分词，直接借用输入文本
+    pub(crate) fn tokens<'t>(&self, text: &'t str) -> impl Iterator<Item = &'t str> {
+        text
+            .split_whitespace()
+            .filter(|word| !word.is_empty())
+            .map(|word| word.trim_matches(|c: char| !c.is_alphabetic()))
+            .filter(|word| !word.is_empty())
+    }
+    
+    /// 展开缩写，只匹配完整的单词，`first.` 中的 `st.` 不受影响
+    fn expand_abbreviations<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        lazy_static! {
+            static ref ABBREVIATION_RE: Regex = Regex::new(r"\b[a-z]+\.").unwrap();
         }
         
-        result
+        ABBREVIATION_RE.replace_all(text, |caps: &regex::Captures| {
+            self.abbreviations.get(&caps[0]).map_or(&caps[0], |expansion| *expansion).to_string()
+        })
+    }
+    
+    /// 展开数字时使用的读法，关闭 `expand_numbers` 或没有读法时为None
+    fn number_words(&self) -> Option<&dyn NumberWords> {
+        self.number_words.as_deref().filter(|_| self.options.expand_numbers)
     }
     
-    /// 展开数字
-    fn expand_numbers(&self, text: &str) -> String {
+    /// 展开数字，`1st`、`22nd` 这样的序数词读作序数，`1980s`、`80s` 读作年代（见 `numbers::decade`），
+    /// 其余按上下文读作基数、序数或年份（见 `numbers::reading_in_context`）；
+    /// 数词的性和格由后面的单词决定；`before` 和 `after` 为文本前后的单词，`before` 由近及远
+    fn expand_numbers<'a>(&self, number_words: &dyn NumberWords, text: &'a str, before: &[String], after: &[String]) -> Cow<'a, str> {
         lazy_static! {
-            static ref NUMBER_RE: Regex = Regex::new(r"\b\d+\b").unwrap();
+            static ref NUMBER_RE: Regex = Regex::new(r"\b(\d+)(st|nd|rd|th|s)?\b").unwrap();
         }
         
+        fn trim(word: &str) -> &str {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+        }
         NUMBER_RE.replace_all(text, |caps: &regex::Captures| {
-            let number = &caps[0];
-            self.number_words.get(number).unwrap_or(&number).to_string()
-        }).to_string()
+            let whole = caps.get(0).unwrap();
+            let previous: Vec<&str> = text[..whole.start()]
+                .split_whitespace()
+                .rev()
+                .chain(before.iter().map(String::as_str))
+                .take(2)
+                .map(trim)
+                .collect();
+            let following: Vec<&str> = text[whole.end()..]
+                .split_whitespace()
+                .chain(after.iter().map(String::as_str))
+                .take(2)
+                .map(trim)
+                .collect();
+            let agreement = number_words.agreement(following.first().copied().filter(|word| !word.is_empty()));
+            let Ok(n) = caps[1].parse() else {
+                return number_words.digits(&caps[1]);
+            };
+            match caps.get(2).map(|suffix| suffix.as_str()) {
+                Some("s") => return numbers::decade(&caps[1]),
+                Some(_) => return number_words.ordinal(n, agreement),
+                None => {}
+            }
+            match numbers::reading_in_context(&previous, &caps[1], &following) {
+                NumberReading::Cardinal => number_words.cardinal(n, agreement),
+                NumberReading::Ordinal => number_words.ordinal(n, agreement),
+                NumberReading::Year => number_words.year(n),
+            }
+        })
     }
     
     /// 清理标点符号
-    fn clean_punctuation(&self, text: &str) -> String {
+    fn clean_punctuation<'a>(&self, text: &'a str) -> Cow<'a, str> {
         lazy_static! {
             static ref PUNCT_RE: Regex = Regex::new(r"[^\w\s]").unwrap();
         }
         
-        PUNCT_RE.replace_all(text, " ").to_string()
+        // 单词内部的撇号（don't、o'clock）保留，统一为直撇号
+        PUNCT_RE.replace_all(text, |caps: &regex::Captures| {
+            let mark = caps.get(0).unwrap();
+            let inside_word = matches!(mark.as_str(), "'" | "’")
+                && text[..mark.start()].chars().next_back().is_some_and(char::is_alphanumeric)
+                && text[mark.end()..].chars().next().is_some_and(char::is_alphanumeric);
+            if inside_word { "'" } else { " " }
+        })
     }
     
-    /// 标准化空格
-    fn normalize_whitespace(&self, text: &str) -> String {
+    /// 标准化空格，已经是单个空格分隔且首尾没有空白时不复制
+    fn normalize_whitespace<'a>(&self, text: &'a str) -> Cow<'a, str> {
         lazy_static! {
             static ref WHITESPACE_RE: Regex = Regex::new(r"\s+").unwrap();
         }
         
-        WHITESPACE_RE.replace_all(text.trim(), " ").to_string()
+        let normalized = text.trim().len() == text.len()
+            && !text.contains("  ")
+            && !text.contains(|c: char| c.is_whitespace() && c != ' ');
+        if normalized {
+            return Cow::Borrowed(text);
+        }
+        Cow::Owned(WHITESPACE_RE.replace_all(text.trim(), " ").into_owned())
     }
 }
@@ -0,0 +1,189 @@
+use crate::learn;
+use crate::phoneme::{self, Notation, Phoneme};
+use crate::rules::{Rule, RuleStep};
+use crate::{letters, translit, Fallback, RustG2P};
+use crate::error::Result;
+use std::fmt;
+use std::ops::Range;
+
+/// 单词发音的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordSource {
+    Dictionary,
+    User, // 运行时加入的用户词典层
+    Cache,
+    Irregular, // 不规则词汇层或规则文件中的 IRREGULAR 行
+    Stem,      // 还原词干后在词典中找到
+    Rules,
+    /// 规则输出不可靠，使用了后备策略（`Fallback::Error` 时实际转换会报错）
+    Fallback(Fallback),
+}
+
+/// 单词转换的推导过程
+#[derive(Debug, Clone)]
+pub struct WordTrace {
+    pub word: String,
+    pub source: WordSource,
+    pub confidence: Option<f32>, // 仅规则路径有置信度
+    pub steps: Vec<RuleStep>,    // 规则路径上每一步匹配的规则
+    pub phonemes: Vec<Phoneme>,
+}
+
+/// 被规则映射为零个音素的字母
+#[derive(Debug, Clone)]
+pub struct SilentLetter {
+    pub position: usize, // 字符位置
+    pub letter: char,
+    pub rule: Rule,      // 输出为 SILENT 的规则
+}
+
+impl fmt::Display for SilentLetter {
+    /// 形如 `e@4: #[e]_ -> SILENT (priority 2, word_end)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}: {}", self.letter, self.position, self.rule)
+    }
+}
+
+/// 字母在拼读中的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LetterCategory {
+    Consistent,  // 实际读音与拼读规则一致
+    Exceptional, // 实际读音与规则不同
+    Silent,      // 不发音
+}
+
+/// 单个字母的拼读标注
+#[derive(Debug, Clone)]
+pub struct LetterHighlight {
+    pub position: usize,        // 字符位置
+    pub letter: char,
+    pub grapheme: Range<usize>, // 字母所属的字母组合（如 `sh`）的字符范围
+    pub phonemes: Vec<Phoneme>, // 字母组合在实际读音中对应的音素，同一组合的字母相同
+    pub category: LetterCategory,
+}
+
+impl RustG2P {
+    /// 逐字母标注单词的拼读，供自然拼读教学界面按类别给字母着色
+    ///
+    /// 按规则推导切分字母组合，再把实际读音（用户词典、词典、不规则词汇或规则的结果）对齐到各组合：
+    /// 对应的音素为空的是不发音字母，与规则输出相同（忽略重音）的是规则字母，其余为例外。
+    pub fn highlight_letters(&self, word: &str) -> Result<Vec<LetterHighlight>> {
+        let word = word.to_lowercase();
+        let pronunciation = self.trace_word(&word)?.phonemes;
+        let (_, steps) = self.rules_engine.trace_rules(&word)?;
+        let assigned = learn::assign_phonemes(&steps, &pronunciation);
+        
+        let mut highlights = Vec::new();
+        for (step, actual) in steps.iter().zip(assigned) {
+            let predicted = step.phonemes.iter().map(|p| learn::strip_stress(p));
+            let category = if actual.is_empty() {
+                LetterCategory::Silent
+            } else if predicted.eq(actual.iter().map(|p| p.symbol.as_str())) {
+                LetterCategory::Consistent
+            } else {
+                LetterCategory::Exceptional
+            };
+            let grapheme = step.position..step.position + step.letters.chars().count();
+            let phonemes: Vec<Phoneme> = actual.into_iter().cloned().collect();
+            for (i, letter) in step.letters.chars().enumerate() {
+                highlights.push(LetterHighlight {
+                    position: step.position + i,
+                    letter,
+                    grapheme: grapheme.clone(),
+                    phonemes: phonemes.clone(),
+                    category,
+                });
+            }
+        }
+        Ok(highlights)
+    }
+    
+    /// 找出按规则推导时不发音的字母及造成不发音的规则，供拼写教学标注哑字母
+    ///
+    /// 无论单词是否在词典中都按规则分析；只报告 SILENT 规则，`kn -> N` 这类多字母规则不拆分到单个字母。
+    /// 不规则词汇整体查表，没有逐字母的推导，结果为空。
+    pub fn silent_letters(&self, word: &str) -> Result<Vec<SilentLetter>> {
+        let (_, steps) = self.rules_engine.trace_rules(&word.to_lowercase())?;
+        let mut silent = Vec::new();
+        for step in steps {
+            if let (Some(rule), true) = (&step.rule, step.phonemes.is_empty()) {
+                for (i, letter) in step.letters.chars().enumerate() {
+                    silent.push(SilentLetter { position: step.position + i, letter, rule: rule.clone() });
+                }
+            }
+        }
+        Ok(silent)
+    }
+    
+    /// 给出单词的推导过程，不计入统计也不通知观察者
+    pub fn trace_word(&self, word: &str) -> Result<WordTrace> {
+        let lowercase = word.to_lowercase();
+        let mut trace = WordTrace {
+            word: lowercase.clone(),
+            source: WordSource::Dictionary,
+            confidence: None,
+            steps: Vec::new(),
+            phonemes: Vec::new(),
+        };
+        
+        if let Some(codes) = self.lookup_user(&lowercase) {
+            trace.source = WordSource::User;
+            trace.phonemes = codes.iter().map(|code| code.decode()).collect();
+            return Ok(trace);
+        }
+        if let Some(phonemes) = self.dictionary.lookup(&lowercase) {
+            trace.phonemes = phonemes;
+            return Ok(trace);
+        }
+        if let Some(latin) = self.transliteration.then(|| translit::transliterate(&lowercase)).flatten() {
+            return Ok(WordTrace { word: lowercase, ..self.trace_word(&latin)? });
+        }
+        if let Some(phonemes) = self.irregular.lookup(&lowercase) {
+            trace.source = WordSource::Irregular;
+            trace.phonemes = phonemes;
+            return Ok(trace);
+        }
+        if let Some((_, phonemes)) = self.stem_recovery.then(|| self.recover_stem(&lowercase)).flatten() {
+            trace.source = WordSource::Stem;
+            trace.phonemes = phonemes;
+            return Ok(trace);
+        }
+        if let Some(phonemes) = self.cache.as_ref().and_then(|cache| cache.get(&lowercase)) {
+            trace.source = WordSource::Cache;
+            trace.phonemes = phonemes;
+            return Ok(trace);
+        }
+        
+        let (output, steps) = self.rules_engine.trace_rules(&lowercase)?;
+        trace.confidence = Some(output.confidence);
+        trace.steps = steps;
+        trace.source = if output.irregular { WordSource::Irregular } else { WordSource::Rules };
+        if self.needs_fallback(&output) {
+            trace.source = WordSource::Fallback(self.routing.fallback);
+        }
+        trace.phonemes = match trace.source {
+            WordSource::Fallback(Fallback::SpellOut) => letters::spell_out(&lowercase),
+            _ => output.phonemes,
+        };
+        Ok(trace)
+    }
+}
+
+impl fmt::Display for WordTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} -> {}", self.word, phoneme::render(&self.phonemes, Notation::Arpabet))?;
+        write!(f, "  source: {:?}", self.source)?;
+        if let Some(confidence) = self.confidence {
+            write!(f, " (confidence {:.2})", confidence)?;
+        }
+        for step in &self.steps {
+            write!(f, "\n  {:>2} {:<4} ", step.position, step.letters)?;
+            match &step.rule {
+                Some(rule) => write!(f, "{}", rule)?,
+                None if step.phonemes.is_empty() => write!(f, "(no rule, skipped)")?,
+                None => write!(f, "(default) -> {}", step.phonemes.join(" "))?,
+            }
+        }
+        Ok(())
+    }
+}
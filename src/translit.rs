@@ -0,0 +1,122 @@
+//! 非拉丁字母转写为拉丁字母
+//!
+//! 转换器只加载英语语言包，英语文本中的希腊字母、西里尔字母单词（"αλφα"、"Москва"）
+//! 先按下面的表转写（"alfa"、"moskva"），再查词典或按英语规则读，不再读成空音素。
+//! 希腊字母按 ELOT 743，西里尔字母按俄语的 BGN/PCGN 简化写法，另收乌克兰语和塞尔维亚语的字母。
+
+/// 字符所属的文字
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Digit, // ASCII数字
+    Other, // 其他文字的字母和数字，没有对应的读法
+}
+
+/// 字母和数字所属的文字，标点、空白和组合附加符号返回 `None`
+pub fn script_of(c: char) -> Option<Script> {
+    let script = match c {
+        '0'..='9' => Script::Digit,
+        _ if !c.is_alphanumeric() => return None,
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => Script::Latin,
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Script::Greek,
+        '\u{0400}'..='\u{052F}' => Script::Cyrillic,
+        _ => Script::Other,
+    };
+    Some(script)
+}
+
+/// 小写字母的转写，没有的字符原样保留
+fn transliterate_char(c: char) -> Option<&'static str> {
+    let latin = match c {
+        // 希腊字母，带重音符号和分音符的元音按不带的转写
+        'α' | 'ά' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' | 'έ' => "e",
+        'ζ' => "z",
+        'η' | 'ή' => "i",
+        'θ' => "th",
+        'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' | 'ό' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' | 'ώ' => "o",
+        // 西里尔字母
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' | 'э' => "e",
+        'ё' => "yo",
+        'є' => "ye",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' | 'ы' => "y",
+        'ј' => "j",
+        'к' => "k",
+        'л' => "l",
+        'љ' => "lj",
+        'м' => "m",
+        'н' => "n",
+        'њ' => "nj",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'ћ' => "c",
+        'ђ' => "dj",
+        'у' => "u",
+        'ў' => "w",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'џ' => "dz",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    };
+    Some(latin)
+}
+
+/// 是否为可以转写的字母
+pub fn is_transliterable(c: char) -> bool {
+    c.to_lowercase().all(|c| transliterate_char(c).is_some())
+}
+
+/// 把单词中的希腊字母和西里尔字母转写为小写拉丁字母，其他字符原样保留（小写）；
+/// 单词中没有可转写的字母时返回 `None`
+pub fn transliterate(word: &str) -> Option<String> {
+    if !word.chars().any(is_transliterable) {
+        return None;
+    }
+    let mut latin = String::with_capacity(word.len());
+    for c in word.chars().flat_map(char::to_lowercase) {
+        match transliterate_char(c) {
+            Some(letters) => latin.push_str(letters),
+            None => latin.push(c),
+        }
+    }
+    Some(latin)
+}
@@ -1,4 +1,4 @@
-use rust_g2p::RustG2P;
+use rust_g2p::{GoldenSnapshot, LexiconLayer, RustG2P};
 
 #[test]
 fn test_basic_word_conversion() {
@@ -41,3 +41,1770 @@ fn test_rules_engine() {
     let phonemes = g2p.word_to_phonemes("pseudoword").unwrap();
     assert!(!phonemes.is_empty());
 }
+
+#[test]
+fn test_confidence_routing() {
+    use rust_g2p::{Fallback, RoutingConfig};
+
+    let routing = RoutingConfig {
+        min_confidence: 0.0,
+        require_valid_phonotactics: true,
+        fallback: Fallback::SpellOut,
+    };
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_routing(routing.clone());
+
+    // 没有元音的规则输出转为逐字母拼读
+    let symbols: Vec<String> = g2p.word_to_phonemes("bcdx").unwrap().iter().map(|p| p.symbol.clone()).collect();
+    assert_eq!(symbols, vec!["B", "IY", "S", "IY", "D", "IY", "EH", "K", "S"]);
+
+    let strict = RustG2P::new()
+        .expect("Failed to create G2P")
+        .with_routing(RoutingConfig { fallback: Fallback::Error, ..routing });
+    assert!(strict.word_to_phonemes("bcdx").is_err());
+    assert!(strict.word_to_phonemes("hello").is_ok());
+}
+
+#[test]
+fn test_compare_strategies() {
+    use rust_g2p::eval::{compare_strategies, DiffOp};
+    use rust_g2p::{Fallback, RoutingConfig};
+
+    let baseline = RustG2P::new().expect("Failed to create G2P");
+    let spelled = RustG2P::new().expect("Failed to create G2P").with_routing(RoutingConfig {
+        min_confidence: 0.0,
+        require_valid_phonotactics: true,
+        fallback: Fallback::SpellOut,
+    });
+
+    let report = compare_strategies(&baseline, &spelled, &["hello", "world", "bcdx"]);
+    assert_eq!(report.total, 3);
+    assert_eq!(report.disagreements.len(), 1);
+    assert_eq!(report.disagreements[0].word, "bcdx");
+    assert!(report.disagreements[0].diff().iter().any(|op| matches!(op, DiffOp::Insert(_))));
+    assert!(report.render().contains("bcdx"));
+}
+
+#[test]
+fn test_compare_pronunciations() {
+    use rust_g2p::compare::EditKind;
+    use rust_g2p::compare_pronunciations;
+    use rust_g2p::phoneme::parse_arpabet;
+
+    // 学习者把 photograph 的重音放错，读错了第二个元音，末尾多读了一个 S
+    let expected = parse_arpabet("F OW1 T AH0 G R AE2 F").unwrap();
+    let observed = parse_arpabet("F OW0 T AA1 G R AE2 F S").unwrap();
+    let report = compare_pronunciations(&expected, &observed);
+    assert!(!report.is_correct());
+    assert_eq!(report.stress_errors(), 2);
+    assert_eq!(report.phoneme_errors(), 2);
+    assert_eq!(report.edits[1].kind, EditKind::Match);
+    assert!(report.edits[1].stress_mismatch);
+    assert_eq!(report.edits[3].kind, EditKind::Substitute);
+    assert_eq!(report.to_string(), "F [OW1≠OW0] T [AH0→AA1] G R AE2 F [+S]");
+
+    let report = compare_pronunciations(&expected, &expected[..7]);
+    assert_eq!(report.edits.last().unwrap().kind, EditKind::Delete);
+    assert!(compare_pronunciations(&expected, &expected).is_correct());
+}
+
+#[test]
+fn test_phoneme_alignment() {
+    use rust_g2p::align::{align, feature_distance, AlignCosts, AlignStep, SubstitutionCost};
+    use rust_g2p::phoneme::parse_arpabet;
+
+    let p = |s: &str| parse_arpabet(s).unwrap();
+    let (t, d, iy) = (&p("T")[0], &p("D")[0], &p("IY1")[0]);
+    assert!(feature_distance(t, d) < feature_distance(t, iy));
+    assert_eq!(feature_distance(iy, &p("IY0")[0]), 0.0);
+    assert!(feature_distance(&p("IH")[0], iy) > 0.0);
+
+    // 按特征计算代价时，D 对齐到相近的 T 而不是元音
+    let alignment = align(&p("IY1 T"), &p("D"), &AlignCosts::features());
+    assert_eq!(alignment.steps, vec![AlignStep::Delete(0), AlignStep::Substitute(1, 0)]);
+    assert_eq!(alignment.edits(), 2);
+    assert!(alignment.cost < 2.0);
+
+    let uniform = align(&p("K AE1 T"), &p("K AE0 T S"), &AlignCosts::default());
+    assert_eq!(uniform.cost, 1.0);
+    assert_eq!(uniform.steps.last(), Some(&AlignStep::Insert(3)));
+
+    let cheap_inserts = AlignCosts { insertion: 0.1, deletion: 0.1, substitution: SubstitutionCost::Uniform(1.0) };
+    assert_eq!(align(&p("T"), &p("D"), &cheap_inserts).steps.len(), 2);
+}
+
+#[test]
+fn test_phonetic_search() {
+    use rust_g2p::PhoneticIndex;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let documents = ["Meeting notes from Kirsten", "Invoice for Mr. Smith", "Lunch with Stephen and Smith"];
+    let index = PhoneticIndex::build(&g2p, &documents).unwrap();
+    assert_eq!(index.len(), 3);
+
+    // 拼写不同、读音相同的单词完全匹配
+    let hits = index.search(&g2p, "smyth", 0).unwrap();
+    assert_eq!(hits.iter().map(|hit| hit.document).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(hits[0].matched, vec!["smith"]);
+
+    // 读音相近的单词在允许的距离内匹配，多个查询词都须匹配
+    let hits = index.search(&g2p, "kristen", 1).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(index.document(hits[0].document), Some("Meeting notes from Kirsten"));
+    assert_eq!(index.search(&g2p, "steven smith", 0).unwrap().len(), 1);
+    assert!(index.search(&g2p, "kristen", 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_syllabify() {
+    use rust_g2p::phoneme::parse_arpabet;
+    use rust_g2p::syllable::syllabify;
+
+    let split = |pronunciation: &str| -> Vec<String> {
+        let phonemes = parse_arpabet(pronunciation).unwrap();
+        syllabify(&phonemes)
+            .iter()
+            .map(|s| phonemes[s.start..s.end].iter().map(|p| p.symbol.as_str()).collect::<Vec<_>>().join(" "))
+            .collect()
+    };
+    // 最大首音：ST R 可以作首音，N T 不行
+    assert_eq!(split("AH0 B S T R AE1 K T"), vec!["AH B", "S T R AE K T"]);
+    assert_eq!(split("W IH1 N T ER0"), vec!["W IH N", "T ER"]);
+    assert_eq!(split("S IH1 NG ER0"), vec!["S IH NG", "ER"]);
+    assert_eq!(split("HH M"), vec!["HH M"]);
+}
+
+#[test]
+fn test_lyrics_mode() {
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+
+    let syllables = g2p.lyrics_to_syllables("A-ma-zing grace__ how sweet").unwrap();
+    let texts: Vec<Option<&str>> = syllables.iter().map(|s| s.text.as_deref()).collect();
+    assert_eq!(texts, vec![Some("A"), Some("ma"), Some("zing"), None, None, None]);
+    assert!(syllables[0].word_start && !syllables[1].word_start && syllables[3].word_start);
+    assert_eq!(syllables[1].word, "Amazing");
+    assert_eq!(syllables[1].sustained().unwrap().symbol, "EY");
+
+    // 延长线：grace 占三个音符
+    assert_eq!(syllables[3].notes, 3);
+    assert!(syllables[3].is_melisma() && !syllables[4].is_melisma());
+
+    // 空格分隔的 `--` 与连字符相同，单独的 `_` 延长前一个音节
+    let spaced = g2p.lyrics_to_syllables("a -- ma -- zing _").unwrap();
+    assert_eq!(spaced.len(), 3);
+    assert_eq!(spaced[2].text.as_deref(), Some("zing"));
+    assert_eq!(spaced[2].notes, 2);
+
+    // 不带连字符的多音节单词自动切分
+    assert_eq!(g2p.lyrics_to_syllables("beautiful").unwrap().len(), 3);
+}
+
+#[test]
+fn test_budgeted_conversion() {
+    use rust_g2p::{Fallback, RoutingConfig};
+    use std::time::Duration;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_routing(RoutingConfig {
+        min_confidence: 0.0,
+        require_valid_phonotactics: true,
+        fallback: Fallback::Error,
+    });
+
+    // 预算充足时走正常路径（后备策略报错）
+    assert!(g2p.text_to_phonemes_budgeted("hello bcdx", Duration::from_secs(60)).is_err());
+
+    // 预算耗尽时跳过后备策略并标记单词
+    let output = g2p.text_to_phonemes_budgeted("hello bcdx", Duration::ZERO).unwrap();
+    assert_eq!(output.degraded_words, vec!["bcdx"]);
+    assert!(!output.phonemes.is_empty());
+    
+    // 超出预算后也不再还原词干
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let output = g2p.text_to_phonemes_budgeted("vaped", Duration::ZERO).unwrap();
+    assert_eq!((output.degraded_words, g2p.get_stats().stem_hits), (vec!["vaped".to_string()], 0));
+    let output = g2p.text_to_phonemes_budgeted("vaped", Duration::from_secs(60)).unwrap();
+    assert!(output.degraded_words.is_empty());
+    assert_eq!(output.phonemes, g2p.text_to_phonemes("vaped").unwrap());
+}
+
+#[test]
+fn test_batch_session() {
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let mut session = g2p.batch_session();
+
+    let symbols = session.convert_document("Hello, world!").unwrap().to_vec();
+    assert_eq!(symbols, vec!["HH", "AH", "L", "OW", "|", "W", "ER", "L", "D", "|"]);
+
+    // 每篇文档开始时重置内存池，占用不随文档数增长
+    session.convert_document("Hello, world!").unwrap();
+    let first = session.allocated_bytes();
+    for _ in 0..100 {
+        session.convert_document("Hello, world!").unwrap();
+    }
+    assert!(session.allocated_bytes() <= first);
+}
+
+#[test]
+fn test_batch_session_matches_text_to_phonemes() {
+    use rust_g2p::phoneme::{BoundarySymbols, StressMarks};
+    let g2p = RustG2P::new()
+        .expect("Failed to create G2P")
+        .with_phoneme_input(true)
+        .with_linking_r()
+        .with_rhythm_rule(true)
+        .with_pause_markers(true)
+        .with_phone_stress(StressMarks::Keep);
+    let text = "Say /HH AH0 L OW1/ now, far away. In 1984 the thirteen men met.";
+    let mut session = g2p.batch_session();
+    let symbols = session.convert_document(text).unwrap().to_vec();
+    let phones: Vec<&str> = symbols.iter().copied().filter(|s| !["|", ",", "."].contains(s)).collect();
+    assert_eq!(phones.join(" "), g2p.text_to_phone_string(text).unwrap());
+    // 斜杠音素输入不再逐个字母拼读，连读R与 `text_to_phonemes` 相同
+    assert_eq!(symbols[..8], ["S", "EY1", "|", "HH", "AH0", "L", "OW1", "|"]);
+    assert!(symbols.join(" ").contains("F AA1 | R AH0"));
+    assert!(symbols.contains(&",") && symbols.contains(&"."));
+
+    // 边界写法和词边界开关
+    let g2p = g2p.with_boundary_symbols(BoundarySymbols { word: "sp".into(), pause: "sil".into(), sentence: String::new() });
+    let mut session = g2p.batch_session();
+    let symbols = session.convert_document("far away, now.").unwrap();
+    assert!(symbols.contains(&"sp") && symbols.contains(&"sil") && !symbols.contains(&"."));
+    let g2p = g2p.with_word_boundaries(false);
+    let mut session = g2p.batch_session();
+    assert!(!session.convert_document("far away").unwrap().contains(&"sp"));
+}
+
+#[test]
+fn test_disk_cache() {
+    use rust_g2p::{DiskCache, Fallback, RoutingConfig};
+
+    let path = std::env::temp_dir().join(format!("rust_g2p_cache_{}.tsv", std::process::id()));
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let g2p = RustG2P::new()
+        .expect("Failed to create G2P")
+        .with_routing(RoutingConfig {
+            min_confidence: 0.0,
+            require_valid_phonotactics: true,
+            fallback: Fallback::SpellOut,
+        })
+        .with_disk_cache(DiskCache::open(path).unwrap());
+
+    // 只缓存未登录词
+    let spelled = g2p.word_to_phonemes("bcdx").unwrap();
+    g2p.word_to_phonemes("hello").unwrap();
+    assert!(g2p.memory_report().cache_bytes > 0);
+    drop(g2p);
+
+    // 重新打开时从文件预热
+    let cache = DiskCache::open(path).unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get("bcdx"), Some(spelled));
+    drop(cache);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_runtime_stats() {
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+
+    g2p.text_to_phonemes("hello bcdx").unwrap();
+    let stats = g2p.get_stats();
+    assert_eq!(stats.words_converted, 2);
+    assert_eq!(stats.dictionary_hits, 1);
+    assert_eq!(stats.rule_words + stats.irregular_hits, 1);
+    assert_eq!(stats.oov_rate(), 0.5);
+    assert_eq!(stats.words_by_language.get("en"), Some(&2));
+
+    g2p.reset_stats();
+    assert_eq!(g2p.get_stats().words_converted, 0);
+    assert!(g2p.get_stats().dict_entries > 0);
+}
+
+#[test]
+fn test_observer() {
+    use rust_g2p::{Fallback, Observer, Phoneme, RoutingConfig};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct EventLog(Mutex<Vec<String>>);
+
+    impl Observer for EventLog {
+        fn on_word_converted(&self, word: &str, _phonemes: &[Phoneme]) {
+            self.0.lock().unwrap().push(format!("converted {}", word));
+        }
+        fn on_oov(&self, word: &str) {
+            self.0.lock().unwrap().push(format!("oov {}", word));
+        }
+        fn on_fallback(&self, word: &str, fallback: Fallback) {
+            self.0.lock().unwrap().push(format!("fallback {} {:?}", word, fallback));
+        }
+        fn on_error(&self, word: &str, _error: &rust_g2p::G2PError) {
+            self.0.lock().unwrap().push(format!("error {}", word));
+        }
+    }
+
+    let log = Arc::new(EventLog::default());
+    let g2p = RustG2P::new()
+        .expect("Failed to create G2P")
+        .with_routing(RoutingConfig {
+            min_confidence: 0.0,
+            require_valid_phonotactics: true,
+            fallback: Fallback::Error,
+        })
+        .with_observer(log.clone());
+
+    g2p.word_to_phonemes("hello").unwrap();
+    assert!(g2p.word_to_phonemes("bcdx").is_err());
+    assert_eq!(
+        *log.0.lock().unwrap(),
+        vec!["converted hello", "oov bcdx", "fallback bcdx Error", "error bcdx"]
+    );
+}
+
+#[test]
+fn test_strict_mode() {
+    use rust_g2p::G2PError;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    // 默认跳过无法处理的字符
+    assert!(!g2p.word_to_phonemes("naïve").unwrap().is_empty());
+
+    let g2p = g2p.with_strict(true);
+    let error = g2p.text_to_phonemes("a naïve idea").unwrap_err();
+    let G2PError::UnknownCharacter(error) = error else {
+        panic!("expected an unknown character error, got {:?}", error);
+    };
+    assert_eq!(error.word, "naïve");
+    assert_eq!(error.offset, 2);
+    assert_eq!(error.character, 'ï');
+    // 附带拼写和读音相近的词典单词
+    assert_eq!(error.suggestions.spelling[0], "naive");
+    assert!(!error.suggestions.sound.is_empty());
+    assert!(error.to_string().contains("did you mean: naive"));
+
+    assert!(g2p.word_to_phonemes("hello").is_ok());
+}
+
+#[test]
+fn test_toml_config() {
+    use rust_g2p::G2PConfig;
+
+    assert!(G2PConfig::parse("[oov]\nunknown_key = 1\n").is_err());
+    assert!(G2PConfig::parse("language = \"fr\"\n").is_ok());
+
+    let path = std::env::temp_dir().join(format!("rust_g2p_config_{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "[normalization]\n\
+         expand_numbers = false\n\
+         \n\
+         [output]\n\
+         notation = \"arpabet_no_stress\"\n\
+         \n\
+         [oov]\n\
+         require_valid_phonotactics = true\n\
+         fallback = \"spell_out\"\n",
+    )
+    .unwrap();
+    let g2p = RustG2P::from_config(path.to_str().unwrap()).expect("Failed to load config");
+    std::fs::remove_file(&path).unwrap();
+
+    let phonemes = g2p.word_to_phonemes("hello").unwrap();
+    assert_eq!(g2p.render(&phonemes), "HH AH L OW");
+    // 低质量规则输出改为逐字母拼读
+    let phonemes = g2p.word_to_phonemes("bcdx").unwrap();
+    assert_eq!(phonemes[0].symbol, "B");
+    assert_eq!(phonemes[1].symbol, "IY");
+    // 关闭数字展开后数字被丢弃
+    let phonemes = g2p.text_to_phonemes("5").unwrap();
+    assert!(phonemes.is_empty());
+}
+
+#[test]
+fn test_case_rules() {
+    use rust_g2p::lang::{CaseRules, English, Language};
+    use rust_g2p::{G2PBuilder, G2PConfig};
+
+    let words = |g2p: &RustG2P, text: &str| -> Vec<String> {
+        g2p.text_to_words(text).unwrap().into_iter().map(|word| word.word).collect()
+    };
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_case_rules(CaseRules::Turkic);
+    assert_eq!(g2p.case_rules(), CaseRules::Turkic);
+    // 快速路径和词典查找都按土耳其语规则转小写
+    assert_eq!(words(&g2p, "It is IT"), ["ıt", "is", "ıt"]);
+    assert_eq!(g2p.word_to_phonemes("ISTANBUL").unwrap(), g2p.text_to_words("ISTANBUL").unwrap()[0].phonemes);
+    assert_eq!(g2p.word_to_phonemes("İT").unwrap(), RustG2P::new().unwrap().word_to_phonemes("it").unwrap());
+    g2p.insert_word("IŞIK", "IH0 SH IH1 K").unwrap();
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("ışık").unwrap()), "IH0 SH IH1 K");
+    assert!(g2p.remove_word("IŞIK"));
+
+    let english = RustG2P::new().unwrap().with_case_rules(English.case_rules());
+    assert_eq!(words(&english, "It is IT"), ["it", "is", "it"]);
+
+    // 配置文件省略 case_rules 时按 language 选择
+    assert_eq!(G2PConfig::default().case_rules(), CaseRules::Unicode);
+    assert_eq!(G2PConfig::parse("language = \"tr\"\n").unwrap().case_rules(), CaseRules::Turkic);
+    let config = G2PConfig::parse("[normalization]\ncase_rules = \"turkic\"\n").unwrap();
+    assert_eq!(config.case_rules(), CaseRules::Turkic);
+    assert_eq!(config.normalization.options().case_rules, CaseRules::Turkic);
+    
+    // 按语言选择的规则用于构建出的转换器
+    let turkish = G2PBuilder::from_config(G2PConfig::parse("language = \"tr\"\n").unwrap()).build().unwrap();
+    assert_eq!((turkish.language(), turkish.case_rules()), ("tr", CaseRules::Turkic));
+    assert_eq!(words(&turkish, "It is IT"), ["ıt", "is", "ıt"]);
+    let german = G2PBuilder::from_config(G2PConfig::parse("language = \"de\"\n").unwrap()).build().unwrap();
+    assert_eq!((german.language(), german.case_rules()), ("de", CaseRules::German));
+    assert_eq!(words(&german, "STRAẞE"), ["straße"]);
+}
+
+#[test]
+fn test_trace_and_user_entries() {
+    use rust_g2p::WordSource;
+
+    let mut g2p = RustG2P::new().expect("Failed to create G2P");
+
+    let trace = g2p.trace_word("Hello").unwrap();
+    assert_eq!(trace.source, WordSource::Dictionary);
+    assert!(trace.steps.is_empty());
+
+    let trace = g2p.trace_word("bcdx").unwrap();
+    assert_eq!(trace.source, WordSource::Rules);
+    assert_eq!(trace.steps.len(), 4);
+    assert_eq!(trace.steps[3].letters, "x");
+    assert_eq!(trace.steps[3].phonemes, vec!["K", "S"]);
+    assert!(trace.to_string().starts_with("bcdx -> B K D K S"));
+
+    assert!(g2p.add_word("bcdx", "B IY1 XX").is_err());
+    g2p.add_word("bcdx", "B IY1 S IY1").unwrap();
+    assert_eq!(g2p.trace_word("bcdx").unwrap().source, WordSource::Dictionary);
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("BCDX").unwrap()), "B IY1 S IY1");
+}
+
+#[test]
+fn test_silent_letters() {
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+
+    // 词典中的单词也按规则分析
+    let silent = g2p.silent_letters("Sighs").unwrap();
+    let letters: Vec<(usize, char)> = silent.iter().map(|s| (s.position, s.letter)).collect();
+    assert_eq!(letters, vec![(2, 'g'), (3, 'h')]);
+    assert_eq!(silent[0].rule.pattern, "gh");
+
+    let silent = g2p.silent_letters("make").unwrap();
+    assert_eq!(silent.len(), 1);
+    assert_eq!((silent[0].position, silent[0].letter), (3, 'e'));
+    assert!(silent[0].to_string().starts_with("e@3: "));
+
+    assert!(g2p.silent_letters("cat").unwrap().is_empty());
+}
+
+#[test]
+fn test_highlight_letters() {
+    use rust_g2p::LetterCategory;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let categories = |word: &str| -> Vec<LetterCategory> {
+        g2p.highlight_letters(word).unwrap().iter().map(|h| h.category).collect()
+    };
+    assert!(categories("bat").iter().all(|&c| c == LetterCategory::Consistent));
+
+    // sighs: i 读作 AY1 而不是规则的 IH0，gh 不发音
+    let highlights = g2p.highlight_letters("Sighs").unwrap();
+    assert_eq!(highlights.len(), 5);
+    assert_eq!(highlights[1].category, LetterCategory::Exceptional);
+    assert_eq!(g2p.render(&highlights[1].phonemes), "AY1");
+    assert_eq!(highlights[2].category, LetterCategory::Silent);
+    assert_eq!(highlights[3].grapheme, 2..4);
+
+    // 用户词条改变实际读音，标注随之变化
+    g2p.insert_word("bat", "B AA1 T").unwrap();
+    assert_eq!(categories("bat")[1], LetterCategory::Exceptional);
+}
+
+#[test]
+fn test_text_to_words() {
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+
+    let words = g2p.text_to_words("Hello, qzxv!").unwrap();
+    assert_eq!(words.len(), 2);
+    assert_eq!(words[0].word, "hello");
+    assert!(!words[0].oov);
+    assert_eq!(words[1].word, "qzxv");
+    assert!(words[1].oov);
+    assert!(!words[1].phonemes.is_empty());
+}
+
+#[test]
+fn test_phoneme_input() {
+    use rust_g2p::text::{split_phonemic, Segment};
+
+    assert_eq!(
+        split_phonemic("say /T EH1 S T/ now"),
+        vec![Segment::Text("say "), Segment::Phonemic("T EH1 S T"), Segment::Text(" now")]
+    );
+    // 单词内部的斜杠不是音素输入
+    assert_eq!(split_phonemic("and/or/either"), vec![Segment::Text("and/or/either")]);
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_phoneme_input(true);
+    let words = g2p.text_to_words("Say /T EH1 S T/, please").unwrap();
+    let rendered: Vec<String> = words.iter().map(|w| g2p.render(&w.phonemes)).collect();
+    assert_eq!(words[1].word, "/T EH1 S T/");
+    assert_eq!(rendered[1], "T EH1 S T");
+    assert_eq!(words.len(), 3);
+
+    // 音素串中的未知符号报错
+    assert!(g2p.text_to_phonemes("say /T QQ1/").is_err());
+
+    // 未启用时斜杠片段按普通文本处理
+    let plain = RustG2P::new().expect("Failed to create G2P");
+    assert_ne!(plain.text_to_words("/T EH1 S T/").unwrap().len(), 1);
+}
+
+#[test]
+fn test_letter_sequences() {
+    use rust_g2p::text::{split_letter_sequences, Segment};
+
+    assert_eq!(
+        split_letter_sequences("plan B, a.k.a. the U.S. plan"),
+        vec![
+            Segment::Text("plan "),
+            Segment::Letters("B"),
+            Segment::Text(", "),
+            Segment::Letters("a.k.a."),
+            Segment::Text(" the "),
+            Segment::Letters("U.S."),
+            Segment::Text(" plan"),
+        ]
+    );
+    // 冠词a和单词内部的字母不受影响
+    assert_eq!(split_letter_sequences("a cat doesn't x-ray"), vec![Segment::Text("a cat doesn't x-ray")]);
+    assert_eq!(split_letter_sequences("grade A"), vec![Segment::Text("grade "), Segment::Letters("A")]);
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let words = g2p.text_to_words("plan B, a.k.a. this").unwrap();
+    let rendered: Vec<String> = words.iter().map(|w| g2p.render(&w.phonemes)).collect();
+    assert_eq!(rendered[..3], ["P L AE1 N", "B IY1", "EY1 K EY1 EY1"]);
+    assert_eq!(words[2].word, "a.k.a.");
+
+    // 批量会话的结果一致
+    let mut session = g2p.batch_session();
+    assert_eq!(session.convert_document("plan B").unwrap(), &["P", "L", "AE", "N", "|", "B", "IY", "|"]);
+}
+
+#[test]
+fn test_latin_abbreviations() {
+    use rust_g2p::text::{LatinStyle, NormalizeOptions};
+
+    let words = |g2p: &RustG2P, text: &str| -> Vec<String> {
+        g2p.text_to_words(text).unwrap().into_iter().map(|w| w.word).collect()
+    };
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    assert_eq!(words(&g2p, "E.g. cats, i.e. pets"), vec!["for", "example", "cats", "that", "is", "pets"]);
+    assert_eq!(words(&g2p, "Smith et al. etc."), vec!["smith", "and", "others", "etcetera"]);
+
+    let g2p = g2p.with_normalization(NormalizeOptions {
+        latin_style: LatinStyle::Letters,
+        ..Default::default()
+    });
+    let converted = g2p.text_to_words("e.g. Smith et al.").unwrap();
+    let readings: Vec<String> = converted.iter().map(|w| g2p.render(&w.phonemes)).collect();
+    assert_eq!(converted[0].word, "e.g.");
+    assert_eq!(readings[0], "IY1 JH IY1");
+    assert_eq!(words(&g2p, "Smith et al. cf. Jones"), vec!["smith", "et", "al", "cf.", "jones"]);
+}
+
+#[test]
+fn test_quoted_dialogue() {
+    use rust_g2p::text::{Span, TextProcessor};
+
+    let processor = TextProcessor::new();
+    assert_eq!(
+        processor.sentences("He said, \"Stop!\" Then he left."),
+        vec![
+            vec![Span::Narration("He said,"), Span::Quote("Stop!")],
+            vec![Span::Narration("Then he left.")],
+        ]
+    );
+    // 说话人标记留在同一个句子里
+    assert_eq!(
+        processor.sentences("“Stop!” he said. Dr. Smith agreed."),
+        vec![
+            vec![Span::Quote("Stop!"), Span::Narration("he said.")],
+            vec![Span::Narration("Dr. Smith agreed.")],
+        ]
+    );
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let sentences = g2p.text_to_sentences("He said, \"Stop!\" Then he left.").unwrap();
+    assert_eq!(sentences.len(), 2);
+    assert!(sentences[0][1].quoted);
+    assert_eq!(sentences[0][1].words[0].word, "stop");
+
+    // 引号边界标记
+    let marked = RustG2P::new().expect("Failed to create G2P").with_quote_markers(true);
+    let rendered = marked.render(&marked.text_to_phonemes("He said, \"Stop!\"").unwrap());
+    assert_eq!(rendered.matches('"').count(), 2);
+    assert!(rendered.contains("\" | S T AA1 P | \""));
+    assert_eq!(marked.text_to_words("He said, \"Stop!\"").unwrap().len(), 3);
+}
+
+#[test]
+fn test_token_kinds() {
+    use rust_g2p::text::{classify_tokens, TokenKind};
+
+    assert_eq!(
+        classify_tokens("NASA's 2nd launch, see https://nasa.gov. 🚀"),
+        vec![
+            ("NASA's", TokenKind::Word),
+            ("2nd", TokenKind::Ordinal),
+            ("launch", TokenKind::Word),
+            (",", TokenKind::Punctuation),
+            ("see", TokenKind::Word),
+            ("https://nasa.gov", TokenKind::Url),
+            (".", TokenKind::Punctuation),
+            ("🚀", TokenKind::Emoji),
+        ]
+    );
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let tokens = g2p.text_to_tokens("The FBI saw 25 cats on the 3rd day, U.S. time!").unwrap();
+    let kinds: Vec<(&str, TokenKind)> = tokens.iter().map(|t| (t.word.as_str(), t.kind)).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("the", TokenKind::Word),
+            ("fbi", TokenKind::Acronym),
+            ("saw", TokenKind::Word),
+            ("twenty", TokenKind::Number),
+            ("five", TokenKind::Number),
+            ("cats", TokenKind::Word),
+            ("on", TokenKind::Word),
+            ("the", TokenKind::Word),
+            ("third", TokenKind::Ordinal),
+            ("day", TokenKind::Word),
+            (",", TokenKind::Punctuation),
+            ("u.s.", TokenKind::Acronym),
+            ("time", TokenKind::Word),
+            ("!", TokenKind::Punctuation),
+        ]
+    );
+    assert!(tokens[10].phonemes.is_empty());
+
+    // text_to_words只包含发音的词元
+    let words = g2p.text_to_words("The FBI saw 25 cats on the 3rd day, U.S. time!").unwrap();
+    assert_eq!(words.len(), 12);
+    assert!(words.iter().all(|w| w.kind.is_spoken()));
+}
+
+#[test]
+fn test_oov_report() {
+    use rust_g2p::corpus;
+    use rust_g2p::phoneme::Notation;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let texts = ["The zorblax ate a qwibble", "Another zorblax!", "hello world"];
+    let mut report = corpus::oov_report(&g2p, &texts).unwrap();
+
+    assert_eq!(report.total_words, 9);
+    assert_eq!(report.entries.len(), 2);
+    assert_eq!(report.entries[0].word, "zorblax");
+    assert_eq!(report.entries[0].count, 2);
+    assert_eq!(report.entries[0].phonemes, g2p.word_to_phonemes("zorblax").unwrap());
+    assert_eq!(report.oov_words(), 3);
+    assert!((report.oov_rate() - 3.0 / 9.0).abs() < 1e-9);
+
+    report.sort_alphabetically();
+    assert_eq!(report.entries[0].word, "qwibble");
+    let tsv = report.render(Notation::Arpabet);
+    assert!(tsv.lines().nth(1).unwrap().starts_with("zorblax\t2\t"));
+}
+
+#[test]
+fn test_record_correction() {
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("blargh").unwrap()), "B L AA1 R F");
+
+    g2p.record_correction("Blargh", "B L AA1 R G").unwrap();
+    g2p.record_correction("snorgle", "S N AO1 R G AH0 L").unwrap();
+    assert!(g2p.record_correction("zorph", "Z QQ1").is_err());
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("blargh").unwrap()), "B L AA1 R G");
+
+    // 读错的字母组合归纳为带上下文的规则
+    let lines: Vec<String> = g2p.suggest_rules().unwrap().iter().map(|s| s.line()).collect();
+    assert_eq!(lines, vec!["gh|r|_|G|6|", "g|r|l|G AH0|3|"]);
+
+    // 重复纠正同一个单词只保留最新的一条
+    g2p.record_correction("blargh", "B L AA1 R F").unwrap();
+    assert_eq!(g2p.suggest_rules().unwrap().len(), 1);
+}
+
+#[test]
+fn test_user_lexicon() {
+    let g2p = std::sync::Arc::new(RustG2P::new().expect("Failed to create G2P"));
+    let original = g2p.render(&g2p.word_to_phonemes("hello").unwrap());
+
+    // 其他线程通过共享引用写入，之后的查询立即可见
+    let writer = std::sync::Arc::clone(&g2p);
+    std::thread::spawn(move || {
+        writer.insert_word("Hello", "HH AH0 L OW1").unwrap();
+        writer.insert_word("zorblax", "Z AO1 R B L AE0 K S").unwrap();
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(g2p.user_word_count(), 2);
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("hello").unwrap()), "HH AH0 L OW1");
+    let words = g2p.text_to_words("zorblax").unwrap();
+    assert!(!words[0].oov);
+    assert!(g2p.insert_word("zorph", "Z QQ1").is_err());
+
+    // 删除后恢复主词典中的发音
+    assert!(g2p.remove_word("HELLO"));
+    assert!(!g2p.remove_word("hello"));
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("hello").unwrap()), original);
+}
+
+#[test]
+fn test_irregular_lexicon() {
+    use rust_g2p::trace::WordSource;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let irregular = g2p.irregular_lexicon();
+    assert!(irregular.len() >= 30);
+    assert_eq!(irregular.lookup("Colonel").unwrap().len(), 5);
+
+    // 主词典未收录的单词先查不规则词汇层，再交给规则
+    irregular.insert("zorblax", "Z AO1 R B L AE0 K S").unwrap();
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("zorblax").unwrap()), "Z AO1 R B L AE0 K S");
+    assert_eq!(g2p.trace_word("zorblax").unwrap().source, WordSource::Irregular);
+    assert_eq!(g2p.get_stats().irregular_hits, 1);
+
+    // 用户词典层优先于不规则词汇层
+    g2p.insert_word("zorblax", "Z AO1 R B L AA0 K S").unwrap();
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("zorblax").unwrap()), "Z AO1 R B L AA0 K S");
+
+    // 同一份词汇文件可以加载到其他转换器上，替换后原来的词条不再生效
+    let shared = LexiconLayer::load("data/en_irregular.txt").unwrap();
+    let other = RustG2P::new().expect("Failed to create G2P").with_irregular(shared);
+    assert_eq!(other.irregular_lexicon().len(), irregular.len() - 1);
+    assert_ne!(other.trace_word("zorblax").unwrap().source, WordSource::Irregular);
+    assert!(LexiconLayer::from_cmu_str(";;;!g2p-lexicon 1\nWORD  QQ1\n").is_err());
+}
+
+#[test]
+fn test_golden_snapshot() {
+    let inputs = ["Hello world", "zorblax\tqwibble", "I have 3 apples."];
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let snapshot = GoldenSnapshot::record(&g2p, &inputs).unwrap();
+    let rendered = snapshot.render();
+    assert!(rendered.contains("zorblax\\tqwibble\t"));
+    assert_eq!(GoldenSnapshot::parse(&rendered).unwrap(), snapshot);
+
+    // 同样的数据文件在另一个实例上给出逐字节相同的结果
+    let other = RustG2P::new().expect("Failed to create G2P");
+    assert_eq!(other.data_fingerprint(), snapshot.fingerprint);
+    let report = snapshot.check(&other).unwrap();
+    assert!(report.is_clean(), "{}", report);
+
+    // 修改词典后报告变化的输入
+    other.insert_word("hello", "HH EH0 L OW1").unwrap();
+    let report = snapshot.check(&other).unwrap();
+    assert!(report.fingerprint_changed);
+    assert_eq!(report.mismatches.len(), 1);
+    assert_eq!(report.mismatches[0].input, "Hello world");
+    assert!(report.mismatches[0].actual.starts_with("HH EH0 L OW1"));
+
+    assert!(GoldenSnapshot::parse("hello\tHH AH0 L OW1").is_err());
+}
+
+#[test]
+fn test_rewrite_rules() {
+    use rust_g2p::RewriteEngine;
+
+    let flapping = RewriteEngine::parse(
+        "#!g2p-rewrite 1\nT|[vowel]|[vowel]|D|   # 闪音化\nR||_|SILENT|\nHH|_||SILENT|\n",
+    )
+    .unwrap();
+    assert_eq!(flapping.rules().len(), 3);
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_rewrite(flapping);
+
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("water her").unwrap()), "W AO1 D ER0 | ER1 |");
+    let words = g2p.text_to_words("water").unwrap();
+    assert_eq!(g2p.render(&words[0].phonemes), "W AO1 D ER0");
+    // 逐词接口之外的词汇读音不改写
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("water").unwrap()), "W AO1 T ER0");
+
+    assert!(RewriteEngine::parse("T|[vowel]|[vowel]|$2|\n").is_err());
+    assert!(RewriteEngine::parse("T|[vowel]\n").is_err());
+    assert!(RewriteEngine::parse("QQ|||D|\n").is_err());
+}
+
+#[test]
+fn test_schwa_insertion() {
+    use rust_g2p::SchwaInsertion;
+
+    let schwa = SchwaInsertion::new()
+        .with_cluster("# S [consonant]")
+        .unwrap()
+        .with_cluster("[consonant] K #")
+        .unwrap();
+    assert_eq!(schwa.contexts()[0].to_string(), "# S [consonant]");
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_schwa_insertion(schwa);
+
+    // 只拆开词首的 S T 和词尾的 S K，词中的 S T 不变
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("street desk").unwrap()), "S AH0 T R IY1 T | D EH1 S AH0 K |");
+    assert_eq!(g2p.render(&g2p.text_to_words("mistake").unwrap()[0].phonemes), "M IH0 S T EY1 K");
+
+    assert!(SchwaInsertion::new().with_cluster("S").is_err());
+    assert!(SchwaInsertion::new().with_cluster("S [vowel]").is_err());
+    assert!(SchwaInsertion::new().with_cluster("# S T #").is_err());
+}
+
+#[test]
+fn test_non_verbal_tokens() {
+    use rust_g2p::text::{NonVerbalStyle, NormalizeOptions, TokenKind};
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let phonemes = g2p.text_to_phonemes("[Laughs] hello (coughs) [inaudible]").unwrap();
+    assert_eq!(g2p.render(&phonemes), "[laughter] | HH AH0 L OW1 | [cough] | [inaudible] |");
+    assert!(phonemes[0].is_event());
+
+    // 圆括号中的普通文字和Markdown链接照常读
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("(hello)").unwrap()), "HH AH0 L OW1 |");
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("[hello](x)").unwrap()), "HH AH0 L OW1 | EH1 K S |");
+
+    let tagged = RustG2P::new().expect("Failed to create G2P").with_normalization(NormalizeOptions {
+        non_verbal: NonVerbalStyle::Tagged,
+        ..Default::default()
+    });
+    assert_eq!(tagged.render(&tagged.text_to_phonemes("[sigh] hello").unwrap()), "HH AH0 L OW1 |");
+    let tokens = tagged.text_to_tokens("[sigh] hello").unwrap();
+    assert_eq!((tokens[0].word.as_str(), tokens[0].kind), ("[sigh]", TokenKind::NonVerbal));
+
+    let literal = RustG2P::new().expect("Failed to create G2P").with_normalization(NormalizeOptions {
+        non_verbal: NonVerbalStyle::Literal,
+        ..Default::default()
+    });
+    assert_eq!(literal.render(&literal.text_to_phonemes("[sigh]").unwrap()), "S AY1 |");
+}
+
+#[test]
+fn test_fillers() {
+    use rust_g2p::text::{DisfluencyStyle, NormalizeOptions, TokenKind};
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let words = g2p.text_to_words("Ummm, hmm. Mm-hmm uh-huh").unwrap();
+    let rendered: Vec<_> = words.iter().map(|w| format!("{}={}", w.word, g2p.render(&w.phonemes))).collect();
+    assert_eq!(rendered, ["ummm=AH1 M", "hmm=HH M", "mm-hmm=M HH M", "uh-huh=AH0 HH AH1"]);
+    assert!(words.iter().all(|w| w.kind == TokenKind::Filler));
+    // 单词中的 um 不受影响
+    assert_eq!(g2p.text_to_words("umbrella").unwrap()[0].kind, TokenKind::Word);
+
+    let normalize = RustG2P::new().expect("Failed to create G2P").with_normalization(NormalizeOptions {
+        disfluency: DisfluencyStyle::Normalize,
+        ..Default::default()
+    });
+    let words = normalize.text_to_words("erm uhh").unwrap();
+    assert_eq!((words[0].word.as_str(), normalize.render(&words[0].phonemes).as_str()), ("um", "AH1 M"));
+    assert_eq!(words[1].word, "uh");
+
+    let drop = RustG2P::new().expect("Failed to create G2P").with_normalization(NormalizeOptions {
+        disfluency: DisfluencyStyle::Drop,
+        ..Default::default()
+    });
+    assert_eq!(drop.render(&drop.text_to_phonemes("so, um, hello").unwrap()), "S OW1 | HH AH0 L OW1 |");
+    // err 和全大写的缩写不是填充词
+    let spoken = |text: &str| -> Vec<String> {
+        drop.text_to_words(text).unwrap().into_iter().map(|w| w.surface).collect()
+    };
+    assert_eq!(spoken("To err is human"), ["To", "err", "is", "human"]);
+    assert_eq!(spoken("Take him to the ER."), ["Take", "him", "to", "the", "ER"]);
+    assert_eq!(spoken("HM the Queen"), ["HM", "the", "Queen"]);
+    assert_eq!(drop.text_to_words("er, hmm").unwrap().len(), 0);
+}
+
+#[test]
+fn test_rhythm_rule() {
+    use rust_g2p::rhythm::apply_rhythm_rule;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("thirteen men").unwrap()), "TH ER1 T IY1 N | M EH1 N |");
+
+    let g2p = g2p.with_rhythm_rule(true);
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("thirteen men").unwrap()), "TH ER1 T IY2 N | M EH1 N |");
+    // 后一个词以非重读音节开头时没有冲突
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("thirteen again").unwrap()), "TH ER1 T IY1 N | AH0 G EH1 N |");
+
+    // 主重音之前没有重读音节时无法前移
+    let phonemes = rust_g2p::phoneme::parse_arpabet("AH0 N OW1 N").unwrap();
+    assert_eq!(apply_rhythm_rule(phonemes.clone()), phonemes);
+}
+
+#[test]
+fn test_linking_r() {
+    use rust_g2p::RewriteEngine;
+
+    let non_rhotic = RewriteEngine::parse("#!g2p-rewrite 1\nR||_|SILENT|\n").unwrap();
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_rewrite(non_rhotic).with_linking_r();
+    assert_eq!(rust_g2p::rules::SandhiRule::linking_r().len(), 5);
+
+    assert_eq!(
+        g2p.render(&g2p.text_to_phonemes("law and order").unwrap()),
+        "L AO1 | R AH0 N D | AO1 R D ER0 |"
+    );
+    // 词尾的R在元音前保留，在辅音前和句末不发音
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("far away").unwrap()), "F AA1 | R AH0 W EY1 |");
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("far too").unwrap()), "F AA1 | T UW1 |");
+    // 以ER结尾的词是最常见的连接R位置
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("better off").unwrap()), "B EH1 T ER0 | R AO1 F |");
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("her own").unwrap()), "HH ER1 | R OW1 N |");
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("her car").unwrap()), "HH ER1 | K AA1 |");
+}
+
+#[test]
+fn test_stem_recovery() {
+    use rust_g2p::stem::{stem_candidates, Suffix};
+    use rust_g2p::WordSource;
+
+    let first = |word: &str| stem_candidates(word)[0].stem.clone();
+    assert_eq!(first("running"), "run");
+    assert_eq!(first("hoped"), "hope");
+    assert_eq!(first("happier"), "happy");
+    assert_eq!(first("boxes"), "box");
+    assert_eq!(stem_candidates("tried")[0].suffix, Suffix::Ed);
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let arpabet = |word: &str| g2p.render(&g2p.word_to_phonemes(word).unwrap());
+    assert_eq!(arpabet("vaped"), "V EY1 P T");
+    assert_eq!(arpabet("blogged"), "B L AO1 G D");
+    assert_eq!(arpabet("zippiest"), "Z IH1 P IY0 AH0 S T");
+    assert_eq!(arpabet("hashtags"), "HH AE1 SH T AE2 G Z");
+    assert_eq!(g2p.trace_word("vaping").unwrap().source, WordSource::Stem);
+    assert_eq!(g2p.get_stats().stem_hits, 4);
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_stem_recovery(false);
+    assert_eq!(g2p.trace_word("vaping").unwrap().source, WordSource::Rules);
+}
+
+#[test]
+fn test_transliteration() {
+    use rust_g2p::translit::transliterate;
+
+    assert_eq!(transliterate("Москва").as_deref(), Some("moskva"));
+    assert_eq!(transliterate("αλφα").as_deref(), Some("alfa"));
+    assert_eq!(transliterate("Ωmega").as_deref(), Some("omega"));
+    assert_eq!(transliterate("omega"), None);
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let words = g2p.text_to_words("the αλφα test").unwrap();
+    assert_eq!(words[1].word, "αλφα");
+    assert_eq!(g2p.render(&words[1].phonemes), g2p.render(&g2p.word_to_phonemes("alfa").unwrap()));
+    assert!(!g2p.word_to_phonemes("Москва").unwrap().is_empty());
+    assert_eq!(g2p.trace_word("МОСКВА").unwrap().word, "москва");
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_transliteration(false);
+    assert!(g2p.word_to_phonemes("Москва").unwrap().is_empty());
+}
+
+#[test]
+fn test_mixed_scripts() {
+    use rust_g2p::text::{split_scripts, Segment, TokenKind};
+    use rust_g2p::translit::{script_of, Script};
+
+    assert_eq!(script_of('Ω'), Some(Script::Greek));
+    assert_eq!(script_of('ï'), Some(Script::Latin));
+    assert_eq!(script_of('-'), None);
+    assert_eq!(split_scripts("Ωmega3"), vec![Segment::Text("Ω"), Segment::Text("mega"), Segment::Text("3")]);
+    assert_eq!(split_scripts("the 21st, 1980s"), vec![Segment::Text("the 21st, 1980s")]);
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let words = g2p.text_to_words("Ωmega3").unwrap();
+    let spelled: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+    assert_eq!(spelled, vec!["ω", "mega", "three"]);
+    assert_eq!(words[2].kind, TokenKind::Number);
+    assert!(words.iter().all(|w| !w.phonemes.is_empty()));
+
+    // 年代的复数 s 随数字一起读
+    let spelled = |text: &str| -> Vec<String> { g2p.text_to_words(text).unwrap().into_iter().map(|w| w.word).collect() };
+    assert_eq!(spelled("1980s"), ["nineteen", "eighties"]);
+    assert_eq!(spelled("the 1990s"), ["the", "nineteen", "nineties"]);
+    assert_eq!(spelled("the 80s"), ["the", "eighties"]);
+}
+
+#[test]
+fn test_bench_report() {
+    use rust_g2p::bench::{self, BenchConfig, Workload};
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let config = BenchConfig { iterations: 3, warmup: 1, min_words_per_sec: Some(f64::MAX), ..BenchConfig::default() };
+    let report = bench::run(&g2p, &config).unwrap();
+    assert_eq!(report.results.len(), 2);
+
+    let dictionary = report.get("dictionary").unwrap();
+    assert_eq!(dictionary.oov_words, 0);
+    assert!(dictionary.words_per_sec() > 0.0);
+    assert!(dictionary.phonemes_per_sec() > dictionary.words_per_sec());
+    let oov = report.get("oov").unwrap();
+    assert!(oov.oov_words * 2 > oov.words);
+    assert!(!report.meets_targets());
+    assert!(report.render().contains("below target"));
+
+    let config = BenchConfig { workloads: vec![Workload::new("hello", "hello world")], iterations: 1, ..BenchConfig::default() };
+    let report = bench::run(&g2p, &config).unwrap();
+    assert_eq!(report.results[0].words, 2);
+    assert!(report.meets_targets());
+}
+
+#[test]
+fn test_frequent_words() {
+    use rust_g2p::frequent::{index_of, index_of_token, FREQUENT_WORDS};
+
+    assert!(FREQUENT_WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(index_of_token("The"), index_of("the"));
+    assert!(index_of_token("the").is_some());
+    assert_eq!(index_of_token("THE"), None);
+    assert_eq!(index_of_token("the."), None);
+    assert_eq!(index_of("hello"), None);
+
+    let mut g2p = RustG2P::new().expect("Failed to create G2P");
+    let words = g2p.text_to_words("The cat and THE dog").unwrap();
+    assert_eq!(words[0].word, "the");
+    assert_eq!(g2p.render(&words[0].phonemes), "DH AH0");
+    assert_eq!(words[2].kind, rust_g2p::text::TokenKind::Word);
+    assert_eq!(words[3].kind, rust_g2p::text::TokenKind::Acronym);
+    assert_eq!(g2p.get_stats().dictionary_hits, 5);
+
+    // 修改词典后快速路径同步更新
+    g2p.add_word("of", "AH1 V").unwrap();
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("of").unwrap()), "AH1 V");
+    g2p.insert_word("and", "AE1 N").unwrap();
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("and").unwrap()), "AE1 N");
+}
+
+#[test]
+fn test_frequent_words_match_slow_path() {
+    use rust_g2p::frequent::FREQUENT_WORDS;
+    use rust_g2p::text::TextProcessor;
+    use rust_g2p::Dictionary;
+
+    assert!(FREQUENT_WORDS.len() >= 1000);
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let dictionary = Dictionary::load_cmu_dict("data/cmudict.txt").unwrap();
+    let processor = TextProcessor::new();
+    for &word in FREQUENT_WORDS {
+        let capitalized = word[..1].to_uppercase() + &word[1..];
+        let expected = match dictionary.lookup(word) {
+            Some(phonemes) => phonemes,
+            None => g2p.word_to_phonemes(word).unwrap(),
+        };
+        // 单独的大写字母按字母读，不经过快速路径
+        let forms = if word.len() > 1 { vec![word, capitalized.as_str()] } else { vec![word] };
+        for token in forms {
+            // 标准化不改变高频单词
+            assert_eq!(processor.normalize(token).unwrap(), word);
+            let words = g2p.text_to_words(token).unwrap();
+            assert_eq!(words.len(), 1, "{}", token);
+            assert_eq!((words[0].word.as_str(), &words[0].phonemes), (word, &expected), "{}", token);
+        }
+    }
+}
+
+#[test]
+fn test_global_instance() {
+    let first = rust_g2p::global().unwrap();
+    let second = rust_g2p::global().unwrap();
+    assert!(std::ptr::eq(first, second));
+
+    let phonemes = rust_g2p::phonemize("cat").unwrap();
+    assert_eq!(first.render(&phonemes), first.render(&first.word_to_phonemes("cat").unwrap()));
+    assert_eq!(rust_g2p::phonemize_text("big cat").unwrap(), first.render(&first.text_to_phonemes("big cat").unwrap()));
+}
+
+#[test]
+fn test_load_report() {
+    use rust_g2p::dict::Dictionary;
+    use rust_g2p::report::IssueKind;
+    use rust_g2p::rules::RulesEngine;
+
+    let content = "HELLO  HH AH0 L OW1\nBROKEN\nCAF\u{c9}  K AE0 F EY1\nTEST  T EH1 S QQ T\nWORLD  W ER1 L D\n";
+    let (dictionary, report) = Dictionary::from_cmu_str_with_report(content).unwrap();
+    assert_eq!(dictionary.size(), 3);
+    assert_eq!((report.lines, report.loaded, report.skipped_lines()), (5, 3, 2));
+    let issues: Vec<(usize, IssueKind)> = report.issues.iter().map(|issue| (issue.line, issue.kind)).collect();
+    assert_eq!(issues, [(2, IssueKind::MalformedLine), (3, IssueKind::InvalidLine), (4, IssueKind::SkippedPhoneme)]);
+    assert_eq!(report.issues[0].text, "BROKEN");
+    assert!(report.render().contains("Line 4: skipped invalid phoneme 'QQ'"));
+
+    let (_, report) = Dictionary::from_cmu_str_with_report("HELLO  HH AH0 L OW1\n").unwrap();
+    assert!(report.is_clean());
+
+    // 带版本头的词典仍然报错
+    assert!(Dictionary::from_cmu_str_with_report(";;;!g2p-lexicon 1\nHELLO  HH AH0 L OW1\nBROKEN\n").is_err());
+
+    let rules = "a|||AE|x|nonsense\nbroken|line\nIRREGULAR|foo\nb|||B|1|\n";
+    let (engine, report) = RulesEngine::parse_english_rules_with_report(rules).unwrap();
+    assert_eq!(report.loaded, 2);
+    let issues: Vec<(usize, IssueKind)> = report.issues.iter().map(|issue| (issue.line, issue.kind)).collect();
+    assert_eq!(
+        issues,
+        [(1, IssueKind::IgnoredField), (1, IssueKind::IgnoredField), (2, IssueKind::MalformedLine), (3, IssueKind::MalformedLine)]
+    );
+    assert_eq!(report.of_kind(IssueKind::IgnoredField).count(), 2);
+    assert!(!engine.apply_rules("ab").unwrap().is_empty());
+}
+
+#[test]
+fn test_builder() {
+    let g2p = RustG2P::builder()
+        .dict_path("data/cmudict_min.txt")
+        .rules_path("data/en_rules.txt")
+        .irregular_path("")
+        .word_boundaries(false)
+        .build()
+        .unwrap();
+    let phonemes = g2p.text_to_phonemes("hello the world").unwrap();
+    assert!(!phonemes.is_empty());
+    assert!(phonemes.iter().all(|p| p.symbol != " "));
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("the").unwrap()), "DH AH0");
+
+    let default = RustG2P::builder().build().unwrap();
+    assert_eq!(default.text_to_phonemes("hello world").unwrap().iter().filter(|p| p.symbol == " ").count(), 2);
+
+    let compact = RustG2P::builder()
+        .dict_path("data/cmudict_min.txt")
+        .irregular_path("")
+        .word_boundaries(false)
+        .compact_dictionary(true)
+        .build()
+        .unwrap();
+    assert_eq!(compact.text_to_phonemes("hello the world").unwrap(), g2p.text_to_phonemes("hello the world").unwrap());
+    assert!(compact.memory_report().dictionary_bytes < g2p.memory_report().dictionary_bytes);
+
+    let error = RustG2P::builder().dict_path("data/missing.txt").build().err().unwrap();
+    assert!(error.to_string().contains("data/missing.txt"));
+}
+
+#[test]
+fn test_surface_forms() {
+    use rust_g2p::text::{Casing, TokenKind};
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let words = g2p.text_to_tokens("The NASA iPhone costs 25 dollars!").unwrap();
+    let surfaces: Vec<&str> = words.iter().map(|w| w.surface.as_str()).collect();
+    assert_eq!(surfaces, ["The", "NASA", "iPhone", "costs", "25", "25", "dollars", "!"]);
+    assert_eq!(words[0].word, "the");
+    assert_eq!(words[0].casing(), Casing::Capitalized);
+    assert_eq!(words[1].casing(), Casing::Upper);
+    assert_eq!(words[2].casing(), Casing::Mixed);
+    assert_eq!(words[3].casing(), Casing::Lower);
+    assert_eq!(words[4].casing(), Casing::Uncased);
+    assert_eq!(words[7].kind, TokenKind::Punctuation);
+}
+
+#[cfg(feature = "embedded-data")]
+#[test]
+fn test_embedded_data() {
+    let embedded = RustG2P::embedded().unwrap();
+    let loaded = RustG2P::builder().build().unwrap();
+    for text in ["hello world", "The knight read a book", "blorfing"] {
+        assert_eq!(
+            embedded.render(&embedded.text_to_phonemes(text).unwrap()),
+            loaded.render(&loaded.text_to_phonemes(text).unwrap())
+        );
+    }
+}
+
+#[test]
+fn test_subtitles() {
+    use rust_g2p::subtitle::{self, SubtitleFormat};
+    use std::time::Duration;
+
+    let srt = "1\r\n00:00:01,000 --> 00:00:02,500\r\n<i>Hello</i> world\r\n\r\n2\r\n00:01:03,040 --> 00:01:04,000\r\nThe cat\r\nsat\r\n";
+    let cues = subtitle::parse(srt).unwrap();
+    assert_eq!(cues.len(), 2);
+    assert_eq!(cues[0].id.as_deref(), Some("1"));
+    assert_eq!((cues[0].start, cues[0].end), (Duration::from_millis(1000), Duration::from_millis(2500)));
+    assert_eq!(cues[1].start, Duration::from_millis(63_040));
+    assert_eq!(cues[1].text, "The cat\nsat");
+    assert_eq!(subtitle::plain_text(&cues[0].text), "Hello world");
+
+    let vtt = "WEBVTT\n\nNOTE a comment\n\nintro\n00:01.000 --> 00:02.000 align:start\n<v Ann>Hello\n";
+    assert_eq!(SubtitleFormat::detect(vtt), SubtitleFormat::Vtt);
+    let cues = subtitle::parse(vtt).unwrap();
+    assert_eq!(cues.len(), 1);
+    assert_eq!(cues[0].id.as_deref(), Some("intro"));
+    assert_eq!(cues[0].settings, "align:start");
+    assert_eq!(subtitle::write(&cues, SubtitleFormat::Vtt), "WEBVTT\n\nintro\n00:00:01.000 --> 00:00:02.000 align:start\n<v Ann>Hello\n\n");
+
+    assert!(subtitle::parse("1\n00:00:01 --> 00:00:02,000\nHi\n").is_err());
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let results = g2p.phonemize_subtitles(srt).unwrap();
+    assert_eq!(g2p.render(&results[0].phonemes), "HH AH0 L OW1 | W ER1 L D");
+    assert_eq!(results[1].cue.end, Duration::from_millis(64_000));
+    let output = g2p.phonemize_subtitle_file(srt).unwrap();
+    assert!(output.starts_with("1\n00:00:01,000 --> 00:00:02,500\nHH AH0 L OW1 | W ER1 L D\n\n2\n"));
+}
+
+#[test]
+fn test_difficulty() {
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let scorer = g2p.difficulty_scorer();
+
+    let dog = scorer.word("dog", &g2p.word_to_phonemes("dog").unwrap());
+    assert_eq!((dog.syllables, dog.max_cluster, dog.rare_bigrams), (1, 1, 0));
+    assert_eq!(dog.score, 0.0);
+
+    let strengths = scorer.word("strengths", &g2p.word_to_phonemes("strengths").unwrap());
+    assert_eq!(strengths.syllables, 1);
+    assert!(strengths.max_cluster >= 3);
+    assert!(strengths.score > dog.score);
+
+    let easy = scorer.text(&g2p, "The dog sat on the mat.").unwrap();
+    let hard = scorer.text(&g2p, "Extraordinary institutional responsibilities.").unwrap();
+    assert_eq!(easy.words.len(), 6);
+    assert_eq!(easy.syllables_per_word(), 1.0);
+    assert_eq!(easy.polysyllabic_ratio(), 0.0);
+    assert!(hard.score() > easy.score());
+    assert_eq!(hard.polysyllabic_ratio(), 1.0);
+    assert_eq!(hard.hardest(1)[0].word, "extraordinary");
+    assert_eq!(rust_g2p::difficulty::DifficultyReport::default().score(), 0.0);
+}
+
+#[test]
+fn test_nbest() {
+    use rust_g2p::WordSource;
+
+    // 默认保留词典中的变体读音，不带编号的 `READ` 为主读音
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let read = g2p.word_to_phonemes_nbest("Read", 5).unwrap();
+    let arpabet: Vec<String> = read.iter().map(|c| g2p.render(&c.phonemes)).collect();
+    assert_eq!(arpabet, ["R EH1 D", "R IY1 D"]);
+    assert_eq!(read[0].phonemes, g2p.word_to_phonemes("read").unwrap());
+    assert_eq!(read[0].source, WordSource::Dictionary);
+    assert!(read[0].score > read[1].score);
+    assert_eq!(g2p.word_to_phonemes_nbest("read", 1).unwrap().len(), 1);
+
+    let oov = g2p.word_to_phonemes_nbest("blorfing", 3).unwrap();
+    assert_eq!(oov[0].phonemes, g2p.word_to_phonemes("blorfing").unwrap());
+    assert_eq!(oov[0].source, WordSource::Rules);
+    assert!(oov.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    assert_eq!(g2p.get_stats().words_converted, 2);
+}
+
+#[test]
+fn test_drills() {
+    use rust_g2p::drill::{self, DrillGenerator};
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let vocabulary = ["ship", "sip", "shoe", "sue", "shell", "sell", "sun", "shop", "fish", "miss", "dog", "Sip"];
+    let generator = DrillGenerator::build(&g2p, &vocabulary).unwrap();
+    assert_eq!(generator.len(), 11);
+
+    let drill = generator.generate("s", "SH1", 10).unwrap();
+    assert_eq!(drill.contrast, ("S", "SH"));
+    assert_eq!(drill.minimal_pairs[0], ("sue".to_string(), "shoe".to_string()));
+    assert_eq!(drill.minimal_pairs.len(), 3);
+    assert_eq!(drill.to_string(), "sue shoe sell shell sip ship miss fish sun shop");
+    assert!(!drill.words.contains(&"dog".to_string()));
+    assert_eq!(generator.generate("S", "SH", 3).unwrap().words, ["sue", "shoe", "miss"]);
+
+    assert!(generator.generate("S", "S0", 5).is_err());
+    assert!(generator.generate("S", "QQ", 5).is_err());
+
+    // S 和 SH 只差发音部位，比 S 和 M 更难区分
+    assert!(drill.distance < drill::contrast_distance("S", "M"));
+    let nearest = drill::nearest_contrasts("S", 8).unwrap();
+    assert_eq!(nearest.len(), 8);
+    assert!(nearest.iter().any(|&(symbol, _)| symbol == "Z"));
+    assert!(nearest.iter().all(|&(symbol, _)| symbol != "AA"));
+    assert!(nearest.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+}
+
+#[test]
+fn test_phoneme_profile() {
+    use rust_g2p::analyze;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let profile = analyze::phoneme_profile(&g2p, "She sells sea shells.").unwrap();
+    // SH IY1 | S EH1 L Z | S IY1 | SH EH1 L Z
+    assert_eq!(profile.total_phonemes, 12);
+    assert_eq!(profile.total_biphones, 8);
+    assert_eq!(profile.phonemes["SH"], 2);
+    assert_eq!(profile.biphones[&("EH", "L")], 2);
+    assert!((profile.frequency("IY") - 2.0 / 12.0).abs() < 1e-9);
+    assert!((profile.biphone_frequency("L", "Z") - 2.0 / 8.0).abs() < 1e-9);
+    assert_eq!(profile.top_phonemes(1)[0].1, 2);
+    assert!(profile.frequency("NG") == 0.0);
+
+    let baseline = g2p.lexicon_profile();
+    assert!(baseline.total_phonemes > 100_000);
+    let comparison = profile.compare(&baseline);
+    assert!(comparison.divergence > 0.0 && comparison.divergence < 1.0);
+    let sh = &comparison.phonemes[0];
+    assert_eq!(sh.symbols, "SH");
+    assert!(sh.log_ratio() > 1.0);
+    assert!(comparison.biphones.iter().all(|d| d.observed > 0.0));
+    assert_eq!(profile.compare(&profile).divergence, 0.0);
+}
+
+#[test]
+fn test_dictionary_audit() {
+    use rust_g2p::audit::{self, AuditThresholds};
+    use rust_g2p::eval::DiffOp;
+    use rust_g2p::{Dictionary, RulesEngine};
+
+    let rules = RulesEngine::load_english_rules("data/en_rules.txt").unwrap();
+    // "table" 的读音是故意录错的
+    let dictionary = Dictionary::from_cmu_str("DOG  D AO1 G\nTABLE  Z UW1 K IY0 N IY0\nSTOP  S T AA1 P\n").unwrap();
+    let report = audit::audit_dictionary(&dictionary, &rules, AuditThresholds::default());
+    assert_eq!(report.total, 3);
+    assert_eq!(report.flagged.len(), 1);
+    let entry = &report.flagged[0];
+    assert_eq!(entry.word, "table");
+    assert!(entry.distance >= 3 && entry.ratio >= 0.6);
+    assert!(entry.diff().iter().any(|op| matches!(op, DiffOp::Substitute(..) | DiffOp::Delete(_))));
+    assert!(report.patterns.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert!(report.patterns.iter().all(|(op, _)| !matches!(op, DiffOp::Same(_))));
+    assert!(report.render(5).starts_with("3 words audited, 1 flagged (33.3%)\n"));
+
+    // 只要有一个变体与规则一致就不标记
+    let variants = Dictionary::from_cmu_str("TABLE  Z UW1 K IY0 N IY0\nTABLE(2)  T EY1 B AH0 L\n").unwrap();
+    let report = audit::audit_dictionary(&variants, &rules, AuditThresholds::default());
+    assert!(report.flagged.is_empty());
+
+    let strict = AuditThresholds { min_distance: 0, min_ratio: 0.0 };
+    let report = audit::audit_dictionary(&dictionary, &rules, strict);
+    // 读音与规则完全一致的词条从不标记
+    assert!(report.flagged.iter().all(|entry| entry.distance > 0));
+    assert_eq!(report.flagged[0].word, "table");
+}
+
+#[test]
+fn test_boundary_symbols() {
+    use rust_g2p::phoneme::{self, BoundarySymbols, Notation};
+
+    let text = "Hello, world. Dr. Smith left";
+    let plain = RustG2P::new().expect("Failed to create G2P");
+    assert!(!plain.render(&plain.text_to_phonemes(text).unwrap()).contains(','));
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_pause_markers(true);
+    let phonemes = g2p.text_to_phonemes(text).unwrap();
+    assert_eq!(
+        g2p.render(&phonemes),
+        "HH AH0 L OW1 | , | W ER1 L D | . | D AA1 K T ER0 | S M IH1 TH | L EH1 F T | . |"
+    );
+    assert_eq!(g2p.text_to_words(text).unwrap().len(), 5);
+
+    // Kaldi/HTK 风格：词间短停顿 sp，句间静音 sil
+    let kaldi = BoundarySymbols { word: String::new(), pause: "sp".to_string(), sentence: "sil".to_string() };
+    assert_eq!(
+        phoneme::render_with(&phonemes, Notation::ArpabetNoStress, &kaldi),
+        "HH AH L OW sp W ER L D sil D AA K T ER S M IH TH L EH F T sil"
+    );
+    let tagged = g2p.with_boundary_symbols(BoundarySymbols { word: "<wb>".to_string(), ..BoundarySymbols::default() });
+    assert!(tagged.render(&phonemes).starts_with("HH AH0 L OW1 <wb> , <wb> W ER1 L D"));
+
+    let configured = RustG2P::builder()
+        .pause_markers(true)
+        .boundary_symbols(kaldi)
+        .word_boundaries(false)
+        .build()
+        .unwrap();
+    let rendered = configured.render(&configured.text_to_phonemes("Wait; what?").unwrap());
+    assert_eq!(rendered, "W EY1 T sp W AH1 T sil");
+}
+
+#[test]
+fn test_structured_errors() {
+    use rust_g2p::dict::Dictionary;
+    use rust_g2p::{Fallback, G2PError, RoutingConfig, RulesEngine};
+
+    let error = Dictionary::load_cmu_dict("data/missing.txt").err().unwrap();
+    assert!(matches!(&error, G2PError::DictNotFound { path } if path == "data/missing.txt"));
+
+    let error = RulesEngine::parse_english_rules("a|||AE|1\n#include \"more.rules\"\n").err().unwrap();
+    assert!(matches!(error.root(), G2PError::MalformedRule { line: 2, .. }), "{:?}", error);
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_routing(RoutingConfig {
+        min_confidence: 0.0,
+        require_valid_phonotactics: true,
+        fallback: Fallback::Error,
+    });
+    match g2p.word_to_phonemes("bcdx") {
+        Err(G2PError::OovWord { word, valid, .. }) => {
+            assert_eq!(word, "bcdx");
+            assert!(!valid);
+        }
+        other => panic!("expected an OOV error, got {:?}", other),
+    }
+    assert!(matches!(rust_g2p::phoneme::parse_arpabet("HH QQ"), Err(G2PError::InvalidPhoneme(symbol)) if symbol == "QQ"));
+}
+
+#[test]
+fn test_phone_strings() {
+    use rust_g2p::phoneme::StressMarks;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_pause_markers(true);
+    assert_eq!(g2p.word_to_phone_string("doctor").unwrap(), "D AA K T ER");
+    // 不受记法和边界标记的配置影响
+    assert_eq!(g2p.text_to_phone_string("Hello, world.").unwrap(), "HH AH L OW W ER L D");
+
+    let g2p = g2p.with_phone_stress(StressMarks::Keep);
+    assert_eq!(g2p.text_to_phone_string("Hello, world.").unwrap(), "HH AH0 L OW1 W ER1 L D");
+    let g2p = g2p.with_phone_stress(StressMarks::PrimaryOnly);
+    assert_eq!(g2p.text_to_phone_string("Hello, world.").unwrap(), "HH AH L OW1 W ER1 L D");
+
+    let configured = RustG2P::builder().phone_stress(StressMarks::Keep).build().unwrap();
+    assert_eq!(configured.word_to_phone_string("doctor").unwrap(), "D AA1 K T ER0");
+}
+
+#[test]
+fn test_load_progress() {
+    use rust_g2p::report::LoadProgress;
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&events);
+    let g2p = RustG2P::builder()
+        .on_progress(move |progress| recorded.lock().unwrap().push(progress.clone()))
+        .build()
+        .unwrap();
+    assert!(!g2p.word_to_phonemes("hello").unwrap().is_empty());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0], LoadProgress::Started { path: "data/cmudict.txt".to_string() });
+    let lines: Vec<(usize, usize)> = events
+        .iter()
+        .filter_map(|event| match event {
+            LoadProgress::Lines { done, total } => Some((*done, *total)),
+            _ => None,
+        })
+        .collect();
+    assert!(!lines.is_empty());
+    assert!(lines.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(lines.last().unwrap().0, lines.last().unwrap().1);
+    let finished: Vec<&str> = events
+        .iter()
+        .filter_map(|event| match event {
+            LoadProgress::Finished { path, entries } if *entries > 0 => Some(path.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(finished, vec!["data/cmudict.txt", "data/en_rules.txt"]);
+}
+
+#[test]
+fn test_max_word_length() {
+    use rust_g2p::{G2PError, LongWordPolicy};
+
+    let blob = "qmfzdgvybmvywxsbdlza";
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_max_word_length(4, LongWordPolicy::SpellOut);
+    assert_eq!(g2p.word_to_phonemes(blob).unwrap(), rust_g2p::letters::spell_out("qmfz"));
+    // 词典收录的单词和不超过上限的单词照常转换
+    assert_eq!(g2p.word_to_phone_string("doctor").unwrap(), "D AA K T ER");
+    assert_eq!(g2p.word_to_phone_string("blim").unwrap(), RustG2P::new().unwrap().word_to_phone_string("blim").unwrap());
+
+    let g2p = g2p.with_max_word_length(4, LongWordPolicy::Skip);
+    assert_eq!(g2p.text_to_phone_string(&format!("hello {} doctor", blob)).unwrap(), "HH AH L OW D AA K T ER");
+
+    let g2p = g2p.with_max_word_length(4, LongWordPolicy::Error);
+    match g2p.word_to_phonemes(blob) {
+        Err(G2PError::WordTooLong { word, length, limit }) => {
+            assert_eq!(word, blob);
+            assert_eq!((length, limit), (20, 4));
+        }
+        other => panic!("expected a word length error, got {:?}", other),
+    }
+
+    let config = rust_g2p::G2PConfig::parse("[oov]\nmax_word_length = 4\nlong_words = \"skip\"\n").unwrap();
+    let configured = RustG2P::from_parsed_config(&config).unwrap();
+    assert!(configured.word_to_phonemes(blob).unwrap().is_empty());
+}
+
+#[test]
+fn test_phonemize_iter() {
+    use rust_g2p::{Fallback, Phoneme, RoutingConfig};
+
+    let text = "Hello world. The doctor said \"read it\" twice!\n\nA second\nparagraph, wrapped over lines.\n\n\n";
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    let streamed: Vec<Phoneme> = g2p.phonemize_iter(text).collect::<Result<_, _>>().unwrap();
+    assert_eq!(streamed, g2p.text_to_phonemes(text).unwrap());
+
+    let g2p = g2p.with_pause_markers(true).with_quote_markers(true);
+    let streamed: Vec<Phoneme> = g2p.phonemize_iter(text).collect::<Result<_, _>>().unwrap();
+    assert_eq!(streamed, g2p.text_to_phonemes(text).unwrap());
+    assert_eq!(g2p.phonemize_iter("").count(), 0);
+
+    // 出错的句子之前的音素照常产生，错误之后结束
+    let g2p = g2p.with_routing(RoutingConfig {
+        min_confidence: 0.0,
+        require_valid_phonotactics: true,
+        fallback: Fallback::Error,
+    });
+    let results: Vec<_> = g2p.phonemize_iter("Hello there. Say bcdx now. Goodbye.").collect();
+    assert!(results[..results.len() - 1].iter().all(Result::is_ok));
+    assert!(results.last().unwrap().is_err());
+    assert!(results.len() > 1);
+}
+
+#[test]
+fn test_rule_cache() {
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_rule_cache(2);
+
+    let first = g2p.word_to_phonemes("zorblax").unwrap();
+    assert_eq!(g2p.word_to_phonemes("Zorblax").unwrap(), first);
+    g2p.word_to_phonemes("hello").unwrap();
+    let stats = g2p.get_stats();
+    assert_eq!((stats.rule_cache_hits, stats.rule_cache_misses), (1, 1));
+    assert_eq!(stats.rule_words, 1);
+    assert_eq!(stats.rule_cache_hit_rate(), 0.5);
+    assert!(g2p.memory_report().rule_cache_bytes > 0);
+
+    // 容量满时淘汰最久未使用的单词：snarfle 挤掉 zorblax，quibbet 仍在缓存中
+    g2p.reset_stats();
+    for word in ["zorblax", "quibbet", "snarfle", "quibbet", "zorblax"] {
+        g2p.word_to_phonemes(word).unwrap();
+    }
+    let stats = g2p.get_stats();
+    assert_eq!((stats.rule_cache_hits, stats.rule_words), (2, 3));
+
+    let uncached = RustG2P::builder().rule_cache_size(0).build().unwrap();
+    uncached.word_to_phonemes("zorblax").unwrap();
+    uncached.word_to_phonemes("zorblax").unwrap();
+    let stats = uncached.get_stats();
+    assert_eq!((stats.rule_cache_hits, stats.rule_cache_misses, stats.rule_words), (0, 0, 2));
+    assert_eq!(uncached.memory_report().rule_cache_bytes, 0);
+}
+
+#[test]
+fn test_number_context() {
+    use rust_g2p::numbers::{self, Agreement, Gender, NumberWords};
+    use std::sync::Arc;
+
+    let words = |g2p: &RustG2P, text: &str| -> Vec<String> {
+        g2p.text_to_words(text).unwrap().into_iter().map(|word| word.word).collect()
+    };
+    let g2p = RustG2P::new().expect("Failed to create G2P");
+    assert_eq!(words(&g2p, "May 3, 2024"), ["may", "third", "twenty", "twenty", "four"]);
+    assert_eq!(words(&g2p, "He was born in 1984."), ["he", "was", "born", "in", "nineteen", "eighty", "four"]);
+    assert_eq!(words(&g2p, "Chapter 3"), ["chapter", "three"]);
+    assert_eq!(g2p.text_to_phonemes("in 1984").unwrap(), g2p.text_to_phonemes("in nineteen eighty four").unwrap());
+
+    /// 只区分“一”的阴阳性
+    struct Spanish;
+
+    impl NumberWords for Spanish {
+        fn cardinal(&self, n: u64, agreement: Agreement) -> String {
+            match (n, agreement.gender) {
+                (1, Gender::Feminine) => "una".to_string(),
+                (1, _) => "uno".to_string(),
+                _ => numbers::cardinal(n),
+            }
+        }
+
+        fn ordinal(&self, n: u64, _agreement: Agreement) -> String {
+            numbers::ordinal(n)
+        }
+
+        fn digits(&self, text: &str) -> String {
+            numbers::digits(text)
+        }
+
+        fn agreement(&self, following: Option<&str>) -> Agreement {
+            let gender = match following {
+                Some(word) if word.ends_with('a') => Gender::Feminine,
+                _ => Gender::Masculine,
+            };
+            Agreement { gender, ..Default::default() }
+        }
+    }
+
+    let spanish = RustG2P::new().unwrap().with_number_words(Arc::new(Spanish));
+    assert_eq!(words(&spanish, "1 casa, 1 perro"), ["una", "casa", "uno", "perro"]);
+    
+    // 按语言注册的读法用于该语言的语言包，配置文件也可按名称选择
+    numbers::register_number_words("es", Arc::new(Spanish));
+    assert!(numbers::number_words_for("ES-mx").is_some());
+    assert!(numbers::number_words_for("fr").is_none());
+    let dir = std::env::temp_dir().join(format!("g2p_number_words_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("pack.toml"),
+        "language = \"es\"\n\n[pack]\nname = \"es-numbers\"\n\n[data]\ndictionary = \"dict.txt\"\nrules = \"rules.txt\"\nirregular = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("dict.txt"), "UNA  UW N AA\nCASA  K AA S AA\n").unwrap();
+    std::fs::write(dir.join("rules.txt"), "a|||AA\n").unwrap();
+    let pack = RustG2P::load_language_pack(&dir).unwrap();
+    assert_eq!(words(&pack, "1 casa"), ["una", "casa"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let config = rust_g2p::G2PConfig::parse("[normalization]\nnumber_words = \"es\"\n").unwrap();
+    assert_eq!(words(&RustG2P::from_parsed_config(&config).unwrap(), "1 casa"), ["una", "casa"]);
+    let config = rust_g2p::G2PConfig::parse("[normalization]\nnumber_words = \"xx\"\n").unwrap();
+    assert!(RustG2P::from_parsed_config(&config).is_err());
+}
+
+#[test]
+fn test_phoneme_sets() {
+    use rust_g2p::phoneme::{self, Notation, PhonemeSet};
+    use rust_g2p::{G2PBuilder, G2PConfig, WordPhonemes};
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_phoneme_input(true);
+    let english = PhonemeSet::Arpabet("en".into());
+    assert_eq!(g2p.phoneme_set(), &english);
+    let tokens = g2p.text_to_tokens("Hello, /T EH1 S T/ world!").unwrap();
+    assert!(tokens.iter().all(|token| token.phoneme_set == english));
+
+    // 不同语言的转换器输出的词元混在一起时按音素集区分，逐个投影到IPA后使用同一套符号
+    let british = RustG2P::new().unwrap().with_phoneme_set(PhonemeSet::Arpabet("en-GB".into()));
+    let mut mixed = g2p.text_to_words("hello").unwrap();
+    mixed.extend(british.text_to_words("world").unwrap());
+    assert_eq!(mixed[1].phoneme_set, PhonemeSet::Arpabet("en-GB".into()));
+    let ipa: Vec<WordPhonemes> = mixed.iter().map(WordPhonemes::to_ipa).collect();
+    assert!(ipa.iter().all(|word| word.phoneme_set == PhonemeSet::Ipa));
+    let symbols: Vec<&str> = ipa[0].phonemes.iter().map(|p| p.symbol.as_str()).collect();
+    assert_eq!(symbols, ["h", "ə", "l", "oʊ"]);
+    assert_eq!(phoneme::render(&ipa[0].phonemes, Notation::Ipa), phoneme::render(&mixed[0].phonemes, Notation::Ipa));
+    assert_eq!(ipa[0].to_ipa().phonemes, ipa[0].phonemes);
+    // 原来的词元不变
+    assert_eq!(mixed[0].phonemes[0].symbol, "HH");
+
+    // 配置文件中的 language 决定标注
+    let config = G2PConfig::parse("language = \"en-GB\"\n").unwrap();
+    let configured = G2PBuilder::from_config(config).build().unwrap();
+    assert_eq!(configured.text_to_words("hello").unwrap()[0].phoneme_set, PhonemeSet::Arpabet("en-GB".into()));
+    
+    // 英语转换器和其他语言的语言包的输出混在一起
+    let dir = std::env::temp_dir().join(format!("g2p_phoneme_sets_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("pack.toml"),
+        "language = \"es\"\n\n[pack]\nname = \"es-test\"\n\n[data]\ndictionary = \"dict.txt\"\nrules = \"rules.txt\"\nirregular = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("dict.txt"), "HOLA  OW1 L AA0\n").unwrap();
+    std::fs::write(dir.join("rules.txt"), "a|||AA\n").unwrap();
+    let spanish = RustG2P::load_language_pack(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let spanish_set = PhonemeSet::Arpabet("es".into());
+    assert_eq!(spanish.phoneme_set(), &spanish_set);
+    let mut mixed = g2p.text_to_words("hello").unwrap();
+    mixed.extend(spanish.text_to_words("hola").unwrap());
+    let sets: Vec<&PhonemeSet> = mixed.iter().map(|word| &word.phoneme_set).collect();
+    assert_eq!(sets, [&english, &spanish_set]);
+    let ipa: Vec<WordPhonemes> = mixed.iter().map(WordPhonemes::to_ipa).collect();
+    assert!(ipa.iter().all(|word| word.phoneme_set == PhonemeSet::Ipa));
+    let symbols: Vec<&str> = ipa[1].phonemes.iter().map(|p| p.symbol.as_str()).collect();
+    assert_eq!(symbols, ["oʊ", "l", "ɑ"]);
+    // with_phoneme_set 在语言包的标注之上改写
+    let mexican = spanish.with_phoneme_set(PhonemeSet::Arpabet("es-MX".into()));
+    assert_eq!(mexican.text_to_words("hola").unwrap()[0].phoneme_set, PhonemeSet::Arpabet("es-MX".into()));
+    assert_eq!(mexican.text_to_words("hola").unwrap()[0].to_ipa().phonemes, ipa[1].phonemes);
+}
+
+#[test]
+fn test_contractions() {
+    use rust_g2p::phoneme::StressMarks;
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_phone_stress(StressMarks::Keep);
+    for (word, expected) in [
+        ("don't", "D OW1 N T"),
+        ("didn't", "D IH1 D AH0 N T"),
+        ("I'm", "AY1 M"),
+        ("isn't", "IH1 Z AH0 N T"),
+        ("o'clock", "AH0 K L AA1 K"),
+        ("don’t", "D OW1 N T"),
+        ("o’clock", "AH0 K L AA1 K"),
+    ] {
+        assert_eq!(g2p.word_to_phone_string(word).unwrap(), expected, "{}", word);
+    }
+    assert_eq!(
+        g2p.text_to_phone_string("I'm sure it isn't five o’clock, don't you think?").unwrap(),
+        "AY1 M SH UH1 R IH1 T IH1 Z AH0 N T F AY1 V AH0 K L AA1 K D OW1 N T Y UW1 TH IH1 NG K"
+    );
+}
+
+#[test]
+fn test_hunspell_affixes() {
+    use rust_g2p::hunspell::AffixAnalysis;
+    use rust_g2p::AffixDictionary;
+
+    let aff = "SET UTF-8\nPFX R Y 1\nPFX R   0  re  .\nSFX D Y 2\nSFX D   0  d   e\nSFX D   y  ied [^aeiou]y\n";
+    let dic = "3\nvape/RD\ncry/D\nplay/D\n";
+    let affixes = AffixDictionary::parse(aff, dic).unwrap();
+    assert_eq!(affixes.len(), 3);
+    assert_eq!(
+        affixes.analyze("revaped"),
+        vec![AffixAnalysis { stem: "vape".to_string(), prefix: Some("re".to_string()), suffix: Some("d".to_string()) }]
+    );
+    assert_eq!(affixes.analyze("cried")[0].stem, "cry");
+    // 条件不满足：play 的 y 前是元音
+    assert!(affixes.analyze("plaied").is_empty());
+
+    let g2p = RustG2P::new().expect("Failed to create G2P").with_affix_dictionary(affixes);
+    let phonemes = g2p.render(&g2p.word_to_phonemes("revaped").unwrap());
+    assert!(phonemes.ends_with("V EY1 P T"), "{}", phonemes);
+
+    let long = AffixDictionary::parse("FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n", "1\nvape/AaBb\n").unwrap();
+    assert_eq!(long.analyze("vapes")[0].stem, "vape");
+}
+
+#[test]
+fn test_language_packs() {
+    use rust_g2p::pack;
+    use rust_g2p::LanguagePack;
+
+    let root = std::env::temp_dir().join(format!("g2p_packs_{}", std::process::id()));
+    let dir = root.join("en-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("pack.toml"),
+        "language = \"en-GB\"\n\n[pack]\nname = \"en-test\"\nversion = \"0.1.0\"\nphonemes = \"phonemes.txt\"\n\n\
+         [data]\ndictionary = \"dict.txt\"\nrules = \"rules.txt\"\nirregular = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("dict.txt"), "TOMATO  T AH0 M AA1 T OW2\n").unwrap();
+    std::fs::write(dir.join("rules.txt"), "b|||B\na|||AE1\nt|||T\n").unwrap();
+    std::fs::write(dir.join("phonemes.txt"), "# vowels\nAA\nAE\nAH\nOW\nB\nM\nT\n").unwrap();
+    // 没有清单的目录不是语言包
+    std::fs::create_dir_all(root.join("notes")).unwrap();
+
+    let packs = pack::discover(&root).unwrap();
+    assert_eq!(packs.len(), 1);
+    assert_eq!((packs[0].name.as_str(), packs[0].language()), ("en-test", "en-GB"));
+    assert_eq!(packs[0].phonemes.as_ref().unwrap().len(), 7);
+
+    let g2p = RustG2P::load_language_pack(&dir).unwrap();
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("tomato").unwrap()), "T AH0 M AA1 T OW2");
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("bat").unwrap()), "B AE1 T");
+    
+    // 发音用到音素集以外的音素时加载失败
+    std::fs::write(dir.join("dict.txt"), "TOMATO  T AH0 M EY1 T OW2\n").unwrap();
+    let err = RustG2P::load_language_pack(&dir).err().unwrap().chain().to_string();
+    assert!(err.contains("'tomato'") && err.contains("'EY'"), "{}", err);
+    std::fs::write(dir.join("dict.txt"), "TOMATO  T AH0 M AA1 T OW2\n").unwrap();
+    std::fs::write(dir.join("rules.txt"), "b|||B\na|||EY1\nt|||T\n").unwrap();
+    assert!(RustG2P::load_language_pack(&dir).is_err());
+    
+    // 其他语言的语言包只使用自己的数据
+    let es = root.join("es-test");
+    std::fs::create_dir_all(&es).unwrap();
+    std::fs::write(
+        es.join("pack.toml"),
+        "language = \"es\"\n\n[pack]\nname = \"es-test\"\n\n[data]\ndictionary = \"dict.txt\"\nrules = \"rules.txt\"\nirregular = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(es.join("dict.txt"), "CASA  K AA S AA\n").unwrap();
+    std::fs::write(es.join("rules.txt"), "c|||K\na|||AA\ns|||S\no|||OW\n").unwrap();
+    let g2p = RustG2P::load_language_pack(&es).unwrap();
+    assert_eq!(g2p.language(), "es");
+    assert_eq!(g2p.render(&g2p.text_to_phonemes("casa").unwrap()), "K AA0 S AA0 |");
+    assert_eq!(g2p.render(&g2p.word_to_phonemes("cosa").unwrap()), "K OW0 S AA0");
+
+    std::fs::write(dir.join("pack.toml"), "language = \"en\"\n").unwrap();
+    assert!(LanguagePack::open(&dir).is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,76 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn test_pipe_jsonl() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_g2p"))
+        .args(["pipe", "--format", "jsonl"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start g2p");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"Hello world\nqzxv\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    // stdout只包含JSON记录
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let records: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["text"], "Hello world");
+    assert_eq!(records[0]["words"], serde_json::json!(["hello", "world"]));
+    assert_eq!(records[0]["phonemes"][0], serde_json::json!(["HH", "AH0", "L", "OW1"]));
+    assert_eq!(records[0]["oov"], serde_json::json!([false, false]));
+    assert_eq!(records[1]["oov"], serde_json::json!([true]));
+    assert_eq!(records[0]["kinds"], serde_json::json!(["word", "word"]));
+    assert_eq!(records[0]["surfaces"], serde_json::json!(["Hello", "world"]));
+}
+
+#[test]
+fn test_unknown_command() {
+    let output = Command::new(env!("CARGO_BIN_EXE_g2p"))
+        .arg("bogus")
+        .output()
+        .expect("Failed to start g2p");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown command 'bogus'"));
+}
+
+#[test]
+fn test_lexicon_command() {
+    let output = std::env::temp_dir().join(format!("g2p-lexicon-{}.dict", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_g2p"))
+        .args(["lexicon", "--format", "kaldi", "--output"])
+        .arg(&output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start g2p");
+    child.stdin.take().unwrap().write_all(b"# words\nhello 120\nqzxv\nhello\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let lexicon = std::fs::read_to_string(&output).unwrap();
+    std::fs::remove_file(&output).unwrap();
+    let lines: Vec<&str> = lexicon.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "hello HH AH0 L OW1");
+    assert!(lines[1].starts_with("qzxv "));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_g2p"))
+        .args(["lexicon", "--format", "htk"])
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("Unknown format 'htk'"));
+}
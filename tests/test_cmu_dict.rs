@@ -25,6 +25,13 @@ mod tests {
             let phonemes = dict.lookup(word);
             assert!(phonemes.is_some(), "Word '{}' should be in dictionary", word);
             
+            // 借用查找的编码解码后与复制查找结果一致，且不区分大小写
+            let decoded = |w: &str| {
+                dict.lookup_ref(w).map(|codes| codes.iter().map(|c| c.decode()).collect::<Vec<_>>())
+            };
+            assert_eq!(decoded(word), phonemes);
+            assert_eq!(decoded(&word.to_uppercase()), phonemes);
+            
             if let Some(phonemes) = phonemes {
                 assert!(!phonemes.is_empty(), "Phonemes for '{}' should not be empty", word);
                 println!("{}: {:?}", word, phonemes);
@@ -45,4 +52,276 @@ mod tests {
             assert_eq!(phonemes[0].symbol, "HH", "First phoneme should be HH");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_phoneme_codes() {
+        use rust_g2p::phoneme::PhonemeCode;
+        use rust_g2p::Phoneme;
+
+        for symbol in ["AH0", "EY1", "ER2", "B", "NG", "ZH"] {
+            let phoneme = Phoneme::from_arpabet(symbol);
+            let code = PhonemeCode::encode(&phoneme).unwrap();
+            assert_eq!(code.decode(), phoneme);
+            assert_eq!(code.arpabet(), symbol);
+            assert_eq!(PhonemeCode::from_u8(code.to_u8()), Some(code));
+        }
+
+        assert!(PhonemeCode::encode(&Phoneme::word_boundary()).is_none());
+        assert!(PhonemeCode::from_u8(0xff).is_none());
+    }
+
+    #[test]
+    fn test_minimal_lexicon() {
+        use rust_g2p::dict;
+
+        let dict = Dictionary::load_cmu_dict("data/cmudict.txt")
+            .expect("Failed to load CMU dictionary");
+        let ranked = dict::parse_frequency_list("# 注释\nthe 100\nzzzzqx\nhello\nworld\n");
+        assert_eq!(ranked, vec!["the", "zzzzqx", "hello", "world"]);
+
+        // 跳过词典中没有的单词，只保留前两个
+        let minimal = dict.minimal_lexicon(&ranked, 2);
+        assert_eq!(minimal.size(), 2);
+        assert!(minimal.lookup("world").is_none());
+
+        // 输出的CMU格式可以重新加载
+        let reloaded = Dictionary::from_cmu_str(&minimal.to_cmu_string()).unwrap();
+        assert_eq!(reloaded.lookup("hello"), dict.lookup("hello"));
+        assert_eq!(reloaded.lookup("the"), dict.lookup("the"));
+    }
+
+    #[test]
+    fn test_prune_by_frequency() {
+        use rust_g2p::RulesEngine;
+
+        let mut dict = Dictionary::from_cmu_str(
+            "THE  DH AH0\n\
+             CAT  K AE1 T\n\
+             DOG  D AO1 G\n\
+             YACHT  Y AA1 T\n",
+        )
+        .unwrap();
+
+        let report = dict.prune_by_frequency(&["the", "cat", "dog"], 2);
+        assert_eq!(report.kept, 2);
+        assert_eq!(dict.size(), 2);
+        assert!(dict.lookup("dog").is_none());
+        let removed: Vec<&str> = report.removed.iter().map(|(word, _)| word.as_str()).collect();
+        assert_eq!(removed, vec!["dog", "yacht"]);
+
+        // 被移除的单词由规则引擎处理，评估其准确率
+        let rules = RulesEngine::load_english_rules("data/en_rules.txt").unwrap();
+        let impact = report.accuracy_impact(&rules);
+        assert_eq!(impact.total, 2);
+        assert_eq!(impact.reference_phonemes, 6);
+        assert!(impact.word_accuracy() <= 1.0);
+    }
+
+    #[test]
+    fn test_export_ipa() {
+        let dict = Dictionary::from_cmu_str(
+            "HELLO  HH AH0 L OW1
+             BIRD  B ER1 D
+             ONION  AH1 N Y AH0 N
+",
+        )
+        .unwrap();
+
+        let path = std::env::temp_dir().join(format!("g2p_ipa_{}.tsv", std::process::id()));
+        dict.export_ipa(path.to_str().unwrap()).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // 按单词排序，AH/ER按重读区分
+        assert_eq!(content, "bird\tbˈɝd\nhello\thəlˈoʊ\nonion\tˈʌnjən\n");
+    }
+
+    #[test]
+    fn test_lexicon_schema() {
+        use rust_g2p::schema::Capability;
+
+        let dict = Dictionary::from_cmu_str(";;;!g2p-lexicon 1\nREAD  R IY1 D\nREAD(2)  R EH1 D\n").unwrap();
+        assert_eq!(dict.schema().version, Some(1));
+        assert!(dict.schema().uses(Capability::Variants));
+
+        // 带版本头时不再跳过无法解析的行
+        assert!(Dictionary::from_cmu_str(";;;!g2p-lexicon 1\nREAD  R IY1 D\nHELLO  HH QQ1\n").is_err());
+        assert!(Dictionary::from_cmu_str(";;;!g2p-lexicon 1\n;;;!requires classes\nREAD  R IY1 D\n").is_err());
+        assert!(Dictionary::from_cmu_str(";;;!g2p-lexicon 9\nREAD  R IY1 D\n").is_err());
+
+        let legacy = Dictionary::from_cmu_str("READ  R IY1 D\nHELLO  HH QQ1\n").unwrap();
+        assert_eq!(legacy.schema().version, None);
+        assert_eq!(legacy.lookup("hello").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        use rust_g2p::DuplicatePolicy;
+
+        let content = "READ  R IY1 D\nREAD(2)  R EH1 D\nCAT  K AE1 T\nREAD  R IY1 D\n";
+        let render = |dict: &Dictionary| {
+            let phonemes = dict.lookup("read").unwrap();
+            phonemes.iter().map(|p| p.symbol.as_str()).collect::<Vec<_>>().join(" ")
+        };
+
+        let (dict, report) = Dictionary::from_cmu_str_with_policy(content, DuplicatePolicy::KeepLast).unwrap();
+        assert_eq!(report.duplicates, 2);
+        assert_eq!(render(&dict), "R IY D");
+        let readings = dict.lookup_all("read");
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[1][1].symbol, "EH");
+        // 带编号的变体不会成为主读音，重新加载得到相同的词典
+        assert_eq!(dict.to_cmu_string(), "CAT  K AE1 T\nREAD  R IY1 D\nREAD(2)  R EH1 D\n");
+        let reloaded = Dictionary::from_cmu_str_with_report(&dict.to_cmu_string()).unwrap().0;
+        assert_eq!(render(&reloaded), "R IY D");
+        assert_eq!(reloaded.lookup_all("read").len(), 2);
+        let (dict, _) = Dictionary::from_cmu_str_with_policy("DON'T  D OW1 N T\nDON'T(2)  D OW1 N\n", DuplicatePolicy::KeepLast).unwrap();
+        assert_eq!(dict.lookup("don't").unwrap().len(), 4);
+        // 不带编号的重复词条中后出现的成为主读音，先出现的保留为变体
+        let (dict, _) = Dictionary::from_cmu_str_with_policy("READ  R EH1 D\nREAD  R IY1 D\n", DuplicatePolicy::KeepLast).unwrap();
+        assert_eq!(render(&dict), "R IY D");
+        assert_eq!(dict.to_cmu_string(), "READ  R IY1 D\nREAD(2)  R EH1 D\n");
+        // 去掉数字和标点后才相同的写法不会取代主读音
+        let (dict, _) = Dictionary::from_cmu_str_with_policy(
+            "A  AH0\nA42128  EY1 F AO1 R\nCAT  K AE1 T\nCAT-1  K AE1 T W AH1 N\n",
+            DuplicatePolicy::KeepLast,
+        )
+        .unwrap();
+        assert_eq!(dict.lookup("a").unwrap()[0].symbol, "AH");
+        assert_eq!(dict.lookup("cat").unwrap().len(), 3);
+        assert_eq!(dict.lookup_all("cat").len(), 2);
+
+        let (dict, _) = Dictionary::from_cmu_str_with_policy(content, DuplicatePolicy::Overwrite).unwrap();
+        assert_eq!(render(&dict), "R IY D");
+        assert_eq!(dict.lookup_all("read").len(), 1);
+
+        let (dict, _) = Dictionary::from_cmu_str_with_policy("READ  R IY1 D\nREAD(2)  R EH1 D\n", DuplicatePolicy::KeepFirst).unwrap();
+        assert_eq!(render(&dict), "R IY D");
+        assert_eq!(dict.lookup_all("read").len(), 1);
+
+        // 相同的读音只保留一次
+        let (dict, report) = Dictionary::from_cmu_str_with_policy(content, DuplicatePolicy::KeepVariants).unwrap();
+        assert_eq!(report.duplicates, 2);
+        assert_eq!(dict.size(), 2);
+        let readings = dict.lookup_all("READ");
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[1][1].symbol, "EH");
+        assert_eq!(dict.to_cmu_string(), "CAT  K AE1 T\nREAD  R IY1 D\nREAD(2)  R EH1 D\n");
+
+        let error = Dictionary::from_cmu_str_with_policy(content, DuplicatePolicy::Error).err().unwrap();
+        assert_eq!(error.to_string(), "Line 2: duplicate entry for 'read'");
+    }
+
+    #[test]
+    fn test_compiled_dictionary() {
+        let text = std::env::temp_dir().join(format!("g2p_compiled_{}.txt", std::process::id()));
+        let compiled = std::env::temp_dir().join(format!("g2p_compiled_{}.bin", std::process::id()));
+        let (text, compiled) = (text.to_str().unwrap(), compiled.to_str().unwrap());
+        std::fs::write(text, "READ  R IY1 D\nREAD(2)  R EH1 D\nDOG  D AO1 G\nHELLO  HH AH0 L OW1\n").unwrap();
+
+        Dictionary::compile(text, compiled).unwrap();
+        assert!(rust_g2p::dict::is_compiled(compiled));
+        assert!(!rust_g2p::dict::is_compiled(text));
+
+        let original = Dictionary::load_cmu_dict(text).unwrap();
+        let loaded = Dictionary::load_compiled(compiled).unwrap();
+        assert_eq!(loaded.size(), 3);
+        assert_eq!(loaded.lookup("dog"), original.lookup("dog"));
+        assert_eq!(loaded.lookup_all("read"), original.lookup_all("read"));
+        assert_eq!(loaded.to_cmu_string(), original.to_cmu_string());
+
+        // load_cmu_dict 按魔数识别二进制词典
+        let detected = Dictionary::load_cmu_dict(compiled).unwrap();
+        assert_eq!(detected.lookup_all("read").len(), 2);
+        // 明确要求的处理方式与编译时不同时报错，不静默忽略
+        let same = Dictionary::load_cmu_dict_with_progress(compiled, rust_g2p::dict::DuplicatePolicy::default(), &|_| {});
+        assert_eq!(same.unwrap().size(), 3);
+        let error = Dictionary::load_cmu_dict_with_progress(compiled, rust_g2p::dict::DuplicatePolicy::KeepFirst, &|_| {}).err().unwrap();
+        assert!(matches!(error, rust_g2p::error::G2PError::Config(_)));
+        assert!(error.to_string().contains("KeepFirst was requested"));
+
+        let bytes = std::fs::read(compiled).unwrap();
+        let mut corrupt = bytes.clone();
+        *corrupt.last_mut().unwrap() ^= 0xff;
+        let error = Dictionary::from_compiled_bytes(&corrupt).err().unwrap();
+        assert!(error.to_string().contains("Checksum mismatch"));
+        assert!(Dictionary::from_compiled_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut version = bytes.clone();
+        version[8] = 99;
+        let error = Dictionary::from_compiled_bytes(&version).err().unwrap();
+        assert_eq!(error.to_string(), "Unsupported compiled dictionary version 99 (expected 2)");
+        assert!(Dictionary::from_compiled_bytes(b"READ  R IY1 D\n").is_err());
+        // 头部的单词数不在校验范围内，过大时按载荷长度预留内存并报错
+        let mut count = bytes.clone();
+        count[21..25].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Dictionary::from_compiled_bytes(&count).is_err());
+        
+        // 版本头和特性随二进制词典保存
+        assert_eq!(loaded.schema(), original.schema());
+        std::fs::write(text, ";;;!g2p-lexicon 1\n;;;!requires variants\nREAD  R IY1 D\nREAD(2)  R EH1 D\n").unwrap();
+        Dictionary::compile(text, compiled).unwrap();
+        let versioned = Dictionary::load_compiled(compiled).unwrap();
+        assert_eq!(versioned.schema(), Dictionary::load_cmu_dict(text).unwrap().schema());
+        assert_eq!((versioned.schema().version, versioned.schema().declared.len()), (Some(1), 1));
+
+        std::fs::remove_file(text).unwrap();
+        std::fs::remove_file(compiled).unwrap();
+    }
+
+    #[test]
+    fn test_compact_dictionary() {
+        use rust_g2p::phoneme::Phoneme;
+
+        let content = "READ  R IY1 D\nREAD(2)  R EH1 D\nDOG  D AO1 G\nDOGS  D AO1 G Z\nREED  R IY1 D\n";
+        let dict = Dictionary::from_cmu_str(content).unwrap();
+        let compact = Dictionary::from_cmu_str(content).unwrap().into_compact().unwrap();
+        assert!(compact.is_compact() && !dict.is_compact());
+        assert_eq!(compact.size(), 4);
+        for word in ["read", "READ", "dog", "dogs", "reed", "cat"] {
+            assert_eq!(compact.lookup(word), dict.lookup(word));
+            assert_eq!(compact.lookup_all(word), dict.lookup_all(word));
+        }
+        assert_eq!(compact.lookup_all("read").len(), 2);
+        assert_eq!(compact.to_cmu_string(), dict.to_cmu_string());
+        assert_eq!(compact.to_compiled_bytes().unwrap(), dict.to_compiled_bytes().unwrap());
+        assert_eq!(compact.get_sample_words(2), vec!["dog", "dogs"]);
+        assert_eq!(compact.nearest_spellings("dgs", 1), vec!["dogs"]);
+
+        // 修改词条时转换回哈希表
+        let mut compact = compact;
+        compact.add_entry("cat".to_string(), vec![Phoneme::from_arpabet("K"), Phoneme::from_arpabet("AE1"), Phoneme::from_arpabet("T")]);
+        assert!(!compact.is_compact());
+        assert_eq!(compact.size(), 5);
+        assert_eq!(compact.lookup_all("read").len(), 2);
+
+        let full = Dictionary::load_cmu_dict("data/cmudict.txt").unwrap();
+        let map_size = full.heap_size();
+        let words = full.get_sample_words(1000);
+        let full_compact = Dictionary::load_cmu_dict("data/cmudict.txt").unwrap().into_compact().unwrap();
+        assert!(full_compact.heap_size() * 4 < map_size);
+        assert!(words.iter().all(|word| full_compact.lookup_all(word) == full.lookup_all(word)));
+    }
+
+    #[test]
+    fn test_shared_pronunciations() {
+        use rust_g2p::phoneme::Phoneme;
+
+        let content = "KNIGHT  N AY1 T\nNIGHT  N AY1 T\nREAD  R IY1 D\nREAD(2)  R EH1 D\nREED  R IY1 D\nRED  R EH1 D\n";
+        let mut dict = Dictionary::from_cmu_str(content).unwrap();
+        assert_eq!(dict.lookup("knight"), dict.lookup("night"));
+        assert_eq!(dict.lookup_all("read").len(), 2);
+
+        // 共享读音的单词互不影响
+        dict.add_entry("night".to_string(), vec![Phoneme::from_arpabet("N"), Phoneme::from_arpabet("AY1"), Phoneme::from_arpabet("T"), Phoneme::from_arpabet("S")]);
+        assert_eq!(dict.lookup("knight").unwrap().len(), 3);
+        assert_eq!(dict.lookup("night").unwrap().len(), 4);
+
+        let report = dict.prune_by_frequency(&["read", "knight", "red"], 3);
+        assert_eq!(report.kept, 3);
+        assert_eq!(report.removed.iter().map(|(word, _)| word.as_str()).collect::<Vec<_>>(), vec!["night", "reed"]);
+        assert_eq!(dict.lookup_all("read"), Dictionary::from_cmu_str(content).unwrap().lookup_all("read"));
+        assert_eq!(dict.lookup("read").unwrap()[1].symbol, "IY"); // 不带编号的 READ 为主读音
+        assert_eq!(dict.lookup("knight").unwrap().len(), 3);
+    }
+}
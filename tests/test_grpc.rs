@@ -0,0 +1,45 @@
+#![cfg(feature = "grpc")]
+
+use rust_g2p::grpc::proto::PhonemizeRequest;
+use rust_g2p::grpc::{PhonemizerClient, PhonemizerService};
+use rust_g2p::RustG2P;
+use std::sync::Arc;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_stream::StreamExt;
+
+#[test]
+fn test_phonemize_stream() {
+    let g2p = Arc::new(RustG2P::new().expect("Failed to create G2P"));
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(PhonemizerService::new(g2p).into_server())
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let mut client = PhonemizerClient::connect(format!("http://{}", addr)).await.unwrap();
+        let requests = tokio_stream::iter(["Hello world", "qzxv"].map(|text| PhonemizeRequest {
+            text: text.to_string(),
+        }));
+        let mut responses = client.phonemize(requests).await.unwrap().into_inner();
+
+        // 响应与请求一一对应且顺序一致
+        let first = responses.next().await.unwrap().unwrap();
+        assert_eq!(first.text, "Hello world");
+        assert!(first.error.is_empty());
+        assert_eq!(first.words.len(), 2);
+        assert_eq!(first.words[0].word, "hello");
+        assert_eq!(first.words[0].phonemes, vec!["HH", "AH0", "L", "OW1"]);
+        assert!(!first.words[0].oov);
+        assert_eq!(first.words[0].kind, "word");
+
+        let second = responses.next().await.unwrap().unwrap();
+        assert_eq!(second.text, "qzxv");
+        assert!(second.words[0].oov);
+        assert!(responses.next().await.is_none());
+    });
+}
@@ -0,0 +1,317 @@
+use rust_g2p::rules::import;
+use rust_g2p::RulesEngine;
+
+fn symbols(engine: &RulesEngine, word: &str) -> Vec<String> {
+    engine
+        .apply_rules(word)
+        .unwrap()
+        .iter()
+        .map(|p| p.symbol.clone())
+        .collect()
+}
+
+#[test]
+fn test_nrl_import() {
+    let rules = import::parse_nrl(
+        "// Elovitz风格规则\n\
+         [CH]=CH\n\
+         #:[E] =\n\
+         [E]=EH\n\
+         [A]=AE\n\
+         [T]=T\n\
+         [S]=S\n",
+    )
+    .unwrap();
+    assert_eq!(rules.len(), 6);
+    assert_eq!(rules[1].left_context.as_deref(), Some("#:"));
+    assert_eq!(rules[1].right_context.as_deref(), Some("_"));
+    assert!(rules[1].phonemes.is_empty());
+
+    let engine = RulesEngine::from_rules(rules);
+    // 词尾e在元音之后不发音
+    assert_eq!(symbols(&engine, "taste"), vec!["T", "AE", "S", "T"]);
+    assert_eq!(symbols(&engine, "chest"), vec!["CH", "EH", "S", "T"]);
+}
+
+#[test]
+fn test_festival_import() {
+    let rules = import::parse_festival(
+        "; 简单的Festival规则集\n\
+         (lts.ruleset\n\
+          demo\n\
+          ((V a e i o u) (C b c d f g h j k l m n p q r s t v w x y z))\n\
+          (\n\
+           ( [ c ] e = s )\n\
+           ( [ c ] = k )\n\
+           ( V [ a ] # = ax )\n\
+           ( [ a ] = ae )\n\
+           ( V C * [ e ] # = _epsilon_ )\n\
+           ( [ e ] = eh )\n\
+           ( [ x ] = k-s )\n\
+          ))\n",
+    )
+    .unwrap();
+    assert_eq!(rules.len(), 7);
+    assert_eq!(rules[2].left_context.as_deref(), Some("{aeiou}"));
+    assert_eq!(rules[6].phonemes, vec!["K", "S"]);
+
+    let engine = RulesEngine::from_rules(rules);
+    assert_eq!(symbols(&engine, "cex"), vec!["S", "EH", "K", "S"]);
+    assert_eq!(symbols(&engine, "aea"), vec!["AE", "EH", "AH"]);
+    // 词尾e前可隔任意个辅音出现元音时不发音
+    assert_eq!(symbols(&engine, "ace"), vec!["AE", "S"]);
+}
+
+#[test]
+fn test_rule_format_round_trip() {
+    use rust_g2p::rules::{convert, RuleFormat, RuleSet};
+
+    let original = std::fs::read_to_string("data/en_rules.txt").unwrap();
+    let festival = convert(&original, RuleFormat::Pipe, RuleFormat::Festival).unwrap();
+    let nrl = convert(&festival, RuleFormat::Festival, RuleFormat::Nrl).unwrap();
+    let pipe = convert(&nrl, RuleFormat::Nrl, RuleFormat::Pipe).unwrap();
+
+    let before = RulesEngine::load_english_rules("data/en_rules.txt").unwrap();
+    let after = RulesEngine::from_rule_set(RuleSet::parse(&pipe, RuleFormat::Pipe).unwrap());
+    for word in ["church", "knot", "nation", "cell", "phone", "thing", "yacht", "gnome"] {
+        assert_eq!(symbols(&before, word), symbols(&after, word), "mismatch for '{}'", word);
+    }
+}
+
+#[test]
+fn test_rule_file_schema() {
+    use rust_g2p::rules::{RuleFormat, RuleSet};
+    use rust_g2p::schema::Capability;
+
+    let engine = RulesEngine::load_english_rules("data/en_rules.txt").unwrap();
+    assert_eq!(engine.schema().version, Some(1));
+    assert!(!engine.schema().uses(Capability::Irregular));
+    assert!(!engine.schema().uses(Capability::Classes));
+
+    // 带版本头的文件拒绝无法识别的内容
+    let parse = |content: &str| RuleSet::parse(content, RuleFormat::Pipe);
+    let versioned = "#!g2p-rules 1\nph|||F|2|\n";
+    let set = parse(versioned).unwrap();
+    assert_eq!(set.schema.used, vec![Capability::Weights]);
+    assert!(parse(&format!("{}c|{{e}}||S\n", versioned)).unwrap().schema.uses(Capability::Classes));
+    assert!(parse(&format!("{}bad line\n", versioned)).is_err());
+    assert!(parse(&format!("{}b|||B|1|sometimes\n", versioned)).is_err());
+    assert!(parse(&format!("{}b|||B|high|\n", versioned)).is_err());
+    assert!(parse("#!g2p-rules 2\nb|||B\n").is_err());
+    assert!(parse("#!g2p-rules 1\n#!requires stems\nb|||B\n").is_err());
+    assert!(parse("#include \"suffixes.rules\"\nb|||B\n").is_err());
+
+    // 没有版本头的旧文件跳过无法识别的行
+    let legacy = parse("bad line\nb|||B|1|sometimes\n").unwrap();
+    assert_eq!(legacy.schema.version, None);
+    assert_eq!(legacy.rules.len(), 1);
+
+    // 输出的规则文本带版本头，可按严格模式重新解析
+    let rendered = set.render(RuleFormat::Pipe).unwrap();
+    assert!(rendered.starts_with("#!g2p-rules 1\n"));
+    assert_eq!(parse(&rendered).unwrap().rules.len(), 1);
+}
+
+#[test]
+fn test_rule_includes() {
+    use rust_g2p::schema::Capability;
+
+    let dir = std::env::temp_dir().join(format!("g2p_includes_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("shared")).unwrap();
+    std::fs::write(dir.join("main.rules"), "#!g2p-rules 1\n#include \"shared/vowels.rules\"\nc|||K\nt|||T\n#include \"suffixes.rules\"\n").unwrap();
+    std::fs::write(dir.join("shared/vowels.rules"), "a|||AE\nIRREGULAR|cat|K AE1 T\n").unwrap();
+    std::fs::write(dir.join("suffixes.rules"), "#!g2p-rules 1\ntion||_|SH AH0 N|6|\n").unwrap();
+    std::fs::write(dir.join("loop.rules"), "#include \"loop.rules\"\n").unwrap();
+
+    let engine = RulesEngine::load_english_rules(dir.join("main.rules").to_str().unwrap()).unwrap();
+    assert_eq!(symbols(&engine, "tact"), vec!["T", "AE", "K", "T"]);
+    assert_eq!(symbols(&engine, "action"), vec!["AE", "K", "SH", "AH", "N"]);
+    assert!(engine.apply_rules_scored("cat").unwrap().irregular);
+    assert!(engine.schema().uses(Capability::Includes));
+    assert!(engine.schema().uses(Capability::Weights));
+
+    let cycle = RulesEngine::load_english_rules(dir.join("loop.rules").to_str().unwrap());
+    assert!(cycle.err().unwrap().chain().to_string().contains("Include cycle"));
+    std::fs::write(dir.join("missing.rules"), "#include \"nowhere.rules\"\n").unwrap();
+    assert!(RulesEngine::load_english_rules(dir.join("missing.rules").to_str().unwrap()).is_err());
+    std::fs::remove_dir_all(&dir).ok();
+
+    // 从文本解析时无法解析包含的文件
+    assert!(RulesEngine::parse_english_rules("#include \"suffixes.rules\"\n").is_err());
+}
+
+#[test]
+fn test_named_captures() {
+    use rust_g2p::rules::{convert, RuleFormat};
+    use rust_g2p::schema::Capability;
+
+    // 词尾的h读出前面元音的默认音素，重音改为主重音
+    let rules = "#!g2p-rules 1\na|||AE\no|||AA\nk|||K\nh|<v:{aeo}>|_|HH $v1|5|\n";
+    let engine = RulesEngine::parse_english_rules(rules).unwrap();
+    assert_eq!(symbols(&engine, "kah"), vec!["K", "AE", "HH", "AE"]);
+    let output = engine.apply_rules("koh").unwrap();
+    assert_eq!(output[3].to_notation(rust_g2p::phoneme::Notation::Arpabet), "OW1");
+    assert!(engine.schema().uses(Capability::Captures));
+
+    let (_, steps) = engine.trace_rules("koh").unwrap();
+    assert_eq!(steps[2].phonemes, vec!["HH", "OW1"]);
+
+    // 不匹配捕获的上下文时规则不适用
+    assert_eq!(symbols(&engine, "kh"), vec!["K", "HH"]);
+
+    assert!(RulesEngine::parse_english_rules("#!g2p-rules 1\nh|<v:#>||HH $w|5|\n").is_err());
+    assert!(convert(rules, RuleFormat::Pipe, RuleFormat::Festival).is_err());
+}
+
+#[test]
+fn test_sandhi_rules() {
+    use rust_g2p::phoneme::{self, Notation, Phoneme};
+    use rust_g2p::rules::{convert, RuleFormat, RuleSet};
+
+    let rules = "#!g2p-rules 1\nt|||T\nSANDHI|T _ Y|CH _|\nSANDHI|[vowel] _ [vowel]|$1 _ R $3|\nSANDHI|D _ Y|JH _|5\n";
+    let engine = RulesEngine::parse_english_rules(rules).unwrap();
+    assert_eq!(engine.sandhi_rules()[0].pattern, vec!["D", "_", "Y"]);
+
+    // 按词拼接的音素序列，词后带词边界
+    let stream = |words: &[&str]| {
+        let mut phonemes = Vec::new();
+        for word in words {
+            phonemes.extend(phoneme::parse_arpabet(word).unwrap());
+            phonemes.push(Phoneme::word_boundary());
+        }
+        phonemes
+    };
+    let render = |phonemes: Vec<Phoneme>| phoneme::render(&phonemes, Notation::Arpabet);
+    assert_eq!(render(engine.apply_sandhi(stream(&["G AA1 T", "Y UW1"]))), "G AA1 CH | UW1 |");
+    assert_eq!(render(engine.apply_sandhi(stream(&["D IH1 D", "Y UW1"]))), "D IH1 JH | UW1 |");
+    assert_eq!(render(engine.apply_sandhi(stream(&["L AO1", "AH0 N D"]))), "L AO1 | R AH0 N D |");
+    // 词内不跨词边界的序列不受影响
+    assert_eq!(render(engine.apply_sandhi(stream(&["T Y UW1"]))), "T Y UW1 |");
+
+    let set = RuleSet::parse(rules, RuleFormat::Pipe).unwrap();
+    assert!(set.render(RuleFormat::Pipe).unwrap().contains("SANDHI|T _ Y|CH _|3"));
+    assert!(convert(rules, RuleFormat::Pipe, RuleFormat::Nrl).is_err());
+    assert!(RulesEngine::parse_english_rules("#!g2p-rules 1\nSANDHI|T _ Y|$4|\n").is_err());
+    assert!(RulesEngine::parse_english_rules("#!g2p-rules 1\nSANDHI|T _ QQ|CH|\n").is_err());
+}
+
+#[test]
+fn test_stress_templates() {
+    use rust_g2p::phoneme::{render, Notation};
+    use rust_g2p::rules::{convert, RuleFormat, RuleSet};
+    use rust_g2p::schema::Capability;
+
+    // -ation 的第一个音节带主重音，前面的主重音降为次重音
+    let rules = "#!g2p-rules 1\n#!requires weights, stress\nk|||K\no|||AA1\nm|||M\nb|||B\ni|||IH0\nn|||N\nation||_|EY SH AH N|10||10\n";
+    let engine = RulesEngine::parse_english_rules(rules).unwrap();
+    let arpabet = |word: &str| render(&engine.apply_rules(word).unwrap(), Notation::Arpabet);
+    assert_eq!(arpabet("kombination"), "K AA2 M B IH0 N EY1 SH AH0 N");
+    assert_eq!(arpabet("komb"), "K AA1 M B");
+    assert!(engine.schema().uses(Capability::Stress));
+
+    let set = RuleSet::parse(rules, RuleFormat::Pipe).unwrap();
+    assert!(set.render(RuleFormat::Pipe).unwrap().contains("ation||_|EY SH AH N|10||10"));
+    assert!(convert(rules, RuleFormat::Pipe, RuleFormat::Nrl).is_err());
+    assert!(RulesEngine::parse_english_rules("#!g2p-rules 1\nation||_|EY SH AH N|10||1x\n").is_err());
+}
+
+#[test]
+fn test_rules_nbest() {
+    use rust_g2p::phoneme::{render, Notation};
+
+    // 两条 `c` 规则优先级相同，靠前的给出首选读音
+    let rules = "#!g2p-rules 1\n#!requires weights\nc|||K\nc|||S\nc||e|S|5\na|||AE1\ne|||IY1\nt|||T\n";
+    let engine = RulesEngine::parse_english_rules(rules).unwrap();
+    let arpabet = |word: &str, n: usize| -> Vec<String> {
+        engine.apply_rules_nbest(word, n).unwrap().iter().map(|output| render(&output.phonemes, Notation::Arpabet)).collect()
+    };
+    assert_eq!(arpabet("cat", 5), ["K AE1 T", "S AE1 T"]);
+    assert_eq!(arpabet("catc", 5), ["K AE1 T K", "S AE1 T K", "K AE1 T S"]);
+    assert_eq!(arpabet("catc", 1), ["K AE1 T K"]);
+    assert_eq!(arpabet("ce", 5), ["S IY1"]);
+    assert!(arpabet("cat", 0).is_empty());
+    assert_eq!(engine.apply_rules_nbest("cat", 2).unwrap()[0].phonemes, engine.apply_rules("cat").unwrap());
+}
+
+#[test]
+fn test_rules_across_threads() {
+    let engine = RulesEngine::load_english_rules("data/en_rules.txt").unwrap();
+    let words = ["blorfing", "snazzletrix", "a", "extraordinarily", "quibbery", "antidisestablishmentarianism"];
+    let expected: Vec<Vec<String>> = words.iter().map(|word| symbols(&engine, word)).collect();
+
+    // 每个线程复用自己的缓冲区，长短单词交替转换的结果不受上一个单词影响
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..3 {
+                    for (word, expected) in words.iter().zip(&expected) {
+                        assert_eq!(&symbols(&engine, word), expected);
+                    }
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn test_apostrophes_and_digits() {
+    use rust_g2p::phoneme::{render, Notation};
+
+    // 规则模式可以包含撇号，弯撇号按直撇号匹配
+    let engine = RulesEngine::parse_english_rules("d|||D\no|||OW1\nn|||N\nt|||T\nn't|||AH0 N T\n").unwrap();
+    let arpabet = |word: &str| render(&engine.apply_rules(word).unwrap(), Notation::Arpabet);
+    assert_eq!(arpabet("don't"), "D OW1 AH0 N T");
+    assert_eq!(arpabet("don’t"), "D OW1 AH0 N T");
+    // 没有规则匹配的撇号不发音，数字按名称读，都不算无法处理的字符
+    let output = engine.apply_rules_scored("to'd2").unwrap();
+    // 单词只有一个主重音，数字的重音降为次重音
+    assert_eq!(render(&output.phonemes, Notation::Arpabet), "T OW1 D T UW2");
+    assert!(output.uncovered.is_empty());
+    assert_eq!(output.default_letters, 0);
+
+    let engine = RulesEngine::load_english_rules("data/en_rules.txt").unwrap();
+    assert_eq!(symbols(&engine, "gov't"), symbols(&engine, "govt"));
+    assert_eq!(symbols(&engine, "mp3"), ["M", "P", "TH", "R", "IY"]);
+    let output = engine.apply_rules_scored("b4").unwrap();
+    assert!(output.uncovered.is_empty());
+    assert_eq!(output.confidence, 1.0);
+}
+
+#[test]
+fn test_stress_assignment() {
+    use rust_g2p::phoneme::{render, Notation};
+
+    let mut engine = RulesEngine::load_english_rules("data/en_rules.txt").unwrap();
+    let arpabet = |engine: &RulesEngine, word: &str| render(&engine.apply_rules(word).unwrap(), Notation::Arpabet);
+    // 各元音的重音数字，只检查重音位置，不涉及规则输出的其他音素
+    let stresses = |engine: &RulesEngine, word: &str| -> String {
+        engine
+            .apply_rules(word)
+            .unwrap()
+            .iter()
+            .filter(|p| p.is_vowel())
+            .map(|p| p.to_notation(Notation::Arpabet).chars().last().unwrap())
+            .collect()
+    };
+
+    // 后缀前的音节重读，重读后缀本身重读
+    assert_eq!(stresses(&engine, "nation"), "10");
+    assert_eq!(stresses(&engine, "blorptastic"), "210");
+    assert_eq!(stresses(&engine, "cassette"), "01");
+    // 不影响重音的后缀去掉后按词干判断
+    assert_eq!(stresses(&engine, "carelessness"), "100");
+    // 两个音节重读第一个，其他的主重音降为次重音
+    assert_eq!(stresses(&engine, "rainbow"), "12");
+    // 三个音节、倒数第二个音节不是长元音时重读倒数第三个
+    assert_eq!(stresses(&engine, "cinema"), "100");
+    assert_eq!(stresses(&engine, "elephant"), "100");
+
+    // 关闭后保留规则给出的重音
+    engine.set_stress_assignment(false);
+    assert_eq!(stresses(&engine, "nation"), "00");
+    assert_eq!(stresses(&engine, "rainbow"), "11");
+
+    // 重音模板给出主重音时不再指派
+    let engine = RulesEngine::parse_english_rules("#!g2p-rules 1\n#!requires weights, stress\nb|||B\na|||AE\nn|||N\nana|||AE N AH|9||01\n").unwrap();
+    assert_eq!(arpabet(&engine, "banana"), "B AE0 N AH1 N AE0");
+}
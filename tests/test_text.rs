@@ -0,0 +1,367 @@
+use rust_g2p::numbers;
+use rust_g2p::text::TextProcessor;
+
+/// 预处理并标准化，得到最终送去转换的文本
+fn normalize(processor: &TextProcessor, text: &str) -> String {
+    processor.normalize(&processor.preprocess(text)).unwrap().into_owned()
+}
+
+#[test]
+fn test_number_words() {
+    assert_eq!(numbers::cardinal(0), "zero");
+    assert_eq!(numbers::cardinal(25), "twenty five");
+    assert_eq!(numbers::cardinal(1_000_001), "one million one");
+    assert_eq!(numbers::cardinal(123_456), "one hundred twenty three thousand four hundred fifty six");
+
+    // 门牌号读法
+    assert_eq!(numbers::paired("123"), "one twenty three");
+    assert_eq!(numbers::paired("105"), "one oh five");
+    assert_eq!(numbers::paired("1200"), "twelve hundred");
+    assert_eq!(numbers::paired("2000"), "two thousand");
+    assert_eq!(numbers::paired("1234"), "twelve thirty four");
+    assert_eq!(numbers::paired("007"), "oh oh seven");
+
+    let processor = TextProcessor::new();
+    assert_eq!(normalize(&processor, "I have 25 cats"), "i have twenty five cats");
+}
+
+#[test]
+fn test_addresses() {
+    let processor = TextProcessor::new();
+
+    assert_eq!(
+        normalize(&processor, "123 Main St., Apt. 4B"),
+        "one twenty three main street apartment four b"
+    );
+    assert_eq!(
+        normalize(&processor, "Meet at 1600 N. Elm Dr. today"),
+        "meet at sixteen hundred north elm drive today"
+    );
+    assert_eq!(
+        normalize(&processor, "42 Old Mill Rd Ste 200"),
+        "forty two old mill road suite two hundred"
+    );
+    // 不是地址时不改写
+    assert_eq!(processor.preprocess("Dr. Smith lives here"), "Dr. Smith lives here");
+}
+
+#[test]
+fn test_ranges() {
+    let processor = TextProcessor::new();
+
+    assert_eq!(normalize(&processor, "They won 3-2"), "they won three to two");
+    assert_eq!(normalize(&processor, "pages 10–15"), "pages ten to fifteen");
+    assert_eq!(
+        normalize(&processor, "from 2019–2021"),
+        "from twenty nineteen to twenty twenty one"
+    );
+    assert_eq!(normalize(&processor, "5 - 3 = 2"), "five minus three two");
+    assert_eq!(normalize(&processor, "Open Mon–Fri"), "open monday through friday");
+
+    // 电话号码、日期和时间不是范围
+    for text in ["call 555-1234", "on 2020-01-05"] {
+        assert_eq!(processor.preprocess(text), text);
+    }
+    assert_eq!(numbers::expand_ranges("at 10:00-11:00"), "at 10:00-11:00");
+    assert_eq!(normalize(&processor, "at 10:00-11:00"), "at ten o'clock to eleven o'clock");
+}
+
+#[test]
+fn test_dotted_numbers() {
+    let processor = TextProcessor::new();
+
+    assert_eq!(normalize(&processor, "v2.0 is out"), "version two point oh is out");
+    assert_eq!(normalize(&processor, "Python 3.11.4"), "python three point eleven point four");
+    assert_eq!(normalize(&processor, "version 1.10"), "version one point ten");
+    assert_eq!(
+        normalize(&processor, "ping 192.168.0.1"),
+        "ping one hundred ninety two dot one hundred sixty eight dot zero dot one"
+    );
+    assert_eq!(normalize(&processor, "pi is 3.14."), "pi is three point one four");
+    assert_eq!(normalize(&processor, "1.5-2.5 kg"), "one point five to two point five kg");
+}
+
+#[test]
+fn test_scientific_notation() {
+    let processor = TextProcessor::new();
+
+    assert_eq!(numbers::ordinal(23), "twenty third");
+    assert_eq!(numbers::ordinal(40), "fortieth");
+    assert_eq!(numbers::ordinal(112), "one hundred twelfth");
+
+    assert_eq!(
+        normalize(&processor, "6.02e23 atoms"),
+        "six point oh two times ten to the twenty third atoms"
+    );
+    assert_eq!(normalize(&processor, "3×10^8 m/s"), "three times ten to the eighth m s");
+    assert_eq!(normalize(&processor, "about 1E-9"), "about one times ten to the minus ninth");
+    assert_eq!(normalize(&processor, "10^6"), "ten to the sixth");
+}
+
+#[test]
+fn test_signed_quantities() {
+    let processor = TextProcessor::new();
+
+    assert_eq!(normalize(&processor, "It was -5°C outside"), "it was minus five degrees celsius outside");
+    assert_eq!(normalize(&processor, "down −3.2%"), "down minus three point two percent");
+    assert_eq!(normalize(&processor, "a +2 bonus"), "a plus two bonus");
+    assert_eq!(normalize(&processor, "x = -7"), "x minus seven");
+    // 范围和减法不受影响
+    assert_eq!(normalize(&processor, "5-3"), "five to three");
+}
+
+#[test]
+fn test_number_locale() {
+    use rust_g2p::numbers::NumberLocale;
+    use rust_g2p::text::NormalizeOptions;
+
+    let us = TextProcessor::new();
+    assert_eq!(normalize(&us, "1,000.5 kg"), "one thousand point five kg");
+    assert_eq!(normalize(&us, "12,345,678"), "twelve million three hundred forty five thousand six hundred seventy eight");
+
+    let eu = TextProcessor::new().with_options(NormalizeOptions {
+        number_locale: NumberLocale::Eu,
+        ..Default::default()
+    });
+    assert_eq!(normalize(&eu, "1.000,5 kg"), "one thousand point five kg");
+    assert_eq!(normalize(&eu, "3,5%"), "three point five percent");
+    // 版本号和IP地址不是分组数字
+    assert_eq!(normalize(&eu, "Python 3.11.4"), "python three point eleven point four");
+    assert_eq!(eu.preprocess("ip 192.168.0.1"), us.preprocess("ip 192.168.0.1"));
+}
+
+#[test]
+fn test_units_and_plurals() {
+    let processor = TextProcessor::new();
+
+    assert_eq!(normalize(&processor, "1% and 5%"), "one percent and five percent");
+    assert_eq!(normalize(&processor, "3‰"), "three per mille");
+    assert_eq!(normalize(&processor, "1°C, -1°F, 10°F"), "one degree celsius minus one degree fahrenheit ten degrees fahrenheit");
+    assert_eq!(normalize(&processor, "turn 90°"), "turn ninety degrees");
+    assert_eq!(normalize(&processor, "1.5°C"), "one point five degrees celsius");
+    assert_eq!(normalize(&processor, "at 300 K"), "at three hundred kelvins");
+    assert_eq!(normalize(&processor, "1 K"), "one kelvin");
+    // 5K 不是温度
+    assert_eq!(processor.preprocess("a 5K run"), "a 5K run");
+}
+
+#[test]
+fn test_times() {
+    let processor = TextProcessor::new();
+
+    assert_eq!(normalize(&processor, "10 AM EST"), "ten a m eastern standard time");
+    assert_eq!(normalize(&processor, "at 10:30 p.m."), "at ten thirty p m");
+    assert_eq!(normalize(&processor, "wake at 7:05am"), "wake at seven oh five a m");
+    assert_eq!(normalize(&processor, "Open 9–11 AM PT"), "open nine to eleven a m pacific time");
+    assert_eq!(normalize(&processor, "Lunch at 12:00"), "lunch at twelve o'clock");
+    assert_eq!(normalize(&processor, "Depart 14:00 UTC"), "depart fourteen hundred coordinated universal time");
+    assert_eq!(processor.preprocess("10 PM"), "ten P.M.");
+    // 不是时间时不改写
+    assert_eq!(processor.preprocess("I have 10 cats"), "I have 10 cats");
+    assert_eq!(processor.preprocess("13 PM and 10:75"), "13 PM and 10:75");
+}
+
+#[test]
+fn test_list_items() {
+    let processor = TextProcessor::new();
+    let list = "Shopping:\n1. Buy milk\n2) Get 3 eggs\n- Call Bob";
+
+    assert_eq!(
+        processor.preprocess(list),
+        "Shopping:\nOne. Buy milk.\nTwo. Get 3 eggs.\nCall Bob."
+    );
+    // 编号和每一项各自成句
+    let preprocessed = processor.preprocess(list);
+    assert_eq!(processor.sentences(&preprocessed).len(), 5);
+    // 句中的编号不是列表
+    assert_eq!(processor.preprocess("Step 1. Done"), "Step 1. Done");
+
+    use rust_g2p::text::{ListStyle, NormalizeOptions};
+    let ordinal = TextProcessor::new().with_options(NormalizeOptions {
+        list_style: ListStyle::Ordinal,
+        ..Default::default()
+    });
+    assert_eq!(normalize(&ordinal, "1. Buy milk\n2. Eggs."), "first buy milk second eggs");
+}
+
+#[test]
+fn test_strip_markup() {
+    use rust_g2p::markup::strip_markup;
+    use rust_g2p::text::NormalizeOptions;
+
+    assert_eq!(
+        strip_markup("See [the docs](https://example.com) and **bold** or _em_ text"),
+        "See the docs and bold or em text"
+    );
+    assert_eq!(strip_markup("Run `ls -a` first"), "Run L.S. dash A. first");
+    assert_eq!(strip_markup("# Title\n> quoted ~~old~~"), "Title.\nquoted old");
+    assert_eq!(strip_markup("```\nfn main() {}\n```\nDone"), "Done");
+    assert_eq!(
+        strip_markup("<p>Tom &amp; Jerry</p><a href=\"x\">link</a>&#33;"),
+        "Tom & Jerry\nlink!"
+    );
+    // 变量名中的下划线和普通文本不受影响
+    assert_eq!(strip_markup("snake_case_name"), "snake_case_name");
+    assert_eq!(strip_markup("plain text"), "plain text");
+
+    let processor = TextProcessor::new().with_options(NormalizeOptions {
+        strip_markup: true,
+        ..Default::default()
+    });
+    assert_eq!(
+        normalize(&processor, "<ul><li>Buy **milk**</li><li>Call [Bob](tel:5551234)</li></ul>"),
+        "buy milk call bob"
+    );
+    // 默认不处理标记
+    assert_eq!(TextProcessor::new().preprocess("**bold**"), "**bold**");
+}
+
+#[test]
+fn test_normalize_borrows_unchanged_text() {
+    use std::borrow::Cow;
+
+    let processor = TextProcessor::new();
+    assert!(matches!(processor.normalize("the quick brown fox").unwrap(), Cow::Borrowed(_)));
+    assert!(matches!(processor.normalize("dog").unwrap(), Cow::Borrowed(_)));
+    assert!(matches!(processor.normalize("The  dog").unwrap(), Cow::Owned(_)));
+    assert_eq!(processor.normalize(" Dr. Smith has 2 dogs!\t").unwrap(), "doctor smith has two dogs");
+    assert_eq!(processor.normalize("a  b\nc").unwrap(), "a b c");
+}
+
+#[test]
+fn test_word_internal_apostrophes() {
+    let processor = TextProcessor::new();
+
+    // 单词内部的撇号保留并统一为直撇号，引号和所有格词尾的撇号去掉
+    assert_eq!(normalize(&processor, "I don’t know, y'all."), "i don't know y'all");
+    assert_eq!(normalize(&processor, "'Rock'n'roll' at five o’clock"), "rock'n'roll at five o'clock");
+    assert_eq!(normalize(&processor, "the dogs' bowls"), "the dogs bowls");
+}
+
+#[test]
+fn test_case_rules() {
+    use rust_g2p::lang::{CaseRules, English, Language};
+    use rust_g2p::text::NormalizeOptions;
+
+    assert_eq!(CaseRules::Unicode.lowercase("DİYARBAKIR"), "di\u{307}yarbakir");
+    assert_eq!(CaseRules::Turkic.lowercase("DİYARBAKIR"), "diyarbakır");
+    assert_eq!(CaseRules::Turkic.uppercase("istanbul ılık"), "İSTANBUL ILIK");
+    assert_eq!(CaseRules::Unicode.uppercase("straße"), "STRASSE");
+    assert_eq!(CaseRules::German.uppercase("straße"), "STRAẞE");
+    assert_eq!(CaseRules::German.lowercase("STRAẞE"), "straße");
+    // 词尾的 Σ 仍按上下文转为 ς
+    assert_eq!(CaseRules::Turkic.lowercase("ΟΔΟΣ"), "οδο\u{3c2}");
+    assert!(matches!(CaseRules::Turkic.lowercase("ılık"), std::borrow::Cow::Borrowed(_)));
+
+    assert_eq!(CaseRules::for_language("tr"), CaseRules::Turkic);
+    assert_eq!(CaseRules::for_language("az-Latn"), CaseRules::Turkic);
+    assert_eq!(CaseRules::for_language("de_AT"), CaseRules::German);
+    assert_eq!(CaseRules::for_language("en-GB"), CaseRules::Unicode);
+    assert_eq!(English.lowercase("Istanbul"), "istanbul");
+
+    let processor = TextProcessor::new().with_options(NormalizeOptions { case_rules: CaseRules::Turkic, ..Default::default() });
+    assert_eq!(normalize(&processor, "IĞDIR and İzmir"), "ığdır and izmir");
+}
+
+#[test]
+fn test_number_agreement() {
+    use rust_g2p::numbers::{Agreement, EnglishNumbers, Gender, NumberWords};
+    use std::sync::Arc;
+
+    /// 只区分“一”的阴阳性的西班牙语数词
+    struct Spanish;
+
+    impl NumberWords for Spanish {
+        fn cardinal(&self, n: u64, agreement: Agreement) -> String {
+            match (n, agreement.gender) {
+                (1, Gender::Feminine) => "una".to_string(),
+                (1, _) => "uno".to_string(),
+                (2, _) => "dos".to_string(),
+                _ => numbers::cardinal(n),
+            }
+        }
+
+        fn ordinal(&self, n: u64, agreement: Agreement) -> String {
+            match (n, agreement.gender) {
+                (1, Gender::Feminine) => "primera".to_string(),
+                (1, _) => "primero".to_string(),
+                _ => numbers::ordinal(n),
+            }
+        }
+
+        fn digits(&self, text: &str) -> String {
+            numbers::digits(text)
+        }
+
+        fn agreement(&self, following: Option<&str>) -> Agreement {
+            let gender = match following {
+                Some(word) if word.ends_with('a') => Gender::Feminine,
+                _ => Gender::Masculine,
+            };
+            Agreement { gender, ..Default::default() }
+        }
+    }
+
+    let processor = TextProcessor::new().with_number_words(Arc::new(Spanish));
+    assert_eq!(normalize(&processor, "1 casa, 1 perro y 2 gatos"), "una casa uno perro y dos gatos");
+    assert_eq!(normalize(&processor, "la 1st casa"), "la primera casa");
+    assert_eq!(normalize(&processor, "tengo 1."), "tengo uno");
+    assert_eq!(normalize(&processor, "1. Casa roja"), "una casa roja");
+
+    // 默认的英语数词不受后面单词的影响
+    assert_eq!(EnglishNumbers.cardinal(21, Agreement::default()), "twenty one");
+    assert_eq!(normalize(&TextProcessor::new(), "1 apple"), "one apple");
+}
+
+#[test]
+fn test_number_context() {
+    use rust_g2p::numbers::{reading_in_context, NumberReading};
+
+    let processor = TextProcessor::new();
+    // 日期中的日读作序数，年份按两位一组读
+    assert_eq!(normalize(&processor, "May 3, 2024"), "may third twenty twenty four");
+    assert_eq!(normalize(&processor, "on 3 March"), "on third march");
+    assert_eq!(normalize(&processor, "the 21 of June"), "the twenty first of june");
+    assert_eq!(normalize(&processor, "Born in 1984."), "born in nineteen eighty four");
+    assert_eq!(normalize(&processor, "1200 BC"), "twelve hundred bc");
+    // 编号和普通的数量读作基数
+    assert_eq!(normalize(&processor, "Chapter 3"), "chapter three");
+    assert_eq!(normalize(&processor, "3 apples"), "three apples");
+    assert_eq!(normalize(&processor, "room 1205"), "room one thousand two hundred five");
+    assert_eq!(normalize(&processor, "I have 1984 coins"), "i have one thousand nine hundred eighty four coins");
+
+    // 逐段标准化时由前后的原文单词决定读法
+    assert_eq!(processor.normalize_in_context("2024", &["3,", "May"], &[]).unwrap(), "twenty twenty four");
+    assert_eq!(processor.normalize_in_context("3,", &["May"], &["2024"]).unwrap(), "third");
+    assert_eq!(processor.normalize_in_context("1984.", &["in", "born"], &[]).unwrap(), "nineteen eighty four");
+
+    // 年代和数字的复数
+    assert_eq!(normalize(&processor, "the 1990s and 2000s"), "the nineteen nineties and two thousands");
+    assert_eq!(normalize(&processor, "in the 1900s"), "in the nineteen hundreds");
+    assert_eq!(normalize(&processor, "the 60s, 7s and 6s"), "the sixties sevens and sixes");
+
+    assert_eq!(reading_in_context(&["may"], "32", &[]), NumberReading::Cardinal);
+    assert_eq!(reading_in_context(&["in"], "2150", &[]), NumberReading::Cardinal);
+    assert_eq!(reading_in_context(&["page"], "3", &["of", "may"]), NumberReading::Cardinal);
+}
+
+#[test]
+fn test_currency() {
+    let processor = TextProcessor::new();
+    // 数量级读在货币单位之前
+    assert_eq!(normalize(&processor, "$3.5M"), "three point five million dollars");
+    assert_eq!(normalize(&processor, "£2bn"), "two billion pounds");
+    assert_eq!(normalize(&processor, "raised €40k."), "raised forty thousand euros");
+    assert_eq!(normalize(&processor, "$1.2 trillion"), "one point two trillion dollars");
+    // 两位小数读作辅币
+    assert_eq!(normalize(&processor, "$5"), "five dollars");
+    assert_eq!(normalize(&processor, "$1"), "one dollar");
+    assert_eq!(normalize(&processor, "£1.50"), "one pound and fifty pence");
+    assert_eq!(normalize(&processor, "$0.99"), "ninety nine cents");
+    assert_eq!(normalize(&processor, "$2.01"), "two dollars and one cent");
+    assert_eq!(normalize(&processor, "$1,250.00"), "one thousand two hundred fifty dollars");
+    assert_eq!(normalize(&processor, "¥300"), "three hundred yen");
+    assert_eq!(normalize(&processor, "-$20"), "minus twenty dollars");
+    // 与数字隔开的单个字母不是数量级
+    assert_eq!(normalize(&processor, "$5 T-shirts"), "five dollars t shirts");
+}